@@ -26,7 +26,7 @@ use ::demikernel::{
     QToken,
 };
 use ::std::{
-    net::SocketAddrV4,
+    net::SocketAddr,
     str::FromStr,
     time::{
         Duration,
@@ -53,13 +53,13 @@ pub const SOCK_STREAM: i32 = libc::SOCK_STREAM;
 /// Program Arguments
 #[derive(Debug)]
 pub struct ProgramArguments {
-    /// Local socket IPv4 address.
-    local: SocketAddrV4,
+    /// Local socket address.
+    local: SocketAddr,
 }
 
 /// Associate functions for Program Arguments
 impl ProgramArguments {
-    /// Default local socket IPv4 address.
+    /// Default local socket address.
     const DEFAULT_LOCAL: &'static str = "127.0.0.1:12345";
 
     /// Parses the program arguments from the command line interface.
@@ -79,7 +79,7 @@ impl ProgramArguments {
 
         // Default arguments.
         let mut args: ProgramArguments = ProgramArguments {
-            local: SocketAddrV4::from_str(Self::DEFAULT_LOCAL)?,
+            local: SocketAddr::from_str(Self::DEFAULT_LOCAL)?,
         };
 
         // Local address.
@@ -91,13 +91,13 @@ impl ProgramArguments {
     }
 
     /// Returns the local endpoint address parameter stored in the target program arguments.
-    pub fn get_local(&self) -> SocketAddrV4 {
+    pub fn get_local(&self) -> SocketAddr {
         self.local
     }
 
     /// Sets the local address and port number parameters in the target program arguments.
     fn set_local_addr(&mut self, addr: &str) -> Result<()> {
-        self.local = SocketAddrV4::from_str(addr)?;
+        self.local = SocketAddr::from_str(addr)?;
         Ok(())
     }
 }
@@ -122,7 +122,7 @@ impl Application {
     /// Instantiates the application.
     pub fn new(mut libos: LibOS, args: &ProgramArguments) -> Self {
         // Extract arguments.
-        let local: SocketAddrV4 = args.get_local();
+        let local: SocketAddr = args.get_local();
 
         // Create TCP socket.
         let sockqd: QDesc = match libos.socket(AF_INET, SOCK_STREAM, 0) {
@@ -29,7 +29,7 @@ use ::demikernel::{
     QToken,
 };
 use ::std::{
-    net::SocketAddrV4,
+    net::SocketAddr,
     slice,
     str::FromStr,
     time::{
@@ -57,8 +57,8 @@ pub const SOCK_STREAM: i32 = libc::SOCK_STREAM;
 /// Program Arguments
 #[derive(Debug)]
 pub struct ProgramArguments {
-    /// Socket IPv4 address.
-    saddr: Option<SocketAddrV4>,
+    /// Socket address.
+    saddr: Option<SocketAddr>,
     /// Buffer size (in bytes).
     bufsize: usize,
     /// Peer type.
@@ -138,7 +138,7 @@ impl ProgramArguments {
     }
 
     /// Returns the socket address parameter stored in the target program arguments.
-    pub fn get_socket_addr(&self) -> Option<SocketAddrV4> {
+    pub fn get_socket_addr(&self) -> Option<SocketAddr> {
         self.saddr
     }
 
@@ -165,7 +165,7 @@ impl ProgramArguments {
 
     /// Sets the local address and port number parameters in the target program arguments.
     fn set_socket_addr(&mut self, addr: &str) -> Result<()> {
-        self.saddr = Some(SocketAddrV4::from_str(addr)?);
+        self.saddr = Some(SocketAddr::from_str(addr)?);
         Ok(())
     }
 }
@@ -16,7 +16,7 @@ use ::demikernel::{
 };
 use ::std::{
     env,
-    net::SocketAddrV4,
+    net::SocketAddr,
     panic,
     slice,
     str::FromStr,
@@ -72,7 +72,7 @@ fn mksga(libos: &mut LibOS, size: usize, value: u8) -> demi_sgarray_t {
 // server()
 //======================================================================================================================
 
-fn server(local: SocketAddrV4) -> Result<()> {
+fn server(local: SocketAddr) -> Result<()> {
     let libos_name: LibOSName = match LibOSName::from_env() {
         Ok(libos_name) => libos_name.into(),
         Err(e) => panic!("{:?}", e),
@@ -151,7 +151,7 @@ fn server(local: SocketAddrV4) -> Result<()> {
 // client()
 //======================================================================================================================
 
-fn client(remote: SocketAddrV4) -> Result<()> {
+fn client(remote: SocketAddr) -> Result<()> {
     let libos_name: LibOSName = match LibOSName::from_env() {
         Ok(libos_name) => libos_name.into(),
         Err(e) => panic!("{:?}", e),
@@ -229,7 +229,7 @@ pub fn main() -> Result<()> {
     let args: Vec<String> = env::args().collect();
 
     if args.len() >= 3 {
-        let sockaddr: SocketAddrV4 = SocketAddrV4::from_str(&args[2])?;
+        let sockaddr: SocketAddr = SocketAddr::from_str(&args[2])?;
         if args[1] == "--server" {
             let ret: Result<()> = server(sockaddr);
             return ret;
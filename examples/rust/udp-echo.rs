@@ -30,6 +30,7 @@ use ::std::mem;
 use ::std::{
     net::{
         Ipv4Addr,
+        SocketAddr,
         SocketAddrV4,
     },
     str::FromStr,
@@ -39,7 +40,7 @@ use ::std::{
     },
 };
 #[cfg(target_os = "windows")]
-use windows::Win32::Networking::WinSock::SOCKADDR;
+use windows::Win32::Networking::WinSock::SOCKADDR_STORAGE;
 
 #[cfg(target_os = "windows")]
 pub const AF_INET: i32 = windows::Win32::Networking::WinSock::AF_INET.0 as i32;
@@ -63,8 +64,8 @@ pub const SOCK_DGRAM: i32 = libc::SOCK_DGRAM;
 /// Program Arguments
 #[derive(Debug)]
 pub struct ProgramArguments {
-    /// Local socket IPv4 address.
-    local: SocketAddrV4,
+    /// Local socket address.
+    local: SocketAddr,
 }
 
 /// Associate functions for Program Arguments
@@ -89,7 +90,7 @@ impl ProgramArguments {
 
         // Default arguments.
         let mut args: ProgramArguments = ProgramArguments {
-            local: SocketAddrV4::from_str(Self::DEFAULT_LOCAL)?,
+            local: SocketAddr::from_str(Self::DEFAULT_LOCAL)?,
         };
 
         // Local address.
@@ -101,13 +102,13 @@ impl ProgramArguments {
     }
 
     /// Returns the local endpoint address parameter stored in the target program arguments.
-    pub fn get_local(&self) -> SocketAddrV4 {
+    pub fn get_local(&self) -> SocketAddr {
         self.local
     }
 
     /// Sets the local address and port number parameters in the target program arguments.
     fn set_local_addr(&mut self, addr: &str) -> Result<()> {
-        self.local = SocketAddrV4::from_str(addr)?;
+        self.local = SocketAddr::from_str(addr)?;
         Ok(())
     }
 }
@@ -132,7 +133,7 @@ impl Application {
     /// Instantiates the application.
     pub fn new(mut libos: LibOS, args: &ProgramArguments) -> Self {
         // Extract arguments.
-        let local: SocketAddrV4 = args.get_local();
+        let local: SocketAddr = args.get_local();
 
         // Create UDP socket.
         let sockqd: QDesc = match libos.socket(AF_INET, SOCK_DGRAM, 0) {
@@ -189,7 +190,7 @@ impl Application {
                         .expect("could not parse sockaddr");
                     nbytes += sga.sga_segs[0].sgaseg_len as usize;
                     // Push packet back.
-                    let qt: QToken = match self.libos.pushto(qd, &sga, saddr) {
+                    let qt: QToken = match self.libos.pushto(qd, &sga, SocketAddr::V4(saddr)) {
                         Ok(qt) => qt,
                         Err(e) => panic!("failed to push data to socket: {:?}", e.cause),
                     };
@@ -216,11 +217,11 @@ impl Application {
     }
 
     #[cfg(target_os = "linux")]
-    /// Converts a [sockaddr] into a [SocketAddrV4].
-    pub fn sockaddr_to_socketaddrv4(saddr: *const libc::sockaddr) -> Result<SocketAddrV4> {
+    /// Converts a [sockaddr_storage] into a [SocketAddrV4].
+    pub fn sockaddr_to_socketaddrv4(saddr: *const libc::sockaddr_storage) -> Result<SocketAddrV4> {
         // TODO: Change the logic below and rename this function once we support V6 addresses as well.
         let sin: libc::sockaddr_in =
-            unsafe { *mem::transmute::<*const libc::sockaddr, *const libc::sockaddr_in>(saddr) };
+            unsafe { *mem::transmute::<*const libc::sockaddr_storage, *const libc::sockaddr_in>(saddr) };
         if sin.sin_family != libc::AF_INET as u16 {
             bail!("communication domain not supported");
         };
@@ -230,8 +231,8 @@ impl Application {
     }
 
     #[cfg(target_os = "windows")]
-    /// Converts a [sockaddr] into a [SocketAddrV4].
-    pub fn sockaddr_to_socketaddrv4(saddr: *const SOCKADDR) -> Result<SocketAddrV4> {
+    /// Converts a [SOCKADDR_STORAGE] into a [SocketAddrV4].
+    pub fn sockaddr_to_socketaddrv4(saddr: *const SOCKADDR_STORAGE) -> Result<SocketAddrV4> {
         // TODO: Change the logic below and rename this function once we support V6 addresses as well.
 
         let sin: SOCKADDR_IN = unsafe { *(saddr as *const SOCKADDR_IN) };
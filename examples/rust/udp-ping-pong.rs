@@ -19,7 +19,7 @@ use ::demikernel::{
 };
 use ::std::{
     env,
-    net::SocketAddrV4,
+    net::SocketAddr,
     panic,
     slice,
     str::FromStr,
@@ -75,7 +75,7 @@ fn mksga(libos: &mut LibOS, size: usize, value: u8) -> demi_sgarray_t {
 // server()
 //======================================================================================================================
 
-fn server(local: SocketAddrV4, remote: SocketAddrV4) -> ! {
+fn server(local: SocketAddr, remote: SocketAddr) -> ! {
     let libos_name: LibOSName = match LibOSName::from_env() {
         Ok(libos_name) => libos_name.into(),
         Err(e) => panic!("{:?}", e),
@@ -140,7 +140,7 @@ fn server(local: SocketAddrV4, remote: SocketAddrV4) -> ! {
 // client()
 //======================================================================================================================
 
-fn client(local: SocketAddrV4, remote: SocketAddrV4) -> Result<()> {
+fn client(local: SocketAddr, remote: SocketAddr) -> Result<()> {
     let libos_name: LibOSName = match LibOSName::from_env() {
         Ok(libos_name) => libos_name.into(),
         Err(e) => panic!("{:?}", e),
@@ -252,8 +252,8 @@ pub fn main() -> Result<()> {
     let args: Vec<String> = env::args().collect();
 
     if args.len() >= 4 {
-        let local: SocketAddrV4 = SocketAddrV4::from_str(&args[2])?;
-        let remote: SocketAddrV4 = SocketAddrV4::from_str(&args[3])?;
+        let local: SocketAddr = SocketAddr::from_str(&args[2])?;
+        let remote: SocketAddr = SocketAddr::from_str(&args[3])?;
         if args[1] == "--server" {
             server(local, remote);
         } else if args[1] == "--client" {
@@ -16,7 +16,7 @@ use ::demikernel::{
 };
 use ::std::{
     env,
-    net::SocketAddrV4,
+    net::SocketAddr,
     panic,
     slice,
     str::FromStr,
@@ -72,7 +72,7 @@ fn mksga(libos: &mut LibOS, size: usize, value: u8) -> demi_sgarray_t {
 // server()
 //======================================================================================================================
 
-fn server(local: SocketAddrV4) -> Result<()> {
+fn server(local: SocketAddr) -> Result<()> {
     let libos_name: LibOSName = match LibOSName::from_env() {
         Ok(libos_name) => libos_name.into(),
         Err(e) => panic!("{:?}", e),
@@ -133,7 +133,7 @@ fn server(local: SocketAddrV4) -> Result<()> {
 // client()
 //======================================================================================================================
 
-fn client(local: SocketAddrV4, remote: SocketAddrV4) -> Result<()> {
+fn client(local: SocketAddr, remote: SocketAddr) -> Result<()> {
     let libos_name: LibOSName = match LibOSName::from_env() {
         Ok(libos_name) => libos_name.into(),
         Err(e) => panic!("{:?}", e),
@@ -203,12 +203,12 @@ pub fn main() -> Result<()> {
     let args: Vec<String> = env::args().collect();
 
     if args.len() >= 3 {
-        let local: SocketAddrV4 = SocketAddrV4::from_str(&args[2])?;
+        let local: SocketAddr = SocketAddr::from_str(&args[2])?;
         if args[1] == "--server" {
             let ret: Result<()> = server(local);
             return ret;
         } else if args[1] == "--client" && args.len() == 4 {
-            let remote: SocketAddrV4 = SocketAddrV4::from_str(&args[3])?;
+            let remote: SocketAddr = SocketAddr::from_str(&args[3])?;
             let ret: Result<()> = client(local, remote);
             return ret;
         }
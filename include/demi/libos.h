@@ -12,7 +12,7 @@
 #endif
 
 #ifdef _WIN32
-#include <winsock.h>
+#include <winsock2.h>
 typedef int socklen_t;
 #endif
 
@@ -31,6 +31,29 @@ extern "C"
      */
     extern int demi_init(int argc, char *const argv[]);
 
+    /**
+     * @brief Initializes a Demikernel instance.
+     *
+     * Unlike demi_init(), this may be called many times, so that each thread owns its own instance. An instance may
+     * only be used by the thread that initialized it.
+     *
+     * @param libos_out Storage location for the handle of the Demikernel instance.
+     * @param argc      Number of arguments.
+     * @param argv      Argument values.
+     *
+     * @return On successful completion, zero is returned. On failure, a positive error code is returned instead.
+     */
+    extern int demi_init_instance(demi_libos_t **libos_out, int argc, char *const argv[]);
+
+    /**
+     * @brief Releases a Demikernel instance, and all I/O queues thereof.
+     *
+     * @param libos Target Demikernel instance.
+     *
+     * @return On successful completion, zero is returned. On failure, a positive error code is returned instead.
+     */
+    extern int demi_destroy_instance(demi_libos_t *libos);
+
     /**
      * @brief Creates a new memory I/O queue.
      *
@@ -41,6 +64,13 @@ extern "C"
      */
     extern int demi_create_pipe(int *memqd_out, const char *name);
 
+    /**
+     * @brief Same as demi_create_pipe(), but on the Demikernel instance @p libos.
+     *
+     * @param libos Target Demikernel instance, or NULL for the one initialized with demi_init().
+     */
+    extern int demi_create_pipe_ex(demi_libos_t *libos, int *memqd_out, const char *name);
+
     /**
      * @brief Opens an existing memory I/O queue.
      *
@@ -51,6 +81,13 @@ extern "C"
      */
     extern int demi_open_pipe(int *memqd_out, const char *name);
 
+    /**
+     * @brief Same as demi_open_pipe(), but on the Demikernel instance @p libos.
+     *
+     * @param libos Target Demikernel instance, or NULL for the one initialized with demi_init().
+     */
+    extern int demi_open_pipe_ex(demi_libos_t *libos, int *memqd_out, const char *name);
+
     /**
      * @brief Creates a socket I/O queue.
      *
@@ -63,6 +100,13 @@ extern "C"
      */
     extern int demi_socket(int *sockqd_out, int domain, int type, int protocol);
 
+    /**
+     * @brief Same as demi_socket(), but on the Demikernel instance @p libos.
+     *
+     * @param libos Target Demikernel instance, or NULL for the one initialized with demi_init().
+     */
+    extern int demi_socket_ex(demi_libos_t *libos, int *sockqd_out, int domain, int type, int protocol);
+
     /**
      * @brief Sets as passive a socket I/O queue.
      *
@@ -73,6 +117,13 @@ extern "C"
      */
     extern int demi_listen(int sockqd, int backlog);
 
+    /**
+     * @brief Same as demi_listen(), but on the Demikernel instance @p libos.
+     *
+     * @param libos Target Demikernel instance, or NULL for the one initialized with demi_init().
+     */
+    extern int demi_listen_ex(demi_libos_t *libos, int sockqd, int backlog);
+
     /**
      * @brief Binds an address to a socket I/O queue.
      *
@@ -84,6 +135,13 @@ extern "C"
      */
     extern int demi_bind(int sockqd, const struct sockaddr *addr, socklen_t size);
 
+    /**
+     * @brief Same as demi_bind(), but on the Demikernel instance @p libos.
+     *
+     * @param libos Target Demikernel instance, or NULL for the one initialized with demi_init().
+     */
+    extern int demi_bind_ex(demi_libos_t *libos, int sockqd, const struct sockaddr *addr, socklen_t size);
+
     /**
      * @brief Asynchronously accepts a connection request on a socket I/O queue.
      *
@@ -94,6 +152,13 @@ extern "C"
      */
     extern int demi_accept(demi_qtoken_t *qt_out, int sockqd);
 
+    /**
+     * @brief Same as demi_accept(), but on the Demikernel instance @p libos.
+     *
+     * @param libos Target Demikernel instance, or NULL for the one initialized with demi_init().
+     */
+    extern int demi_accept_ex(demi_libos_t *libos, demi_qtoken_t *qt_out, int sockqd);
+
     /**
      * @brief Asynchronously initiates a connection on a socket I/O queue.
      *
@@ -106,6 +171,130 @@ extern "C"
      */
     extern int demi_connect(demi_qtoken_t *qt_out, int sockqd, const struct sockaddr *addr, socklen_t size);
 
+    /**
+     * @brief Same as demi_connect(), but on the Demikernel instance @p libos.
+     *
+     * @param libos Target Demikernel instance, or NULL for the one initialized with demi_init().
+     */
+    extern int demi_connect_ex(demi_libos_t *libos, demi_qtoken_t *qt_out, int sockqd, const struct sockaddr *addr,
+                               socklen_t size);
+
+    /**
+     * @brief Sets an option on a socket I/O queue.
+     *
+     * @param sockqd  I/O queue descriptor of the target socket.
+     * @param level   Protocol level at which the option resides.
+     * @param optname Name of the target option.
+     * @param optval  Value for the target option.
+     * @param optlen  Effective size of the option value.
+     *
+     * @return On successful completion, zero is returned. On failure, a positive error code is returned instead.
+     */
+    extern int demi_setsockopt(int sockqd, int level, int optname, const void *optval, socklen_t optlen);
+
+    /**
+     * @brief Same as demi_setsockopt(), but on the Demikernel instance @p libos.
+     *
+     * @param libos Target Demikernel instance, or NULL for the one initialized with demi_init().
+     */
+    extern int demi_setsockopt_ex(demi_libos_t *libos, int sockqd, int level, int optname, const void *optval,
+                                  socklen_t optlen);
+
+    /**
+     * @brief Gets the value of an option on a socket I/O queue.
+     *
+     * @param sockqd  I/O queue descriptor of the target socket.
+     * @param level   Protocol level at which the option resides.
+     * @param optname Name of the target option.
+     * @param optval  Store location for the option value.
+     * @param optlen  Size of the store location. On successful completion, this is set to the effective size of the
+     *                option value.
+     *
+     * @return On successful completion, zero is returned. On failure, a positive error code is returned instead.
+     */
+    extern int demi_getsockopt(int sockqd, int level, int optname, void *optval, socklen_t *optlen);
+
+    /**
+     * @brief Same as demi_getsockopt(), but on the Demikernel instance @p libos.
+     *
+     * @param libos Target Demikernel instance, or NULL for the one initialized with demi_init().
+     */
+    extern int demi_getsockopt_ex(demi_libos_t *libos, int sockqd, int level, int optname, void *optval,
+                                  socklen_t *optlen);
+
+    /**
+     * @brief Gets the address to which a socket I/O queue is bound.
+     *
+     * @param sockqd I/O queue descriptor of the target socket.
+     * @param addr   Store location for the local address.
+     * @param size   Size of the store location. On successful completion, this is set to the effective size of the
+     *               socket address data structure.
+     *
+     * @return On successful completion, zero is returned. On failure, a positive error code is returned instead.
+     */
+    extern int demi_getsockname(int sockqd, struct sockaddr *addr, socklen_t *size);
+
+    /**
+     * @brief Same as demi_getsockname(), but on the Demikernel instance @p libos.
+     *
+     * @param libos Target Demikernel instance, or NULL for the one initialized with demi_init().
+     */
+    extern int demi_getsockname_ex(demi_libos_t *libos, int sockqd, struct sockaddr *addr, socklen_t *size);
+
+    /**
+     * @brief Gets the address of the peer connected to a socket I/O queue.
+     *
+     * @param sockqd I/O queue descriptor of the target socket.
+     * @param addr   Store location for the remote address.
+     * @param size   Size of the store location. On successful completion, this is set to the effective size of the
+     *               socket address data structure.
+     *
+     * @return On successful completion, zero is returned. On failure, a positive error code is returned instead.
+     */
+    extern int demi_getpeername(int sockqd, struct sockaddr *addr, socklen_t *size);
+
+    /**
+     * @brief Same as demi_getpeername(), but on the Demikernel instance @p libos.
+     *
+     * @param libos Target Demikernel instance, or NULL for the one initialized with demi_init().
+     */
+    extern int demi_getpeername_ex(demi_libos_t *libos, int sockqd, struct sockaddr *addr, socklen_t *size);
+
+    /**
+     * @brief Gets the statistics of an I/O queue.
+     *
+     * @param qd        Target I/O queue descriptor.
+     * @param stats_out Store location for the statistics.
+     * @param size      Size of the structure pointed to by @p stats_out, that is sizeof(demi_qstats_t).
+     *
+     * @return On successful completion, zero is returned. On failure, a positive error code is returned instead.
+     */
+    extern int demi_getstats(int qd, demi_qstats_t *stats_out, size_t size);
+
+    /**
+     * @brief Same as demi_getstats(), but on the Demikernel instance @p libos.
+     *
+     * @param libos Target Demikernel instance, or NULL for the one initialized with demi_init().
+     */
+    extern int demi_getstats_ex(demi_libos_t *libos, int qd, demi_qstats_t *stats_out, size_t size);
+
+    /**
+     * @brief Shuts down the sending half, the receiving half, or both halves of a connected socket.
+     *
+     * @param sockqd I/O queue descriptor of the target socket.
+     * @param how    Halves to shut down (SHUT_RD, SHUT_WR or SHUT_RDWR).
+     *
+     * @return On successful completion, zero is returned. On failure, a positive error code is returned instead.
+     */
+    extern int demi_shutdown(int sockqd, int how);
+
+    /**
+     * @brief Same as demi_shutdown(), but on the Demikernel instance @p libos.
+     *
+     * @param libos Target Demikernel instance, or NULL for the one initialized with demi_init().
+     */
+    extern int demi_shutdown_ex(demi_libos_t *libos, int sockqd, int how);
+
     /**
      * @brief Closes an I/O queue descriptor.
      *
@@ -115,6 +304,31 @@ extern "C"
      */
     extern int demi_close(int qd);
 
+    /**
+     * @brief Same as demi_close(), but on the Demikernel instance @p libos.
+     *
+     * @param libos Target Demikernel instance, or NULL for the one initialized with demi_init().
+     */
+    extern int demi_close_ex(demi_libos_t *libos, int qd);
+
+    /**
+     * @brief Asynchronously closes an I/O queue descriptor. The operation completes once the I/O queue is fully shut
+     * down, that is, for connected sockets, once pushed data is acknowledged by the remote peer.
+     *
+     * @param qt_out Store location for I/O queue token.
+     * @param qd     Target I/O queue descriptor.
+     *
+     * @return On successful completion, zero is returned. On failure, a positive error code is returned instead.
+     */
+    extern int demi_async_close(demi_qtoken_t *qt_out, int qd);
+
+    /**
+     * @brief Same as demi_async_close(), but on the Demikernel instance @p libos.
+     *
+     * @param libos Target Demikernel instance, or NULL for the one initialized with demi_init().
+     */
+    extern int demi_async_close_ex(demi_libos_t *libos, demi_qtoken_t *qt_out, int qd);
+
     /**
      * @brief Asynchronously pushes a scatter-gather array to an I/O queue.
      *
@@ -126,6 +340,13 @@ extern "C"
      */
     extern int demi_push(demi_qtoken_t *qt_out, int qd, const demi_sgarray_t *sga);
 
+    /**
+     * @brief Same as demi_push(), but on the Demikernel instance @p libos.
+     *
+     * @param libos Target Demikernel instance, or NULL for the one initialized with demi_init().
+     */
+    extern int demi_push_ex(demi_libos_t *libos, demi_qtoken_t *qt_out, int qd, const demi_sgarray_t *sga);
+
     /**
      * @brief Asynchronously pushes a scatter-gather array to a socket I/O queue.
      *
@@ -140,6 +361,14 @@ extern "C"
     extern int demi_pushto(demi_qtoken_t *qt_out, int sockqd, const demi_sgarray_t *sga,
                            const struct sockaddr *dest_addr, socklen_t size);
 
+    /**
+     * @brief Same as demi_pushto(), but on the Demikernel instance @p libos.
+     *
+     * @param libos Target Demikernel instance, or NULL for the one initialized with demi_init().
+     */
+    extern int demi_pushto_ex(demi_libos_t *libos, demi_qtoken_t *qt_out, int sockqd, const demi_sgarray_t *sga,
+                              const struct sockaddr *dest_addr, socklen_t size);
+
     /**
      * @brief Asynchronously pops a scatter-gather array from an I/O queue.
      *
@@ -150,6 +379,82 @@ extern "C"
      */
     extern int demi_pop(demi_qtoken_t *qt_out, int qd);
 
+    /**
+     * @brief Same as demi_pop(), but on the Demikernel instance @p libos.
+     *
+     * @param libos Target Demikernel instance, or NULL for the one initialized with demi_init().
+     */
+    extern int demi_pop_ex(demi_libos_t *libos, demi_qtoken_t *qt_out, int qd);
+
+    /**
+     * @brief Asynchronously pops a scatter-gather array from an I/O queue, within size bounds.
+     *
+     * On stream queues, the operation does not complete until at least @p min_size bytes are available, or the end
+     * of the stream is reached. On datagram sockets, @p min_size is ignored, and the bytes of a datagram that exceed
+     * @p max_size are discarded.
+     *
+     * @param qt_out   Store location for I/O queue token.
+     * @param qd       Target I/O queue descriptor.
+     * @param min_size Minimum number of bytes to pop.
+     * @param max_size Maximum number of bytes to pop.
+     *
+     * @return On successful completion, zero is returned. On failure, a positive error code is returned instead.
+     */
+    extern int demi_pop_bounded(demi_qtoken_t *qt_out, int qd, size_t min_size, size_t max_size);
+
+    /**
+     * @brief Same as demi_pop_bounded(), but on the Demikernel instance @p libos.
+     *
+     * @param libos Target Demikernel instance, or NULL for the one initialized with demi_init().
+     */
+    extern int demi_pop_bounded_ex(demi_libos_t *libos, demi_qtoken_t *qt_out, int qd, size_t min_size,
+                                   size_t max_size);
+
+    /**
+     * @brief Asynchronously pops data from an I/O queue into a scatter-gather array provided by the caller.
+     *
+     * At most as many bytes as the segments of @p sga hold are popped, as in demi_pop_bounded(). When the operation
+     * completes, the data is copied into the segments of @p sga, whose lengths are set accordingly, and @p sga is
+     * handed back in the result of the operation. It remains owned by the caller.
+     *
+     * @param qt_out   Store location for I/O queue token.
+     * @param qd       Target I/O queue descriptor.
+     * @param sga      Scatter-gather array to pop into.
+     * @param min_size Minimum number of bytes to pop.
+     *
+     * @return On successful completion, zero is returned. On failure, a positive error code is returned instead.
+     */
+    extern int demi_pop_into(demi_qtoken_t *qt_out, int qd, const demi_sgarray_t *sga, size_t min_size);
+
+    /**
+     * @brief Same as demi_pop_into(), but on the Demikernel instance @p libos.
+     *
+     * @param libos Target Demikernel instance, or NULL for the one initialized with demi_init().
+     */
+    extern int demi_pop_into_ex(demi_libos_t *libos, demi_qtoken_t *qt_out, int qd, const demi_sgarray_t *sga,
+                                size_t min_size);
+
+    /**
+     * @brief Describes the failure of an asynchronous I/O operation.
+     *
+     * If @p qr is the result of an operation that completed with DEMI_OPC_FAILED, the cause reported by Demikernel for
+     * that operation is described. Otherwise, or once the queue token of the operation has been reused by another one,
+     * the system description of the qr_ret field of @p qr is returned.
+     *
+     * @param qr Result of the target operation.
+     *
+     * @return A string describing the failure, or NULL if @p qr is NULL. The string remains valid until the next call to
+     * this function on the same thread.
+     */
+    extern const char *demi_strerror(const demi_qresult_t *qr);
+
+    /**
+     * @brief Same as demi_strerror(), but on the Demikernel instance @p libos.
+     *
+     * @param libos Target Demikernel instance, or NULL for the one initialized with demi_init().
+     */
+    extern const char *demi_strerror_ex(demi_libos_t *libos, const demi_qresult_t *qr);
+
 #ifdef __cplusplus
 }
 #endif
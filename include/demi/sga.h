@@ -22,6 +22,13 @@ extern "C"
      */
     extern demi_sgarray_t demi_sgaalloc(size_t size);
 
+    /**
+     * @brief Same as demi_sgaalloc(), but on the Demikernel instance @p libos.
+     *
+     * @param libos Target Demikernel instance, or NULL for the one initialized with demi_init().
+     */
+    extern demi_sgarray_t demi_sgaalloc_ex(demi_libos_t *libos, size_t size);
+
     /**
      * @brief Releases a scatter-gather array.
      *
@@ -31,6 +38,31 @@ extern "C"
      */
     extern int demi_sgafree(demi_sgarray_t *sga);
 
+    /**
+     * @brief Same as demi_sgafree(), but on the Demikernel instance @p libos.
+     *
+     * @param libos Target Demikernel instance, or NULL for the one initialized with demi_init().
+     */
+    extern int demi_sgafree_ex(demi_libos_t *libos, demi_sgarray_t *sga);
+
+    /**
+     * @brief Appends the segments of a scatter-gather array to another one, without copying any data.
+     *
+     * @param sga  Target scatter-gather array. On successful completion, it is updated to also hold the segments of
+     *             @p tail.
+     * @param tail Scatter-gather array to append. On successful completion, it is released.
+     *
+     * @return On successful completion, zero is returned. On failure, a positive error code is returned instead.
+     */
+    extern int demi_sgaappend(demi_sgarray_t *sga, demi_sgarray_t *tail);
+
+    /**
+     * @brief Same as demi_sgaappend(), but on the Demikernel instance @p libos.
+     *
+     * @param libos Target Demikernel instance, or NULL for the one initialized with demi_init().
+     */
+    extern int demi_sgaappend_ex(demi_libos_t *libos, demi_sgarray_t *sga, demi_sgarray_t *tail);
+
 #ifdef __cplusplus
 }
 #endif
@@ -13,7 +13,8 @@
 #endif
 
 #ifdef _WIN32
-#include <winsock.h>
+#include <winsock2.h>
+#include <ws2ipdef.h>
 #endif
 
 #ifdef __cplusplus
@@ -24,7 +25,12 @@ extern "C"
 /**
  * @brief Maximum number of segments in a scatter-gather array.
  */
-#define DEMI_SGARRAY_MAXSIZE 1
+#define DEMI_SGARRAY_MAXSIZE 16
+
+    /**
+     * @brief A Demikernel instance. This is an opaque type.
+     */
+    typedef struct demi_libos demi_libos_t;
 
     /**
      * @brief An I/O queue token.
@@ -42,13 +48,16 @@ extern "C"
 
     /**
      * @brief A scatter-gather array.
+     *
+     * When returned by demi_pop(), sga_addr holds the address of the peer that sent the data. Both its address and its
+     * port are in network byte order, as in the socket addresses that are passed to demi_bind() and demi_connect().
      */
     typedef struct demi_sgarray
     {
         void *sga_buf;                                /**< Reserved.                                       */
         uint32_t sga_numsegs;                         /**< Number of segments in the scatter-gather array. */
         demi_sgaseg_t sga_segs[DEMI_SGARRAY_MAXSIZE]; /**< Scatter-gather array segments.                  */
-        struct sockaddr_in sga_addr;                  /**< Source address of scatter-gather array.         */
+        struct sockaddr_storage sga_addr;             /**< Source address of scatter-gather array.         */
     } demi_sgarray_t;
 
     /**
@@ -62,6 +71,7 @@ extern "C"
         DEMI_OPC_ACCEPT,      /**< Accept operation.  */
         DEMI_OPC_CONNECT,     /**< Connect operation. */
         DEMI_OPC_FAILED,      /**< Operation failed.  */
+        DEMI_OPC_CLOSE,       /**< Close operation.   */
     } demi_opcode_t;
 
     /**
@@ -69,8 +79,8 @@ extern "C"
      */
     typedef struct demi_accept_result
     {
-        int qd;                  /**< Socket I/O queue descriptor of accepted connection. */
-        struct sockaddr_in addr; /**< Remote address of accepted connection.              */
+        int qd;                       /**< Socket I/O queue descriptor of accepted connection. */
+        struct sockaddr_storage addr; /**< Remote address of accepted connection.              */
     } demi_accept_result_t;
 
     /**
@@ -81,6 +91,7 @@ extern "C"
         enum demi_opcode qr_opcode; /**< Opcode of completed operation.                              */
         int qr_qd;                  /**< I/O queue descriptor associated to the completed operation. */
         demi_qtoken_t qr_qt;        /**< I/O queue token of the completed operation.                 */
+        int qr_ret;                 /**< Error code of the completed operation (zero on success).    */
 
         /**
          * @brief Result value.
@@ -91,6 +102,55 @@ extern "C"
         } qr_value;
     } demi_qresult_t;
 
+/**
+ * @brief Version of the statistics of an I/O queue.
+ */
+#define DEMI_QSTATS_VERSION 1
+
+/**
+ * @brief Flag that is set in the statistics of an I/O queue when its TCP fields are valid.
+ */
+#define DEMI_QSTATS_TCP (1 << 0)
+
+    /**
+     * @brief Statistics of an I/O queue.
+     */
+    typedef struct demi_qstats
+    {
+        uint32_t qs_version;             /**< Version of this structure (DEMI_QSTATS_VERSION).      */
+        uint32_t qs_flags;               /**< Flags stating which optional fields are valid.        */
+        uint64_t qs_bytes_pushed;        /**< Number of bytes pushed by completed operations.       */
+        uint64_t qs_bytes_popped;        /**< Number of bytes popped by completed operations.       */
+        uint64_t qs_pushes;              /**< Number of completed push operations.                  */
+        uint64_t qs_pops;                /**< Number of completed pop operations.                   */
+        uint64_t qs_pending;             /**< Number of pending operations.                         */
+        uint64_t qs_tcp_cwnd;            /**< Congestion window of a TCP connection, in bytes.      */
+        uint64_t qs_tcp_rto_us;          /**< Retransmission timeout of a TCP connection, in us.    */
+        uint64_t qs_tcp_retransmissions; /**< Number of segments retransmitted on a TCP connection. */
+        uint64_t qs_tcp_recv_window;     /**< Receive window of a TCP connection, in bytes.         */
+        uint64_t qs_tcp_send_window;     /**< Send window of a TCP connection, or zero if unknown.  */
+    } demi_qstats_t;
+
+/**
+ * @brief Readiness event of an I/O queue from which data may be popped, or whose end of stream was reached.
+ */
+#define DEMI_POLLIN (1 << 0)
+
+/**
+ * @brief Readiness event of an I/O queue to which data may be pushed without being held back.
+ */
+#define DEMI_POLLOUT (1 << 1)
+
+/**
+ * @brief Readiness event of a passive socket on which an incoming connection may be accepted.
+ */
+#define DEMI_POLLACCEPT (1 << 2)
+
+/**
+ * @brief Readiness event of a connected socket whose remote peer has hung up. This is always reported.
+ */
+#define DEMI_POLLHUP (1 << 3)
+
 #ifdef __cplusplus
 }
 #endif
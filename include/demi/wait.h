@@ -23,6 +23,14 @@ extern "C"
      */
     extern int demi_wait(demi_qresult_t *qr_out, demi_qtoken_t qt, const struct timespec *timeout);
 
+    /**
+     * @brief Same as demi_wait(), but on the Demikernel instance @p libos.
+     *
+     * @param libos Target Demikernel instance, or NULL for the one initialized with demi_init().
+     */
+    extern int demi_wait_ex(demi_libos_t *libos, demi_qresult_t *qr_out, demi_qtoken_t qt,
+                            const struct timespec *timeout);
+
     /**
      * @brief Waits for an asynchronous I/O operation to complete or a timeout to expire.
      *
@@ -34,6 +42,14 @@ extern "C"
      */
     extern int demi_timedwait(demi_qresult_t *qr_out, demi_qtoken_t qt, const struct timespec *abstime);
 
+    /**
+     * @brief Same as demi_timedwait(), but on the Demikernel instance @p libos.
+     *
+     * @param libos Target Demikernel instance, or NULL for the one initialized with demi_init().
+     */
+    extern int demi_timedwait_ex(demi_libos_t *libos, demi_qresult_t *qr_out, demi_qtoken_t qt,
+                                 const struct timespec *abstime);
+
     /**
      * @brief Waits for the first asynchronous I/O operation in a list to complete.
      *
@@ -47,6 +63,87 @@ extern "C"
      */
     extern int demi_wait_any(demi_qresult_t *qr_out, int *ready_offset, const demi_qtoken_t qts[], int num_qts, const struct timespec *timeout);
 
+    /**
+     * @brief Same as demi_wait_any(), but on the Demikernel instance @p libos.
+     *
+     * @param libos Target Demikernel instance, or NULL for the one initialized with demi_init().
+     */
+    extern int demi_wait_any_ex(demi_libos_t *libos, demi_qresult_t *qr_out, int *ready_offset,
+                                const demi_qtoken_t qts[], int num_qts, const struct timespec *timeout);
+
+    /**
+     * @brief Waits for some asynchronous I/O operations in a list to complete.
+     *
+     * The results of the I/O operations in the list that have completed are stored at once, in the order of the list,
+     * up to @p max_qrs of them. Further completed I/O operations are left for a later call.
+     *
+     * @param qrs_out  Store location for the results of the completed I/O operations.
+     * @param max_qrs  Number of results that @p qrs_out has room for.
+     * @param nqrs_out Store location for the number of completed I/O operations.
+     * @param qts      List of I/O queue tokens to wait for completion.
+     * @param num_qts  Length of the list of I/O queue tokens to wait for completion.
+     * @param timeout  Timeout interval in seconds and nanoseconds.
+     *
+     * @return On successful completion, zero is returned. On failure, a positive error code is returned instead.
+     */
+    extern int demi_wait_many(demi_qresult_t qrs_out[], int max_qrs, int *nqrs_out, const demi_qtoken_t qts[],
+                              int num_qts, const struct timespec *timeout);
+
+    /**
+     * @brief Same as demi_wait_many(), but on the Demikernel instance @p libos.
+     *
+     * @param libos Target Demikernel instance, or NULL for the one initialized with demi_init().
+     */
+    extern int demi_wait_many_ex(demi_libos_t *libos, demi_qresult_t qrs_out[], int max_qrs, int *nqrs_out,
+                                 const demi_qtoken_t qts[], int num_qts, const struct timespec *timeout);
+
+    /**
+     * @brief Waits for some I/O queues in a list to be ready for I/O.
+     *
+     * Readiness events are DEMI_POLLIN, DEMI_POLLOUT, DEMI_POLLACCEPT and DEMI_POLLHUP. The last one is always reported,
+     * even if it is not asked for.
+     *
+     * @param nready_out  Store location for the number of ready I/O queues, which is zero if the timeout expires.
+     * @param revents_out Store location for the readiness events of each I/O queue. It should have room for @p num_qds
+     *                    masks.
+     * @param qds         List of I/O queue descriptors to check for readiness.
+     * @param events      Readiness events to check for, for each I/O queue descriptor in @p qds.
+     * @param num_qds     Length of the lists of I/O queue descriptors and readiness events.
+     * @param timeout     Timeout interval in seconds and nanoseconds.
+     *
+     * @return On successful completion, zero is returned. On failure, a positive error code is returned instead.
+     */
+    extern int demi_poll(int *nready_out, uint32_t revents_out[], const int qds[], const uint32_t events[],
+                         int num_qds, const struct timespec *timeout);
+
+    /**
+     * @brief Same as demi_poll(), but on the Demikernel instance @p libos.
+     *
+     * @param libos Target Demikernel instance, or NULL for the one initialized with demi_init().
+     */
+    extern int demi_poll_ex(demi_libos_t *libos, int *nready_out, uint32_t revents_out[], const int qds[],
+                            const uint32_t events[], int num_qds, const struct timespec *timeout);
+
+    /**
+     * @brief Cancels an asynchronous I/O operation.
+     *
+     * If the target I/O operation has already completed, its result is stored as is. Otherwise, the operation is
+     * aborted and its result is stored with DEMI_OPC_FAILED and ECANCELED. Either way, the I/O queue token is released.
+     *
+     * @param qr_out Store location for the result of the cancelled I/O operation.
+     * @param qt     I/O queue token of the target operation.
+     *
+     * @return On successful completion, zero is returned. On failure, a positive error code is returned instead.
+     */
+    extern int demi_cancel(demi_qresult_t *qr_out, demi_qtoken_t qt);
+
+    /**
+     * @brief Same as demi_cancel(), but on the Demikernel instance @p libos.
+     *
+     * @param libos Target Demikernel instance, or NULL for the one initialized with demi_init().
+     */
+    extern int demi_cancel_ex(demi_libos_t *libos, demi_qresult_t *qr_out, demi_qtoken_t qt);
+
 #ifdef __cplusplus
 }
 #endif
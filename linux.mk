@@ -65,6 +65,12 @@ ifeq ($(PROFILER),yes)
 CARGO_FEATURES += --features=profiler
 endif
 
+# Switch for POSIX socket interposition.
+export PRELOAD=no
+ifeq ($(PRELOAD),yes)
+CARGO_FEATURES += --features=preload
+endif
+
 CARGO_FEATURES += $(FEATURES)
 
 #=======================================================================================================================
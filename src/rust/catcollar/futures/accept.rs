@@ -14,10 +14,15 @@ use crate::{
 };
 use ::nix::{
     errno::Errno,
-    sys::socket,
+    sys::socket::{
+        self,
+        SockaddrStorage,
+    },
+    unistd,
 };
 use ::std::{
     future::Future,
+    net::SocketAddr,
     os::unix::prelude::RawFd,
     pin::Pin,
     task::{
@@ -70,7 +75,7 @@ impl AcceptFuture {
 
 /// Future Trait Implementation for Accept Operation Descriptors
 impl Future for AcceptFuture {
-    type Output = Result<RawFd, Fail>;
+    type Output = Result<(RawFd, SocketAddr), Fail>;
 
     /// Polls the underlying accept operation.
     fn poll(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
@@ -80,6 +85,30 @@ impl Future for AcceptFuture {
             Ok(new_fd) => {
                 trace!("connection accepted ({:?})", new_fd);
 
+                // Get address of remote peer.
+                let addr: SocketAddr = match socket::getpeername::<SockaddrStorage>(new_fd) {
+                    Ok(addr) => match linux::sockaddr_to_socketaddr(&addr) {
+                        Some(addr) => addr,
+                        None => {
+                            warn!("unsupported peer address ({:?})", addr);
+                            if let Err(e) = unistd::close(new_fd) {
+                                warn!("failed to close socket ({:?})", e);
+                            }
+                            return Poll::Ready(Err(Fail::new(
+                                libc::EAFNOSUPPORT,
+                                "communication domain not supported",
+                            )));
+                        },
+                    },
+                    Err(e) => {
+                        warn!("failed to get peer address ({:?})", e);
+                        if let Err(e) = unistd::close(new_fd) {
+                            warn!("failed to close socket ({:?})", e);
+                        }
+                        return Poll::Ready(Err(Fail::new(e as i32, "operation failed")));
+                    },
+                };
+
                 // Set socket options.
                 unsafe {
                     if linux::set_tcp_nodelay(new_fd) != 0 {
@@ -93,7 +122,7 @@ impl Future for AcceptFuture {
                     }
                 }
 
-                Poll::Ready(Ok(new_fd))
+                Poll::Ready(Ok((new_fd, addr)))
             },
             // Operation in progress.
             Err(e) if e == Errno::EWOULDBLOCK || e == Errno::EAGAIN => {
@@ -0,0 +1,87 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//==============================================================================
+// Imports
+//==============================================================================
+
+use crate::{
+    pal::linux::close::ClosingSocket,
+    runtime::{
+        fail::Fail,
+        QDesc,
+    },
+};
+use ::std::{
+    future::Future,
+    os::unix::prelude::RawFd,
+    pin::Pin,
+    task::{
+        Context,
+        Poll,
+    },
+};
+
+//==============================================================================
+// Structures
+//==============================================================================
+
+/// Close Operation Descriptor
+pub struct CloseFuture {
+    /// Associated queue descriptor.
+    qd: QDesc,
+    /// Underlying socket. Its connection is reset if the operation is dropped before it completes.
+    socket: ClosingSocket,
+}
+
+//==============================================================================
+// Associate Functions
+//==============================================================================
+
+/// Associate Functions for Close Operation Descriptors
+impl CloseFuture {
+    /// Creates a descriptor for a close operation.
+    pub fn new(qd: QDesc, fd: RawFd) -> Self {
+        Self {
+            qd,
+            socket: ClosingSocket::new(fd),
+        }
+    }
+
+    /// Returns the queue descriptor associated to the target [CloseFuture].
+    pub fn get_qd(&self) -> QDesc {
+        self.qd
+    }
+}
+
+//==============================================================================
+// Trait Implementations
+//==============================================================================
+
+/// Future Trait Implementation for Close Operation Descriptors
+impl Future for CloseFuture {
+    type Output = Result<(), Fail>;
+
+    /// Polls the target [CloseFuture]. The underlying socket is closed once it is flushed, or as soon as that fails.
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
+        let self_: &mut CloseFuture = self.get_mut();
+        match self_.socket.flush() {
+            // Data is yet to be acknowledged, so keep polling.
+            Ok(true) => {
+                ctx.waker().wake_by_ref();
+                Poll::Pending
+            },
+            Ok(false) => {
+                let result: Result<(), Fail> = self_.socket.close(false);
+                if result.is_ok() {
+                    trace!("socket closed ({:?})", self_.qd);
+                }
+                Poll::Ready(result)
+            },
+            Err(e) => {
+                let _ = self_.socket.close(e.errno == libc::ETIMEDOUT);
+                Poll::Ready(Err(e))
+            },
+        }
+    }
+}
@@ -6,6 +6,7 @@
 //==============================================================================
 
 pub mod accept;
+pub mod close;
 pub mod connect;
 pub mod pop;
 pub mod push;
@@ -17,6 +18,7 @@ pub mod pushto;
 
 use self::{
     accept::AcceptFuture,
+    close::CloseFuture,
     connect::ConnectFuture,
     pop::PopFuture,
     push::PushFuture,
@@ -51,6 +53,8 @@ pub enum Operation {
     Accept(FutureResult<AcceptFuture>),
     /// Connection operation
     Connect(FutureResult<ConnectFuture>),
+    /// Close operation.
+    Close(FutureResult<CloseFuture>),
     /// Push operation
     Push(FutureResult<PushFuture>),
     /// Pushto operation.
@@ -71,12 +75,12 @@ impl Operation {
             // Accept operation.
             Operation::Accept(FutureResult {
                 future,
-                done: Some(Ok(new_fd)),
+                done: Some(Ok((new_fd, addr))),
             }) => (
                 future.get_qd(),
                 Some(future.get_new_qd()),
                 Some(new_fd),
-                OperationResult::Accept(future.get_new_qd()),
+                OperationResult::Accept((future.get_new_qd(), addr)),
             ),
             Operation::Accept(FutureResult {
                 future,
@@ -98,6 +102,16 @@ impl Operation {
                 done: Some(Err(e)),
             }) => (future.get_qd(), None, None, OperationResult::Failed(e)),
 
+            // Close operation.
+            Operation::Close(FutureResult {
+                future,
+                done: Some(Ok(())),
+            }) => (future.get_qd(), None, None, OperationResult::Close),
+            Operation::Close(FutureResult {
+                future,
+                done: Some(Err(e)),
+            }) => (future.get_qd(), None, None, OperationResult::Failed(e)),
+
             // Push operation.
             Operation::Push(FutureResult {
                 future,
@@ -131,6 +145,33 @@ impl Operation {
             _ => panic!("future not ready"),
         }
     }
+
+    /// Completes the target [Operation] with `ECANCELED`, unless it has already completed.
+    pub fn cancel(&mut self) {
+        match self {
+            Operation::Accept(ref mut f) => f.cancel(),
+            Operation::Connect(ref mut f) => f.cancel(),
+            Operation::Close(ref mut f) => f.cancel(),
+            Operation::Push(ref mut f) => {
+                if f.done.is_none() {
+                    f.future.cancel();
+                }
+                f.cancel()
+            },
+            Operation::Pushto(ref mut f) => {
+                if f.done.is_none() {
+                    f.future.cancel();
+                }
+                f.cancel()
+            },
+            Operation::Pop(ref mut f) => {
+                if f.done.is_none() {
+                    f.future.cancel();
+                }
+                f.cancel()
+            },
+        }
+    }
 }
 
 //==============================================================================
@@ -157,6 +198,7 @@ impl Future for Operation {
         match self.get_mut() {
             Operation::Accept(ref mut f) => Future::poll(Pin::new(f), ctx),
             Operation::Connect(ref mut f) => Future::poll(Pin::new(f), ctx),
+            Operation::Close(ref mut f) => Future::poll(Pin::new(f), ctx),
             Operation::Push(ref mut f) => Future::poll(Pin::new(f), ctx),
             Operation::Pushto(ref mut f) => Future::poll(Pin::new(f), ctx),
             Operation::Pop(ref mut f) => Future::poll(Pin::new(f), ctx),
@@ -178,6 +220,13 @@ impl From<ConnectFuture> for Operation {
     }
 }
 
+/// From Trait Implementation for Operation Descriptors
+impl From<CloseFuture> for Operation {
+    fn from(f: CloseFuture) -> Self {
+        Operation::Close(FutureResult::new(f, None))
+    }
+}
+
 /// From Trait Implementation for Operation Descriptors
 impl From<PushFuture> for Operation {
     fn from(f: PushFuture) -> Self {
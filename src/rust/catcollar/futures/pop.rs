@@ -18,7 +18,8 @@ use crate::{
 };
 use ::std::{
     future::Future,
-    net::SocketAddrV4,
+    net::SocketAddr,
+    os::unix::prelude::RawFd,
     pin::Pin,
     task::{
         Context,
@@ -36,8 +37,14 @@ pub struct PopFuture {
     rt: IoUringRuntime,
     /// Associated queue descriptor.
     qd: QDesc,
+    /// Underlying file descriptor.
+    fd: RawFd,
     /// Associated receive buffer.
     buf: DemiBuffer,
+    /// Number of bytes received so far.
+    len: usize,
+    /// Minimum number of bytes to pop.
+    min_size: usize,
     /// Associated request.
     request_id: RequestId,
 }
@@ -48,12 +55,23 @@ pub struct PopFuture {
 
 /// Associate Functions for Pop Operation Descriptors
 impl PopFuture {
-    /// Creates a descriptor for a pop operation.
-    pub fn new(rt: IoUringRuntime, request_id: RequestId, qd: QDesc, buf: DemiBuffer) -> Self {
+    /// Creates a descriptor for a pop operation. The operation does not complete until at least `min_size` bytes are
+    /// received in `buf`, unless the end of the stream is reached.
+    pub fn new(
+        rt: IoUringRuntime,
+        request_id: RequestId,
+        qd: QDesc,
+        fd: RawFd,
+        buf: DemiBuffer,
+        min_size: usize,
+    ) -> Self {
         Self {
             rt,
             qd,
+            fd,
             buf,
+            len: 0,
+            min_size,
             request_id,
         }
     }
@@ -62,6 +80,13 @@ impl PopFuture {
     pub fn get_qd(&self) -> QDesc {
         self.qd
     }
+
+    /// Cancels the underlying request of the target pop operation descriptor.
+    pub fn cancel(&mut self) {
+        if let Err(e) = self.rt.cancel(self.request_id) {
+            warn!("failed to cancel pop ({:?})", e);
+        }
+    }
 }
 
 //==============================================================================
@@ -70,20 +95,31 @@ impl PopFuture {
 
 /// Future Trait Implementation for Pop Operation Descriptors
 impl Future for PopFuture {
-    type Output = Result<(Option<SocketAddrV4>, DemiBuffer), Fail>;
+    type Output = Result<(Option<SocketAddr>, DemiBuffer), Fail>;
 
     /// Polls the underlying pop operation.
     fn poll(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
         let self_: &mut PopFuture = self.get_mut();
         match self_.rt.peek(self_.request_id) {
             // Operation completed.
-            Ok((addr, Some(size))) if size >= 0 => {
-                trace!("data received ({:?} bytes)", size);
-                let trim_size: usize = self_.buf.len() - (size as usize);
+            Ok((addr, Some(size))) if size == 0 || self_.len + (size as usize) >= self_.min_size => {
+                self_.len += size as usize;
+                trace!("data received ({:?} bytes)", self_.len);
+                let trim_size: usize = self_.buf.len() - self_.len;
                 let mut buf: DemiBuffer = self_.buf.clone();
                 buf.trim(trim_size);
                 Poll::Ready(Ok((addr, buf)))
             },
+            // Not enough data yet, so receive the remaining bytes.
+            Ok((_, Some(size))) if size > 0 => {
+                self_.len += size as usize;
+                trace!("data received ({:?}/{:?} bytes)", self_.len, self_.min_size);
+                let mut rest: DemiBuffer = self_.buf.clone();
+                rest.adjust(self_.len)?;
+                self_.request_id = self_.rt.pop(self_.fd, rest)?;
+                ctx.waker().wake_by_ref();
+                Poll::Pending
+            },
             // Operation in progress, re-schedule future.
             Ok((_, None)) => {
                 trace!("pop in progress");
@@ -53,6 +53,13 @@ impl PushFuture {
     pub fn get_qd(&self) -> QDesc {
         self.qd
     }
+
+    /// Cancels the underlying request of the target push operation descriptor.
+    pub fn cancel(&mut self) {
+        if let Err(e) = self.rt.cancel(self.request_id) {
+            warn!("failed to cancel push ({:?})", e);
+        }
+    }
 }
 
 //==============================================================================
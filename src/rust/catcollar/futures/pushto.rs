@@ -53,6 +53,13 @@ impl PushtoFuture {
     pub fn get_qd(&self) -> QDesc {
         self.qd
     }
+
+    /// Cancels the underlying request of the target pushto operation descriptor.
+    pub fn cancel(&mut self) {
+        if let Err(e) = self.rt.cancel(self.request_id) {
+            warn!("failed to cancel pushto ({:?})", e);
+        }
+    }
 }
 
 //==============================================================================
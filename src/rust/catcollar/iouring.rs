@@ -14,7 +14,6 @@ use ::libc::socklen_t;
 use ::nix::{
     errno,
     sys::socket::{
-        SockaddrIn,
         SockaddrLike,
         SockaddrStorage,
     },
@@ -22,7 +21,7 @@ use ::nix::{
 use ::std::{
     ffi::{
         c_void,
-        CString,
+        CStr,
     },
     mem::MaybeUninit,
     os::{
@@ -36,6 +35,14 @@ use ::std::{
     rc::Rc,
 };
 
+//==============================================================================
+// Constants
+//==============================================================================
+
+/// Tag in the user data of cancel requests. Message headers are word-aligned, thus the lowest bit of their address is
+/// always clear.
+pub const CANCEL_TAG: usize = 1;
+
 //==============================================================================
 // Structures
 //==============================================================================
@@ -44,6 +51,8 @@ use ::std::{
 pub struct IoUring {
     /// Underlying io_uring.
     io_uring: liburing::io_uring,
+    /// Event file descriptor that is signaled whenever an operation completes.
+    eventfd: RawFd,
 }
 
 //==============================================================================
@@ -60,22 +69,42 @@ impl IoUring {
             // Failed to initialize io_uring structure.
             if ret < 0 {
                 let errno: i32 = -ret;
-                let strerror: CString = CString::from_raw(libc::strerror(errno));
+                let strerror: &CStr = CStr::from_ptr(libc::strerror(errno));
                 let cause: &str = strerror.to_str().unwrap_or("failed to initialize io_uring");
                 return Err(Fail::new(errno, cause));
             }
+            let mut io_uring: liburing::io_uring = io_uring.assume_init();
 
-            Ok(Self {
-                io_uring: io_uring.assume_init(),
-            })
+            // Get notified of completions through an event file descriptor.
+            let eventfd: RawFd = libc::eventfd(0, libc::EFD_NONBLOCK | libc::EFD_CLOEXEC);
+            if eventfd == -1 {
+                return Err(Fail::new(errno::errno(), "failed to create eventfd"));
+            }
+            let ret: c_int = liburing::io_uring_register_eventfd(&mut io_uring, eventfd);
+            if ret < 0 {
+                libc::close(eventfd);
+                return Err(Fail::new(-ret, "failed to register eventfd"));
+            }
+
+            Ok(Self { io_uring, eventfd })
         }
     }
 
+    /// Returns the event file descriptor that is signaled whenever an operation completes in the target IO user ring.
+    pub fn eventfd(&self) -> RawFd {
+        self.eventfd
+    }
+
     /// Pushes a buffer to the target IO user ring.
-    pub fn push(&mut self, sockfd: RawFd, buf: DemiBuffer) -> Result<*const liburing::msghdr, Fail> {
-        let len: usize = buf.len();
-        let data: &[u8] = &buf[..];
-        let data_ptr: *const u8 = data.as_ptr();
+    pub fn push(&mut self, sockfd: RawFd, buf: &DemiBuffer) -> Result<*const liburing::msghdr, Fail> {
+        // Gather all segments of the buffer chain, so that they are sent at once without copying.
+        let mut iov: Vec<liburing::iovec> = buf
+            .segments()
+            .map(|data| liburing::iovec {
+                iov_base: data.as_ptr() as *mut c_void,
+                iov_len: data.len() as u64,
+            })
+            .collect();
         let io_uring: &mut liburing::io_uring = &mut self.io_uring;
 
         unsafe {
@@ -83,22 +112,18 @@ impl IoUring {
             let sqe: *mut liburing::io_uring_sqe = liburing::io_uring_get_sqe(io_uring);
             if sqe.is_null() {
                 let errno: i32 = errno::errno();
-                let strerror: CString = CString::from_raw(libc::strerror(errno));
+                let strerror: &CStr = CStr::from_ptr(libc::strerror(errno));
                 let cause: &str = strerror.to_str().unwrap_or("failed to get sqe");
                 return Err(Fail::new(errno, cause));
             }
 
             // Submit operation.
-            let mut iov: Box<liburing::iovec> = Box::new(liburing::iovec {
-                iov_base: data_ptr as *mut c_void,
-                iov_len: len as u64,
-            });
-            let iov_ptr: *mut liburing::iovec = iov.as_mut() as *mut liburing::iovec;
+            let iov_ptr: *mut liburing::iovec = iov.as_mut_ptr();
             let msg: Rc<liburing::msghdr> = Rc::new(liburing::msghdr {
                 msg_name: ptr::null_mut() as *mut _,
                 msg_namelen: 0,
                 msg_iov: iov_ptr,
-                msg_iovlen: 1,
+                msg_iovlen: iov.len() as _,
                 msg_control: ptr::null_mut() as *mut _,
                 msg_controllen: 0,
                 msg_flags: 0,
@@ -119,17 +144,20 @@ impl IoUring {
         &mut self,
         sockfd: RawFd,
         addr: SockaddrStorage,
-        buf: DemiBuffer,
+        buf: &DemiBuffer,
     ) -> Result<*const liburing::msghdr, Fail> {
-        let len: usize = buf.len();
-        let data: &[u8] = &buf[..];
-        let data_ptr: *const u8 = data.as_ptr();
-        let saddr: &SockaddrIn = match addr.as_sockaddr_in() {
-            Some(addr) => addr,
-            None => return Err(Fail::new(libc::EINVAL, "invalid socket address")),
-        };
-        let (sockaddr, addrlen): (&libc::sockaddr_in, socklen_t) = (saddr.as_ref(), saddr.len());
-        let sockaddr_ptr: *const libc::sockaddr_in = sockaddr as *const libc::sockaddr_in;
+        // Gather all segments of the buffer chain, so that they are sent at once without copying.
+        let mut iov: Vec<liburing::iovec> = buf
+            .segments()
+            .map(|data| liburing::iovec {
+                iov_base: data.as_ptr() as *mut c_void,
+                iov_len: data.len() as u64,
+            })
+            .collect();
+        if addr.as_sockaddr_in().is_none() && addr.as_sockaddr_in6().is_none() {
+            return Err(Fail::new(libc::EINVAL, "invalid socket address"));
+        }
+        let (sockaddr_ptr, addrlen): (*const libc::sockaddr, socklen_t) = (addr.as_ptr(), addr.len());
         let io_uring: &mut liburing::io_uring = &mut self.io_uring;
 
         unsafe {
@@ -137,22 +165,18 @@ impl IoUring {
             let sqe: *mut liburing::io_uring_sqe = liburing::io_uring_get_sqe(io_uring);
             if sqe.is_null() {
                 let errno: i32 = errno::errno();
-                let strerror: CString = CString::from_raw(libc::strerror(errno));
+                let strerror: &CStr = CStr::from_ptr(libc::strerror(errno));
                 let cause: &str = strerror.to_str().unwrap_or("failed to get sqe");
                 return Err(Fail::new(errno, cause));
             }
 
             // Submit operation.
-            let mut iov: Box<liburing::iovec> = Box::new(liburing::iovec {
-                iov_base: data_ptr as *mut c_void,
-                iov_len: len as u64,
-            });
-            let iov_ptr: *mut liburing::iovec = iov.as_mut() as *mut liburing::iovec;
+            let iov_ptr: *mut liburing::iovec = iov.as_mut_ptr();
             let msg: Rc<liburing::msghdr> = Rc::new(liburing::msghdr {
                 msg_name: sockaddr_ptr as *mut c_void,
                 msg_namelen: addrlen as u32,
                 msg_iov: iov_ptr,
-                msg_iovlen: 1,
+                msg_iovlen: iov.len() as _,
                 msg_control: ptr::null_mut() as *mut _,
                 msg_controllen: 0,
                 msg_flags: 0,
@@ -169,7 +193,7 @@ impl IoUring {
     }
 
     /// Pops a buffer from the target IO user ring.
-    pub fn pop(&mut self, sockfd: RawFd, buf: DemiBuffer) -> Result<*const liburing::msghdr, Fail> {
+    pub fn pop(&mut self, sockfd: RawFd, buf: &DemiBuffer) -> Result<*const liburing::msghdr, Fail> {
         let len: usize = buf.len();
         let data: &[u8] = &buf[..];
         let data_ptr: *const u8 = data.as_ptr();
@@ -180,7 +204,7 @@ impl IoUring {
             let sqe: *mut liburing::io_uring_sqe = liburing::io_uring_get_sqe(io_uring);
             if sqe.is_null() {
                 let errno: i32 = errno::errno();
-                let strerror: CString = CString::from_raw(libc::strerror(errno));
+                let strerror: &CStr = CStr::from_ptr(libc::strerror(errno));
                 let cause: &str = strerror.to_str().unwrap_or("failed to get sqe");
                 return Err(Fail::new(errno, cause));
             }
@@ -211,6 +235,32 @@ impl IoUring {
         }
     }
 
+    /// Cancels a pending operation in the target IO user ring.
+    pub fn cancel(&mut self, msg_ptr: *const liburing::msghdr) -> Result<(), Fail> {
+        let io_uring: &mut liburing::io_uring = &mut self.io_uring;
+
+        unsafe {
+            // Allocate a submission queue entry.
+            let sqe: *mut liburing::io_uring_sqe = liburing::io_uring_get_sqe(io_uring);
+            if sqe.is_null() {
+                let errno: i32 = errno::errno();
+                let strerror: &CStr = CStr::from_ptr(libc::strerror(errno));
+                let cause: &str = strerror.to_str().unwrap_or("failed to get sqe");
+                return Err(Fail::new(errno, cause));
+            }
+
+            // Submit operation. The completion of the cancel request is told apart from the one of the cancelled request
+            // by tagging its user data.
+            liburing::io_uring_prep_cancel(sqe, msg_ptr as *mut c_void, 0);
+            liburing::io_uring_sqe_set_data(sqe, (msg_ptr as usize | CANCEL_TAG) as *mut c_void);
+            if liburing::io_uring_submit(io_uring) < 1 {
+                return Err(Fail::new(libc::EAGAIN, "failed to submit cancel operation"));
+            }
+
+            Ok(())
+        }
+    }
+
     /// Waits for an operation to complete in the target IO user ring.
     pub fn wait(&mut self) -> Result<(*mut liburing::msghdr, i32), Fail> {
         let io_uring: &mut liburing::io_uring = &mut self.io_uring;
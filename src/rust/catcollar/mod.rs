@@ -18,6 +18,7 @@ pub use self::runtime::IoUringRuntime;
 use self::{
     futures::{
         accept::AcceptFuture,
+        close::CloseFuture,
         connect::ConnectFuture,
         pop::PopFuture,
         push::PushFuture,
@@ -27,8 +28,18 @@ use self::{
     runtime::RequestId,
 };
 use crate::{
-    demikernel::config::Config,
+    demikernel::{
+        config::Config,
+        libos::backend::LibOSBackend,
+    },
     inetstack::operations::OperationResult,
+    pal::{
+        functions::create_sock_addr_storage,
+        linux::{
+            self,
+            epoll::Epoll,
+        },
+    },
     runtime::{
         fail::Fail,
         memory::{
@@ -41,7 +52,13 @@ use crate::{
             demi_opcode_t,
             demi_qr_value_t,
             demi_qresult_t,
+            demi_qstats_t,
             demi_sgarray_t,
+            DEMI_POLLACCEPT,
+            DEMI_POLLHUP,
+            DEMI_POLLIN,
+            DEMI_POLLOUT,
+            DEMI_QSTATS_TCP,
         },
         QDesc,
         QToken,
@@ -49,11 +66,18 @@ use crate::{
     },
     scheduler::SchedulerHandle,
 };
-use ::libc::c_int;
+use ::libc::{
+    c_int,
+    SHUT_RD,
+    SHUT_RDWR,
+    SHUT_WR,
+};
 use ::nix::{
+    errno::Errno,
     sys::socket::{
         self,
         AddressFamily,
+        Shutdown,
         SockFlag,
         SockProtocol,
         SockType,
@@ -65,11 +89,10 @@ use ::std::{
     any::Any,
     collections::HashMap,
     mem,
-    net::{
-        Ipv4Addr,
-        SocketAddrV4,
-    },
+    net::SocketAddr,
     os::unix::prelude::RawFd,
+    rc::Rc,
+    time::Duration,
 };
 
 //======================================================================================================================
@@ -91,6 +114,9 @@ pub struct CatcollarLibOS {
     sockets: HashMap<QDesc, RawFd>,
     /// Underlying runtime.
     runtime: IoUringRuntime,
+    /// Epoll instance where all sockets and the completion notifications of the runtime are registered, so as to block
+    /// until some of them have changed state.
+    epoll: Epoll,
 }
 
 //======================================================================================================================
@@ -104,10 +130,15 @@ impl CatcollarLibOS {
         let qtable: IoQueueTable = IoQueueTable::new();
         let sockets: HashMap<QDesc, RawFd> = HashMap::new();
         let runtime: IoUringRuntime = IoUringRuntime::new();
+        let epoll: Epoll = Epoll::new().expect("cannot create epoll instance");
+        epoll
+            .add(runtime.eventfd())
+            .expect("cannot register io_uring for notifications");
         Self {
             qtable,
             sockets,
             runtime,
+            epoll,
         }
     }
 
@@ -121,6 +152,7 @@ impl CatcollarLibOS {
         // Parse communication domain.
         let domain: AddressFamily = match domain {
             libc::AF_INET => AddressFamily::Inet,
+            libc::AF_INET6 => AddressFamily::Inet6,
             _ => return Err(Fail::new(libc::ENOTSUP, "communication domain not supported")),
         };
 
@@ -147,6 +179,10 @@ impl CatcollarLibOS {
                     warn!("cannot set SO_REUSEPORT option");
                 }
                 let qd: QDesc = self.qtable.alloc(qtype.into());
+                // Register socket for notifications. If we fail, keep going because waits are bounded anyway.
+                if let Err(e) = self.epoll.add(fd) {
+                    warn!("cannot register socket for notifications: {:?}", e);
+                }
                 assert_eq!(self.sockets.insert(qd, fd).is_none(), true);
                 Ok(qd)
             },
@@ -155,7 +191,7 @@ impl CatcollarLibOS {
     }
 
     /// Binds a socket to a local endpoint.
-    pub fn bind(&mut self, qd: QDesc, local: SocketAddrV4) -> Result<(), Fail> {
+    pub fn bind(&mut self, qd: QDesc, local: SocketAddr) -> Result<(), Fail> {
         trace!("bind() qd={:?}, local={:?}", qd, local);
 
         // Issue bind operation.
@@ -206,7 +242,7 @@ impl CatcollarLibOS {
     }
 
     /// Establishes a connection to a remote endpoint.
-    pub fn connect(&mut self, qd: QDesc, remote: SocketAddrV4) -> Result<QToken, Fail> {
+    pub fn connect(&mut self, qd: QDesc, remote: SocketAddr) -> Result<QToken, Fail> {
         trace!("connect() qd={:?}, remote={:?}", qd, remote);
 
         // Issue connect operation.
@@ -236,13 +272,169 @@ impl CatcollarLibOS {
         }
     }
 
+    /// Asynchronously closes a socket. The operation completes once data that was pushed to the socket is acknowledged
+    /// by the remote peer.
+    pub fn async_close(&mut self, qd: QDesc) -> Result<QToken, Fail> {
+        trace!("async_close() qd={:?}", qd);
+
+        // Issue close operation.
+        match self.sockets.remove(&qd) {
+            Some(fd) => {
+                let future: Operation = Operation::from(CloseFuture::new(qd, fd));
+                let handle: SchedulerHandle = match self.runtime.scheduler.insert(future) {
+                    Some(handle) => handle,
+                    None => return Err(Fail::new(libc::EAGAIN, "cannot schedule co-routine")),
+                };
+                Ok(handle.into_raw().into())
+            },
+            _ => Err(Fail::new(libc::EBADF, "invalid queue descriptor")),
+        }
+    }
+
+    /// Shuts down the sending half, the receiving half, or both halves of a socket.
+    pub fn shutdown(&mut self, qd: QDesc, how: c_int) -> Result<(), Fail> {
+        trace!("shutdown() qd={:?}, how={:?}", qd, how);
+
+        // Parse shutdown direction.
+        let how: Shutdown = match how {
+            SHUT_RD => Shutdown::Read,
+            SHUT_WR => Shutdown::Write,
+            SHUT_RDWR => Shutdown::Both,
+            _ => return Err(Fail::new(libc::EINVAL, "invalid value for how")),
+        };
+
+        match self.sockets.get(&qd) {
+            Some(&fd) => match socket::shutdown(fd, how) {
+                Ok(()) => Ok(()),
+                Err(e) => Err(Fail::new(e as i32, "failed to shut down socket")),
+            },
+            _ => Err(Fail::new(libc::EBADF, "invalid queue descriptor")),
+        }
+    }
+
+    /// Gets the statistics of a socket. Those of TCP sockets are queried from the kernel, which does not expose the
+    /// send window.
+    pub fn get_stats(&mut self, qd: QDesc) -> Result<demi_qstats_t, Fail> {
+        trace!("get_stats() qd={:?}", qd);
+        match self.sockets.get(&qd) {
+            Some(&fd) => {
+                let mut stats: demi_qstats_t = demi_qstats_t::default();
+                if let Some(info) = linux::get_tcp_info(fd) {
+                    stats.qs_flags |= DEMI_QSTATS_TCP;
+                    stats.qs_tcp_cwnd = info.tcpi_snd_cwnd as u64 * info.tcpi_snd_mss as u64;
+                    stats.qs_tcp_rto_us = info.tcpi_rto as u64;
+                    stats.qs_tcp_retransmissions = info.tcpi_total_retrans as u64;
+                    stats.qs_tcp_recv_window = info.tcpi_rcv_space as u64;
+                }
+                Ok(stats)
+            },
+            _ => Err(Fail::new(libc::EBADF, "invalid queue descriptor")),
+        }
+    }
+
+    /// Checks which operations on a socket would complete right away, as reported by the kernel.
+    pub fn readiness(&mut self, qd: QDesc) -> Result<u32, Fail> {
+        trace!("readiness() qd={:?}", qd);
+        match self.sockets.get(&qd) {
+            Some(&fd) => {
+                let revents: libc::c_short = linux::get_poll_events(fd);
+                let mut events: u32 = 0;
+                if revents & libc::POLLIN != 0 {
+                    events |= if linux::is_listening(fd) {
+                        DEMI_POLLACCEPT
+                    } else {
+                        DEMI_POLLIN
+                    };
+                }
+                if revents & libc::POLLOUT != 0 {
+                    events |= DEMI_POLLOUT;
+                }
+                if revents & (libc::POLLHUP | libc::POLLRDHUP | libc::POLLERR) != 0 {
+                    events |= DEMI_POLLHUP;
+                }
+                Ok(events)
+            },
+            _ => Err(Fail::new(libc::EBADF, "invalid queue descriptor")),
+        }
+    }
+
+    /// Sets an option on a socket.
+    pub fn setsockopt(&mut self, qd: QDesc, level: c_int, optname: c_int, optval: &[u8]) -> Result<(), Fail> {
+        trace!("setsockopt() qd={:?}, level={:?}, optname={:?}", qd, level, optname);
+
+        // Issue setsockopt operation.
+        match self.sockets.get(&qd) {
+            Some(&fd) => {
+                let optval_ptr: *const libc::c_void = optval.as_ptr() as *const libc::c_void;
+                let optlen: libc::socklen_t = optval.len() as libc::socklen_t;
+                match unsafe { libc::setsockopt(fd, level, optname, optval_ptr, optlen) } {
+                    0 => Ok(()),
+                    _ => Err(Fail::new(Errno::last() as i32, "failed to set socket option")),
+                }
+            },
+            _ => Err(Fail::new(libc::EBADF, "invalid queue descriptor")),
+        }
+    }
+
+    /// Gets the value of an option on a socket.
+    pub fn getsockopt(&mut self, qd: QDesc, level: c_int, optname: c_int, optval: &mut [u8]) -> Result<usize, Fail> {
+        trace!("getsockopt() qd={:?}, level={:?}, optname={:?}", qd, level, optname);
+
+        // Issue getsockopt operation.
+        match self.sockets.get(&qd) {
+            Some(&fd) => {
+                let optval_ptr: *mut libc::c_void = optval.as_mut_ptr() as *mut libc::c_void;
+                let mut optlen: libc::socklen_t = optval.len() as libc::socklen_t;
+                match unsafe { libc::getsockopt(fd, level, optname, optval_ptr, &mut optlen) } {
+                    0 => Ok(optlen as usize),
+                    _ => Err(Fail::new(Errno::last() as i32, "failed to get socket option")),
+                }
+            },
+            _ => Err(Fail::new(libc::EBADF, "invalid queue descriptor")),
+        }
+    }
+
+    /// Gets the local address of a socket.
+    pub fn getsockname(&mut self, qd: QDesc) -> Result<SocketAddr, Fail> {
+        trace!("getsockname() qd={:?}", qd);
+
+        // Issue getsockname operation.
+        match self.sockets.get(&qd) {
+            Some(&fd) => match socket::getsockname::<SockaddrStorage>(fd) {
+                Ok(addr) => match linux::sockaddr_to_socketaddr(&addr) {
+                    Some(addr) => Ok(addr),
+                    None => Err(Fail::new(libc::EAFNOSUPPORT, "communication domain not supported")),
+                },
+                Err(err) => Err(Fail::new(err as i32, "failed to get socket address")),
+            },
+            _ => Err(Fail::new(libc::EBADF, "invalid queue descriptor")),
+        }
+    }
+
+    /// Gets the remote address of a connected socket.
+    pub fn getpeername(&mut self, qd: QDesc) -> Result<SocketAddr, Fail> {
+        trace!("getpeername() qd={:?}", qd);
+
+        // Issue getpeername operation.
+        match self.sockets.get(&qd) {
+            Some(&fd) => match socket::getpeername::<SockaddrStorage>(fd) {
+                Ok(addr) => match linux::sockaddr_to_socketaddr(&addr) {
+                    Some(addr) => Ok(addr),
+                    None => Err(Fail::new(libc::EAFNOSUPPORT, "communication domain not supported")),
+                },
+                Err(err) => Err(Fail::new(err as i32, "failed to get peer address")),
+            },
+            _ => Err(Fail::new(libc::EBADF, "invalid queue descriptor")),
+        }
+    }
+
     /// Pushes a scatter-gather array to a socket.
     pub fn push(&mut self, qd: QDesc, sga: &demi_sgarray_t) -> Result<QToken, Fail> {
         trace!("push() qd={:?}", qd);
 
         let buf: DemiBuffer = self.runtime.clone_sgarray(sga)?;
 
-        if buf.len() == 0 {
+        if buf.total_len() == 0 {
             return Err(Fail::new(libc::EINVAL, "zero-length buffer"));
         }
 
@@ -264,12 +456,12 @@ impl CatcollarLibOS {
     }
 
     /// Pushes a scatter-gather array to a socket.
-    pub fn pushto(&mut self, qd: QDesc, sga: &demi_sgarray_t, remote: SocketAddrV4) -> Result<QToken, Fail> {
+    pub fn pushto(&mut self, qd: QDesc, sga: &demi_sgarray_t, remote: SocketAddr) -> Result<QToken, Fail> {
         trace!("pushto() qd={:?}", qd);
 
         match self.runtime.clone_sgarray(sga) {
             Ok(buf) => {
-                if buf.len() == 0 {
+                if buf.total_len() == 0 {
                     return Err(Fail::new(libc::EINVAL, "zero-length buffer"));
                 }
 
@@ -295,17 +487,24 @@ impl CatcollarLibOS {
         }
     }
 
-    /// Pops data from a socket.
-    pub fn pop(&mut self, qd: QDesc) -> Result<QToken, Fail> {
-        trace!("pop() qd={:?}", qd);
+    /// Pops data from a socket, within size bounds (see [PopFuture::new]).
+    pub fn pop(&mut self, qd: QDesc, min_size: usize, max_size: Option<usize>) -> Result<QToken, Fail> {
+        trace!("pop() qd={:?}, min_size={:?}, max_size={:?}", qd, min_size, max_size);
 
-        let buf: DemiBuffer = DemiBuffer::new(CATCOLLAR_RECVBUF_SIZE);
+        let size: u16 = max_size.map_or(CATCOLLAR_RECVBUF_SIZE, |max_size| max_size as u16);
+        let buf: DemiBuffer = DemiBuffer::new(size);
 
         // Issue pop operation.
         match self.sockets.get(&qd) {
             Some(&fd) => {
+                // Minimum sizes only apply to stream sockets.
+                let min_size: usize = match socket::getsockopt(fd, socket::sockopt::SockType) {
+                    Ok(SockType::Stream) => min_size,
+                    _ => 0,
+                };
                 let request_id: RequestId = self.runtime.pop(fd, buf.clone())?;
-                let future: Operation = Operation::from(PopFuture::new(self.runtime.clone(), request_id, qd, buf));
+                let future: Operation =
+                    Operation::from(PopFuture::new(self.runtime.clone(), request_id, qd, fd, buf, min_size));
                 let handle: SchedulerHandle = match self.runtime.scheduler.insert(future) {
                     Some(handle) => handle,
                     None => return Err(Fail::new(libc::EAGAIN, "cannot schedule co-routine")),
@@ -321,6 +520,15 @@ impl CatcollarLibOS {
         self.runtime.scheduler.poll()
     }
 
+    /// Blocks until some socket has changed state or some operation has completed in the runtime, or a timeout
+    /// expires.
+    pub fn wait_for_events(&self, timeout: Duration) {
+        trace!("wait_for_events() timeout={:?}", timeout);
+        if let Err(e) = self.epoll.wait(timeout) {
+            warn!("wait_for_events(): {:?}", e);
+        }
+    }
+
     pub fn schedule(&mut self, qt: QToken) -> Result<SchedulerHandle, Fail> {
         match self.runtime.scheduler.from_raw_handle(qt.into()) {
             Some(handle) => Ok(handle),
@@ -328,8 +536,29 @@ impl CatcollarLibOS {
         }
     }
 
-    pub fn pack_result(&mut self, handle: SchedulerHandle, qt: QToken) -> Result<demi_qresult_t, Fail> {
-        let (qd, r): (QDesc, OperationResult) = self.take_result(handle);
+    /// Returns the queue tokens of all operations that have completed, but whose results were not taken out yet.
+    pub fn completed(&self) -> Vec<QToken> {
+        self.runtime
+            .scheduler
+            .completed()
+            .into_iter()
+            .map(QToken::from)
+            .collect()
+    }
+
+    pub fn pack_result(&mut self, handle: SchedulerHandle, qt: QToken) -> Result<(demi_qresult_t, Option<Fail>), Fail> {
+        let operation: Operation = self.take_operation(handle);
+        let (qd, r): (QDesc, OperationResult) = self.take_result(operation);
+        Ok(pack_result(&self.runtime, r, qd, qt.into()))
+    }
+
+    /// Cancels the operation associated with the target [SchedulerHandle]. If the operation has already completed, its
+    /// result is packed as is. Otherwise, it completes with `ECANCELED`.
+    pub fn cancel(&mut self, handle: SchedulerHandle, qt: QToken) -> Result<(demi_qresult_t, Option<Fail>), Fail> {
+        trace!("cancel() qt={:?}", qt);
+        let mut operation: Operation = self.take_operation(handle);
+        operation.cancel();
+        let (qd, r): (QDesc, OperationResult) = self.take_result(operation);
         Ok(pack_result(&self.runtime, r, qd, qt.into()))
     }
 
@@ -345,29 +574,36 @@ impl CatcollarLibOS {
         self.runtime.free_sgarray(sga)
     }
 
-    #[deprecated]
-    pub fn local_ipv4_addr(&self) -> Ipv4Addr {
-        todo!()
-    }
-
-    #[deprecated]
-    pub fn rt(&self) -> &IoUringRuntime {
-        &self.runtime
+    /// Appends the segments of a scatter-gather array to another one, and releases the former.
+    pub fn sgaappend(&self, sga: &mut demi_sgarray_t, tail: demi_sgarray_t) -> Result<(), Fail> {
+        trace!("sgaappend()");
+        self.runtime.append_sgarray(sga, tail)
     }
 
-    /// Takes out the operation result descriptor associated with the target scheduler handle.
-    fn take_result(&mut self, handle: SchedulerHandle) -> (QDesc, OperationResult) {
+    /// Takes out the [Operation] associated with the target [SchedulerHandle].
+    fn take_operation(&mut self, handle: SchedulerHandle) -> Operation {
         let boxed_future: Box<dyn Any> = self.runtime.scheduler.take(handle).as_any();
-        let boxed_concrete_type: Operation = *boxed_future.downcast::<Operation>().expect("Wrong type!");
+        *boxed_future.downcast::<Operation>().expect("Wrong type!")
+    }
 
-        let (qd, new_qd, new_fd, qr): (QDesc, Option<QDesc>, Option<RawFd>, OperationResult) =
-            boxed_concrete_type.get_result();
+    /// Takes out the [OperationResult] associated with the target [Operation].
+    fn take_result(&mut self, operation: Operation) -> (QDesc, OperationResult) {
+        let closing: bool = matches!(operation, Operation::Close(..));
+        let (qd, new_qd, new_fd, qr): (QDesc, Option<QDesc>, Option<RawFd>, OperationResult) = operation.get_result();
         trace!("qd={:?}, new_qd={:?}, new_fd={:?}", qd, new_qd, new_fd,);
 
+        // Release queue descriptor of closed socket.
+        if closing {
+            self.qtable.free(qd);
+        }
+
         // Handle accept operation.
         if let Some(new_qd) = new_qd {
             // Associate raw file descriptor with queue descriptor.
             if let Some(new_fd) = new_fd {
+                if let Err(e) = self.epoll.add(new_fd) {
+                    warn!("cannot register socket for notifications: {:?}", e);
+                }
                 assert_eq!(self.sockets.insert(new_qd, new_fd).is_none(), true);
             }
             // Release entry in queue table.
@@ -380,39 +616,171 @@ impl CatcollarLibOS {
     }
 }
 
+//======================================================================================================================
+// Trait Implementations
+//======================================================================================================================
+
+/// LibOS Backend Trait Implementation for Catcollar LibOS
+impl LibOSBackend for CatcollarLibOS {
+    fn socket(&mut self, domain: libc::c_int, socket_type: libc::c_int, protocol: libc::c_int) -> Result<QDesc, Fail> {
+        CatcollarLibOS::socket(self, domain, socket_type, protocol)
+    }
+
+    fn bind(&mut self, sockqd: QDesc, local: SocketAddr) -> Result<(), Fail> {
+        CatcollarLibOS::bind(self, sockqd, local)
+    }
+
+    fn listen(&mut self, sockqd: QDesc, backlog: usize) -> Result<(), Fail> {
+        CatcollarLibOS::listen(self, sockqd, backlog)
+    }
+
+    fn accept(&mut self, sockqd: QDesc) -> Result<QToken, Fail> {
+        CatcollarLibOS::accept(self, sockqd)
+    }
+
+    fn connect(&mut self, sockqd: QDesc, remote: SocketAddr) -> Result<QToken, Fail> {
+        CatcollarLibOS::connect(self, sockqd, remote)
+    }
+
+    fn setsockopt(
+        &mut self,
+        sockqd: QDesc,
+        level: libc::c_int,
+        optname: libc::c_int,
+        optval: &[u8],
+    ) -> Result<(), Fail> {
+        CatcollarLibOS::setsockopt(self, sockqd, level, optname, optval)
+    }
+
+    fn getsockopt(
+        &mut self,
+        sockqd: QDesc,
+        level: libc::c_int,
+        optname: libc::c_int,
+        optval: &mut [u8],
+    ) -> Result<usize, Fail> {
+        CatcollarLibOS::getsockopt(self, sockqd, level, optname, optval)
+    }
+
+    fn getsockname(&mut self, sockqd: QDesc) -> Result<SocketAddr, Fail> {
+        CatcollarLibOS::getsockname(self, sockqd)
+    }
+
+    fn getpeername(&mut self, sockqd: QDesc) -> Result<SocketAddr, Fail> {
+        CatcollarLibOS::getpeername(self, sockqd)
+    }
+
+    fn pushto(&mut self, sockqd: QDesc, sga: &demi_sgarray_t, to: SocketAddr) -> Result<QToken, Fail> {
+        CatcollarLibOS::pushto(self, sockqd, sga, to)
+    }
+
+    fn shutdown(&mut self, sockqd: QDesc, how: libc::c_int) -> Result<(), Fail> {
+        CatcollarLibOS::shutdown(self, sockqd, how)
+    }
+
+    fn close(&mut self, qd: QDesc) -> Result<(), Fail> {
+        CatcollarLibOS::close(self, qd)
+    }
+
+    fn async_close(&mut self, qd: QDesc) -> Result<QToken, Fail> {
+        CatcollarLibOS::async_close(self, qd)
+    }
+
+    fn get_stats(&mut self, qd: QDesc) -> Result<demi_qstats_t, Fail> {
+        CatcollarLibOS::get_stats(self, qd)
+    }
+
+    fn readiness(&mut self, qd: QDesc) -> Result<u32, Fail> {
+        CatcollarLibOS::readiness(self, qd)
+    }
+
+    fn push(&mut self, qd: QDesc, sga: &demi_sgarray_t) -> Result<QToken, Fail> {
+        CatcollarLibOS::push(self, qd, sga)
+    }
+
+    fn pop(&mut self, qd: QDesc, min_size: usize, max_size: Option<usize>) -> Result<QToken, Fail> {
+        CatcollarLibOS::pop(self, qd, min_size, max_size)
+    }
+
+    fn poll(&mut self) {
+        CatcollarLibOS::poll(self)
+    }
+
+    fn wait_for_events(&mut self, timeout: Duration) {
+        CatcollarLibOS::wait_for_events(self, timeout)
+    }
+
+    fn schedule(&mut self, qt: QToken) -> Result<SchedulerHandle, Fail> {
+        CatcollarLibOS::schedule(self, qt)
+    }
+
+    fn completed(&self) -> Vec<QToken> {
+        CatcollarLibOS::completed(self)
+    }
+
+    fn pack_result(&mut self, handle: SchedulerHandle, qt: QToken) -> Result<(demi_qresult_t, Option<Fail>), Fail> {
+        CatcollarLibOS::pack_result(self, handle, qt)
+    }
+
+    fn cancel(&mut self, handle: SchedulerHandle, qt: QToken) -> Result<(demi_qresult_t, Option<Fail>), Fail> {
+        CatcollarLibOS::cancel(self, handle, qt)
+    }
+
+    fn sgaalloc(&self, size: usize) -> Result<demi_sgarray_t, Fail> {
+        CatcollarLibOS::sgaalloc(self, size)
+    }
+
+    fn sgafree(&self, sga: demi_sgarray_t) -> Result<(), Fail> {
+        CatcollarLibOS::sgafree(self, sga)
+    }
+
+    fn sgaappend(&self, sga: &mut demi_sgarray_t, tail: demi_sgarray_t) -> Result<(), Fail> {
+        CatcollarLibOS::sgaappend(self, sga, tail)
+    }
+
+    fn memory(&self) -> Rc<dyn MemoryRuntime> {
+        Rc::new(self.runtime.clone())
+    }
+}
+
 //======================================================================================================================
 // Standalone Functions
 //======================================================================================================================
 
-/// Parses a [SocketAddrV4] into a [SockaddrStorage].
-fn parse_addr(endpoint: SocketAddrV4) -> SockaddrStorage {
-    let addr: &Ipv4Addr = endpoint.ip();
-    let port: u16 = endpoint.port();
-    let ipv4: SocketAddrV4 = SocketAddrV4::new(*addr, port);
-    SockaddrStorage::from(ipv4)
+/// Parses a [SocketAddr] into a [SockaddrStorage].
+fn parse_addr(endpoint: SocketAddr) -> SockaddrStorage {
+    SockaddrStorage::from(endpoint)
 }
 
 /// Packs a [OperationResult] into a [demi_qresult_t].
-fn pack_result(rt: &IoUringRuntime, result: OperationResult, qd: QDesc, qt: u64) -> demi_qresult_t {
-    match result {
+fn pack_result(rt: &IoUringRuntime, result: OperationResult, qd: QDesc, qt: u64) -> (demi_qresult_t, Option<Fail>) {
+    let qr: demi_qresult_t = match result {
         OperationResult::Connect => demi_qresult_t {
             qr_opcode: demi_opcode_t::DEMI_OPC_CONNECT,
             qr_qd: qd.into(),
             qr_qt: qt,
+            qr_ret: 0,
             qr_value: unsafe { mem::zeroed() },
         },
-        OperationResult::Accept(new_qd) => {
-            let sin = unsafe { mem::zeroed() };
+        OperationResult::Close => demi_qresult_t {
+            qr_opcode: demi_opcode_t::DEMI_OPC_CLOSE,
+            qr_qd: qd.into(),
+            qr_qt: qt,
+            qr_ret: 0,
+            qr_value: unsafe { mem::zeroed() },
+        },
+        OperationResult::Accept((new_qd, addr)) => {
             let qr_value = demi_qr_value_t {
                 ares: demi_accept_result_t {
                     qd: new_qd.into(),
-                    addr: sin,
+                    addr: create_sock_addr_storage(&addr).0,
                 },
             };
             demi_qresult_t {
                 qr_opcode: demi_opcode_t::DEMI_OPC_ACCEPT,
                 qr_qd: qd.into(),
                 qr_qt: qt,
+                qr_ret: 0,
                 qr_value,
             }
         },
@@ -420,50 +788,53 @@ fn pack_result(rt: &IoUringRuntime, result: OperationResult, qd: QDesc, qt: u64)
             qr_opcode: demi_opcode_t::DEMI_OPC_PUSH,
             qr_qd: qd.into(),
             qr_qt: qt,
+            qr_ret: 0,
             qr_value: unsafe { mem::zeroed() },
         },
         OperationResult::Pop(addr, bytes) => match rt.into_sgarray(bytes) {
             Ok(mut sga) => {
                 if let Some(endpoint) = addr {
-                    let saddr: libc::sockaddr_in = {
-                        // TODO: check the following byte order conversion.
-                        libc::sockaddr_in {
-                            sin_family: libc::AF_INET as u16,
-                            sin_port: endpoint.port().into(),
-                            sin_addr: libc::in_addr {
-                                s_addr: u32::from_le_bytes(endpoint.ip().octets()),
-                            },
-                            sin_zero: [0; 8],
-                        }
-                    };
-                    sga.sga_addr = unsafe { mem::transmute::<libc::sockaddr_in, libc::sockaddr>(saddr) };
+                    sga.sga_addr = create_sock_addr_storage(&endpoint).0;
                 }
                 let qr_value: demi_qr_value_t = demi_qr_value_t { sga };
                 demi_qresult_t {
                     qr_opcode: demi_opcode_t::DEMI_OPC_POP,
                     qr_qd: qd.into(),
                     qr_qt: qt,
+                    qr_ret: 0,
                     qr_value,
                 }
             },
             Err(e) => {
                 warn!("Operation Failed: {:?}", e);
+                return (
+                    demi_qresult_t {
+                        qr_opcode: demi_opcode_t::DEMI_OPC_FAILED,
+                        qr_qd: qd.into(),
+                        qr_qt: qt,
+                        qr_ret: e.errno,
+                        qr_value: unsafe { mem::zeroed() },
+                    },
+                    Some(e),
+                );
+            },
+        },
+        OperationResult::AcceptUnix(..) | OperationResult::PopUnix(..) => {
+            unreachable!("unix domain sockets are not supported")
+        },
+        OperationResult::Failed(e) => {
+            warn!("Operation Failed: {:?}", e);
+            return (
                 demi_qresult_t {
                     qr_opcode: demi_opcode_t::DEMI_OPC_FAILED,
                     qr_qd: qd.into(),
                     qr_qt: qt,
+                    qr_ret: e.errno,
                     qr_value: unsafe { mem::zeroed() },
-                }
-            },
-        },
-        OperationResult::Failed(e) => {
-            warn!("Operation Failed: {:?}", e);
-            demi_qresult_t {
-                qr_opcode: demi_opcode_t::DEMI_OPC_FAILED,
-                qr_qd: qd.into(),
-                qr_qt: qt,
-                qr_value: unsafe { mem::zeroed() },
-            }
+                },
+                Some(e),
+            );
         },
-    }
+    };
+    (qr, None)
 }
@@ -8,8 +8,12 @@ mod network;
 // Imports
 //==============================================================================
 
-use super::iouring::IoUring;
+use super::iouring::{
+    IoUring,
+    CANCEL_TAG,
+};
 use crate::{
+    pal::linux,
     runtime::{
         fail::Fail,
         liburing,
@@ -18,18 +22,17 @@ use crate::{
     },
     scheduler::scheduler::Scheduler,
 };
-use ::nix::sys::socket::SockaddrStorage;
+use ::nix::sys::socket::{
+    SockaddrLike,
+    SockaddrStorage,
+};
 use ::std::{
     cell::RefCell,
     collections::{
+        hash_map::Entry,
         HashMap,
-        HashSet,
-    },
-    mem,
-    net::{
-        Ipv4Addr,
-        SocketAddrV4,
     },
+    net::SocketAddr,
     os::unix::prelude::RawFd,
     rc::Rc,
 };
@@ -56,10 +59,13 @@ pub struct IoUringRuntime {
     pub scheduler: Scheduler,
     /// Underlying io_uring.
     io_uring: Rc<RefCell<IoUring>>,
-    /// Pending requests.
-    pending: HashSet<RequestId>,
+    /// Pending requests, along with the buffers that they operate on.
+    pending: Rc<RefCell<HashMap<RequestId, DemiBuffer>>>,
     /// Completed requests.
-    completed: HashMap<RequestId, i32>,
+    completed: Rc<RefCell<HashMap<RequestId, i32>>>,
+    /// Cancelled requests, along with the buffers that they operate on and the number of completions that are yet to be
+    /// reaped for them. The kernel may access these until the cancelled request and the cancel request both complete.
+    cancelled: Rc<RefCell<HashMap<RequestId, (DemiBuffer, usize)>>>,
 }
 
 //==============================================================================
@@ -74,97 +80,120 @@ impl IoUringRuntime {
         Self {
             scheduler: Scheduler::default(),
             io_uring: Rc::new(RefCell::new(io_uring)),
-            pending: HashSet::new(),
-            completed: HashMap::new(),
+            pending: Rc::new(RefCell::new(HashMap::new())),
+            completed: Rc::new(RefCell::new(HashMap::new())),
+            cancelled: Rc::new(RefCell::new(HashMap::new())),
         }
     }
 
+    /// Returns the event file descriptor that is signaled whenever an operation completes in the target I/O user ring.
+    pub fn eventfd(&self) -> RawFd {
+        self.io_uring.borrow().eventfd()
+    }
+
     /// Pushes a buffer to the target I/O user ring.
     pub fn push(&mut self, sockfd: RawFd, buf: DemiBuffer) -> Result<RequestId, Fail> {
-        let msg_ptr: *const liburing::msghdr = self.io_uring.borrow_mut().push(sockfd, buf)?;
+        let msg_ptr: *const liburing::msghdr = self.io_uring.borrow_mut().push(sockfd, &buf)?;
         let request_id: RequestId = RequestId(msg_ptr);
-        self.pending.insert(request_id);
+        self.pending.borrow_mut().insert(request_id, buf);
         Ok(request_id)
     }
 
     /// Pushes a buffer to the target I/O user ring.
     pub fn pushto(&mut self, sockfd: i32, addr: SockaddrStorage, buf: DemiBuffer) -> Result<RequestId, Fail> {
-        let msg_ptr: *const liburing::msghdr = self.io_uring.borrow_mut().pushto(sockfd, addr, buf)?;
+        let msg_ptr: *const liburing::msghdr = self.io_uring.borrow_mut().pushto(sockfd, addr, &buf)?;
         let request_id: RequestId = RequestId(msg_ptr);
-        self.pending.insert(request_id);
+        self.pending.borrow_mut().insert(request_id, buf);
         Ok(request_id)
     }
 
     /// Pops a buffer from the target I/O user ring.
     pub fn pop(&mut self, sockfd: RawFd, buf: DemiBuffer) -> Result<RequestId, Fail> {
-        let msg_ptr: *const liburing::msghdr = self.io_uring.borrow_mut().pop(sockfd, buf)?;
+        let msg_ptr: *const liburing::msghdr = self.io_uring.borrow_mut().pop(sockfd, &buf)?;
         let request_id: RequestId = RequestId(msg_ptr);
-        self.pending.insert(request_id);
+        self.pending.borrow_mut().insert(request_id, buf);
         Ok(request_id)
     }
 
+    /// Cancels a pending operation in the target I/O user ring.
+    pub fn cancel(&mut self, request_id: RequestId) -> Result<(), Fail> {
+        // The request may have completed already, but its result was not consumed yet.
+        if self.completed.borrow_mut().remove(&request_id).is_some() {
+            drop(unsafe { Rc::from_raw(request_id.0) });
+            return Ok(());
+        }
+        let buf: DemiBuffer = match self.pending.borrow_mut().remove(&request_id) {
+            Some(buf) => buf,
+            None => return Err(Fail::new(libc::EINVAL, "invalid request")),
+        };
+
+        // Keep the request alive until the kernel is done with it. The cancel request only completes as well if it was
+        // submitted.
+        let result: Result<(), Fail> = self.io_uring.borrow_mut().cancel(request_id.0);
+        let ncompletions: usize = if result.is_ok() { 2 } else { 1 };
+        self.cancelled.borrow_mut().insert(request_id, (buf, ncompletions));
+        result
+    }
+
     /// Peeks for the completion of an operation in the target I/O user ring.
-    pub fn peek(&mut self, request_id: RequestId) -> Result<(Option<SocketAddrV4>, Option<i32>), Fail> {
-        // Check if pending request has completed.
-        match self.completed.remove(&request_id) {
-            // The target request has already completed.
-            Some(size) => {
-                let msg: Rc<liburing::msghdr> = unsafe { Rc::from_raw(request_id.0) };
-                let addr: Option<SocketAddrV4> = if msg.msg_name.is_null() {
-                    None
-                } else {
-                    let saddr: *const libc::sockaddr = msg.msg_name as *const libc::sockaddr;
-                    let sin: libc::sockaddr_in =
-                        unsafe { *mem::transmute::<*const libc::sockaddr, *const libc::sockaddr_in>(saddr) };
-                    let addr: Ipv4Addr = Ipv4Addr::from(u32::from_be(sin.sin_addr.s_addr));
-                    let port: u16 = u16::from_be(sin.sin_port);
-                    Some(SocketAddrV4::new(addr, port))
-                };
-
-                // Done.
-                Ok((addr, Some(size)))
-            },
-            // The target request may not be completed.
-            None => {
-                // Peek the underlying io_uring.
-                match self.io_uring.borrow_mut().wait() {
-                    // Some operation has completed.
-                    Ok((other_request_id, size)) => {
-                        // This is not the request that we are waiting for.
-                        if request_id.0 != other_request_id {
-                            let other_request_id: RequestId = RequestId(other_request_id);
-                            match self.pending.remove(&other_request_id) {
-                                true => self.completed.insert(other_request_id, size),
-                                false => None,
-                            };
-                            return Ok((None, None));
-                        }
-                        let msg: Rc<liburing::msghdr> = unsafe { Rc::from_raw(request_id.0) };
-                        let addr: Option<SocketAddrV4> = if msg.msg_name.is_null() {
-                            None
-                        } else {
-                            let saddr: *const libc::sockaddr = msg.msg_name as *const libc::sockaddr;
-                            let sin: libc::sockaddr_in =
-                                unsafe { *mem::transmute::<*const libc::sockaddr, *const libc::sockaddr_in>(saddr) };
-                            let addr: Ipv4Addr = Ipv4Addr::from(u32::from_be(sin.sin_addr.s_addr));
-                            let port: u16 = u16::from_be(sin.sin_port);
-                            Some(SocketAddrV4::new(addr, port))
-                        };
-
-                        // Done.
-                        Ok((addr, Some(size)))
-                    },
-                    // Something bad has happened.
-                    Err(e) => {
-                        match e.errno {
-                            // Operation in progress.
-                            libc::EAGAIN => Ok((None, None)),
-                            // Operation failed.
-                            _ => Err(e),
-                        }
-                    },
-                }
-            },
+    pub fn peek(&mut self, request_id: RequestId) -> Result<(Option<SocketAddr>, Option<i32>), Fail> {
+        // The target request has already completed.
+        if let Some(size) = self.completed.borrow_mut().remove(&request_id) {
+            let msg: Rc<liburing::msghdr> = unsafe { Rc::from_raw(request_id.0) };
+            let addr: Option<SocketAddr> = parse_msg_name(&msg);
+
+            // Done.
+            return Ok((addr, Some(size)));
+        }
+
+        // Peek the underlying io_uring.
+        let (msg_ptr, size): (*mut liburing::msghdr, i32) = match self.io_uring.borrow_mut().wait() {
+            Ok(completion) => completion,
+            // Operation in progress.
+            Err(e) if e.errno == libc::EAGAIN => return Ok((None, None)),
+            // Operation failed.
+            Err(e) => return Err(e),
+        };
+
+        // Some cancel request has completed.
+        if msg_ptr as usize & CANCEL_TAG != 0 {
+            let cancelled_request_id: RequestId =
+                RequestId((msg_ptr as usize & !CANCEL_TAG) as *const liburing::msghdr);
+            self.reap_cancelled(cancelled_request_id);
+            return Ok((None, None));
+        }
+
+        // Some operation has completed.
+        let other_request_id: RequestId = RequestId(msg_ptr);
+        if self.cancelled.borrow().contains_key(&other_request_id) {
+            self.reap_cancelled(other_request_id);
+            return Ok((None, None));
+        }
+        if self.pending.borrow_mut().remove(&other_request_id).is_none() {
+            warn!("completion of unknown request ({:?})", other_request_id);
+            return Ok((None, None));
+        }
+
+        // This is not the request that we are waiting for.
+        if other_request_id != request_id {
+            self.completed.borrow_mut().insert(other_request_id, size);
+            return Ok((None, None));
+        }
+        let msg: Rc<liburing::msghdr> = unsafe { Rc::from_raw(request_id.0) };
+        let addr: Option<SocketAddr> = parse_msg_name(&msg);
+
+        // Done.
+        Ok((addr, Some(size)))
+    }
+
+    /// Reaps a completion of a cancelled request, and releases the request once all of its completions are reaped.
+    fn reap_cancelled(&mut self, request_id: RequestId) {
+        if let Entry::Occupied(mut entry) = self.cancelled.borrow_mut().entry(request_id) {
+            entry.get_mut().1 -= 1;
+            if entry.get().1 == 0 {
+                entry.remove();
+                drop(unsafe { Rc::from_raw(request_id.0) });
+            }
         }
     }
 }
@@ -175,3 +204,17 @@ impl IoUringRuntime {
 
 /// Runtime Trait Implementation for I/O User Ring Runtime
 impl Runtime for IoUringRuntime {}
+
+//==============================================================================
+// Standalone Functions
+//==============================================================================
+
+/// Parses the socket address that is attached to a message, if any.
+fn parse_msg_name(msg: &liburing::msghdr) -> Option<SocketAddr> {
+    if msg.msg_name.is_null() {
+        return None;
+    }
+    let saddr: *const libc::sockaddr = msg.msg_name as *const libc::sockaddr;
+    let addr: SockaddrStorage = unsafe { SockaddrStorage::from_raw(saddr, Some(msg.msg_namelen)) }?;
+    linux::sockaddr_to_socketaddr(&addr)
+}
@@ -0,0 +1,69 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//======================================================================================================================
+// Imports
+//======================================================================================================================
+
+use crate::{
+    catmem::SharedRingBuffer,
+    runtime::fail::Fail,
+    QDesc,
+};
+use ::std::{
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{
+        Context,
+        Poll,
+    },
+};
+
+//======================================================================================================================
+// Structures
+//======================================================================================================================
+
+/// Close Operation Descriptor
+pub struct CloseFuture {
+    /// Associated queue descriptor.
+    qd: QDesc,
+    // Underlying shared ring buffer.
+    ring: Option<Rc<SharedRingBuffer<u8>>>,
+}
+
+//======================================================================================================================
+// Associate Functions
+//======================================================================================================================
+
+/// Associate Functions for Close Operation Descriptors
+impl CloseFuture {
+    /// Creates a descriptor for a close operation.
+    pub fn new(qd: QDesc, ring: Rc<SharedRingBuffer<u8>>) -> Self {
+        CloseFuture { qd, ring: Some(ring) }
+    }
+
+    /// Returns the queue descriptor associated to the target [CloseFuture].
+    pub fn get_qd(&self) -> QDesc {
+        self.qd
+    }
+}
+
+//======================================================================================================================
+// Trait Implementations
+//======================================================================================================================
+
+/// Future Trait Implementation for Close Operation Descriptors
+impl Future for CloseFuture {
+    type Output = Result<(), Fail>;
+
+    /// Polls the target [CloseFuture].
+    fn poll(self: Pin<&mut Self>, _ctx: &mut Context<'_>) -> Poll<Self::Output> {
+        let self_: &mut CloseFuture = self.get_mut();
+        // Pending push and pop operations hold their own reference to the shared ring buffer, so they still complete.
+        // The ring buffer is released once they are done.
+        self_.ring.take();
+        trace!("memory queue closed ({:?})", self_.qd);
+        Poll::Ready(Ok(()))
+    }
+}
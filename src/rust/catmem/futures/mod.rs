@@ -5,6 +5,7 @@
 // Exports
 //======================================================================================================================
 
+pub mod close;
 pub mod pop;
 pub mod push;
 
@@ -13,6 +14,7 @@ pub mod push;
 //======================================================================================================================
 
 use self::{
+    close::CloseFuture,
     pop::PopFuture,
     push::PushFuture,
 };
@@ -45,6 +47,7 @@ use ::std::{
 pub enum OperationResult {
     Push,
     Pop(DemiBuffer),
+    Close,
     Failed(Fail),
 }
 
@@ -54,6 +57,8 @@ pub enum Operation {
     Push(FutureResult<PushFuture>),
     /// Pop operation.
     Pop(FutureResult<PopFuture>),
+    /// Close operation.
+    Close(FutureResult<CloseFuture>),
 }
 
 //======================================================================================================================
@@ -84,9 +89,28 @@ impl Operation {
                 future,
                 done: Some(Err(e)),
             }) => (future.get_qd(), OperationResult::Failed(e)),
+
+            // Close operation.
+            Operation::Close(FutureResult {
+                future,
+                done: Some(Ok(())),
+            }) => (future.get_qd(), OperationResult::Close),
+            Operation::Close(FutureResult {
+                future,
+                done: Some(Err(e)),
+            }) => (future.get_qd(), OperationResult::Failed(e)),
             _ => panic!("future not ready"),
         }
     }
+
+    /// Completes the target [Operation] with `ECANCELED`, unless it has already completed.
+    pub fn cancel(&mut self) {
+        match self {
+            Operation::Push(ref mut f) => f.cancel(),
+            Operation::Pop(ref mut f) => f.cancel(),
+            Operation::Close(ref mut f) => f.cancel(),
+        }
+    }
 }
 
 //======================================================================================================================
@@ -113,6 +137,7 @@ impl Future for Operation {
         match self.get_mut() {
             Operation::Push(ref mut f) => Future::poll(Pin::new(f), ctx),
             Operation::Pop(ref mut f) => Future::poll(Pin::new(f), ctx),
+            Operation::Close(ref mut f) => Future::poll(Pin::new(f), ctx),
         }
     }
 }
@@ -130,3 +155,10 @@ impl From<PopFuture> for Operation {
         Operation::Pop(FutureResult::new(f, None))
     }
 }
+
+/// From Trait Implementation for Operation Descriptors
+impl From<CloseFuture> for Operation {
+    fn from(f: CloseFuture) -> Self {
+        Operation::Close(FutureResult::new(f, None))
+    }
+}
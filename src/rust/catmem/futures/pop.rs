@@ -15,6 +15,7 @@ use crate::{
 };
 use ::std::{
     future::Future,
+    mem,
     pin::Pin,
     rc::Rc,
     task::{
@@ -33,6 +34,12 @@ pub struct PopFuture {
     qd: QDesc,
     /// Underlying shared ring buffer.
     ring: Rc<SharedRingBuffer<u8>>,
+    /// Minimum number of bytes to pop.
+    min_size: usize,
+    /// Buffer holding the bytes popped so far.
+    buf: DemiBuffer,
+    /// Number of bytes popped so far.
+    len: usize,
 }
 
 //======================================================================================================================
@@ -44,9 +51,17 @@ impl PopFuture {
     /// Maximum Size for a Pop Operation
     const POP_SIZE_MAX: usize = 9216;
 
-    /// Creates a descriptor for a pop operation.
-    pub fn new(qd: QDesc, ring: Rc<SharedRingBuffer<u8>>) -> Self {
-        PopFuture { qd, ring }
+    /// Creates a descriptor for a pop operation. At most `max_size` bytes are popped, and the operation does not
+    /// complete until at least `min_size` bytes are.
+    pub fn new(qd: QDesc, ring: Rc<SharedRingBuffer<u8>>, min_size: usize, max_size: Option<usize>) -> Self {
+        let max_size: usize = max_size.unwrap_or(Self::POP_SIZE_MAX);
+        PopFuture {
+            qd,
+            ring,
+            min_size,
+            buf: DemiBuffer::new(max_size as u16),
+            len: 0,
+        }
     }
 
     /// Returns the queue descriptor associated to the target [PopFuture].
@@ -66,27 +81,24 @@ impl Future for PopFuture {
     /// Polls the target [PopFuture].
     fn poll(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
         let self_: &mut PopFuture = self.get_mut();
-        let mut buf: DemiBuffer = DemiBuffer::new(Self::POP_SIZE_MAX as u16);
-        let mut index: usize = 0;
-        loop {
+        let max_size: usize = self_.buf.len();
+        while self_.len < max_size {
             match self_.ring.try_dequeue() {
                 Some(x) => {
-                    buf[index] = x;
-                    index += 1;
-                },
-                None => {
-                    if index > 0 {
-                        trace!("data read ({:?}/{:?} bytes)", index, Self::POP_SIZE_MAX);
-                        buf.trim(Self::POP_SIZE_MAX - index)
-                            .expect("cannot trim more bytes than the buffer has");
-                        break;
-                    } else {
-                        ctx.waker().wake_by_ref();
-                        return Poll::Pending;
-                    }
+                    self_.buf[self_.len] = x;
+                    self_.len += 1;
                 },
+                None => break,
             }
         }
+        if self_.len == 0 || self_.len < self_.min_size {
+            ctx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        trace!("data read ({:?}/{:?} bytes)", self_.len, max_size);
+        let mut buf: DemiBuffer = mem::replace(&mut self_.buf, DemiBuffer::new(0));
+        buf.trim(max_size - self_.len)
+            .expect("cannot trim more bytes than the buffer has");
         Poll::Ready(Ok(buf))
     }
 }
@@ -73,7 +73,8 @@ impl Future for PushFuture {
     fn poll(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
         let self_: &mut PushFuture = self.get_mut();
         let mut index: usize = self_.index;
-        for x in &self_.buf[index..] {
+        // Write the data of all segments in the buffer chain, resuming where we left off.
+        for x in self_.buf.segments().flatten().skip(index) {
             match self_.ring.try_enqueue(*x) {
                 Ok(()) => index += 1,
                 Err(_) => {
@@ -83,7 +84,7 @@ impl Future for PushFuture {
                 },
             }
         }
-        trace!("data written ({:?}/{:?} bytes)", index, self_.buf.len());
+        trace!("data written ({:?} bytes)", index);
         Poll::Ready(Ok(()))
     }
 }
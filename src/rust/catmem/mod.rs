@@ -8,6 +8,7 @@ mod futures;
 //======================================================================================================================
 
 use self::futures::{
+    close::CloseFuture,
     pop::PopFuture,
     push::PushFuture,
     Operation,
@@ -15,16 +16,22 @@ use self::futures::{
 };
 use crate::{
     collections::shared_ring::SharedRingBuffer,
+    demikernel::libos::backend::LibOSBackend,
     runtime::{
         fail::Fail,
-        memory::DemiBuffer,
+        memory::{
+            DemiBuffer,
+            MemoryRuntime,
+        },
         queue::IoQueueTable,
         types::{
             demi_opcode_t,
             demi_qr_value_t,
             demi_qresult_t,
+            demi_qstats_t,
             demi_sgarray_t,
-            demi_sgaseg_t,
+            DEMI_POLLIN,
+            DEMI_POLLOUT,
         },
         QDesc,
         QToken,
@@ -39,8 +46,6 @@ use ::std::{
     any::Any,
     collections::HashMap,
     mem,
-    ptr,
-    ptr::NonNull,
     rc::Rc,
 };
 
@@ -59,8 +64,13 @@ pub struct CatmemLibOS {
     qtable: IoQueueTable,
     scheduler: Scheduler,
     rings: HashMap<QDesc, Rc<SharedRingBuffer<u8>>>,
+    runtime: Rc<CatmemRuntime>,
 }
 
+/// Memory runtime of Catmem, which backs scatter-gather arrays with heap-allocated buffers.
+#[derive(Default)]
+pub struct CatmemRuntime {}
+
 //======================================================================================================================
 // Associated Functions
 //======================================================================================================================
@@ -73,6 +83,7 @@ impl CatmemLibOS {
             qtable: IoQueueTable::new(),
             scheduler: Scheduler::default(),
             rings: HashMap::new(),
+            runtime: Rc::new(CatmemRuntime::default()),
         }
     }
 
@@ -111,13 +122,61 @@ impl CatmemLibOS {
         }
     }
 
+    /// Gets the statistics of a memory queue, which has no protocol-specific ones.
+    pub fn get_stats(&mut self, qd: QDesc) -> Result<demi_qstats_t, Fail> {
+        trace!("get_stats() qd={:?}", qd);
+        match self.rings.get(&qd) {
+            Some(_) => Ok(demi_qstats_t::default()),
+            None => Err(Fail::new(libc::EBADF, "invalid queue descriptor")),
+        }
+    }
+
+    /// Checks if data may be popped from or pushed to a memory queue, from the occupancy of its ring buffer.
+    pub fn readiness(&mut self, qd: QDesc) -> Result<u32, Fail> {
+        trace!("readiness() qd={:?}", qd);
+        match self.rings.get(&qd) {
+            Some(ring) => {
+                let mut events: u32 = 0;
+                if !ring.is_empty() {
+                    events |= DEMI_POLLIN;
+                }
+                if !ring.is_full() {
+                    events |= DEMI_POLLOUT;
+                }
+                Ok(events)
+            },
+            None => Err(Fail::new(libc::EBADF, "invalid queue descriptor")),
+        }
+    }
+
+    /// Asynchronously closes a memory queue. The queue descriptor is released when the result of the operation is
+    /// taken out.
+    pub fn async_close(&mut self, qd: QDesc) -> Result<QToken, Fail> {
+        trace!("async_close() qd={:?}", qd);
+
+        // Issue close operation.
+        match self.rings.remove(&qd) {
+            Some(ring) => {
+                let future: Operation = Operation::from(CloseFuture::new(qd, ring));
+                let handle: SchedulerHandle = match self.scheduler.insert(future) {
+                    Some(handle) => handle,
+                    None => return Err(Fail::new(libc::EAGAIN, "cannot schedule co-routine")),
+                };
+                let qt: QToken = handle.into_raw().into();
+                trace!("async_close() qt={:?}", qt);
+                Ok(qt)
+            },
+            None => Err(Fail::new(libc::EBADF, "invalid queue descriptor")),
+        }
+    }
+
     /// Pushes a scatter-gather array to a socket.
     pub fn push(&mut self, qd: QDesc, sga: &demi_sgarray_t) -> Result<QToken, Fail> {
         trace!("push() qd={:?}", qd);
 
-        match self.clone_sgarray(sga) {
+        match self.runtime.clone_sgarray(sga) {
             Ok(buf) => {
-                if buf.len() == 0 {
+                if buf.total_len() == 0 {
                     return Err(Fail::new(libc::EINVAL, "zero-length buffer"));
                 }
 
@@ -140,14 +199,14 @@ impl CatmemLibOS {
         }
     }
 
-    /// Pops data from a socket.
-    pub fn pop(&mut self, qd: QDesc) -> Result<QToken, Fail> {
-        trace!("pop() qd={:?}", qd);
+    /// Pops data from a socket, within size bounds (see [PopFuture::new]).
+    pub fn pop(&mut self, qd: QDesc, min_size: usize, max_size: Option<usize>) -> Result<QToken, Fail> {
+        trace!("pop() qd={:?}, min_size={:?}, max_size={:?}", qd, min_size, max_size);
 
         // Issue pop operation.
         match self.rings.get(&qd) {
             Some(ring) => {
-                let future: Operation = Operation::from(PopFuture::new(qd, ring.clone()));
+                let future: Operation = Operation::from(PopFuture::new(qd, ring.clone(), min_size, max_size));
                 let handle: SchedulerHandle = match self.scheduler.insert(future) {
                     Some(handle) => handle,
                     None => return Err(Fail::new(libc::EAGAIN, "cannot schedule co-routine")),
@@ -160,8 +219,66 @@ impl CatmemLibOS {
         }
     }
 
+    /// Takes out the [Operation] associated with the target [SchedulerHandle].
+    fn take_operation(&mut self, handle: SchedulerHandle) -> Operation {
+        let boxed_future: Box<dyn Any> = self.scheduler.take(handle).as_any();
+        *boxed_future.downcast::<Operation>().expect("Wrong type!")
+    }
+
+    /// Takes out the [OperationResult] associated with the target [Operation].
+    fn take_result(&mut self, operation: Operation) -> (QDesc, OperationResult) {
+        let closing: bool = matches!(operation, Operation::Close(..));
+        let (qd, r): (QDesc, OperationResult) = operation.get_result();
+
+        // Release queue descriptor of closed memory queue.
+        if closing {
+            self.qtable.free(qd);
+        }
+
+        (qd, r)
+    }
+
+    pub fn schedule(&mut self, qt: QToken) -> Result<SchedulerHandle, Fail> {
+        match self.scheduler.from_raw_handle(qt.into()) {
+            Some(handle) => Ok(handle),
+            None => return Err(Fail::new(libc::EINVAL, "invalid queue token")),
+        }
+    }
+
+    /// Returns the queue tokens of all operations that have completed, but whose results were not taken out yet.
+    pub fn completed(&self) -> Vec<QToken> {
+        self.scheduler.completed().into_iter().map(QToken::from).collect()
+    }
+
+    pub fn pack_result(&mut self, handle: SchedulerHandle, qt: QToken) -> Result<(demi_qresult_t, Option<Fail>), Fail> {
+        let operation: Operation = self.take_operation(handle);
+        let (qd, r): (QDesc, OperationResult) = self.take_result(operation);
+        Ok(pack_result(self, r, qd, qt.into()))
+    }
+
+    /// Cancels the operation associated with the target [SchedulerHandle]. If the operation has already completed, its
+    /// result is packed as is. Otherwise, it completes with `ECANCELED`.
+    pub fn cancel(&mut self, handle: SchedulerHandle, qt: QToken) -> Result<(demi_qresult_t, Option<Fail>), Fail> {
+        trace!("cancel() qt={:?}", qt);
+        let mut operation: Operation = self.take_operation(handle);
+        operation.cancel();
+        let (qd, r): (QDesc, OperationResult) = self.take_result(operation);
+        Ok(pack_result(self, r, qd, qt.into()))
+    }
+
+    pub fn poll(&self) {
+        self.scheduler.poll()
+    }
+}
+
+//======================================================================================================================
+// Trait Implementations
+//======================================================================================================================
+
+/// Memory Runtime Trait Implementation for Catmem Runtime
+impl MemoryRuntime for CatmemRuntime {
     /// Allocates a scatter-gather array.
-    pub fn alloc_sgarray(&self, size: usize) -> Result<demi_sgarray_t, Fail> {
+    fn alloc_sgarray(&self, size: usize) -> Result<demi_sgarray_t, Fail> {
         // ToDo: Allocate an array of buffers if requested size is too large for a single buffer.
 
         // We can't allocate more than a single buffer.
@@ -172,144 +289,79 @@ impl CatmemLibOS {
         // First allocate the underlying (heap-allocated) DemiBuffer.
         let buf: DemiBuffer = DemiBuffer::new(size as u16);
 
-        // Create a scatter-gather segment to expose the DemiBuffer to the user.
-        let data: *const u8 = buf.as_ptr();
-        let sga_seg: demi_sgaseg_t = demi_sgaseg_t {
-            sgaseg_buf: data as *mut libc::c_void,
-            sgaseg_len: size as u32,
-        };
-
         // Create and return a new scatter-gather array (which inherits the DemiBuffer's reference).
-        Ok(demi_sgarray_t {
-            sga_buf: buf.into_raw().as_ptr() as *mut libc::c_void,
-            sga_numsegs: 1,
-            sga_segs: [sga_seg],
-            sga_addr: unsafe { mem::zeroed() },
-        })
-    }
-
-    /// Releases a scatter-gather array.
-    pub fn free_sgarray(&self, sga: demi_sgarray_t) -> Result<(), Fail> {
-        // Check arguments.
-        // TODO: Drop this check once we support scatter-gather arrays with multiple segments.
-        if sga.sga_numsegs != 1 {
-            return Err(Fail::new(libc::EINVAL, "demi_sgarray_t has invalid segment count"));
-        }
+        self.into_sgarray(buf)
+    }
+}
 
-        if sga.sga_buf == ptr::null_mut() {
-            return Err(Fail::new(libc::EINVAL, "demi_sgarray_t has invalid DemiBuffer token"));
-        }
+/// LibOS Backend Trait Implementation for Catmem LibOS
+impl LibOSBackend for CatmemLibOS {
+    fn create_pipe(&mut self, name: &str) -> Result<QDesc, Fail> {
+        CatmemLibOS::create_pipe(self, name)
+    }
 
-        // Convert back to a DemiBuffer and drop it.
-        // Safety: The `NonNull::new_unchecked()` call is safe, as we verified `sga.sga_buf` is not null above.
-        let token: NonNull<u8> = unsafe { NonNull::new_unchecked(sga.sga_buf as *mut u8) };
-        // Safety: The `DemiBuffer::from_raw()` call *should* be safe, as the `sga_buf` field in the `demi_sgarray_t`
-        // contained a valid `DemiBuffer` token when we provided it to the user (and the user shouldn't change it).
-        let buf: DemiBuffer = unsafe { DemiBuffer::from_raw(token) };
-        drop(buf);
+    fn open_pipe(&mut self, name: &str) -> Result<QDesc, Fail> {
+        CatmemLibOS::open_pipe(self, name)
+    }
 
-        Ok(())
+    fn close(&mut self, qd: QDesc) -> Result<(), Fail> {
+        CatmemLibOS::close(self, qd)
     }
 
-    /// Clones a scatter-gather array into a DemiBuffer.
-    fn clone_sgarray(&self, sga: &demi_sgarray_t) -> Result<DemiBuffer, Fail> {
-        // Check arguments.
-        // TODO: Drop this check once we support scatter-gather arrays with multiple segments.
-        if sga.sga_numsegs != 1 {
-            return Err(Fail::new(libc::EINVAL, "demi_sgarray_t has invalid segment count"));
-        }
+    fn async_close(&mut self, qd: QDesc) -> Result<QToken, Fail> {
+        CatmemLibOS::async_close(self, qd)
+    }
 
-        if sga.sga_buf == ptr::null_mut() {
-            return Err(Fail::new(libc::EINVAL, "demi_sgarray_t has invalid DemiBuffer token"));
-        }
+    fn get_stats(&mut self, qd: QDesc) -> Result<demi_qstats_t, Fail> {
+        CatmemLibOS::get_stats(self, qd)
+    }
 
-        // Convert back to a DemiBuffer.
-        // Safety: The `NonNull::new_unchecked()` call is safe, as we verified `sga.sga_buf` is not null above.
-        let token: NonNull<u8> = unsafe { NonNull::new_unchecked(sga.sga_buf as *mut u8) };
-        // Safety: The `DemiBuffer::from_raw()` call *should* be safe, as the `sga_buf` field in the `demi_sgarray_t`
-        // contained a valid `DemiBuffer` token when we provided it to the user (and the user shouldn't change it).
-        let buf: DemiBuffer = unsafe { DemiBuffer::from_raw(token) };
-        let mut clone: DemiBuffer = buf.clone();
-
-        // Don't drop buf, as it holds the same reference to the data as the sgarray (which should keep it).
-        mem::forget(buf);
-
-        // Check to see if the user has reduced the size of the buffer described by the sgarray segment since we
-        // provided it to them.  They could have increased the starting address of the buffer (`sgaseg_buf`),
-        // decreased the ending address of the buffer (`sgaseg_buf + sgaseg_len`), or both.
-        let sga_data: *const u8 = sga.sga_segs[0].sgaseg_buf as *const u8;
-        let sga_len: usize = sga.sga_segs[0].sgaseg_len as usize;
-        let clone_data: *const u8 = clone.as_ptr();
-        let mut clone_len: usize = clone.len();
-        if sga_data != clone_data || sga_len != clone_len {
-            // We need to adjust the DemiBuffer to match the user's changes.
-
-            // First check that the user didn't do something non-sensical, like change the buffer description to
-            // reference address space outside of the allocated memory area.
-            if sga_data < clone_data || sga_data.addr() + sga_len > clone_data.addr() + clone_len {
-                return Err(Fail::new(
-                    libc::EINVAL,
-                    "demi_sgarray_t describes data outside backing buffer's allocated region",
-                ));
-            }
-
-            // Calculate the amount the new starting address is ahead of the old.  And then adjust `clone` to match.
-            let adjustment_amount: usize = sga_data.addr() - clone_data.addr();
-            clone.adjust(adjustment_amount)?;
-
-            // An adjustment above would have reduced clone.len() by the adjustment amount.
-            clone_len -= adjustment_amount;
-            debug_assert_eq!(clone_len, clone.len());
-
-            // Trim the clone down to size.
-            let trim_amount: usize = clone_len - sga_len;
-            clone.trim(trim_amount)?;
-        }
+    fn readiness(&mut self, qd: QDesc) -> Result<u32, Fail> {
+        CatmemLibOS::readiness(self, qd)
+    }
 
-        // Return the clone.
-        Ok(clone)
+    fn push(&mut self, qd: QDesc, sga: &demi_sgarray_t) -> Result<QToken, Fail> {
+        CatmemLibOS::push(self, qd, sga)
     }
 
-    /// Takes out the [OperationResult] associated with the target [SchedulerHandle].
-    fn take_result(&mut self, handle: SchedulerHandle) -> (QDesc, OperationResult) {
-        let boxed_future: Box<dyn Any> = self.scheduler.take(handle).as_any();
-        let boxed_concrete_type: Operation = *boxed_future.downcast::<Operation>().expect("Wrong type!");
+    fn pop(&mut self, qd: QDesc, min_size: usize, max_size: Option<usize>) -> Result<QToken, Fail> {
+        CatmemLibOS::pop(self, qd, min_size, max_size)
+    }
 
-        boxed_concrete_type.get_result()
+    fn poll(&mut self) {
+        CatmemLibOS::poll(self)
     }
 
-    /// Converts a runtime buffer into a scatter-gather array.
-    fn into_sgarray(buf: DemiBuffer) -> Result<demi_sgarray_t, Fail> {
-        // Create a scatter-gather segment to expose the DemiBuffer to the user.
-        let data: *const u8 = buf.as_ptr();
-        let sga_seg: demi_sgaseg_t = demi_sgaseg_t {
-            sgaseg_buf: data as *mut libc::c_void,
-            sgaseg_len: buf.len() as u32,
-        };
+    fn schedule(&mut self, qt: QToken) -> Result<SchedulerHandle, Fail> {
+        CatmemLibOS::schedule(self, qt)
+    }
 
-        // Create and return a new scatter-gather array (which inherits the DemiBuffer's reference).
-        Ok(demi_sgarray_t {
-            sga_buf: buf.into_raw().as_ptr() as *mut libc::c_void,
-            sga_numsegs: 1,
-            sga_segs: [sga_seg],
-            sga_addr: unsafe { mem::zeroed() },
-        })
+    fn completed(&self) -> Vec<QToken> {
+        CatmemLibOS::completed(self)
     }
 
-    pub fn schedule(&mut self, qt: QToken) -> Result<SchedulerHandle, Fail> {
-        match self.scheduler.from_raw_handle(qt.into()) {
-            Some(handle) => Ok(handle),
-            None => return Err(Fail::new(libc::EINVAL, "invalid queue token")),
-        }
+    fn pack_result(&mut self, handle: SchedulerHandle, qt: QToken) -> Result<(demi_qresult_t, Option<Fail>), Fail> {
+        CatmemLibOS::pack_result(self, handle, qt)
     }
 
-    pub fn pack_result(&mut self, handle: SchedulerHandle, qt: QToken) -> Result<demi_qresult_t, Fail> {
-        let (qd, r): (QDesc, OperationResult) = self.take_result(handle);
-        Ok(pack_result(r, qd, qt.into()))
+    fn cancel(&mut self, handle: SchedulerHandle, qt: QToken) -> Result<(demi_qresult_t, Option<Fail>), Fail> {
+        CatmemLibOS::cancel(self, handle, qt)
     }
 
-    pub fn poll(&self) {
-        self.scheduler.poll()
+    fn sgaalloc(&self, size: usize) -> Result<demi_sgarray_t, Fail> {
+        self.runtime.alloc_sgarray(size)
+    }
+
+    fn sgafree(&self, sga: demi_sgarray_t) -> Result<(), Fail> {
+        self.runtime.free_sgarray(sga)
+    }
+
+    fn sgaappend(&self, sga: &mut demi_sgarray_t, tail: demi_sgarray_t) -> Result<(), Fail> {
+        self.runtime.append_sgarray(sga, tail)
+    }
+
+    fn memory(&self) -> Rc<dyn MemoryRuntime> {
+        self.runtime.clone()
     }
 }
 
@@ -318,42 +370,60 @@ impl CatmemLibOS {
 //======================================================================================================================
 
 /// Packs a [OperationResult] into a [demi_qresult_t].
-fn pack_result(result: OperationResult, qd: QDesc, qt: u64) -> demi_qresult_t {
-    match result {
+fn pack_result(libos: &CatmemLibOS, result: OperationResult, qd: QDesc, qt: u64) -> (demi_qresult_t, Option<Fail>) {
+    let qr: demi_qresult_t = match result {
         OperationResult::Push => demi_qresult_t {
             qr_opcode: demi_opcode_t::DEMI_OPC_PUSH,
             qr_qd: qd.into(),
             qr_qt: qt,
+            qr_ret: 0,
+            qr_value: unsafe { mem::zeroed() },
+        },
+        OperationResult::Close => demi_qresult_t {
+            qr_opcode: demi_opcode_t::DEMI_OPC_CLOSE,
+            qr_qd: qd.into(),
+            qr_qt: qt,
+            qr_ret: 0,
             qr_value: unsafe { mem::zeroed() },
         },
-        OperationResult::Pop(bytes) => match CatmemLibOS::into_sgarray(bytes) {
+        OperationResult::Pop(bytes) => match libos.runtime.into_sgarray(bytes) {
             Ok(sga) => {
                 let qr_value: demi_qr_value_t = demi_qr_value_t { sga };
                 demi_qresult_t {
                     qr_opcode: demi_opcode_t::DEMI_OPC_POP,
                     qr_qd: qd.into(),
                     qr_qt: qt,
+                    qr_ret: 0,
                     qr_value,
                 }
             },
             Err(e) => {
                 warn!("Operation Failed: {:?}", e);
+                return (
+                    demi_qresult_t {
+                        qr_opcode: demi_opcode_t::DEMI_OPC_FAILED,
+                        qr_qd: qd.into(),
+                        qr_qt: qt,
+                        qr_ret: e.errno,
+                        qr_value: unsafe { mem::zeroed() },
+                    },
+                    Some(e),
+                );
+            },
+        },
+        OperationResult::Failed(e) => {
+            warn!("Operation Failed: {:?}", e);
+            return (
                 demi_qresult_t {
                     qr_opcode: demi_opcode_t::DEMI_OPC_FAILED,
                     qr_qd: qd.into(),
                     qr_qt: qt,
+                    qr_ret: e.errno,
                     qr_value: unsafe { mem::zeroed() },
-                }
-            },
-        },
-        OperationResult::Failed(e) => {
-            warn!("Operation Failed: {:?}", e);
-            demi_qresult_t {
-                qr_opcode: demi_opcode_t::DEMI_OPC_FAILED,
-                qr_qd: qd.into(),
-                qr_qt: qt,
-                qr_value: unsafe { mem::zeroed() },
-            }
+                },
+                Some(e),
+            );
         },
-    }
+    };
+    (qr, None)
 }
@@ -0,0 +1,494 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//==============================================================================
+// Imports
+//==============================================================================
+
+use crate::{
+    pal::linux,
+    runtime::{
+        fail::Fail,
+        memory::DemiBuffer,
+    },
+};
+use ::nix::sys::socket::SockaddrStorage;
+use ::std::{
+    cell::{
+        RefCell,
+        RefMut,
+    },
+    collections::{
+        HashMap,
+        HashSet,
+        VecDeque,
+    },
+    io::IoSlice,
+    mem,
+    os::unix::prelude::RawFd,
+    rc::{
+        Rc,
+        Weak,
+    },
+};
+
+//==============================================================================
+// Constants
+//==============================================================================
+
+/// Maximum number of datagrams that are sent or received with a single system call.
+const BATCH_SIZE: usize = 16;
+
+/// Size of the buffers where datagrams are received. This fits the largest datagram that a pop operation may return.
+const RECV_BUFFER_SIZE: usize = u16::MAX as usize;
+
+/// Number of datagrams that may be queued on a socket before the runtime stops receiving them on its behalf. Further
+/// datagrams are received once pop operations drain the queue.
+const MAX_INCOMING: usize = 4 * BATCH_SIZE;
+
+//==============================================================================
+// Structures
+//==============================================================================
+
+/// Result of a datagram that is queued to be sent. This is set once the datagram is sent, or once it fails to be.
+pub type SendResult = Rc<RefCell<Option<Result<(), Fail>>>>;
+
+/// Datagram that was received, along with its source address, or the failure to receive it.
+type IncomingDatagram = Result<(Option<SockaddrStorage>, DemiBuffer), Fail>;
+
+/// Datagram Queues
+///
+/// This batches the datagrams that are pushed to each socket, so that they are sent with a single `sendmmsg()` system
+/// call. Datagrams are received in batches with a single `recvmmsg()` system call as well, once the runtime is polled
+/// and finds a watched socket readable, and they are buffered until they are popped.
+#[derive(Clone, Default)]
+pub struct DatagramQueues {
+    inner: Rc<RefCell<Inner>>,
+}
+
+/// Inner state of datagram queues.
+#[derive(Default)]
+struct Inner {
+    /// Datagrams that are queued on each socket.
+    queues: HashMap<RawFd, Queues>,
+    /// Sockets that have datagrams waiting to be sent.
+    pending: HashSet<RawFd>,
+    /// Sockets whose datagrams are received as the runtime is polled.
+    watched: HashSet<RawFd>,
+    /// Buffer where batches of datagrams are received. This is allocated once it is first needed.
+    recv_buffer: Vec<u8>,
+}
+
+/// Datagrams that are queued on a socket.
+#[derive(Default)]
+struct Queues {
+    /// Datagrams that are waiting to be sent.
+    outgoing: VecDeque<OutgoingDatagram>,
+    /// Datagrams that were received, but not popped yet, along with their source address. Datagrams that were too large
+    /// to be received whole, or that failed to be received, are kept as failures.
+    incoming: VecDeque<IncomingDatagram>,
+}
+
+/// Datagram that is waiting to be sent.
+struct OutgoingDatagram {
+    /// Destination address, if any.
+    addr: Option<SockaddrStorage>,
+    /// Payload.
+    buf: DemiBuffer,
+    /// Where the result is stored. The datagram is not sent if the push operation that is waiting for it is gone.
+    result: Weak<RefCell<Option<Result<(), Fail>>>>,
+}
+
+//==============================================================================
+// Associate Functions
+//==============================================================================
+
+/// Associate Functions for Datagram Queues
+impl DatagramQueues {
+    /// Queues a datagram to be sent on a socket. The datagram is sent along with those that are queued on the same
+    /// socket once [DatagramQueues::flush] is called, and its result is stored in the returned [SendResult].
+    pub fn push(&self, fd: RawFd, addr: Option<SockaddrStorage>, buf: DemiBuffer) -> SendResult {
+        let result: SendResult = Rc::new(RefCell::new(None));
+        let mut inner: RefMut<Inner> = self.inner.borrow_mut();
+        inner
+            .queues
+            .entry(fd)
+            .or_default()
+            .outgoing
+            .push_back(OutgoingDatagram {
+                addr,
+                buf,
+                result: Rc::downgrade(&result),
+            });
+        inner.pending.insert(fd);
+        result
+    }
+
+    /// Sends the datagrams that are queued on every socket, in batches. Datagrams that would block are kept queued
+    /// until the next call. The sockets where some datagrams were sent, or failed to be, are returned.
+    pub fn flush(&self) -> Vec<RawFd> {
+        let mut inner: RefMut<Inner> = self.inner.borrow_mut();
+        let Inner { queues, pending, .. } = &mut *inner;
+        let mut flushed: Vec<RawFd> = Vec::new();
+        for fd in mem::take(pending) {
+            if let Some(queues) = queues.get_mut(&fd) {
+                if send_batches(fd, &mut queues.outgoing) {
+                    flushed.push(fd);
+                }
+                if !queues.outgoing.is_empty() {
+                    pending.insert(fd);
+                }
+            }
+        }
+        flushed
+    }
+
+    /// Watches a datagram socket, so that its datagrams are received in batches as the runtime is polled. Sockets stop
+    /// being watched once they are released.
+    pub fn watch(&self, fd: RawFd) {
+        self.inner.borrow_mut().watched.insert(fd);
+    }
+
+    /// Receives the datagrams that are waiting on a watched socket in batches, until it would block or enough of them
+    /// are queued. A failure to receive is queued as well, so that the next pop operation reports it. This tells
+    /// whether something was queued.
+    pub fn receive(&self, fd: RawFd) -> bool {
+        let mut inner: RefMut<Inner> = self.inner.borrow_mut();
+        let Inner {
+            queues,
+            watched,
+            recv_buffer,
+            ..
+        } = &mut *inner;
+        if !watched.contains(&fd) {
+            return false;
+        }
+        let incoming: &mut VecDeque<IncomingDatagram> = &mut queues.entry(fd).or_default().incoming;
+        let mut received: bool = false;
+        while incoming.len() < MAX_INCOMING {
+            match receive_batch(fd, recv_buffer, incoming) {
+                Ok(count) => {
+                    received |= count > 0;
+                    // The socket was drained.
+                    if count < BATCH_SIZE {
+                        break;
+                    }
+                },
+                Err(e) if e.errno == libc::EAGAIN || e.errno == libc::EWOULDBLOCK => break,
+                Err(e) => {
+                    warn!("receive failed ({:?})", e);
+                    incoming.push_back(Err(e));
+                    received = true;
+                    break;
+                },
+            }
+        }
+        received
+    }
+
+    /// Pops a datagram that was received on a socket, along with its source address. If none is queued, a batch of
+    /// datagrams is received first. Nothing is returned if no datagram was received yet, and this fails with `EMSGSIZE`
+    /// if the datagram was too large to be received whole.
+    pub fn pop(&self, fd: RawFd) -> Result<Option<(Option<SockaddrStorage>, DemiBuffer)>, Fail> {
+        let mut inner: RefMut<Inner> = self.inner.borrow_mut();
+        let Inner {
+            queues, recv_buffer, ..
+        } = &mut *inner;
+        let incoming: &mut VecDeque<IncomingDatagram> = &mut queues.entry(fd).or_default().incoming;
+        if let Some(datagram) = incoming.pop_front() {
+            return datagram.map(Some);
+        }
+
+        // Receive a batch of datagrams.
+        match receive_batch(fd, recv_buffer, incoming) {
+            Ok(_) => incoming.pop_front().transpose(),
+            Err(e) if e.errno == libc::EAGAIN || e.errno == libc::EWOULDBLOCK => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Checks if some datagrams that were received on a socket are waiting to be popped.
+    pub fn has_incoming(&self, fd: RawFd) -> bool {
+        self.inner
+            .borrow()
+            .queues
+            .get(&fd)
+            .map_or(false, |queues| !queues.incoming.is_empty())
+    }
+
+    /// Drops the datagrams that are queued on a socket. Those that are waiting to be sent fail with `EBADF`.
+    pub fn release(&self, fd: RawFd) {
+        let mut inner: RefMut<Inner> = self.inner.borrow_mut();
+        inner.pending.remove(&fd);
+        inner.watched.remove(&fd);
+        if let Some(queues) = inner.queues.remove(&fd) {
+            for datagram in queues.outgoing {
+                complete(datagram, Err(Fail::new(libc::EBADF, "socket was closed")));
+            }
+        }
+    }
+}
+
+//==============================================================================
+// Standalone Functions
+//==============================================================================
+
+/// Sends the datagrams that are queued on a socket in batches, until they would block. Each datagram is completed
+/// once it is sent, or once it fails to be, and this tells whether some datagrams were completed.
+fn send_batches(fd: RawFd, outgoing: &mut VecDeque<OutgoingDatagram>) -> bool {
+    // Datagrams of push operations that are gone are not sent.
+    outgoing.retain(|datagram| datagram.result.strong_count() > 0);
+
+    let mut completed: bool = false;
+    while !outgoing.is_empty() {
+        let sent: Result<usize, Fail> = {
+            let segments: Vec<Vec<IoSlice>> = outgoing
+                .iter()
+                .take(BATCH_SIZE)
+                .map(|datagram| datagram.buf.segments().map(IoSlice::new).collect())
+                .collect();
+            let datagrams: Vec<(Option<&SockaddrStorage>, &[IoSlice])> = outgoing
+                .iter()
+                .zip(segments.iter())
+                .map(|(datagram, segments)| (datagram.addr.as_ref(), segments.as_slice()))
+                .collect();
+            linux::sendmmsg(fd, &datagrams)
+        };
+        match sent {
+            // Some datagrams were sent.
+            Ok(count) if count > 0 => {
+                trace!("datagrams pushed ({:?}/{:?})", count, outgoing.len());
+                for datagram in outgoing.drain(..count) {
+                    complete(datagram, Ok(()));
+                }
+            },
+            // Datagrams would block.
+            Ok(_) => break,
+            Err(e) if e.errno == libc::EAGAIN || e.errno == libc::EWOULDBLOCK => break,
+            // The first datagram failed to be sent.
+            Err(e) => {
+                warn!("push failed ({:?})", e);
+                if let Some(datagram) = outgoing.pop_front() {
+                    complete(datagram, Err(e));
+                }
+            },
+        }
+        completed = true;
+    }
+    completed
+}
+
+/// Receives a batch of datagrams from a socket with a single system call, and queues them. The receive buffer is
+/// allocated once it is first needed. On success, the number of datagrams that were received is returned.
+fn receive_batch(
+    fd: RawFd,
+    recv_buffer: &mut Vec<u8>,
+    incoming: &mut VecDeque<IncomingDatagram>,
+) -> Result<usize, Fail> {
+    if recv_buffer.is_empty() {
+        *recv_buffer = vec![0; BATCH_SIZE * RECV_BUFFER_SIZE];
+    }
+    let mut buffers: Vec<&mut [u8]> = recv_buffer.chunks_mut(RECV_BUFFER_SIZE).collect();
+    let received: Vec<(usize, Option<SockaddrStorage>, bool)> = linux::recvmmsg(fd, &mut buffers)?;
+    let count: usize = received.len();
+    for ((len, addr, truncated), buf) in received.into_iter().zip(buffers.iter()) {
+        if truncated {
+            warn!("datagram too large ({:?} bytes received)", len);
+            incoming.push_back(Err(Fail::new(libc::EMSGSIZE, "datagram too large")));
+            continue;
+        }
+        trace!("datagram received ({:?} bytes)", len);
+        // A datagram that cannot be copied fails on its own, so that the rest of the batch is still popped.
+        incoming.push_back(DemiBuffer::from_slice(&buf[..len]).map(|buf| (addr, buf)));
+    }
+    Ok(count)
+}
+
+/// Stores the result of a datagram that was sent, or that failed to be.
+fn complete(datagram: OutgoingDatagram, result: Result<(), Fail>) {
+    if let Some(slot) = datagram.result.upgrade() {
+        *slot.borrow_mut() = Some(result);
+    }
+}
+
+//==============================================================================
+// Unit Tests
+//==============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        DatagramQueues,
+        SendResult,
+    };
+    use crate::runtime::{
+        fail::Fail,
+        memory::DemiBuffer,
+    };
+    use ::std::os::unix::prelude::RawFd;
+
+    /// Creates a pair of connected, non-blocking Unix domain datagram sockets.
+    fn socketpair() -> [RawFd; 2] {
+        let mut fds: [RawFd; 2] = [-1; 2];
+        assert_eq!(
+            unsafe {
+                libc::socketpair(
+                    libc::AF_UNIX,
+                    libc::SOCK_DGRAM | libc::SOCK_NONBLOCK,
+                    0,
+                    fds.as_mut_ptr(),
+                )
+            },
+            0
+        );
+        fds
+    }
+
+    /// Closes a pair of sockets.
+    fn close([fd1, fd2]: [RawFd; 2]) {
+        unsafe {
+            libc::close(fd1);
+            libc::close(fd2);
+        }
+    }
+
+    /// Queues a datagram holding some bytes to be sent on a socket.
+    fn push(datagrams: &DatagramQueues, fd: RawFd, bytes: &[u8]) -> Result<SendResult, Fail> {
+        Ok(datagrams.push(fd, None, DemiBuffer::from_slice(bytes)?))
+    }
+
+    /// Pops a datagram from a socket, and returns its bytes.
+    fn pop(datagrams: &DatagramQueues, fd: RawFd) -> Result<Option<Vec<u8>>, Fail> {
+        Ok(datagrams.pop(fd)?.map(|(_, buf)| buf.to_vec()))
+    }
+
+    /// Tests if datagrams that are pushed are sent all at once, and if those that are received in a batch are buffered
+    /// until they are popped.
+    #[test]
+    fn datagram_batches() -> Result<(), Fail> {
+        let datagrams: DatagramQueues = DatagramQueues::default();
+        let [fd1, fd2]: [RawFd; 2] = socketpair();
+
+        // Nothing is sent until the queues are flushed.
+        let results: Vec<SendResult> = vec![
+            push(&datagrams, fd1, b"one")?,
+            push(&datagrams, fd1, b"two")?,
+            push(&datagrams, fd1, b"three")?,
+        ];
+        assert_eq!(pop(&datagrams, fd2)?, None);
+        assert_eq!(datagrams.flush(), [fd1]);
+        assert!(results.iter().all(|result| matches!(*result.borrow(), Some(Ok(())))));
+        assert!(datagrams.flush().is_empty());
+
+        // All datagrams are received at once, and popped in order.
+        assert_eq!(pop(&datagrams, fd2)?.as_deref(), Some(&b"one"[..]));
+        assert!(datagrams.has_incoming(fd2));
+        assert_eq!(pop(&datagrams, fd2)?.as_deref(), Some(&b"two"[..]));
+        assert_eq!(pop(&datagrams, fd2)?.as_deref(), Some(&b"three"[..]));
+        assert!(!datagrams.has_incoming(fd2));
+        assert_eq!(pop(&datagrams, fd2)?, None);
+
+        close([fd1, fd2]);
+        Ok(())
+    }
+
+    /// Tests if the datagrams of a watched socket are received in batches on its behalf, and are buffered until they
+    /// are popped.
+    #[test]
+    fn datagram_receive() -> Result<(), Fail> {
+        let datagrams: DatagramQueues = DatagramQueues::default();
+        let [fd1, fd2]: [RawFd; 2] = socketpair();
+        let _results: [SendResult; 2] = [push(&datagrams, fd1, b"one")?, push(&datagrams, fd1, b"two")?];
+        datagrams.flush();
+
+        // Sockets that are not watched are left alone.
+        assert!(!datagrams.receive(fd2));
+        assert!(!datagrams.has_incoming(fd2));
+
+        // Datagrams of watched sockets are all received at once.
+        datagrams.watch(fd2);
+        assert!(datagrams.receive(fd2));
+        assert!(datagrams.has_incoming(fd2));
+        assert!(!datagrams.receive(fd2));
+        assert_eq!(pop(&datagrams, fd2)?.as_deref(), Some(&b"one"[..]));
+        assert_eq!(pop(&datagrams, fd2)?.as_deref(), Some(&b"two"[..]));
+        assert_eq!(pop(&datagrams, fd2)?, None);
+
+        // Watched sockets are forgotten once they are released.
+        datagrams.release(fd2);
+        let _result: SendResult = push(&datagrams, fd1, b"three")?;
+        datagrams.flush();
+        assert!(!datagrams.receive(fd2));
+
+        close([fd1, fd2]);
+        Ok(())
+    }
+
+    /// Tests if datagrams of push operations that are gone are not sent.
+    #[test]
+    fn datagram_cancelled() -> Result<(), Fail> {
+        let datagrams: DatagramQueues = DatagramQueues::default();
+        let [fd1, fd2]: [RawFd; 2] = socketpair();
+
+        let cancelled: SendResult = push(&datagrams, fd1, b"cancelled")?;
+        let result: SendResult = push(&datagrams, fd1, b"sent")?;
+        drop(cancelled);
+        datagrams.flush();
+        assert!(matches!(*result.borrow(), Some(Ok(()))));
+        assert_eq!(pop(&datagrams, fd2)?.as_deref(), Some(&b"sent"[..]));
+        assert_eq!(pop(&datagrams, fd2)?, None);
+
+        close([fd1, fd2]);
+        Ok(())
+    }
+
+    /// Tests if the datagrams that are queued on a socket are dropped once it is released.
+    #[test]
+    fn datagram_release() -> Result<(), Fail> {
+        let datagrams: DatagramQueues = DatagramQueues::default();
+        let [fd1, fd2]: [RawFd; 2] = socketpair();
+
+        // Datagrams that were received are dropped.
+        let _results: [SendResult; 2] = [push(&datagrams, fd1, b"one")?, push(&datagrams, fd1, b"two")?];
+        datagrams.flush();
+        assert_eq!(pop(&datagrams, fd2)?.as_deref(), Some(&b"one"[..]));
+        datagrams.release(fd2);
+        assert!(!datagrams.has_incoming(fd2));
+
+        // Datagrams that were waiting to be sent fail.
+        let result: SendResult = push(&datagrams, fd1, b"three")?;
+        datagrams.release(fd1);
+        assert_eq!(
+            result
+                .borrow()
+                .as_ref()
+                .and_then(|result| result.as_ref().err())
+                .map(|e| e.errno),
+            Some(libc::EBADF)
+        );
+        assert!(datagrams.flush().is_empty());
+        assert_eq!(pop(&datagrams, fd2)?, None);
+
+        close([fd1, fd2]);
+        Ok(())
+    }
+
+    /// Tests if datagrams that are too large to be received whole are reported.
+    #[test]
+    fn datagram_too_large() -> Result<(), Fail> {
+        let datagrams: DatagramQueues = DatagramQueues::default();
+        let [fd1, fd2]: [RawFd; 2] = socketpair();
+
+        // Such datagrams do not fit in a single buffer, so send it directly.
+        let large: Vec<u8> = vec![1; u16::MAX as usize + 1];
+        let sent: isize = unsafe { libc::send(fd1, large.as_ptr() as *const libc::c_void, large.len(), 0) };
+        assert_eq!(sent, large.len() as isize);
+        let _result: SendResult = push(&datagrams, fd1, b"next")?;
+        datagrams.flush();
+        assert_eq!(pop(&datagrams, fd2).err().map(|e| e.errno), Some(libc::EMSGSIZE));
+        assert_eq!(pop(&datagrams, fd2)?.as_deref(), Some(&b"next"[..]));
+
+        close([fd1, fd2]);
+        Ok(())
+    }
+}
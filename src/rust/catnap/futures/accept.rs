@@ -6,6 +6,7 @@
 //==============================================================================
 
 use crate::{
+    catnap::runtime::SocketWaiter,
     pal::linux,
     runtime::{
         fail::Fail,
@@ -14,7 +15,13 @@ use crate::{
 };
 use ::nix::{
     errno::Errno,
-    sys::socket,
+    sys::socket::{
+        self,
+        AddressFamily,
+        SockaddrLike,
+        SockaddrStorage,
+    },
+    unistd,
 };
 use ::std::{
     future::Future,
@@ -38,6 +45,8 @@ pub struct AcceptFuture {
     fd: RawFd,
     /// Queue descriptor of incoming connection.
     new_qd: QDesc,
+    /// Waiter for the underlying socket to have incoming connections.
+    waiter: SocketWaiter,
 }
 
 //==============================================================================
@@ -47,8 +56,8 @@ pub struct AcceptFuture {
 /// Associate Functions for Accept Operation Descriptors
 impl AcceptFuture {
     /// Creates a descriptor for an accept operation.
-    pub fn new(qd: QDesc, fd: RawFd, new_qd: QDesc) -> Self {
-        Self { qd, fd, new_qd }
+    pub fn new(qd: QDesc, fd: RawFd, new_qd: QDesc, waiter: SocketWaiter) -> Self {
+        Self { qd, fd, new_qd, waiter }
     }
 
     /// Returns the queue descriptor associated to the target [AcceptFuture].
@@ -68,34 +77,48 @@ impl AcceptFuture {
 
 /// Future Trait Implementation for Accept Operation Descriptors
 impl Future for AcceptFuture {
-    type Output = Result<RawFd, Fail>;
+    type Output = Result<(RawFd, SockaddrStorage), Fail>;
 
     /// Polls the target [AcceptFuture].
     fn poll(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
-        let self_: &AcceptFuture = self.get_mut();
+        let self_: &mut AcceptFuture = self.get_mut();
         match socket::accept(self_.fd as i32) {
             // Operation completed.
             Ok(new_fd) => {
                 trace!("connection accepted ({:?})", new_fd);
 
-                // Set socket options.
+                // Get address of remote peer.
+                let addr: SockaddrStorage = match socket::getpeername::<SockaddrStorage>(new_fd) {
+                    Ok(addr) => addr,
+                    Err(e) => {
+                        warn!("failed to get peer address ({:?})", e);
+                        if let Err(e) = unistd::close(new_fd) {
+                            warn!("failed to close socket ({:?})", e);
+                        }
+                        return Poll::Ready(Err(Fail::new(e as i32, "operation failed")));
+                    },
+                };
+
+                // Set socket options. Those of TCP do not apply to Unix domain sockets.
                 unsafe {
-                    if linux::set_tcp_nodelay(new_fd) != 0 {
-                        warn!("cannot set TCP_NONDELAY option");
+                    if addr.family() != Some(AddressFamily::Unix) {
+                        if linux::set_tcp_nodelay(new_fd) != 0 {
+                            warn!("cannot set TCP_NONDELAY option");
+                        }
+                        if linux::set_so_reuseport(new_fd) != 0 {
+                            warn!("cannot set SO_REUSEPORT option");
+                        }
                     }
                     if linux::set_nonblock(new_fd) != 0 {
                         warn!("cannot set NONBLOCK option");
                     }
-                    if linux::set_so_reuseport(new_fd) != 0 {
-                        warn!("cannot set SO_REUSEPORT option");
-                    }
                 }
 
-                Poll::Ready(Ok(new_fd))
+                Poll::Ready(Ok((new_fd, addr)))
             },
             // Operation in progress.
             Err(e) if e == Errno::EWOULDBLOCK || e == Errno::EAGAIN => {
-                ctx.waker().wake_by_ref();
+                self_.waiter.wait(ctx.waker());
                 Poll::Pending
             },
             // Operation failed.
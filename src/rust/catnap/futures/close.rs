@@ -0,0 +1,169 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//==============================================================================
+// Imports
+//==============================================================================
+
+use crate::{
+    pal::linux::close::ClosingSocket,
+    runtime::{
+        fail::Fail,
+        QDesc,
+    },
+};
+use ::std::{
+    future::Future,
+    os::unix::prelude::RawFd,
+    pin::Pin,
+    task::{
+        Context,
+        Poll,
+    },
+};
+
+//==============================================================================
+// Structures
+//==============================================================================
+
+/// Close Operation Descriptor
+pub struct CloseFuture {
+    /// Associated queue descriptor.
+    qd: QDesc,
+    /// Underlying socket. Its connection is reset if the operation is dropped before it completes.
+    socket: ClosingSocket,
+}
+
+//==============================================================================
+// Associate Functions
+//==============================================================================
+
+/// Associate Functions for Close Operation Descriptors
+impl CloseFuture {
+    /// Creates a descriptor for a close operation.
+    pub fn new(qd: QDesc, fd: RawFd) -> Self {
+        Self {
+            qd,
+            socket: ClosingSocket::new(fd),
+        }
+    }
+
+    /// Returns the queue descriptor associated to the target [CloseFuture].
+    pub fn get_qd(&self) -> QDesc {
+        self.qd
+    }
+}
+
+//==============================================================================
+// Trait Implementations
+//==============================================================================
+
+/// Future Trait Implementation for Close Operation Descriptors
+impl Future for CloseFuture {
+    type Output = Result<(), Fail>;
+
+    /// Polls the target [CloseFuture]. The underlying socket is closed once it is flushed, or as soon as that fails.
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
+        let self_: &mut CloseFuture = self.get_mut();
+        match self_.socket.flush() {
+            // The kernel does not notify when the send queue empties, so keep polling it.
+            Ok(true) => {
+                ctx.waker().wake_by_ref();
+                Poll::Pending
+            },
+            Ok(false) => {
+                let result: Result<(), Fail> = self_.socket.close(false);
+                if result.is_ok() {
+                    trace!("socket closed ({:?})", self_.qd);
+                }
+                Poll::Ready(result)
+            },
+            Err(e) => {
+                let _ = self_.socket.close(e.errno == libc::ETIMEDOUT);
+                Poll::Ready(Err(e))
+            },
+        }
+    }
+}
+
+//==============================================================================
+// Unit Tests
+//==============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        super::Operation,
+        CloseFuture,
+    };
+    use crate::{
+        inetstack::operations::OperationResult,
+        runtime::QDesc,
+    };
+    use ::futures::task::noop_waker_ref;
+    use ::std::{
+        future::Future,
+        io::{
+            ErrorKind,
+            Read,
+            Write,
+        },
+        net::{
+            TcpListener,
+            TcpStream,
+        },
+        os::unix::prelude::IntoRawFd,
+        pin::Pin,
+        task::{
+            Context,
+            Poll,
+        },
+        time::Duration,
+    };
+
+    /// Tests if cancelling a close operation that is still pending releases the underlying socket.
+    #[test]
+    fn close_cancelled() {
+        let listener: TcpListener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut client: TcpStream = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (mut server, _): (TcpStream, _) = listener.accept().unwrap();
+
+        // Fill the send queue, as the remote peer does not read anything yet.
+        client.set_nonblocking(true).unwrap();
+        let data: [u8; 65536] = [0; 65536];
+        loop {
+            match client.write(&data) {
+                Ok(_) => continue,
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) => panic!("write() failed ({:?})", e),
+            }
+        }
+
+        // The close operation waits for the send queue to be flushed.
+        let qd: QDesc = QDesc::from(0);
+        let mut operation: Operation = Operation::from(CloseFuture::new(qd, client.into_raw_fd()));
+        let mut ctx: Context = Context::from_waker(noop_waker_ref());
+        assert_eq!(Future::poll(Pin::new(&mut operation), &mut ctx), Poll::Pending);
+
+        // Cancel the operation.
+        operation.cancel();
+        match operation.get_result() {
+            (_, _, _, OperationResult::Failed(e)) => assert_eq!(e.errno, libc::ECANCELED),
+            _ => panic!("close operation should have been cancelled"),
+        }
+
+        // The socket was closed, so the remote peer gets the connection reset after the data that it was sent.
+        server.set_read_timeout(Some(Duration::from_secs(10))).unwrap();
+        let mut buf: [u8; 65536] = [0; 65536];
+        loop {
+            match server.read(&mut buf) {
+                Ok(0) => panic!("connection was shut down gracefully"),
+                Ok(_) => continue,
+                Err(e) => {
+                    assert_eq!(e.kind(), ErrorKind::ConnectionReset);
+                    break;
+                },
+            }
+        }
+    }
+}
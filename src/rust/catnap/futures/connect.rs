@@ -5,9 +5,12 @@
 // Imports
 //==============================================================================
 
-use crate::runtime::{
-    fail::Fail,
-    QDesc,
+use crate::{
+    catnap::runtime::SocketWaiter,
+    runtime::{
+        fail::Fail,
+        QDesc,
+    },
 };
 use ::nix::{
     errno::Errno,
@@ -38,6 +41,8 @@ pub struct ConnectFuture {
     fd: RawFd,
     /// Destination address.
     addr: SockaddrStorage,
+    /// Waiter for the underlying socket to be connected.
+    waiter: SocketWaiter,
 }
 
 //==============================================================================
@@ -47,8 +52,8 @@ pub struct ConnectFuture {
 /// Associate Functions for Connect Operation Descriptors
 impl ConnectFuture {
     /// Creates a descriptor for a connect operation.
-    pub fn new(qd: QDesc, fd: RawFd, addr: SockaddrStorage) -> Self {
-        Self { qd, fd, addr }
+    pub fn new(qd: QDesc, fd: RawFd, addr: SockaddrStorage, waiter: SocketWaiter) -> Self {
+        Self { qd, fd, addr, waiter }
     }
 
     /// Returns the queue descriptor associated to the target [ConnectFuture].
@@ -76,7 +81,7 @@ impl Future for ConnectFuture {
             },
             // Operation not ready yet.
             Err(e) if e == Errno::EINPROGRESS || e == Errno::EALREADY => {
-                ctx.waker().wake_by_ref();
+                self_.waiter.wait(ctx.waker());
                 Poll::Pending
             },
             // Operation failed.
@@ -6,6 +6,7 @@
 //==============================================================================
 
 pub mod accept;
+pub mod close;
 pub mod connect;
 pub mod pop;
 pub mod push;
@@ -17,6 +18,7 @@ pub mod pushto;
 
 use self::{
     accept::AcceptFuture,
+    close::CloseFuture,
     connect::ConnectFuture,
     pop::PopFuture,
     push::PushFuture,
@@ -24,15 +26,21 @@ use self::{
 };
 use crate::{
     inetstack::operations::OperationResult,
+    pal::linux,
     runtime::QDesc,
     scheduler::{
         FutureResult,
         SchedulerFuture,
     },
 };
+use ::nix::sys::socket::SockaddrStorage;
 use ::std::{
     any::Any,
     future::Future,
+    net::{
+        Ipv4Addr,
+        SocketAddr,
+    },
     os::unix::prelude::RawFd,
     pin::Pin,
     task::{
@@ -51,6 +59,8 @@ pub enum Operation {
     Accept(FutureResult<AcceptFuture>),
     /// Connection operation
     Connect(FutureResult<ConnectFuture>),
+    /// Close operation.
+    Close(FutureResult<CloseFuture>),
     /// Push operation
     Push(FutureResult<PushFuture>),
     /// Pushto operation.
@@ -71,12 +81,17 @@ impl Operation {
             // Accept operation.
             Operation::Accept(FutureResult {
                 future,
-                done: Some(Ok(new_fd)),
+                done: Some(Ok((new_fd, addr))),
             }) => (
                 future.get_qd(),
                 Some(future.get_new_qd()),
                 Some(new_fd),
-                OperationResult::Accept(future.get_new_qd()),
+                match addr.as_unix_addr() {
+                    Some(unix_addr) => {
+                        OperationResult::AcceptUnix((future.get_new_qd(), linux::unixaddr_to_unixsocketaddr(unix_addr)))
+                    },
+                    None => OperationResult::Accept((future.get_new_qd(), to_socketaddr(&addr))),
+                },
             ),
             Operation::Accept(FutureResult {
                 future,
@@ -98,6 +113,16 @@ impl Operation {
                 done: Some(Err(e)),
             }) => (future.get_qd(), None, None, OperationResult::Failed(e)),
 
+            // Close operation.
+            Operation::Close(FutureResult {
+                future,
+                done: Some(Ok(())),
+            }) => (future.get_qd(), None, None, OperationResult::Close),
+            Operation::Close(FutureResult {
+                future,
+                done: Some(Err(e)),
+            }) => (future.get_qd(), None, None, OperationResult::Failed(e)),
+
             // Push operation.
             Operation::Push(FutureResult {
                 future,
@@ -122,7 +147,15 @@ impl Operation {
             Operation::Pop(FutureResult {
                 future,
                 done: Some(Ok((addr, buf))),
-            }) => (future.get_qd(), None, None, OperationResult::Pop(addr, buf)),
+            }) => (
+                future.get_qd(),
+                None,
+                None,
+                match addr.as_ref().and_then(|addr| addr.as_unix_addr()) {
+                    Some(unix_addr) => OperationResult::PopUnix(linux::unixaddr_to_unixsocketaddr(unix_addr), buf),
+                    None => OperationResult::Pop(addr.as_ref().and_then(linux::sockaddr_to_socketaddr), buf),
+                },
+            ),
             Operation::Pop(FutureResult {
                 future,
                 done: Some(Err(e)),
@@ -131,6 +164,18 @@ impl Operation {
             _ => panic!("future not ready"),
         }
     }
+
+    /// Completes the target [Operation] with `ECANCELED`, unless it has already completed.
+    pub fn cancel(&mut self) {
+        match self {
+            Operation::Accept(ref mut f) => f.cancel(),
+            Operation::Connect(ref mut f) => f.cancel(),
+            Operation::Close(ref mut f) => f.cancel(),
+            Operation::Push(ref mut f) => f.cancel(),
+            Operation::Pushto(ref mut f) => f.cancel(),
+            Operation::Pop(ref mut f) => f.cancel(),
+        }
+    }
 }
 
 //==============================================================================
@@ -157,6 +202,7 @@ impl Future for Operation {
         match self.get_mut() {
             Operation::Accept(ref mut f) => Future::poll(Pin::new(f), ctx),
             Operation::Connect(ref mut f) => Future::poll(Pin::new(f), ctx),
+            Operation::Close(ref mut f) => Future::poll(Pin::new(f), ctx),
             Operation::Push(ref mut f) => Future::poll(Pin::new(f), ctx),
             Operation::Pushto(ref mut f) => Future::poll(Pin::new(f), ctx),
             Operation::Pop(ref mut f) => Future::poll(Pin::new(f), ctx),
@@ -178,6 +224,13 @@ impl From<ConnectFuture> for Operation {
     }
 }
 
+/// From Trait Implementation for Operation Descriptors
+impl From<CloseFuture> for Operation {
+    fn from(f: CloseFuture) -> Self {
+        Operation::Close(FutureResult::new(f, None))
+    }
+}
+
 /// From Trait Implementation for Operation Descriptors
 impl From<PushFuture> for Operation {
     fn from(f: PushFuture) -> Self {
@@ -198,3 +251,13 @@ impl From<PopFuture> for Operation {
         Operation::Pop(FutureResult::new(f, None))
     }
 }
+
+//==============================================================================
+// Standalone Functions
+//==============================================================================
+
+/// Converts the address of a remote peer into a [SocketAddr]. Addresses of Unix domain sockets are handled apart, so
+/// this only fails if the kernel reported an address of another communication domain, which is then unspecified.
+fn to_socketaddr(addr: &SockaddrStorage) -> SocketAddr {
+    linux::sockaddr_to_socketaddr(addr).unwrap_or_else(|| SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)))
+}
@@ -5,10 +5,16 @@
 // Imports
 //==============================================================================
 
-use crate::runtime::{
-    fail::Fail,
-    memory::DemiBuffer,
-    QDesc,
+use crate::{
+    catnap::{
+        datagram::DatagramQueues,
+        runtime::SocketWaiter,
+    },
+    runtime::{
+        fail::Fail,
+        memory::DemiBuffer,
+        QDesc,
+    },
 };
 use ::nix::{
     errno::Errno,
@@ -19,10 +25,6 @@ use ::nix::{
 };
 use ::std::{
     future::Future,
-    net::{
-        Ipv4Addr,
-        SocketAddrV4,
-    },
     os::unix::prelude::RawFd,
     pin::Pin,
     task::{
@@ -48,6 +50,18 @@ pub struct PopFuture {
     qd: QDesc,
     /// Underlying file descriptor.
     fd: RawFd,
+    /// Minimum number of bytes to pop.
+    min_size: usize,
+    /// Maximum number of bytes to pop.
+    max_size: usize,
+    /// Buffer holding the bytes received so far.
+    bytes: Vec<u8>,
+    /// Number of bytes received so far.
+    len: usize,
+    /// Queues where datagrams are received in batches, if the underlying socket is a datagram one.
+    datagrams: Option<DatagramQueues>,
+    /// Waiter for the underlying socket to have data.
+    waiter: SocketWaiter,
 }
 
 //==============================================================================
@@ -56,9 +70,31 @@ pub struct PopFuture {
 
 /// Associate Functions for Pop Operation Descriptors
 impl PopFuture {
-    /// Creates a descriptor for a pop operation.
-    pub fn new(qd: QDesc, fd: RawFd) -> Self {
-        Self { qd, fd }
+    /// Creates a descriptor for a pop operation. At most `max_size` bytes are popped, and the operation does not
+    /// complete until at least `min_size` bytes are, unless the end of the stream is reached. Datagrams are popped from
+    /// `datagrams` if it is set, and `min_size` is ignored.
+    pub fn new(
+        qd: QDesc,
+        fd: RawFd,
+        min_size: usize,
+        max_size: Option<usize>,
+        datagrams: Option<DatagramQueues>,
+        waiter: SocketWaiter,
+    ) -> Self {
+        let max_size: usize = max_size.unwrap_or(POP_SIZE);
+        Self {
+            qd,
+            fd,
+            min_size,
+            max_size,
+            bytes: match datagrams {
+                Some(_) => Vec::new(),
+                None => vec![0; max_size],
+            },
+            len: 0,
+            datagrams,
+            waiter,
+        }
     }
 
     /// Returns the queue descriptor associated to the target [PopFuture].
@@ -73,40 +109,58 @@ impl PopFuture {
 
 /// Future Trait Implementation for Pop Operation Descriptors
 impl Future for PopFuture {
-    type Output = Result<(Option<SocketAddrV4>, DemiBuffer), Fail>;
+    type Output = Result<(Option<SockaddrStorage>, DemiBuffer), Fail>;
 
     /// Polls the target [PopFuture].
     fn poll(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
         let self_: &mut PopFuture = self.get_mut();
-        let mut bytes: [u8; POP_SIZE] = [0; POP_SIZE];
-        match socket::recvfrom::<SockaddrStorage>(self_.fd, &mut bytes[..]) {
-            // Operation completed.
-            Ok((nbytes, socketaddr)) => {
-                trace!("data received ({:?}/{:?} bytes)", nbytes, POP_SIZE);
-                let buf: DemiBuffer = DemiBuffer::from_slice(&bytes[0..nbytes])?;
-                let addr: Option<SocketAddrV4> = match socketaddr {
-                    Some(addr) => match addr.as_sockaddr_in() {
-                        Some(sin) => {
-                            let ip: Ipv4Addr = Ipv4Addr::from(sin.ip());
-                            let port: u16 = sin.port();
-                            Some(SocketAddrV4::new(ip, port))
-                        },
-                        None => None,
-                    },
-                    _ => None,
-                };
-                Poll::Ready(Ok((addr, buf)))
-            },
-            // Operation in progress.
-            Err(e) if e == Errno::EWOULDBLOCK || e == Errno::EAGAIN => {
-                ctx.waker().wake_by_ref();
-                Poll::Pending
-            },
-            // Error.
-            Err(e) => {
-                trace!("pop failed ({:?})", e);
-                Poll::Ready(Err(Fail::new(e as i32, "operation failed")))
-            },
+
+        // Pop a datagram that was received in a batch.
+        if let Some(datagrams) = self_.datagrams.as_ref() {
+            return match datagrams.pop(self_.fd) {
+                // Operation completed.
+                Ok(Some((addr, mut buf))) => {
+                    // Bytes that do not fit are discarded, as if the datagram was received right away.
+                    if buf.len() > self_.max_size {
+                        buf.trim(buf.len() - self_.max_size)?;
+                    }
+                    Poll::Ready(Ok((addr, buf)))
+                },
+                // Operation in progress.
+                Ok(None) => {
+                    self_.waiter.wait(ctx.waker());
+                    Poll::Pending
+                },
+                // Error.
+                Err(e) => {
+                    trace!("pop failed ({:?})", e);
+                    Poll::Ready(Err(e))
+                },
+            };
+        }
+
+        loop {
+            match socket::recvfrom::<SockaddrStorage>(self_.fd, &mut self_.bytes[self_.len..]) {
+                // Operation completed.
+                Ok((nbytes, socketaddr)) if nbytes == 0 || self_.len + nbytes >= self_.min_size => {
+                    self_.len += nbytes;
+                    trace!("data received ({:?}/{:?} bytes)", self_.len, self_.bytes.len());
+                    let buf: DemiBuffer = DemiBuffer::from_slice(&self_.bytes[0..self_.len])?;
+                    return Poll::Ready(Ok((socketaddr, buf)));
+                },
+                // Not enough data yet, so keep receiving.
+                Ok((nbytes, _)) => self_.len += nbytes,
+                // Operation in progress.
+                Err(e) if e == Errno::EWOULDBLOCK || e == Errno::EAGAIN => {
+                    self_.waiter.wait(ctx.waker());
+                    return Poll::Pending;
+                },
+                // Error.
+                Err(e) => {
+                    trace!("pop failed ({:?})", e);
+                    return Poll::Ready(Err(Fail::new(e as i32, "operation failed")));
+                },
+            }
         }
     }
 }
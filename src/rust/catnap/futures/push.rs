@@ -5,10 +5,13 @@
 // Imports
 //==============================================================================
 
-use crate::runtime::{
-    fail::Fail,
-    memory::DemiBuffer,
-    QDesc,
+use crate::{
+    catnap::runtime::SocketWaiter,
+    runtime::{
+        fail::Fail,
+        memory::DemiBuffer,
+        QDesc,
+    },
 };
 use ::nix::{
     errno::Errno,
@@ -16,6 +19,7 @@ use ::nix::{
 };
 use ::std::{
     future::Future,
+    io::IoSlice,
     os::unix::prelude::RawFd,
     pin::Pin,
     task::{
@@ -36,6 +40,8 @@ pub struct PushFuture {
     fd: RawFd,
     /// Buffer to send.
     buf: DemiBuffer,
+    /// Waiter for the underlying socket to have room in its send buffer.
+    waiter: SocketWaiter,
 }
 
 //==============================================================================
@@ -45,8 +51,8 @@ pub struct PushFuture {
 /// Associate Functions for Push Operation Descriptors
 impl PushFuture {
     /// Creates a descriptor for a push operation.
-    pub fn new(qd: QDesc, fd: RawFd, buf: DemiBuffer) -> Self {
-        Self { qd, fd, buf }
+    pub fn new(qd: QDesc, fd: RawFd, buf: DemiBuffer, waiter: SocketWaiter) -> Self {
+        Self { qd, fd, buf, waiter }
     }
 
     /// Returns the queue descriptor associated to the target [PushFuture].
@@ -66,15 +72,18 @@ impl Future for PushFuture {
     /// Polls the target [PushFuture].
     fn poll(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
         let self_: &mut PushFuture = self.get_mut();
-        match socket::send(self_.fd, &self_.buf[..], socket::MsgFlags::empty()) {
+        // Gather all segments of the buffer chain, so that they are sent at once without copying.
+        let iov: Vec<IoSlice> = self_.buf.segments().map(IoSlice::new).collect();
+        let len: usize = iov.iter().map(|seg| seg.len()).sum();
+        match socket::sendmsg::<()>(self_.fd, &iov, &[], socket::MsgFlags::empty(), None) {
             // Operation completed.
             Ok(nbytes) => {
-                trace!("data pushed ({:?}/{:?} bytes)", nbytes, self_.buf.len());
+                trace!("data pushed ({:?}/{:?} bytes)", nbytes, len);
                 Poll::Ready(Ok(()))
             },
             // Operation in progress.
             Err(e) if e == Errno::EWOULDBLOCK || e == Errno::EAGAIN => {
-                ctx.waker().wake_by_ref();
+                self_.waiter.wait(ctx.waker());
                 Poll::Pending
             },
             // Error.
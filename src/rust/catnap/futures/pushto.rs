@@ -5,22 +5,18 @@
 // Imports
 //==============================================================================
 
-use crate::runtime::{
-    fail::Fail,
-    memory::DemiBuffer,
-    QDesc,
-};
-use ::nix::{
-    errno::Errno,
-    sys::socket::{
-        self,
-        MsgFlags,
-        SockaddrStorage,
+use crate::{
+    catnap::{
+        datagram::SendResult,
+        runtime::SocketWaiter,
+    },
+    runtime::{
+        fail::Fail,
+        QDesc,
     },
 };
 use ::std::{
     future::Future,
-    os::unix::prelude::RawFd,
     pin::Pin,
     task::{
         Context,
@@ -33,15 +29,16 @@ use ::std::{
 //==============================================================================
 
 /// Pushto Operation Descriptor
+///
+/// The datagram itself is queued in the runtime, so that it is sent in a batch along with the others that are pushed
+/// to the same socket.
 pub struct PushtoFuture {
     /// Associated queue descriptor.
     qd: QDesc,
-    /// Destination address.
-    addr: SockaddrStorage,
-    // Underlying file descriptor.
-    fd: RawFd,
-    /// Buffer to send.
-    buf: DemiBuffer,
+    /// Result of the queued datagram.
+    result: SendResult,
+    /// Waiter for the underlying socket to have room in its send buffer.
+    waiter: SocketWaiter,
 }
 
 //==============================================================================
@@ -51,8 +48,8 @@ pub struct PushtoFuture {
 /// Associate Functions for Pushto Operation Descriptors
 impl PushtoFuture {
     /// Creates a descriptor for a pushto operation.
-    pub fn new(qd: QDesc, fd: RawFd, addr: SockaddrStorage, buf: DemiBuffer) -> Self {
-        Self { qd, addr, fd, buf }
+    pub fn new(qd: QDesc, result: SendResult, waiter: SocketWaiter) -> Self {
+        Self { qd, result, waiter }
     }
 
     /// Returns the queue descriptor associated to the target [PushtoFuture].
@@ -72,22 +69,15 @@ impl Future for PushtoFuture {
     /// Polls the target [PushtoFuture].
     fn poll(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
         let self_: &mut PushtoFuture = self.get_mut();
-        match socket::sendto(self_.fd, &self_.buf[..], &self_.addr, MsgFlags::empty()) {
+        let result: Option<Result<(), Fail>> = self_.result.borrow_mut().take();
+        match result {
             // Operation completed.
-            Ok(nbytes) => {
-                trace!("data pushed ({:?}/{:?} bytes)", nbytes, self_.buf.len());
-                Poll::Ready(Ok(()))
-            },
+            Some(result) => Poll::Ready(result),
             // Operation in progress.
-            Err(e) if e == Errno::EWOULDBLOCK || e == Errno::EAGAIN => {
-                ctx.waker().wake_by_ref();
+            None => {
+                self_.waiter.wait(ctx.waker());
                 Poll::Pending
             },
-            // Error.
-            Err(e) => {
-                warn!("push failed ({:?})", e);
-                Poll::Ready(Err(Fail::new(e as i32, "operation failed")))
-            },
         }
     }
 }
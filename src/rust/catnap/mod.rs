@@ -1,6 +1,7 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT license.
 
+mod datagram;
 mod futures;
 mod runtime;
 
@@ -14,27 +15,48 @@ pub use self::runtime::PosixRuntime;
 // Imports
 //==============================================================================
 
-use self::futures::{
-    accept::AcceptFuture,
-    connect::ConnectFuture,
-    pop::PopFuture,
-    push::PushFuture,
-    pushto::PushtoFuture,
-    Operation,
+use self::{
+    datagram::{
+        DatagramQueues,
+        SendResult,
+    },
+    futures::{
+        accept::AcceptFuture,
+        close::CloseFuture,
+        connect::ConnectFuture,
+        pop::PopFuture,
+        push::PushFuture,
+        pushto::PushtoFuture,
+        Operation,
+    },
 };
 use crate::{
-    demikernel::config::Config,
+    demikernel::{
+        config::Config,
+        libos::backend::LibOSBackend,
+    },
     inetstack::operations::OperationResult,
+    pal::{
+        functions::create_sock_addr_storage,
+        linux,
+    },
     runtime::{
         fail::Fail,
         memory::MemoryRuntime,
+        network::types::UnixSocketAddr,
         queue::IoQueueTable,
         types::{
             demi_accept_result_t,
             demi_opcode_t,
             demi_qr_value_t,
             demi_qresult_t,
+            demi_qstats_t,
             demi_sgarray_t,
+            DEMI_POLLACCEPT,
+            DEMI_POLLHUP,
+            DEMI_POLLIN,
+            DEMI_POLLOUT,
+            DEMI_QSTATS_TCP,
         },
         QDesc,
         QToken,
@@ -45,21 +67,31 @@ use crate::{
 use ::libc::{
     c_int,
     AF_INET,
+    AF_INET6,
+    AF_UNIX,
+    EAFNOSUPPORT,
     EBADF,
     EINVAL,
     ENOTSUP,
+    SHUT_RD,
+    SHUT_RDWR,
+    SHUT_WR,
     SOCK_DGRAM,
     SOCK_STREAM,
 };
 use ::nix::{
+    errno::Errno,
     sys::{
         socket,
         socket::{
             AddressFamily,
+            Shutdown,
             SockFlag,
             SockProtocol,
             SockType,
+            SockaddrLike,
             SockaddrStorage,
+            UnixAddr,
         },
     },
     unistd,
@@ -68,11 +100,10 @@ use ::std::{
     any::Any,
     collections::HashMap,
     mem,
-    net::{
-        Ipv4Addr,
-        SocketAddrV4,
-    },
+    net::SocketAddr,
     os::unix::prelude::RawFd,
+    rc::Rc,
+    time::Duration,
 };
 
 //==============================================================================
@@ -117,17 +148,23 @@ impl CatnapLibOS {
         // Parse communication domain.
         let domain: AddressFamily = match domain {
             AF_INET => AddressFamily::Inet,
+            AF_INET6 => AddressFamily::Inet6,
+            AF_UNIX => AddressFamily::Unix,
             _ => return Err(Fail::new(ENOTSUP, "communication domain not supported")),
         };
 
-        // Parse socket type and protocol.
-        let (ty, protocol): (SockType, SockProtocol) = match typ {
-            SOCK_STREAM => (SockType::Stream, SockProtocol::Tcp),
-            SOCK_DGRAM => (SockType::Datagram, SockProtocol::Udp),
+        // Parse socket type and protocol. Unix domain sockets have a single protocol for each socket type.
+        let (ty, protocol): (SockType, Option<SockProtocol>) = match typ {
+            SOCK_STREAM => (SockType::Stream, Some(SockProtocol::Tcp)),
+            SOCK_DGRAM => (SockType::Datagram, Some(SockProtocol::Udp)),
             _ => {
                 return Err(Fail::new(ENOTSUP, "socket type not supported"));
             },
         };
+        let protocol: Option<SockProtocol> = match domain {
+            AddressFamily::Unix => None,
+            _ => protocol,
+        };
 
         // Create socket.
         match socket::socket(domain, ty, flags, protocol) {
@@ -139,9 +176,20 @@ impl CatnapLibOS {
                 };
 
                 // Try to set SO_REUSEPORT option. If we fail, keep going because this is non-critical.
-                if socket::setsockopt(fd, socket::sockopt::ReusePort, &true).is_err() {
+                if domain != AddressFamily::Unix && socket::setsockopt(fd, socket::sockopt::ReusePort, &true).is_err() {
                     warn!("cannot set SO_REUSEPORT option");
                 }
+                // Register socket for notifications, as pending operations would never be woken up otherwise.
+                if let Err(e) = self.runtime.register(fd) {
+                    if let Err(e) = unistd::close(fd) {
+                        warn!("failed to close socket ({:?})", e);
+                    }
+                    return Err(e);
+                }
+                // Receive datagrams in batches as the runtime is polled.
+                if qtype == QType::UdpSocket {
+                    self.runtime.datagrams.watch(fd);
+                }
                 let qd: QDesc = self.qtable.alloc(qtype.into());
                 assert_eq!(self.sockets.insert(qd, fd).is_none(), true);
                 Ok(qd)
@@ -151,7 +199,7 @@ impl CatnapLibOS {
     }
 
     /// Binds a socket to a local endpoint.
-    pub fn bind(&mut self, qd: QDesc, local: SocketAddrV4) -> Result<(), Fail> {
+    pub fn bind(&mut self, qd: QDesc, local: SocketAddr) -> Result<(), Fail> {
         trace!("bind() qd={:?}, local={:?}", qd, local);
 
         // Issue bind operation.
@@ -165,6 +213,23 @@ impl CatnapLibOS {
         }
     }
 
+    /// Binds a Unix domain socket to a local address.
+    pub fn bind_unix(&mut self, qd: QDesc, local: &UnixSocketAddr) -> Result<(), Fail> {
+        trace!("bind_unix() qd={:?}, local={:?}", qd, local);
+
+        // Issue bind operation.
+        match self.sockets.get(&qd) {
+            Some(&fd) => {
+                let addr: UnixAddr = linux::unixsocketaddr_to_unixaddr(local)?;
+                match socket::bind(fd, &addr) {
+                    Ok(()) => Ok(()),
+                    Err(err) => Err(Fail::new(err as i32, "failed to bind socket")),
+                }
+            },
+            _ => Err(Fail::new(EBADF, "invalid queue descriptor")),
+        }
+    }
+
     /// Sets a socket as a passive one.
     pub fn listen(&mut self, qd: QDesc, backlog: usize) -> Result<(), Fail> {
         trace!("listen() qd={:?}, backlog={:?}", qd, backlog);
@@ -187,7 +252,7 @@ impl CatnapLibOS {
         match self.sockets.get(&qd) {
             Some(&fd) => {
                 let new_qd: QDesc = self.qtable.alloc(QType::TcpSocket.into());
-                let future: Operation = Operation::from(AcceptFuture::new(qd, fd, new_qd));
+                let future: Operation = Operation::from(AcceptFuture::new(qd, fd, new_qd, self.runtime.waiter(fd)));
                 let handle: SchedulerHandle = match self.runtime.scheduler.insert(future) {
                     Some(handle) => handle,
                     None => {
@@ -202,14 +267,23 @@ impl CatnapLibOS {
     }
 
     /// Establishes a connection to a remote endpoint.
-    pub fn connect(&mut self, qd: QDesc, remote: SocketAddrV4) -> Result<QToken, Fail> {
+    pub fn connect(&mut self, qd: QDesc, remote: SocketAddr) -> Result<QToken, Fail> {
         trace!("connect() qd={:?}, remote={:?}", qd, remote);
+        self.do_connect(qd, parse_addr(remote))
+    }
+
+    /// Establishes a connection to a remote Unix domain socket.
+    pub fn connect_unix(&mut self, qd: QDesc, remote: &UnixSocketAddr) -> Result<QToken, Fail> {
+        trace!("connect_unix() qd={:?}, remote={:?}", qd, remote);
+        self.do_connect(qd, parse_unix_addr(remote)?)
+    }
 
+    /// Issues a connect operation to a remote address of any communication domain.
+    fn do_connect(&mut self, qd: QDesc, addr: SockaddrStorage) -> Result<QToken, Fail> {
         // Issue connect operation.
         match self.sockets.get(&qd) {
             Some(&fd) => {
-                let addr: SockaddrStorage = parse_addr(remote);
-                let future: Operation = Operation::from(ConnectFuture::new(qd, fd, addr));
+                let future: Operation = Operation::from(ConnectFuture::new(qd, fd, addr, self.runtime.waiter(fd)));
                 let handle: SchedulerHandle = match self.runtime.scheduler.insert(future) {
                     Some(handle) => handle,
                     None => return Err(Fail::new(libc::EAGAIN, "cannot schedule co-routine")),
@@ -225,27 +299,194 @@ impl CatnapLibOS {
         trace!("close() qd={:?}", qd);
         match self.sockets.get(&qd) {
             Some(&fd) => match unistd::close(fd) {
-                Ok(_) => Ok(()),
+                Ok(_) => {
+                    self.runtime.datagrams.release(fd);
+                    Ok(())
+                },
                 _ => Err(Fail::new(EBADF, "invalid queue descriptor")),
             },
             _ => Err(Fail::new(EBADF, "invalid queue descriptor")),
         }
     }
 
+    /// Asynchronously closes a socket. The operation completes once data that was pushed to the socket is acknowledged
+    /// by the remote peer.
+    pub fn async_close(&mut self, qd: QDesc) -> Result<QToken, Fail> {
+        trace!("async_close() qd={:?}", qd);
+
+        // Issue close operation. Datagrams that are still queued are sent beforehand.
+        match self.sockets.remove(&qd) {
+            Some(fd) => {
+                self.runtime.flush_datagrams();
+                self.runtime.datagrams.release(fd);
+                let future: Operation = Operation::from(CloseFuture::new(qd, fd));
+                let handle: SchedulerHandle = match self.runtime.scheduler.insert(future) {
+                    Some(handle) => handle,
+                    None => return Err(Fail::new(libc::EAGAIN, "cannot schedule co-routine")),
+                };
+                Ok(handle.into_raw().into())
+            },
+            _ => Err(Fail::new(EBADF, "invalid queue descriptor")),
+        }
+    }
+
+    /// Shuts down the sending half, the receiving half, or both halves of a socket.
+    pub fn shutdown(&mut self, qd: QDesc, how: c_int) -> Result<(), Fail> {
+        trace!("shutdown() qd={:?}, how={:?}", qd, how);
+
+        // Parse shutdown direction.
+        let how: Shutdown = match how {
+            SHUT_RD => Shutdown::Read,
+            SHUT_WR => Shutdown::Write,
+            SHUT_RDWR => Shutdown::Both,
+            _ => return Err(Fail::new(EINVAL, "invalid value for how")),
+        };
+
+        match self.sockets.get(&qd) {
+            Some(&fd) => match socket::shutdown(fd, how) {
+                Ok(()) => Ok(()),
+                Err(e) => Err(Fail::new(e as i32, "failed to shut down socket")),
+            },
+            _ => Err(Fail::new(EBADF, "invalid queue descriptor")),
+        }
+    }
+
+    /// Gets the statistics of a socket. Those of TCP sockets are queried from the kernel, which does not expose the
+    /// send window.
+    pub fn get_stats(&mut self, qd: QDesc) -> Result<demi_qstats_t, Fail> {
+        trace!("get_stats() qd={:?}", qd);
+        match self.sockets.get(&qd) {
+            Some(&fd) => {
+                let mut stats: demi_qstats_t = demi_qstats_t::default();
+                if let Some(info) = linux::get_tcp_info(fd) {
+                    stats.qs_flags |= DEMI_QSTATS_TCP;
+                    stats.qs_tcp_cwnd = info.tcpi_snd_cwnd as u64 * info.tcpi_snd_mss as u64;
+                    stats.qs_tcp_rto_us = info.tcpi_rto as u64;
+                    stats.qs_tcp_retransmissions = info.tcpi_total_retrans as u64;
+                    stats.qs_tcp_recv_window = info.tcpi_rcv_space as u64;
+                }
+                Ok(stats)
+            },
+            _ => Err(Fail::new(EBADF, "invalid queue descriptor")),
+        }
+    }
+
+    /// Checks which operations on a socket would complete right away, as reported by the kernel.
+    pub fn readiness(&mut self, qd: QDesc) -> Result<u32, Fail> {
+        trace!("readiness() qd={:?}", qd);
+        match self.sockets.get(&qd) {
+            Some(&fd) => {
+                let revents: libc::c_short = linux::get_poll_events(fd);
+                let mut events: u32 = 0;
+                // Datagrams that were received in a batch are not reported by the kernel anymore.
+                if revents & libc::POLLIN != 0 || self.runtime.datagrams.has_incoming(fd) {
+                    events |= if linux::is_listening(fd) {
+                        DEMI_POLLACCEPT
+                    } else {
+                        DEMI_POLLIN
+                    };
+                }
+                if revents & libc::POLLOUT != 0 {
+                    events |= DEMI_POLLOUT;
+                }
+                if revents & (libc::POLLHUP | libc::POLLRDHUP | libc::POLLERR) != 0 {
+                    events |= DEMI_POLLHUP;
+                }
+                Ok(events)
+            },
+            _ => Err(Fail::new(EBADF, "invalid queue descriptor")),
+        }
+    }
+
+    /// Sets an option on a socket.
+    pub fn setsockopt(&mut self, qd: QDesc, level: c_int, optname: c_int, optval: &[u8]) -> Result<(), Fail> {
+        trace!("setsockopt() qd={:?}, level={:?}, optname={:?}", qd, level, optname);
+
+        // Issue setsockopt operation.
+        match self.sockets.get(&qd) {
+            Some(&fd) => {
+                let optval_ptr: *const libc::c_void = optval.as_ptr() as *const libc::c_void;
+                let optlen: libc::socklen_t = optval.len() as libc::socklen_t;
+                match unsafe { libc::setsockopt(fd, level, optname, optval_ptr, optlen) } {
+                    0 => Ok(()),
+                    _ => Err(Fail::new(Errno::last() as i32, "failed to set socket option")),
+                }
+            },
+            _ => Err(Fail::new(EBADF, "invalid queue descriptor")),
+        }
+    }
+
+    /// Gets the value of an option on a socket.
+    pub fn getsockopt(&mut self, qd: QDesc, level: c_int, optname: c_int, optval: &mut [u8]) -> Result<usize, Fail> {
+        trace!("getsockopt() qd={:?}, level={:?}, optname={:?}", qd, level, optname);
+
+        // Issue getsockopt operation.
+        match self.sockets.get(&qd) {
+            Some(&fd) => {
+                let optval_ptr: *mut libc::c_void = optval.as_mut_ptr() as *mut libc::c_void;
+                let mut optlen: libc::socklen_t = optval.len() as libc::socklen_t;
+                match unsafe { libc::getsockopt(fd, level, optname, optval_ptr, &mut optlen) } {
+                    0 => Ok(optlen as usize),
+                    _ => Err(Fail::new(Errno::last() as i32, "failed to get socket option")),
+                }
+            },
+            _ => Err(Fail::new(EBADF, "invalid queue descriptor")),
+        }
+    }
+
+    /// Gets the local address of a socket.
+    pub fn getsockname(&mut self, qd: QDesc) -> Result<SocketAddr, Fail> {
+        trace!("getsockname() qd={:?}", qd);
+
+        // Issue getsockname operation.
+        match self.sockets.get(&qd) {
+            Some(&fd) => match socket::getsockname::<SockaddrStorage>(fd) {
+                Ok(addr) => match linux::sockaddr_to_socketaddr(&addr) {
+                    Some(addr) => Ok(addr),
+                    None => Err(Fail::new(EAFNOSUPPORT, "communication domain not supported")),
+                },
+                Err(err) => Err(Fail::new(err as i32, "failed to get socket address")),
+            },
+            _ => Err(Fail::new(EBADF, "invalid queue descriptor")),
+        }
+    }
+
+    /// Gets the remote address of a connected socket.
+    pub fn getpeername(&mut self, qd: QDesc) -> Result<SocketAddr, Fail> {
+        trace!("getpeername() qd={:?}", qd);
+
+        // Issue getpeername operation.
+        match self.sockets.get(&qd) {
+            Some(&fd) => match socket::getpeername::<SockaddrStorage>(fd) {
+                Ok(addr) => match linux::sockaddr_to_socketaddr(&addr) {
+                    Some(addr) => Ok(addr),
+                    None => Err(Fail::new(EAFNOSUPPORT, "communication domain not supported")),
+                },
+                Err(err) => Err(Fail::new(err as i32, "failed to get peer address")),
+            },
+            _ => Err(Fail::new(EBADF, "invalid queue descriptor")),
+        }
+    }
+
     /// Pushes a scatter-gather array to a socket.
     pub fn push(&mut self, qd: QDesc, sga: &demi_sgarray_t) -> Result<QToken, Fail> {
         trace!("push() qd={:?}", qd);
 
         match self.runtime.clone_sgarray(sga) {
             Ok(buf) => {
-                if buf.len() == 0 {
+                if buf.total_len() == 0 {
                     return Err(Fail::new(EINVAL, "zero-length buffer"));
                 }
 
-                // Issue push operation.
+                // Issue push operation. Datagrams are sent in batches once the runtime is polled.
                 match self.sockets.get(&qd) {
                     Some(&fd) => {
-                        let future: Operation = Operation::from(PushFuture::new(qd, fd, buf));
+                        let future: Operation = if self.is_datagram(qd) {
+                            let result: SendResult = self.runtime.datagrams.push(fd, None, buf);
+                            Operation::from(PushtoFuture::new(qd, result, self.runtime.waiter(fd)))
+                        } else {
+                            Operation::from(PushFuture::new(qd, fd, buf, self.runtime.waiter(fd)))
+                        };
                         let handle: SchedulerHandle = match self.runtime.scheduler.insert(future) {
                             Some(handle) => handle,
                             None => return Err(Fail::new(libc::EAGAIN, "cannot schedule co-routine")),
@@ -260,20 +501,30 @@ impl CatnapLibOS {
     }
 
     /// Pushes a scatter-gather array to a socket.
-    pub fn pushto(&mut self, qd: QDesc, sga: &demi_sgarray_t, remote: SocketAddrV4) -> Result<QToken, Fail> {
+    pub fn pushto(&mut self, qd: QDesc, sga: &demi_sgarray_t, remote: SocketAddr) -> Result<QToken, Fail> {
         trace!("pushto() qd={:?}", qd);
+        self.do_pushto(qd, sga, parse_addr(remote))
+    }
 
+    /// Pushes a scatter-gather array to a Unix domain datagram socket.
+    pub fn pushto_unix(&mut self, qd: QDesc, sga: &demi_sgarray_t, remote: &UnixSocketAddr) -> Result<QToken, Fail> {
+        trace!("pushto_unix() qd={:?}", qd);
+        self.do_pushto(qd, sga, parse_unix_addr(remote)?)
+    }
+
+    /// Issues a pushto operation to a remote address of any communication domain.
+    fn do_pushto(&mut self, qd: QDesc, sga: &demi_sgarray_t, addr: SockaddrStorage) -> Result<QToken, Fail> {
         match self.runtime.clone_sgarray(sga) {
             Ok(buf) => {
-                if buf.len() == 0 {
+                if buf.total_len() == 0 {
                     return Err(Fail::new(EINVAL, "zero-length buffer"));
                 }
 
-                // Issue pushto operation.
+                // Issue pushto operation. The datagram is sent in a batch once the runtime is polled.
                 match self.sockets.get(&qd) {
                     Some(&fd) => {
-                        let addr: SockaddrStorage = parse_addr(remote);
-                        let future: Operation = Operation::from(PushtoFuture::new(qd, fd, addr, buf));
+                        let result: SendResult = self.runtime.datagrams.push(fd, Some(addr), buf);
+                        let future: Operation = Operation::from(PushtoFuture::new(qd, result, self.runtime.waiter(fd)));
                         let handle: SchedulerHandle = match self.runtime.scheduler.insert(future) {
                             Some(handle) => handle,
                             None => return Err(Fail::new(libc::EAGAIN, "cannot schedule co-routine")),
@@ -288,13 +539,25 @@ impl CatnapLibOS {
     }
 
     /// Pops data from a socket.
-    pub fn pop(&mut self, qd: QDesc) -> Result<QToken, Fail> {
-        trace!("pop() qd={:?}", qd);
+    pub fn pop(&mut self, qd: QDesc, min_size: usize, max_size: Option<usize>) -> Result<QToken, Fail> {
+        trace!("pop() qd={:?}, min_size={:?}, max_size={:?}", qd, min_size, max_size);
 
         // Issue pop operation.
         match self.sockets.get(&qd) {
             Some(&fd) => {
-                let future: Operation = Operation::from(PopFuture::new(qd, fd));
+                // Minimum sizes only apply to stream sockets, while datagrams are received in batches.
+                let (min_size, datagrams): (usize, Option<DatagramQueues>) = match self.is_datagram(qd) {
+                    true => (0, Some(self.runtime.datagrams.clone())),
+                    false => (min_size, None),
+                };
+                let future: Operation = Operation::from(PopFuture::new(
+                    qd,
+                    fd,
+                    min_size,
+                    max_size,
+                    datagrams,
+                    self.runtime.waiter(fd),
+                ));
                 let handle: SchedulerHandle = match self.runtime.scheduler.insert(future) {
                     Some(handle) => handle,
                     None => return Err(Fail::new(libc::EAGAIN, "cannot schedule co-routine")),
@@ -306,10 +569,20 @@ impl CatnapLibOS {
         }
     }
 
+    /// Polls pending operations whose sockets have changed state. Datagrams that were pushed since the last poll are
+    /// sent in batches first.
     pub fn poll(&self) {
+        self.runtime.poll_events(Duration::ZERO);
+        self.runtime.flush_datagrams();
         self.runtime.scheduler.poll()
     }
 
+    /// Blocks until some socket has changed state, or a timeout expires.
+    pub fn wait_for_events(&self, timeout: Duration) {
+        trace!("wait_for_events() timeout={:?}", timeout);
+        self.runtime.poll_events(timeout);
+    }
+
     pub fn schedule(&mut self, qt: QToken) -> Result<SchedulerHandle, Fail> {
         match self.runtime.scheduler.from_raw_handle(qt.into()) {
             Some(handle) => Ok(handle),
@@ -317,8 +590,29 @@ impl CatnapLibOS {
         }
     }
 
-    pub fn pack_result(&mut self, handle: SchedulerHandle, qt: QToken) -> Result<demi_qresult_t, Fail> {
-        let (qd, r): (QDesc, OperationResult) = self.take_result(handle);
+    /// Returns the queue tokens of all operations that have completed, but whose results were not taken out yet.
+    pub fn completed(&self) -> Vec<QToken> {
+        self.runtime
+            .scheduler
+            .completed()
+            .into_iter()
+            .map(QToken::from)
+            .collect()
+    }
+
+    pub fn pack_result(&mut self, handle: SchedulerHandle, qt: QToken) -> Result<(demi_qresult_t, Option<Fail>), Fail> {
+        let operation: Operation = self.take_operation(handle);
+        let (qd, r): (QDesc, OperationResult) = self.take_result(operation);
+        Ok(pack_result(&self.runtime, r, qd, qt.into()))
+    }
+
+    /// Cancels the operation associated with the target [SchedulerHandle]. If the operation has already completed, its
+    /// result is packed as is. Otherwise, it completes with `ECANCELED`.
+    pub fn cancel(&mut self, handle: SchedulerHandle, qt: QToken) -> Result<(demi_qresult_t, Option<Fail>), Fail> {
+        trace!("cancel() qt={:?}", qt);
+        let mut operation: Operation = self.take_operation(handle);
+        operation.cancel();
+        let (qd, r): (QDesc, OperationResult) = self.take_result(operation);
         Ok(pack_result(&self.runtime, r, qd, qt.into()))
     }
 
@@ -334,28 +628,45 @@ impl CatnapLibOS {
         self.runtime.free_sgarray(sga)
     }
 
-    #[deprecated]
-    pub fn local_ipv4_addr(&self) -> Ipv4Addr {
-        todo!()
+    /// Appends the segments of a scatter-gather array to another one, and releases the former.
+    pub fn sgaappend(&self, sga: &mut demi_sgarray_t, tail: demi_sgarray_t) -> Result<(), Fail> {
+        trace!("sgaappend()");
+        self.runtime.append_sgarray(sga, tail)
     }
 
-    #[deprecated]
-    pub fn rt(&self) -> &PosixRuntime {
-        &self.runtime
+    /// Checks if a queue descriptor refers to a datagram socket, whose datagrams are sent and received in batches.
+    fn is_datagram(&self, qd: QDesc) -> bool {
+        self.qtable.get(qd) == Some(QType::UdpSocket.into())
     }
 
-    /// Takes out the [OperationResult] associated with the target [SchedulerHandle].
-    fn take_result(&mut self, handle: SchedulerHandle) -> (QDesc, OperationResult) {
+    /// Takes out the [Operation] associated with the target [SchedulerHandle].
+    fn take_operation(&mut self, handle: SchedulerHandle) -> Operation {
         let boxed_future: Box<dyn Any> = self.runtime.scheduler.take(handle).as_any();
-        let boxed_concrete_type: Operation = *boxed_future.downcast::<Operation>().expect("Wrong type!");
+        *boxed_future.downcast::<Operation>().expect("Wrong type!")
+    }
+
+    /// Takes out the [OperationResult] associated with the target [Operation].
+    fn take_result(&mut self, operation: Operation) -> (QDesc, OperationResult) {
+        let closing: bool = matches!(operation, Operation::Close(..));
+        let (qd, new_qd, new_fd, qr): (QDesc, Option<QDesc>, Option<RawFd>, OperationResult) = operation.get_result();
 
-        let (qd, new_qd, new_fd, qr): (QDesc, Option<QDesc>, Option<RawFd>, OperationResult) =
-            boxed_concrete_type.get_result();
+        // Release queue descriptor of closed socket.
+        if closing {
+            self.qtable.free(qd);
+        }
 
         // Handle accept operation.
         if let Some(new_qd) = new_qd {
             // Associate raw file descriptor with queue descriptor.
             if let Some(new_fd) = new_fd {
+                // Register socket for notifications, as pending operations would never be woken up otherwise.
+                if let Err(e) = self.runtime.register(new_fd) {
+                    if let Err(e) = unistd::close(new_fd) {
+                        warn!("failed to close socket ({:?})", e);
+                    }
+                    self.qtable.free(new_qd);
+                    return (qd, OperationResult::Failed(e));
+                }
                 assert!(self.sockets.insert(new_qd, new_fd).is_none());
             } else {
                 // Release entry in queue table.
@@ -367,39 +678,207 @@ impl CatnapLibOS {
     }
 }
 
+//==============================================================================
+// Trait Implementations
+//==============================================================================
+
+/// LibOS Backend Trait Implementation for Catnap LibOS
+impl LibOSBackend for CatnapLibOS {
+    fn socket(&mut self, domain: libc::c_int, socket_type: libc::c_int, protocol: libc::c_int) -> Result<QDesc, Fail> {
+        CatnapLibOS::socket(self, domain, socket_type, protocol)
+    }
+
+    fn bind(&mut self, sockqd: QDesc, local: SocketAddr) -> Result<(), Fail> {
+        CatnapLibOS::bind(self, sockqd, local)
+    }
+
+    fn listen(&mut self, sockqd: QDesc, backlog: usize) -> Result<(), Fail> {
+        CatnapLibOS::listen(self, sockqd, backlog)
+    }
+
+    fn accept(&mut self, sockqd: QDesc) -> Result<QToken, Fail> {
+        CatnapLibOS::accept(self, sockqd)
+    }
+
+    fn connect(&mut self, sockqd: QDesc, remote: SocketAddr) -> Result<QToken, Fail> {
+        CatnapLibOS::connect(self, sockqd, remote)
+    }
+
+    fn bind_unix(&mut self, sockqd: QDesc, local: &UnixSocketAddr) -> Result<(), Fail> {
+        CatnapLibOS::bind_unix(self, sockqd, local)
+    }
+
+    fn connect_unix(&mut self, sockqd: QDesc, remote: &UnixSocketAddr) -> Result<QToken, Fail> {
+        CatnapLibOS::connect_unix(self, sockqd, remote)
+    }
+
+    fn setsockopt(
+        &mut self,
+        sockqd: QDesc,
+        level: libc::c_int,
+        optname: libc::c_int,
+        optval: &[u8],
+    ) -> Result<(), Fail> {
+        CatnapLibOS::setsockopt(self, sockqd, level, optname, optval)
+    }
+
+    fn getsockopt(
+        &mut self,
+        sockqd: QDesc,
+        level: libc::c_int,
+        optname: libc::c_int,
+        optval: &mut [u8],
+    ) -> Result<usize, Fail> {
+        CatnapLibOS::getsockopt(self, sockqd, level, optname, optval)
+    }
+
+    fn getsockname(&mut self, sockqd: QDesc) -> Result<SocketAddr, Fail> {
+        CatnapLibOS::getsockname(self, sockqd)
+    }
+
+    fn getpeername(&mut self, sockqd: QDesc) -> Result<SocketAddr, Fail> {
+        CatnapLibOS::getpeername(self, sockqd)
+    }
+
+    fn pushto(&mut self, sockqd: QDesc, sga: &demi_sgarray_t, to: SocketAddr) -> Result<QToken, Fail> {
+        CatnapLibOS::pushto(self, sockqd, sga, to)
+    }
+
+    fn pushto_unix(&mut self, sockqd: QDesc, sga: &demi_sgarray_t, to: &UnixSocketAddr) -> Result<QToken, Fail> {
+        CatnapLibOS::pushto_unix(self, sockqd, sga, to)
+    }
+
+    fn shutdown(&mut self, sockqd: QDesc, how: libc::c_int) -> Result<(), Fail> {
+        CatnapLibOS::shutdown(self, sockqd, how)
+    }
+
+    fn close(&mut self, qd: QDesc) -> Result<(), Fail> {
+        CatnapLibOS::close(self, qd)
+    }
+
+    fn async_close(&mut self, qd: QDesc) -> Result<QToken, Fail> {
+        CatnapLibOS::async_close(self, qd)
+    }
+
+    fn get_stats(&mut self, qd: QDesc) -> Result<demi_qstats_t, Fail> {
+        CatnapLibOS::get_stats(self, qd)
+    }
+
+    fn readiness(&mut self, qd: QDesc) -> Result<u32, Fail> {
+        CatnapLibOS::readiness(self, qd)
+    }
+
+    fn push(&mut self, qd: QDesc, sga: &demi_sgarray_t) -> Result<QToken, Fail> {
+        CatnapLibOS::push(self, qd, sga)
+    }
+
+    fn pop(&mut self, qd: QDesc, min_size: usize, max_size: Option<usize>) -> Result<QToken, Fail> {
+        CatnapLibOS::pop(self, qd, min_size, max_size)
+    }
+
+    fn poll(&mut self) {
+        CatnapLibOS::poll(self)
+    }
+
+    fn wait_for_events(&mut self, timeout: Duration) {
+        CatnapLibOS::wait_for_events(self, timeout)
+    }
+
+    fn schedule(&mut self, qt: QToken) -> Result<SchedulerHandle, Fail> {
+        CatnapLibOS::schedule(self, qt)
+    }
+
+    fn completed(&self) -> Vec<QToken> {
+        CatnapLibOS::completed(self)
+    }
+
+    fn pack_result(&mut self, handle: SchedulerHandle, qt: QToken) -> Result<(demi_qresult_t, Option<Fail>), Fail> {
+        CatnapLibOS::pack_result(self, handle, qt)
+    }
+
+    fn cancel(&mut self, handle: SchedulerHandle, qt: QToken) -> Result<(demi_qresult_t, Option<Fail>), Fail> {
+        CatnapLibOS::cancel(self, handle, qt)
+    }
+
+    fn sgaalloc(&self, size: usize) -> Result<demi_sgarray_t, Fail> {
+        CatnapLibOS::sgaalloc(self, size)
+    }
+
+    fn sgafree(&self, sga: demi_sgarray_t) -> Result<(), Fail> {
+        CatnapLibOS::sgafree(self, sga)
+    }
+
+    fn sgaappend(&self, sga: &mut demi_sgarray_t, tail: demi_sgarray_t) -> Result<(), Fail> {
+        CatnapLibOS::sgaappend(self, sga, tail)
+    }
+
+    fn memory(&self) -> Rc<dyn MemoryRuntime> {
+        Rc::new(self.runtime.clone())
+    }
+}
+
 //==============================================================================
 // Standalone Functions
 //==============================================================================
 
-/// Parses a [SocketAddrV4] into a [SockaddrStorage].
-fn parse_addr(endpoint: SocketAddrV4) -> SockaddrStorage {
-    let addr: &Ipv4Addr = endpoint.ip();
-    let port: u16 = endpoint.port().into();
-    let ipv4: SocketAddrV4 = SocketAddrV4::new(*addr, port);
-    SockaddrStorage::from(ipv4)
+/// Parses a [SocketAddr] into a [SockaddrStorage].
+fn parse_addr(endpoint: SocketAddr) -> SockaddrStorage {
+    SockaddrStorage::from(endpoint)
+}
+
+/// Parses a [UnixSocketAddr] into a [SockaddrStorage].
+fn parse_unix_addr(endpoint: &UnixSocketAddr) -> Result<SockaddrStorage, Fail> {
+    let addr: UnixAddr = linux::unixsocketaddr_to_unixaddr(endpoint)?;
+    match unsafe { SockaddrStorage::from_raw(addr.as_ptr() as *const libc::sockaddr, Some(addr.len())) } {
+        Some(addr) => Ok(addr),
+        None => Err(Fail::new(EINVAL, "invalid unix socket address")),
+    }
 }
 
 /// Packs a [OperationResult] into a [demi_qresult_t].
-fn pack_result(rt: &PosixRuntime, result: OperationResult, qd: QDesc, qt: u64) -> demi_qresult_t {
-    match result {
+fn pack_result(rt: &PosixRuntime, result: OperationResult, qd: QDesc, qt: u64) -> (demi_qresult_t, Option<Fail>) {
+    let qr: demi_qresult_t = match result {
         OperationResult::Connect => demi_qresult_t {
             qr_opcode: demi_opcode_t::DEMI_OPC_CONNECT,
             qr_qd: qd.into(),
             qr_qt: qt,
+            qr_ret: 0,
             qr_value: unsafe { mem::zeroed() },
         },
-        OperationResult::Accept(new_qd) => {
-            let sin = unsafe { mem::zeroed() };
+        OperationResult::Close => demi_qresult_t {
+            qr_opcode: demi_opcode_t::DEMI_OPC_CLOSE,
+            qr_qd: qd.into(),
+            qr_qt: qt,
+            qr_ret: 0,
+            qr_value: unsafe { mem::zeroed() },
+        },
+        OperationResult::Accept((new_qd, addr)) => {
+            let qr_value = demi_qr_value_t {
+                ares: demi_accept_result_t {
+                    qd: new_qd.into(),
+                    addr: create_sock_addr_storage(&addr).0,
+                },
+            };
+            demi_qresult_t {
+                qr_opcode: demi_opcode_t::DEMI_OPC_ACCEPT,
+                qr_qd: qd.into(),
+                qr_qt: qt,
+                qr_ret: 0,
+                qr_value,
+            }
+        },
+        OperationResult::AcceptUnix((new_qd, addr)) => {
             let qr_value = demi_qr_value_t {
                 ares: demi_accept_result_t {
                     qd: new_qd.into(),
-                    addr: sin,
+                    addr: linux::unixsocketaddr_to_sockaddr_storage(&addr),
                 },
             };
             demi_qresult_t {
                 qr_opcode: demi_opcode_t::DEMI_OPC_ACCEPT,
                 qr_qd: qd.into(),
                 qr_qt: qt,
+                qr_ret: 0,
                 qr_value,
             }
         },
@@ -407,50 +886,76 @@ fn pack_result(rt: &PosixRuntime, result: OperationResult, qd: QDesc, qt: u64) -
             qr_opcode: demi_opcode_t::DEMI_OPC_PUSH,
             qr_qd: qd.into(),
             qr_qt: qt,
+            qr_ret: 0,
             qr_value: unsafe { mem::zeroed() },
         },
         OperationResult::Pop(addr, bytes) => match rt.into_sgarray(bytes) {
             Ok(mut sga) => {
                 if let Some(endpoint) = addr {
-                    let saddr: libc::sockaddr_in = {
-                        // TODO: check the following byte order conversion.
-                        libc::sockaddr_in {
-                            sin_family: libc::AF_INET as u16,
-                            sin_port: endpoint.port().into(),
-                            sin_addr: libc::in_addr {
-                                s_addr: u32::from_le_bytes(endpoint.ip().octets()),
-                            },
-                            sin_zero: [0; 8],
-                        }
-                    };
-                    sga.sga_addr = unsafe { mem::transmute::<libc::sockaddr_in, libc::sockaddr>(saddr) };
+                    sga.sga_addr = create_sock_addr_storage(&endpoint).0;
                 }
                 let qr_value: demi_qr_value_t = demi_qr_value_t { sga };
                 demi_qresult_t {
                     qr_opcode: demi_opcode_t::DEMI_OPC_POP,
                     qr_qd: qd.into(),
                     qr_qt: qt,
+                    qr_ret: 0,
                     qr_value,
                 }
             },
             Err(e) => {
                 warn!("Operation Failed: {:?}", e);
+                return (
+                    demi_qresult_t {
+                        qr_opcode: demi_opcode_t::DEMI_OPC_FAILED,
+                        qr_qd: qd.into(),
+                        qr_qt: qt,
+                        qr_ret: e.errno,
+                        qr_value: unsafe { mem::zeroed() },
+                    },
+                    Some(e),
+                );
+            },
+        },
+        OperationResult::PopUnix(addr, bytes) => match rt.into_sgarray(bytes) {
+            Ok(mut sga) => {
+                sga.sga_addr = linux::unixsocketaddr_to_sockaddr_storage(&addr);
+                let qr_value: demi_qr_value_t = demi_qr_value_t { sga };
                 demi_qresult_t {
-                    qr_opcode: demi_opcode_t::DEMI_OPC_FAILED,
+                    qr_opcode: demi_opcode_t::DEMI_OPC_POP,
                     qr_qd: qd.into(),
                     qr_qt: qt,
-                    qr_value: unsafe { mem::zeroed() },
+                    qr_ret: 0,
+                    qr_value,
                 }
             },
+            Err(e) => {
+                warn!("Operation Failed: {:?}", e);
+                return (
+                    demi_qresult_t {
+                        qr_opcode: demi_opcode_t::DEMI_OPC_FAILED,
+                        qr_qd: qd.into(),
+                        qr_qt: qt,
+                        qr_ret: e.errno,
+                        qr_value: unsafe { mem::zeroed() },
+                    },
+                    Some(e),
+                );
+            },
         },
         OperationResult::Failed(e) => {
             warn!("Operation Failed: {:?}", e);
-            demi_qresult_t {
-                qr_opcode: demi_opcode_t::DEMI_OPC_FAILED,
-                qr_qd: qd.into(),
-                qr_qt: qt,
-                qr_value: unsafe { mem::zeroed() },
-            }
+            return (
+                demi_qresult_t {
+                    qr_opcode: demi_opcode_t::DEMI_OPC_FAILED,
+                    qr_qd: qd.into(),
+                    qr_qt: qt,
+                    qr_ret: e.errno,
+                    qr_value: unsafe { mem::zeroed() },
+                },
+                Some(e),
+            );
         },
-    }
+    };
+    (qr, None)
 }
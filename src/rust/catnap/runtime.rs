@@ -6,38 +6,65 @@
 //==============================================================================
 
 use crate::{
+    catnap::datagram::DatagramQueues,
+    pal::linux::epoll::Epoll,
     runtime::{
         fail::Fail,
         memory::{
             DemiBuffer,
             MemoryRuntime,
         },
-        types::{
-            demi_sgarray_t,
-            demi_sgaseg_t,
-        },
+        types::demi_sgarray_t,
         Runtime,
     },
     scheduler::scheduler::Scheduler,
 };
-use ::libc::c_void;
 use ::std::{
-    mem,
-    ptr::{
-        self,
-        NonNull,
+    cell::{
+        RefCell,
+        RefMut,
+    },
+    collections::{
+        hash_map::Entry,
+        HashMap,
     },
+    os::unix::prelude::RawFd,
+    rc::Rc,
+    task::Waker,
+    time::Duration,
 };
 
 //==============================================================================
 // Structures
 //==============================================================================
 
+/// Wakers of the futures that wait for each socket to change state.
+type Wakers = Rc<RefCell<HashMap<RawFd, Vec<Waker>>>>;
+
 /// POSIX Runtime
 #[derive(Clone)]
 pub struct PosixRuntime {
     /// Scheduler
     pub scheduler: Scheduler,
+    /// Epoll instance where all sockets are registered.
+    epoll: Rc<Epoll>,
+    /// Wakers of the futures that wait for each socket to change state.
+    wakers: Wakers,
+    /// Datagrams that are queued on each socket.
+    pub datagrams: DatagramQueues,
+}
+
+/// Socket Waiter
+///
+/// This lets a future wait for its socket to change state when an operation would block, instead of being polled again
+/// right away. The future stops waiting once this is dropped.
+pub struct SocketWaiter {
+    /// Underlying file descriptor.
+    fd: RawFd,
+    /// Wakers of the futures that wait for each socket to change state.
+    wakers: Wakers,
+    /// Waker that was registered last, if any.
+    waker: Option<Waker>,
 }
 
 //==============================================================================
@@ -49,8 +76,72 @@ impl PosixRuntime {
     pub fn new() -> Self {
         Self {
             scheduler: Scheduler::default(),
+            epoll: Rc::new(Epoll::new().expect("cannot create epoll instance")),
+            wakers: Rc::new(RefCell::new(HashMap::new())),
+            datagrams: DatagramQueues::default(),
         }
     }
+
+    /// Registers a socket in the epoll instance of the target runtime, so that futures may wait for it to change
+    /// state. Sockets are deregistered once they are closed. Datagrams that are still queued on a socket that was
+    /// closed earlier with the same file descriptor are dropped.
+    pub fn register(&self, fd: RawFd) -> Result<(), Fail> {
+        self.datagrams.release(fd);
+        self.epoll.add(fd)
+    }
+
+    /// Creates a waiter for a socket that was registered in the target runtime.
+    pub fn waiter(&self, fd: RawFd) -> SocketWaiter {
+        SocketWaiter {
+            fd,
+            wakers: self.wakers.clone(),
+            waker: None,
+        }
+    }
+
+    /// Waits for some sockets to change state or a timeout to expire, and wakes the futures that wait for them.
+    /// Datagrams that are waiting on watched sockets are received in batches beforehand.
+    pub fn poll_events(&self, timeout: Duration) {
+        match self.epoll.wait(timeout) {
+            Ok(fds) => {
+                for fd in fds {
+                    self.datagrams.receive(fd);
+                    self.wake(fd);
+                }
+            },
+            Err(e) => warn!("poll_events(): {:?}", e),
+        }
+    }
+
+    /// Sends the datagrams that are queued on every socket, and wakes the futures that wait for the sockets where some
+    /// datagrams were sent.
+    pub fn flush_datagrams(&self) {
+        for fd in self.datagrams.flush() {
+            self.wake(fd);
+        }
+    }
+
+    /// Wakes the futures that wait for a socket to change state.
+    fn wake(&self, fd: RawFd) {
+        // Take wakers out first, as woken futures may wait again right away.
+        let wakers: Option<Vec<Waker>> = self.wakers.borrow_mut().remove(&fd);
+        for waker in wakers.into_iter().flatten() {
+            waker.wake();
+        }
+    }
+}
+
+/// Associate Functions for Socket Waiters
+impl SocketWaiter {
+    /// Waits for the socket to change state. The waker is woken up once it does, and it is forgotten afterwards.
+    pub fn wait(&mut self, waker: &Waker) {
+        let mut wakers: RefMut<HashMap<RawFd, Vec<Waker>>> = self.wakers.borrow_mut();
+        let fd_wakers: &mut Vec<Waker> = wakers.entry(self.fd).or_default();
+        if !fd_wakers.iter().any(|other| other.will_wake(waker)) {
+            fd_wakers.push(waker.clone());
+        }
+        self.waker = Some(waker.clone());
+    }
 }
 
 //==============================================================================
@@ -59,24 +150,6 @@ impl PosixRuntime {
 
 /// Memory Runtime Trait Implementation for POSIX Runtime
 impl MemoryRuntime for PosixRuntime {
-    /// Converts a runtime buffer into a scatter-gather array.
-    fn into_sgarray(&self, buf: DemiBuffer) -> Result<demi_sgarray_t, Fail> {
-        // Create a scatter-gather segment to expose the DemiBuffer to the user.
-        let data: *const u8 = buf.as_ptr();
-        let sga_seg: demi_sgaseg_t = demi_sgaseg_t {
-            sgaseg_buf: data as *mut c_void,
-            sgaseg_len: buf.len() as u32,
-        };
-
-        // Create and return a new scatter-gather array (which inherits the DemiBuffer's reference).
-        Ok(demi_sgarray_t {
-            sga_buf: buf.into_raw().as_ptr() as *mut c_void,
-            sga_numsegs: 1,
-            sga_segs: [sga_seg],
-            sga_addr: unsafe { mem::zeroed() },
-        })
-    }
-
     /// Allocates a scatter-gather array.
     fn alloc_sgarray(&self, size: usize) -> Result<demi_sgarray_t, Fail> {
         // ToDo: Allocate an array of buffers if requested size is too large for a single buffer.
@@ -89,104 +162,181 @@ impl MemoryRuntime for PosixRuntime {
         // First allocate the underlying (heap-allocated) DemiBuffer.
         let buf: DemiBuffer = DemiBuffer::new(size as u16);
 
-        // Create a scatter-gather segment to expose the DemiBuffer to the user.
-        let data: *const u8 = buf.as_ptr();
-        let sga_seg: demi_sgaseg_t = demi_sgaseg_t {
-            sgaseg_buf: data as *mut c_void,
-            sgaseg_len: size as u32,
-        };
-
         // Create and return a new scatter-gather array (which inherits the DemiBuffer's reference).
-        Ok(demi_sgarray_t {
-            sga_buf: buf.into_raw().as_ptr() as *mut c_void,
-            sga_numsegs: 1,
-            sga_segs: [sga_seg],
-            sga_addr: unsafe { mem::zeroed() },
-        })
-    }
-
-    /// Releases a scatter-gather array.
-    fn free_sgarray(&self, sga: demi_sgarray_t) -> Result<(), Fail> {
-        // Check arguments.
-        // TODO: Drop this check once we support scatter-gather arrays with multiple segments.
-        if sga.sga_numsegs != 1 {
-            return Err(Fail::new(libc::EINVAL, "demi_sgarray_t has invalid segment count"));
-        }
+        self.into_sgarray(buf)
+    }
+}
+
+/// Runtime Trait Implementation for POSIX Runtime
+impl Runtime for PosixRuntime {}
 
-        if sga.sga_buf == ptr::null_mut() {
-            return Err(Fail::new(libc::EINVAL, "demi_sgarray_t has invalid DemiBuffer token"));
+/// Drop Trait Implementation for Socket Waiters
+impl Drop for SocketWaiter {
+    fn drop(&mut self) {
+        if let Some(waker) = self.waker.take() {
+            if let Entry::Occupied(mut entry) = self.wakers.borrow_mut().entry(self.fd) {
+                entry.get_mut().retain(|other| !other.will_wake(&waker));
+                if entry.get().is_empty() {
+                    entry.remove();
+                }
+            }
         }
+    }
+}
 
-        // Convert back to a DemiBuffer and drop it.
-        // Safety: The `NonNull::new_unchecked()` call is safe, as we verified `sga.sga_buf` is not null above.
-        let token: NonNull<u8> = unsafe { NonNull::new_unchecked(sga.sga_buf as *mut u8) };
-        // Safety: The `DemiBuffer::from_raw()` call *should* be safe, as the `sga_buf` field in the `demi_sgarray_t`
-        // contained a valid `DemiBuffer` token when we provided it to the user (and the user shouldn't change it).
-        let buf: DemiBuffer = unsafe { DemiBuffer::from_raw(token) };
-        drop(buf);
+//==============================================================================
+// Unit Tests
+//==============================================================================
 
-        Ok(())
+#[cfg(test)]
+mod tests {
+    use super::PosixRuntime;
+    use crate::{
+        catnap::futures::pop::PopFuture,
+        runtime::{
+            fail::Fail,
+            QDesc,
+        },
+    };
+    use ::std::{
+        future::Future,
+        os::unix::prelude::RawFd,
+        pin::Pin,
+        sync::{
+            atomic::{
+                AtomicUsize,
+                Ordering,
+            },
+            Arc,
+        },
+        task::{
+            Context,
+            Poll,
+            Wake,
+            Waker,
+        },
+        time::Duration,
+    };
+
+    /// Waker that counts how many times it was woken up.
+    #[derive(Default)]
+    struct CountingWaker(AtomicUsize);
+
+    impl CountingWaker {
+        /// Returns how many times the target waker was woken up.
+        fn count(&self) -> usize {
+            self.0.load(Ordering::SeqCst)
+        }
     }
 
-    /// Clones a scatter-gather array into a DemiBuffer.
-    fn clone_sgarray(&self, sga: &demi_sgarray_t) -> Result<DemiBuffer, Fail> {
-        // Check arguments.
-        // TODO: Drop this check once we support scatter-gather arrays with multiple segments.
-        if sga.sga_numsegs != 1 {
-            return Err(Fail::new(libc::EINVAL, "demi_sgarray_t has invalid segment count"));
+    impl Wake for CountingWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
         }
+    }
+
+    /// Creates a pair of connected, non-blocking Unix domain stream sockets, and registers the first one in a runtime.
+    fn socketpair(rt: &PosixRuntime) -> Result<[RawFd; 2], Fail> {
+        let mut fds: [RawFd; 2] = [-1; 2];
+        assert_eq!(
+            unsafe {
+                libc::socketpair(
+                    libc::AF_UNIX,
+                    libc::SOCK_STREAM | libc::SOCK_NONBLOCK,
+                    0,
+                    fds.as_mut_ptr(),
+                )
+            },
+            0
+        );
+        rt.register(fds[0])?;
+        // The socket is reported as writable once, right after it is registered.
+        rt.poll_events(Duration::ZERO);
+        Ok(fds)
+    }
 
-        if sga.sga_buf == ptr::null_mut() {
-            return Err(Fail::new(libc::EINVAL, "demi_sgarray_t has invalid DemiBuffer token"));
+    /// Closes a pair of sockets.
+    fn close([fd1, fd2]: [RawFd; 2]) {
+        unsafe {
+            libc::close(fd1);
+            libc::close(fd2);
         }
+    }
 
-        // Convert back to a DemiBuffer.
-        // Safety: The `NonNull::new_unchecked()` call is safe, as we verified `sga.sga_buf` is not null above.
-        let token: NonNull<u8> = unsafe { NonNull::new_unchecked(sga.sga_buf as *mut u8) };
-        // Safety: The `DemiBuffer::from_raw()` call *should* be safe, as the `sga_buf` field in the `demi_sgarray_t`
-        // contained a valid `DemiBuffer` token when we provided it to the user (and the user shouldn't change it).
-        let buf: DemiBuffer = unsafe { DemiBuffer::from_raw(token) };
-        let mut clone: DemiBuffer = buf.clone();
-
-        // Don't drop buf, as it holds the same reference to the data as the sgarray (which should keep it).
-        mem::forget(buf);
-
-        // Check to see if the user has reduced the size of the buffer described by the sgarray segment since we
-        // provided it to them.  They could have increased the starting address of the buffer (`sgaseg_buf`),
-        // decreased the ending address of the buffer (`sgaseg_buf + sgaseg_len`), or both.
-        let sga_data: *const u8 = sga.sga_segs[0].sgaseg_buf as *const u8;
-        let sga_len: usize = sga.sga_segs[0].sgaseg_len as usize;
-        let clone_data: *const u8 = clone.as_ptr();
-        let mut clone_len: usize = clone.len();
-        if sga_data != clone_data || sga_len != clone_len {
-            // We need to adjust the DemiBuffer to match the user's changes.
-
-            // First check that the user didn't do something non-sensical, like change the buffer description to
-            // reference address space outside of the allocated memory area.
-            if sga_data < clone_data || sga_data.addr() + sga_len > clone_data.addr() + clone_len {
-                return Err(Fail::new(
-                    libc::EINVAL,
-                    "demi_sgarray_t describes data outside backing buffer's allocated region",
-                ));
-            }
+    /// Sends some bytes on a socket.
+    fn send(fd: RawFd, bytes: &[u8]) {
+        let nbytes: isize = unsafe { libc::send(fd, bytes.as_ptr() as *const libc::c_void, bytes.len(), 0) };
+        assert_eq!(nbytes, bytes.len() as isize);
+    }
+
+    /// Creates a pop operation on a socket that was registered in a runtime.
+    fn pop(rt: &PosixRuntime, fd: RawFd) -> Pin<Box<PopFuture>> {
+        Box::pin(PopFuture::new(QDesc::from(0), fd, 0, None, None, rt.waiter(fd)))
+    }
 
-            // Calculate the amount the new starting address is ahead of the old.  And then adjust `clone` to match.
-            let adjustment_amount: usize = sga_data.addr() - clone_data.addr();
-            clone.adjust(adjustment_amount)?;
+    /// Tests if a pending pop operation is not woken up until its socket has data.
+    #[test]
+    fn socket_waiter_wakes_on_event() -> Result<(), Fail> {
+        let rt: PosixRuntime = PosixRuntime::new();
+        let [fd1, fd2]: [RawFd; 2] = socketpair(&rt)?;
+        let counter: Arc<CountingWaker> = Arc::new(CountingWaker::default());
+        let waker: Waker = Waker::from(counter.clone());
+        let mut ctx: Context = Context::from_waker(&waker);
 
-            // An adjustment above would have reduced clone.len() by the adjustment amount.
-            clone_len -= adjustment_amount;
-            debug_assert_eq!(clone_len, clone.len());
+        // The operation waits for its socket, and polling again does not register the waker twice.
+        let mut future: Pin<Box<PopFuture>> = pop(&rt, fd1);
+        assert!(future.as_mut().poll(&mut ctx).is_pending());
+        assert!(future.as_mut().poll(&mut ctx).is_pending());
+        assert_eq!(rt.wakers.borrow().get(&fd1).map(Vec::len), Some(1));
 
-            // Trim the clone down to size.
-            let trim_amount: usize = clone_len - sga_len;
-            clone.trim(trim_amount)?;
+        // Nothing happens on the socket, so the operation is not woken up.
+        rt.poll_events(Duration::ZERO);
+        rt.flush_datagrams();
+        assert_eq!(counter.count(), 0);
+
+        // The operation is woken up once the socket has data, and only then.
+        send(fd2, b"hello");
+        rt.poll_events(Duration::from_secs(1));
+        assert_eq!(counter.count(), 1);
+        assert!(rt.wakers.borrow().is_empty());
+        match future.as_mut().poll(&mut ctx) {
+            Poll::Ready(Ok((_, buf))) => assert_eq!(&buf[..], b"hello"),
+            _ => panic!("pop should have completed"),
         }
 
-        // Return the clone.
-        Ok(clone)
+        close([fd1, fd2]);
+        Ok(())
     }
-}
 
-/// Runtime Trait Implementation for POSIX Runtime
-impl Runtime for PosixRuntime {}
+    /// Tests if a pending pop operation stops waiting for its socket once it is dropped.
+    #[test]
+    fn socket_waiter_drop() -> Result<(), Fail> {
+        let rt: PosixRuntime = PosixRuntime::new();
+        let [fd1, fd2]: [RawFd; 2] = socketpair(&rt)?;
+        let counter1: Arc<CountingWaker> = Arc::new(CountingWaker::default());
+        let counter2: Arc<CountingWaker> = Arc::new(CountingWaker::default());
+        let waker1: Waker = Waker::from(counter1.clone());
+        let waker2: Waker = Waker::from(counter2.clone());
+
+        // Two operations wait for the same socket.
+        let mut future1: Pin<Box<PopFuture>> = pop(&rt, fd1);
+        let mut future2: Pin<Box<PopFuture>> = pop(&rt, fd1);
+        assert!(future1.as_mut().poll(&mut Context::from_waker(&waker1)).is_pending());
+        assert!(future2.as_mut().poll(&mut Context::from_waker(&waker2)).is_pending());
+        assert_eq!(rt.wakers.borrow().get(&fd1).map(Vec::len), Some(2));
+
+        // Dropping one of them only deregisters its own waker.
+        drop(future1);
+        assert_eq!(rt.wakers.borrow().get(&fd1).map(Vec::len), Some(1));
+
+        // Dropping the last one leaves nothing behind for the socket, so neither is woken up once it has data.
+        drop(future2);
+        assert!(rt.wakers.borrow().is_empty());
+        send(fd2, b"hello");
+        rt.poll_events(Duration::from_secs(1));
+        assert_eq!((counter1.count(), counter2.count()), (0, 0));
+
+        close([fd1, fd2]);
+        Ok(())
+    }
+}
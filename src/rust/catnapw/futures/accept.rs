@@ -13,6 +13,7 @@ use ::socket2::Socket;
 use ::std::{
     cell::RefCell,
     future::Future,
+    net::SocketAddr,
     pin::Pin,
     rc::Rc,
     task::{
@@ -64,16 +65,25 @@ impl AcceptFuture {
 
 /// Future Trait Implementation for Accept Operation Descriptors
 impl Future for AcceptFuture {
-    type Output = Result<Socket, Fail>;
+    type Output = Result<(Socket, SocketAddr), Fail>;
 
     /// Polls the target [AcceptFuture].
     fn poll(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
         let self_: &AcceptFuture = self.get_mut();
         match self_.socket.borrow().accept() {
             // Operation completed.
-            Ok((new_socket, _)) => {
+            Ok((new_socket, addr)) => {
                 trace!("connection accepted ({:?})", new_socket);
 
+                // Get address of remote peer.
+                let addr: SocketAddr = match addr.as_socket() {
+                    Some(addr) => addr,
+                    None => {
+                        warn!("failed to get peer address");
+                        return Poll::Ready(Err(Fail::new(libc::EAFNOSUPPORT, "operation failed")));
+                    },
+                };
+
                 // Set async options in socket.
                 match new_socket.set_nodelay(true) {
                     Ok(_) => {},
@@ -83,7 +93,7 @@ impl Future for AcceptFuture {
                     Ok(_) => {},
                     Err(_) => warn!("cannot set NONBLOCK option"),
                 };
-                Poll::Ready(Ok(new_socket))
+                Poll::Ready(Ok((new_socket, addr)))
             },
             // Operation in progress.
             Err(e) if e.raw_os_error() == Some(WSAEWOULDBLOCK.0) => {
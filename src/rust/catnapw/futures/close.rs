@@ -0,0 +1,83 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//==============================================================================
+// Imports
+//==============================================================================
+
+use crate::runtime::{
+    fail::Fail,
+    QDesc,
+};
+use ::socket2::Socket;
+use ::std::{
+    cell::RefCell,
+    future::Future,
+    io::ErrorKind,
+    net::Shutdown,
+    pin::Pin,
+    rc::Rc,
+    task::{
+        Context,
+        Poll,
+    },
+};
+
+//==============================================================================
+// Structures
+//==============================================================================
+
+/// Close Operation Descriptor
+pub struct CloseFuture {
+    /// Associated queue descriptor.
+    qd: QDesc,
+    // Underlying socket.
+    socket: Rc<RefCell<Socket>>,
+}
+
+//==============================================================================
+// Associate Functions
+//==============================================================================
+
+/// Associate Functions for Close Operation Descriptors
+impl CloseFuture {
+    /// Creates a descriptor for a close operation.
+    pub fn new(qd: QDesc, socket: Rc<RefCell<Socket>>) -> Self {
+        Self { qd, socket }
+    }
+
+    /// Returns the queue descriptor associated to the target [CloseFuture].
+    pub fn get_qd(&self) -> QDesc {
+        self.qd
+    }
+}
+
+//==============================================================================
+// Trait Implementations
+//==============================================================================
+
+/// Future Trait Implementation for Close Operation Descriptors
+impl Future for CloseFuture {
+    type Output = Result<(), Fail>;
+
+    /// Polls the target [CloseFuture].
+    fn poll(self: Pin<&mut Self>, _ctx: &mut Context<'_>) -> Poll<Self::Output> {
+        let self_: &mut CloseFuture = self.get_mut();
+        // ToDo: Wait for data in the send queue to be acknowledged, as Windows sockets do not expose its length.
+        match self_.socket.borrow().shutdown(Shutdown::Both) {
+            Ok(_) => {
+                trace!("socket closed ({:?})", self_.qd);
+                Poll::Ready(Ok(()))
+            },
+            // Sockets that are not connected have nothing to flush.
+            Err(e) if e.kind() == ErrorKind::NotConnected => Poll::Ready(Ok(())),
+            Err(e) => {
+                warn!("failed to close socket ({:?})", e);
+                Poll::Ready(Err(Fail::new(
+                    e.raw_os_error().unwrap_or(libc::EIO),
+                    "operation failed",
+                )))
+            },
+        }
+    }
+}
@@ -14,11 +14,8 @@ use ::socket2::Socket;
 use ::std::{
     cell::RefCell,
     future::Future,
-    mem::{
-        transmute,
-        MaybeUninit,
-    },
-    net::SocketAddrV4,
+    mem::MaybeUninit,
+    net::SocketAddr,
     pin::Pin,
     rc::Rc,
     task::{
@@ -45,6 +42,12 @@ pub struct PopFuture {
     qd: QDesc,
     // Underlying socket.
     socket: Rc<RefCell<Socket>>,
+    /// Minimum number of bytes to pop.
+    min_size: usize,
+    /// Buffer holding the bytes received so far.
+    bytes: Vec<u8>,
+    /// Number of bytes received so far.
+    len: usize,
 }
 
 //==============================================================================
@@ -53,9 +56,16 @@ pub struct PopFuture {
 
 /// Associate Functions for Pop Operation Descriptors
 impl PopFuture {
-    /// Creates a descriptor for a pop operation.
-    pub fn new(qd: QDesc, socket: Rc<RefCell<Socket>>) -> Self {
-        Self { qd, socket }
+    /// Creates a descriptor for a pop operation. At most `max_size` bytes are popped, and the operation does not
+    /// complete until at least `min_size` bytes are, unless the end of the stream is reached.
+    pub fn new(qd: QDesc, socket: Rc<RefCell<Socket>>, min_size: usize, max_size: Option<usize>) -> Self {
+        Self {
+            qd,
+            socket,
+            min_size,
+            bytes: vec![0; max_size.unwrap_or(POP_SIZE)],
+            len: 0,
+        }
     }
 
     /// Returns the queue descriptor associated to the target [PopFuture].
@@ -70,32 +80,36 @@ impl PopFuture {
 
 /// Future Trait Implementation for Pop Operation Descriptors
 impl Future for PopFuture {
-    type Output = Result<(Option<SocketAddrV4>, DemiBuffer), Fail>;
+    type Output = Result<(Option<SocketAddr>, DemiBuffer), Fail>;
 
     /// Polls the target [PopFuture].
     fn poll(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
         let self_: &mut PopFuture = self.get_mut();
-        let mut bytes: [MaybeUninit<u8>; POP_SIZE] = MaybeUninit::uninit_array();
-        match self_.socket.borrow().recv_from(&mut bytes[..]) {
-            // Operation completed.
-            Ok((nbytes, socketaddr)) => {
-                trace!("data received ({:?}/{:?} bytes)", nbytes, POP_SIZE);
-                unsafe {
-                    let bytes_recv: [u8; POP_SIZE] = transmute::<[MaybeUninit<u8>; POP_SIZE], [u8; POP_SIZE]>(bytes);
-                    let buf: DemiBuffer = DemiBuffer::from_slice(&bytes_recv[0..nbytes])?;
-                    Poll::Ready(Ok((socketaddr.as_socket_ipv4(), buf)))
-                }
-            },
-            // Operation in progress.
-            Err(e) if e.raw_os_error() == Some(WSAEWOULDBLOCK.0) => {
-                ctx.waker().wake_by_ref();
-                Poll::Pending
-            },
-            // Error.
-            Err(e) => {
-                trace!("pop failed ({:?})", e);
-                Poll::Ready(Err(Fail::new(e.kind() as i32, "operation failed")))
-            },
+        loop {
+            // Safety: the buffer is initialized already, and only initialized bytes are written to it.
+            let bytes: &mut [MaybeUninit<u8>] =
+                unsafe { &mut *(&mut self_.bytes[self_.len..] as *mut [u8] as *mut [MaybeUninit<u8>]) };
+            match self_.socket.borrow().recv_from(bytes) {
+                // Operation completed.
+                Ok((nbytes, socketaddr)) if nbytes == 0 || self_.len + nbytes >= self_.min_size => {
+                    self_.len += nbytes;
+                    trace!("data received ({:?}/{:?} bytes)", self_.len, self_.bytes.len());
+                    let buf: DemiBuffer = DemiBuffer::from_slice(&self_.bytes[0..self_.len])?;
+                    return Poll::Ready(Ok((socketaddr.as_socket(), buf)));
+                },
+                // Not enough data yet, so keep receiving.
+                Ok((nbytes, _)) => self_.len += nbytes,
+                // Operation in progress.
+                Err(e) if e.raw_os_error() == Some(WSAEWOULDBLOCK.0) => {
+                    ctx.waker().wake_by_ref();
+                    return Poll::Pending;
+                },
+                // Error.
+                Err(e) => {
+                    trace!("pop failed ({:?})", e);
+                    return Poll::Ready(Err(Fail::new(e.kind() as i32, "operation failed")));
+                },
+            }
         }
     }
 }
@@ -14,6 +14,7 @@ use ::socket2::Socket;
 use ::std::{
     cell::RefCell,
     future::Future,
+    io::IoSlice,
     pin::Pin,
     rc::Rc,
     task::{
@@ -65,10 +66,13 @@ impl Future for PushFuture {
     /// Polls the target [PushFuture].
     fn poll(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
         let self_: &mut PushFuture = self.get_mut();
-        match self_.socket.borrow().send(&self_.buf[..]) {
+        // Gather all segments of the buffer chain, so that they are sent at once without copying.
+        let iov: Vec<IoSlice> = self_.buf.segments().map(IoSlice::new).collect();
+        let len: usize = iov.iter().map(|seg| seg.len()).sum();
+        match self_.socket.borrow().send_vectored(&iov) {
             // Operation completed.
             Ok(nbytes) => {
-                trace!("data pushed ({:?}/{:?} bytes)", nbytes, self_.buf.len());
+                trace!("data pushed ({:?}/{:?} bytes)", nbytes, len);
                 Poll::Ready(Ok(()))
             },
             // Operation in progress.
@@ -17,6 +17,7 @@ use ::socket2::{
 use ::std::{
     cell::RefCell,
     future::Future,
+    io::IoSlice,
     pin::Pin,
     rc::Rc,
     task::{
@@ -70,10 +71,13 @@ impl Future for PushtoFuture {
     /// Polls the target [PushtoFuture].
     fn poll(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
         let self_: &mut PushtoFuture = self.get_mut();
-        match self_.socket.borrow().send_to(&self_.buf[..], &self_.addr) {
+        // Gather all segments of the buffer chain, so that they are sent in a single datagram without copying.
+        let iov: Vec<IoSlice> = self_.buf.segments().map(IoSlice::new).collect();
+        let len: usize = iov.iter().map(|seg| seg.len()).sum();
+        match self_.socket.borrow().send_to_vectored(&iov, &self_.addr) {
             // Operation completed.
             Ok(nbytes) => {
-                trace!("data pushed ({:?}/{:?} bytes)", nbytes, self_.buf.len());
+                trace!("data pushed ({:?}/{:?} bytes)", nbytes, len);
                 Poll::Ready(Ok(()))
             },
             // Operation in progress.
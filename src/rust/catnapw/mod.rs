@@ -16,6 +16,7 @@ pub use self::runtime::PosixRuntime;
 
 use self::futures::{
     accept::AcceptFuture,
+    close::CloseFuture,
     connect::ConnectFuture,
     pop::PopFuture,
     push::PushFuture,
@@ -23,15 +24,21 @@ use self::futures::{
     Operation,
 };
 use crate::{
-    demikernel::config::Config,
+    demikernel::{
+        config::Config,
+        libos::backend::LibOSBackend,
+    },
     inetstack::operations::OperationResult,
     pal::{
         constants::{
             AF_INET,
+            SHUT_RD,
+            SHUT_RDWR,
+            SHUT_WR,
             SOCK_DGRAM,
             SOCK_STREAM,
         },
-        data_structures::SockAddrIn,
+        functions::create_sock_addr_storage,
     },
     runtime::{
         fail::Fail,
@@ -39,13 +46,22 @@ use crate::{
             DemiBuffer,
             MemoryRuntime,
         },
+        network::types::{
+            SocketOption,
+            SocketOptionName,
+        },
         queue::IoQueueTable,
         types::{
             demi_accept_result_t,
             demi_opcode_t,
             demi_qr_value_t,
             demi_qresult_t,
+            demi_qstats_t,
             demi_sgarray_t,
+            DEMI_POLLACCEPT,
+            DEMI_POLLHUP,
+            DEMI_POLLIN,
+            DEMI_POLLOUT,
         },
         QDesc,
         QToken,
@@ -55,9 +71,10 @@ use crate::{
 };
 use ::libc::{
     c_int,
-    sockaddr,
+    EAFNOSUPPORT,
     EBADF,
     EINVAL,
+    ENOPROTOOPT,
     ENOTSUP,
 };
 use ::socket2::{
@@ -69,22 +86,34 @@ use ::socket2::{
 };
 use ::std::{
     any::Any,
-    cell::RefCell,
+    cell::{
+        Ref,
+        RefCell,
+    },
     collections::HashMap,
+    convert::TryFrom,
+    io,
     mem,
     net::{
-        Ipv4Addr,
         Shutdown,
-        SocketAddrV4,
+        SocketAddr,
     },
+    os::windows::io::AsRawSocket,
     rc::Rc,
 };
-use ::windows::Win32::{
-    Foundation::CHAR,
-    Networking::WinSock::{
-        self,
-        IN_ADDR,
-        SOCKADDR,
+use ::windows::{
+    core::PSTR,
+    Win32::Networking::WinSock::{
+        getsockopt,
+        WSAPoll,
+        POLLERR,
+        POLLHUP,
+        POLLRDNORM,
+        POLLWRNORM,
+        SOCKET,
+        SOL_SOCKET,
+        SO_ACCEPTCONN,
+        WSAPOLLFD,
     },
 };
 
@@ -156,7 +185,7 @@ impl CatnapWLibOS {
     }
 
     /// Binds a socket to a local endpoint.
-    pub fn bind(&mut self, qd: QDesc, local: SocketAddrV4) -> Result<(), Fail> {
+    pub fn bind(&mut self, qd: QDesc, local: SocketAddr) -> Result<(), Fail> {
         trace!("bind() qd={:?}, local={:?}", qd, local);
 
         // Issue bind operation.
@@ -209,7 +238,7 @@ impl CatnapWLibOS {
     }
 
     /// Establishes a connection to a remote endpoint.
-    pub fn connect(&mut self, qd: QDesc, remote: SocketAddrV4) -> Result<QToken, Fail> {
+    pub fn connect(&mut self, qd: QDesc, remote: SocketAddr) -> Result<QToken, Fail> {
         trace!("connect() qd={:?}, remote={:?}", qd, remote);
 
         // Issue connect operation.
@@ -239,6 +268,164 @@ impl CatnapWLibOS {
         }
     }
 
+    /// Asynchronously closes a socket. The operation completes once data that was pushed to the socket is acknowledged
+    /// by the remote peer.
+    pub fn async_close(&mut self, qd: QDesc) -> Result<QToken, Fail> {
+        trace!("async_close() qd={:?}", qd);
+
+        // Issue close operation. The socket is closed once the operation completes and drops it.
+        match self.sockets.remove(&qd) {
+            Some(socket) => {
+                let future: Operation = Operation::from(CloseFuture::new(qd, socket));
+                let handle: SchedulerHandle = match self.runtime.scheduler.insert(future) {
+                    Some(handle) => handle,
+                    None => return Err(Fail::new(libc::EAGAIN, "cannot schedule co-routine")),
+                };
+                Ok(handle.into_raw().into())
+            },
+            _ => Err(Fail::new(EBADF, "invalid queue descriptor")),
+        }
+    }
+
+    /// Shuts down the sending half, the receiving half, or both halves of a socket.
+    pub fn shutdown(&mut self, qd: QDesc, how: c_int) -> Result<(), Fail> {
+        trace!("shutdown() qd={:?}, how={:?}", qd, how);
+
+        // Parse shutdown direction.
+        let how: Shutdown = match how {
+            SHUT_RD => Shutdown::Read,
+            SHUT_WR => Shutdown::Write,
+            SHUT_RDWR => Shutdown::Both,
+            _ => return Err(Fail::new(EINVAL, "invalid value for how")),
+        };
+
+        match self.sockets.get(&qd) {
+            Some(socket) => match socket.borrow().shutdown(how) {
+                Ok(_) => Ok(()),
+                Err(e) => Err(Fail::new(
+                    e.raw_os_error().unwrap_or(libc::EIO),
+                    "failed to shut down socket",
+                )),
+            },
+            _ => Err(Fail::new(EBADF, "invalid queue descriptor")),
+        }
+    }
+
+    /// Gets the statistics of a socket. Windows sockets do not expose those of the underlying protocol.
+    pub fn get_stats(&mut self, qd: QDesc) -> Result<demi_qstats_t, Fail> {
+        trace!("get_stats() qd={:?}", qd);
+        match self.sockets.get(&qd) {
+            Some(_) => Ok(demi_qstats_t::default()),
+            _ => Err(Fail::new(EBADF, "invalid queue descriptor")),
+        }
+    }
+
+    /// Checks which operations on a socket would complete right away, as reported by Windows Sockets.
+    pub fn readiness(&mut self, qd: QDesc) -> Result<u32, Fail> {
+        trace!("readiness() qd={:?}", qd);
+        match self.sockets.get(&qd) {
+            Some(socket) => {
+                let socket: SOCKET = SOCKET(socket.borrow().as_raw_socket() as usize);
+                let mut pollfd: WSAPOLLFD = WSAPOLLFD {
+                    fd: socket,
+                    events: (POLLRDNORM | POLLWRNORM) as i16,
+                    revents: 0,
+                };
+                if unsafe { WSAPoll(&mut pollfd, 1, 0) } != 1 {
+                    return Ok(0);
+                }
+                let revents: u16 = pollfd.revents as u16;
+                let mut events: u32 = 0;
+                if revents & POLLRDNORM != 0 {
+                    events |= if is_listening(socket) {
+                        DEMI_POLLACCEPT
+                    } else {
+                        DEMI_POLLIN
+                    };
+                }
+                if revents & POLLWRNORM != 0 {
+                    events |= DEMI_POLLOUT;
+                }
+                if revents & (POLLHUP | POLLERR) != 0 {
+                    events |= DEMI_POLLHUP;
+                }
+                Ok(events)
+            },
+            _ => Err(Fail::new(EBADF, "invalid queue descriptor")),
+        }
+    }
+
+    /// Sets an option on a socket.
+    pub fn setsockopt(&mut self, qd: QDesc, level: c_int, optname: c_int, optval: &[u8]) -> Result<(), Fail> {
+        trace!("setsockopt() qd={:?}, level={:?}, optname={:?}", qd, level, optname);
+
+        // Parse socket option.
+        let name: SocketOptionName = SocketOptionName::try_from((level, optname))?;
+        let option: SocketOption = SocketOption::decode(name, optval)?;
+
+        // Issue setsockopt operation.
+        match self.sockets.get(&qd) {
+            Some(socket) => {
+                let socket: Ref<Socket> = socket.borrow();
+                let ret: io::Result<()> = match option {
+                    SocketOption::ReuseAddress(value) => socket.set_reuse_address(value),
+                    SocketOption::ReceiveBufferSize(value) => socket.set_recv_buffer_size(value),
+                    SocketOption::SendBufferSize(value) => socket.set_send_buffer_size(value),
+                    SocketOption::Linger(value) => socket.set_linger(value),
+                    SocketOption::KeepAlive(value) => socket.set_keepalive(value),
+                    SocketOption::NoDelay(value) => socket.set_nodelay(value),
+                    SocketOption::MaxSegmentSize(_) => {
+                        return Err(Fail::new(ENOPROTOOPT, "socket option not supported"));
+                    },
+                };
+                match ret {
+                    Ok(_) => Ok(()),
+                    Err(e) => Err(Fail::new(
+                        e.raw_os_error().unwrap_or(libc::EIO),
+                        "failed to set socket option",
+                    )),
+                }
+            },
+            _ => Err(Fail::new(EBADF, "invalid queue descriptor")),
+        }
+    }
+
+    /// Gets the value of an option on a socket.
+    pub fn getsockopt(&mut self, qd: QDesc, level: c_int, optname: c_int, optval: &mut [u8]) -> Result<usize, Fail> {
+        trace!("getsockopt() qd={:?}, level={:?}, optname={:?}", qd, level, optname);
+
+        // Parse socket option.
+        let name: SocketOptionName = SocketOptionName::try_from((level, optname))?;
+
+        // Issue getsockopt operation.
+        match self.sockets.get(&qd) {
+            Some(socket) => {
+                let socket: Ref<Socket> = socket.borrow();
+                let ret: io::Result<SocketOption> = match name {
+                    SocketOptionName::ReuseAddress => socket.reuse_address().map(SocketOption::ReuseAddress),
+                    SocketOptionName::ReceiveBufferSize => {
+                        socket.recv_buffer_size().map(SocketOption::ReceiveBufferSize)
+                    },
+                    SocketOptionName::SendBufferSize => socket.send_buffer_size().map(SocketOption::SendBufferSize),
+                    SocketOptionName::Linger => socket.linger().map(SocketOption::Linger),
+                    SocketOptionName::KeepAlive => socket.keepalive().map(SocketOption::KeepAlive),
+                    SocketOptionName::NoDelay => socket.nodelay().map(SocketOption::NoDelay),
+                    SocketOptionName::MaxSegmentSize => {
+                        return Err(Fail::new(ENOPROTOOPT, "socket option not supported"));
+                    },
+                };
+                match ret {
+                    Ok(option) => option.encode(optval),
+                    Err(e) => Err(Fail::new(
+                        e.raw_os_error().unwrap_or(libc::EIO),
+                        "failed to get socket option",
+                    )),
+                }
+            },
+            _ => Err(Fail::new(EBADF, "invalid queue descriptor")),
+        }
+    }
+
     // Handles a push operation.
     fn do_push(&mut self, qd: QDesc, buf: DemiBuffer) -> Result<QToken, Fail> {
         match self.sockets.get(&qd) {
@@ -254,13 +441,53 @@ impl CatnapWLibOS {
         }
     }
 
+    /// Gets the local address of a socket.
+    pub fn getsockname(&mut self, qd: QDesc) -> Result<SocketAddr, Fail> {
+        trace!("getsockname() qd={:?}", qd);
+
+        // Issue getsockname operation.
+        match self.sockets.get(&qd) {
+            Some(socket) => match socket.borrow().local_addr() {
+                Ok(addr) => match addr.as_socket() {
+                    Some(addr) => Ok(addr),
+                    None => Err(Fail::new(EAFNOSUPPORT, "communication domain not supported")),
+                },
+                Err(e) => Err(Fail::new(
+                    e.raw_os_error().unwrap_or(libc::EIO),
+                    "failed to get socket address",
+                )),
+            },
+            _ => Err(Fail::new(EBADF, "invalid queue descriptor")),
+        }
+    }
+
+    /// Gets the remote address of a connected socket.
+    pub fn getpeername(&mut self, qd: QDesc) -> Result<SocketAddr, Fail> {
+        trace!("getpeername() qd={:?}", qd);
+
+        // Issue getpeername operation.
+        match self.sockets.get(&qd) {
+            Some(socket) => match socket.borrow().peer_addr() {
+                Ok(addr) => match addr.as_socket() {
+                    Some(addr) => Ok(addr),
+                    None => Err(Fail::new(EAFNOSUPPORT, "communication domain not supported")),
+                },
+                Err(e) => Err(Fail::new(
+                    e.raw_os_error().unwrap_or(libc::EIO),
+                    "failed to get peer address",
+                )),
+            },
+            _ => Err(Fail::new(EBADF, "invalid queue descriptor")),
+        }
+    }
+
     /// Pushes a scatter-gather array to a socket.
     pub fn push(&mut self, qd: QDesc, sga: &demi_sgarray_t) -> Result<QToken, Fail> {
         trace!("push() qd={:?}", qd);
 
         match self.runtime.clone_sgarray(sga) {
             Ok(buf) => {
-                if buf.len() == 0 {
+                if buf.total_len() == 0 {
                     return Err(Fail::new(EINVAL, "zero-length buffer"));
                 }
 
@@ -272,7 +499,7 @@ impl CatnapWLibOS {
     }
 
     /// Handles a pushto operation.
-    fn do_pushto(&mut self, qd: QDesc, buf: DemiBuffer, remote: SocketAddrV4) -> Result<QToken, Fail> {
+    fn do_pushto(&mut self, qd: QDesc, buf: DemiBuffer, remote: SocketAddr) -> Result<QToken, Fail> {
         match self.sockets.get(&qd) {
             Some(socket) => {
                 let addr: SockAddr = parse_addr(remote);
@@ -288,12 +515,12 @@ impl CatnapWLibOS {
     }
 
     /// Pushes a scatter-gather array to a socket.
-    pub fn pushto(&mut self, qd: QDesc, sga: &demi_sgarray_t, remote: SocketAddrV4) -> Result<QToken, Fail> {
+    pub fn pushto(&mut self, qd: QDesc, sga: &demi_sgarray_t, remote: SocketAddr) -> Result<QToken, Fail> {
         trace!("pushto() qd={:?}", qd);
 
         match self.runtime.clone_sgarray(sga) {
             Ok(buf) => {
-                if buf.len() == 0 {
+                if buf.total_len() == 0 {
                     return Err(Fail::new(EINVAL, "zero-length buffer"));
                 }
 
@@ -304,14 +531,19 @@ impl CatnapWLibOS {
         }
     }
 
-    /// Pops data from a socket.
-    pub fn pop(&mut self, qd: QDesc) -> Result<QToken, Fail> {
-        trace!("pop() qd={:?}", qd);
+    /// Pops data from a socket, within size bounds (see [PopFuture::new]).
+    pub fn pop(&mut self, qd: QDesc, min_size: usize, max_size: Option<usize>) -> Result<QToken, Fail> {
+        trace!("pop() qd={:?}, min_size={:?}, max_size={:?}", qd, min_size, max_size);
 
         // Issue pop operation.
         match self.sockets.get(&qd) {
             Some(socket) => {
-                let future: Operation = Operation::from(PopFuture::new(qd, socket.clone()));
+                // Minimum sizes only apply to stream sockets.
+                let min_size: usize = match socket.borrow().r#type() {
+                    Ok(Type::STREAM) => min_size,
+                    _ => 0,
+                };
+                let future: Operation = Operation::from(PopFuture::new(qd, socket.clone(), min_size, max_size));
                 let handle: SchedulerHandle = match self.runtime.scheduler.insert(future) {
                     Some(handle) => handle,
                     None => return Err(Fail::new(libc::EAGAIN, "cannot schedule co-routine")),
@@ -335,13 +567,28 @@ impl CatnapWLibOS {
         self.runtime.free_sgarray(sga)
     }
 
-    /// Takes out the [OperationResult] associated with the target [SchedulerHandle].
-    fn take_result(&mut self, handle: SchedulerHandle) -> (QDesc, OperationResult) {
+    /// Appends the segments of a scatter-gather array to another one, and releases the former.
+    pub fn sgaappend(&self, sga: &mut demi_sgarray_t, tail: demi_sgarray_t) -> Result<(), Fail> {
+        trace!("sgaappend()");
+        self.runtime.append_sgarray(sga, tail)
+    }
+
+    /// Takes out the [Operation] associated with the target [SchedulerHandle].
+    fn take_operation(&mut self, handle: SchedulerHandle) -> Operation {
         let boxed_future: Box<dyn Any> = self.runtime.scheduler.take(handle).as_any();
-        let boxed_concrete_type: Operation = *boxed_future.downcast::<Operation>().expect("Wrong type!");
+        *boxed_future.downcast::<Operation>().expect("Wrong type!")
+    }
 
+    /// Takes out the [OperationResult] associated with the target [Operation].
+    fn take_result(&mut self, operation: Operation) -> (QDesc, OperationResult) {
+        let closing: bool = matches!(operation, Operation::Close(..));
         let (qd, new_qd, new_socket, qr): (QDesc, Option<QDesc>, Option<Socket>, OperationResult) =
-            boxed_concrete_type.get_result();
+            operation.get_result();
+
+        // Release queue descriptor of closed socket.
+        if closing {
+            self.qtable.free(qd);
+        }
 
         // Handle accept operation.
         if let Some(new_qd) = new_qd {
@@ -368,45 +615,212 @@ impl CatnapWLibOS {
         }
     }
 
-    pub fn pack_result(&mut self, handle: SchedulerHandle, qt: QToken) -> Result<demi_qresult_t, Fail> {
-        let (qd, r): (QDesc, OperationResult) = self.take_result(handle);
+    /// Returns the queue tokens of all operations that have completed, but whose results were not taken out yet.
+    pub fn completed(&self) -> Vec<QToken> {
+        self.runtime
+            .scheduler
+            .completed()
+            .into_iter()
+            .map(QToken::from)
+            .collect()
+    }
+
+    pub fn pack_result(&mut self, handle: SchedulerHandle, qt: QToken) -> Result<(demi_qresult_t, Option<Fail>), Fail> {
+        let operation: Operation = self.take_operation(handle);
+        let (qd, r): (QDesc, OperationResult) = self.take_result(operation);
         Ok(pack_result(&self.runtime, r, qd, qt.into()))
     }
+
+    /// Cancels the operation associated with the target [SchedulerHandle]. If the operation has already completed, its
+    /// result is packed as is. Otherwise, it completes with `ECANCELED`.
+    pub fn cancel(&mut self, handle: SchedulerHandle, qt: QToken) -> Result<(demi_qresult_t, Option<Fail>), Fail> {
+        trace!("cancel() qt={:?}", qt);
+        let mut operation: Operation = self.take_operation(handle);
+        operation.cancel();
+        let (qd, r): (QDesc, OperationResult) = self.take_result(operation);
+        Ok(pack_result(&self.runtime, r, qd, qt.into()))
+    }
+}
+
+//==============================================================================
+// Trait Implementations
+//==============================================================================
+
+/// LibOS Backend Trait Implementation for CatnapW LibOS
+impl LibOSBackend for CatnapWLibOS {
+    fn socket(&mut self, domain: libc::c_int, socket_type: libc::c_int, protocol: libc::c_int) -> Result<QDesc, Fail> {
+        CatnapWLibOS::socket(self, domain, socket_type, protocol)
+    }
+
+    fn bind(&mut self, sockqd: QDesc, local: SocketAddr) -> Result<(), Fail> {
+        CatnapWLibOS::bind(self, sockqd, local)
+    }
+
+    fn listen(&mut self, sockqd: QDesc, backlog: usize) -> Result<(), Fail> {
+        CatnapWLibOS::listen(self, sockqd, backlog)
+    }
+
+    fn accept(&mut self, sockqd: QDesc) -> Result<QToken, Fail> {
+        CatnapWLibOS::accept(self, sockqd)
+    }
+
+    fn connect(&mut self, sockqd: QDesc, remote: SocketAddr) -> Result<QToken, Fail> {
+        CatnapWLibOS::connect(self, sockqd, remote)
+    }
+
+    fn setsockopt(
+        &mut self,
+        sockqd: QDesc,
+        level: libc::c_int,
+        optname: libc::c_int,
+        optval: &[u8],
+    ) -> Result<(), Fail> {
+        CatnapWLibOS::setsockopt(self, sockqd, level, optname, optval)
+    }
+
+    fn getsockopt(
+        &mut self,
+        sockqd: QDesc,
+        level: libc::c_int,
+        optname: libc::c_int,
+        optval: &mut [u8],
+    ) -> Result<usize, Fail> {
+        CatnapWLibOS::getsockopt(self, sockqd, level, optname, optval)
+    }
+
+    fn getsockname(&mut self, sockqd: QDesc) -> Result<SocketAddr, Fail> {
+        CatnapWLibOS::getsockname(self, sockqd)
+    }
+
+    fn getpeername(&mut self, sockqd: QDesc) -> Result<SocketAddr, Fail> {
+        CatnapWLibOS::getpeername(self, sockqd)
+    }
+
+    fn pushto(&mut self, sockqd: QDesc, sga: &demi_sgarray_t, to: SocketAddr) -> Result<QToken, Fail> {
+        CatnapWLibOS::pushto(self, sockqd, sga, to)
+    }
+
+    fn shutdown(&mut self, sockqd: QDesc, how: libc::c_int) -> Result<(), Fail> {
+        CatnapWLibOS::shutdown(self, sockqd, how)
+    }
+
+    fn close(&mut self, qd: QDesc) -> Result<(), Fail> {
+        CatnapWLibOS::close(self, qd)
+    }
+
+    fn async_close(&mut self, qd: QDesc) -> Result<QToken, Fail> {
+        CatnapWLibOS::async_close(self, qd)
+    }
+
+    fn get_stats(&mut self, qd: QDesc) -> Result<demi_qstats_t, Fail> {
+        CatnapWLibOS::get_stats(self, qd)
+    }
+
+    fn readiness(&mut self, qd: QDesc) -> Result<u32, Fail> {
+        CatnapWLibOS::readiness(self, qd)
+    }
+
+    fn push(&mut self, qd: QDesc, sga: &demi_sgarray_t) -> Result<QToken, Fail> {
+        CatnapWLibOS::push(self, qd, sga)
+    }
+
+    fn pop(&mut self, qd: QDesc, min_size: usize, max_size: Option<usize>) -> Result<QToken, Fail> {
+        CatnapWLibOS::pop(self, qd, min_size, max_size)
+    }
+
+    fn poll(&mut self) {
+        CatnapWLibOS::poll(self)
+    }
+
+    fn schedule(&mut self, qt: QToken) -> Result<SchedulerHandle, Fail> {
+        CatnapWLibOS::schedule(self, qt)
+    }
+
+    fn completed(&self) -> Vec<QToken> {
+        CatnapWLibOS::completed(self)
+    }
+
+    fn pack_result(&mut self, handle: SchedulerHandle, qt: QToken) -> Result<(demi_qresult_t, Option<Fail>), Fail> {
+        CatnapWLibOS::pack_result(self, handle, qt)
+    }
+
+    fn cancel(&mut self, handle: SchedulerHandle, qt: QToken) -> Result<(demi_qresult_t, Option<Fail>), Fail> {
+        CatnapWLibOS::cancel(self, handle, qt)
+    }
+
+    fn sgaalloc(&self, size: usize) -> Result<demi_sgarray_t, Fail> {
+        CatnapWLibOS::sgaalloc(self, size)
+    }
+
+    fn sgafree(&self, sga: demi_sgarray_t) -> Result<(), Fail> {
+        CatnapWLibOS::sgafree(self, sga)
+    }
+
+    fn sgaappend(&self, sga: &mut demi_sgarray_t, tail: demi_sgarray_t) -> Result<(), Fail> {
+        CatnapWLibOS::sgaappend(self, sga, tail)
+    }
+
+    fn memory(&self) -> Rc<dyn MemoryRuntime> {
+        Rc::new(self.runtime.clone())
+    }
 }
 
 //==============================================================================
 // Standalone Functions
 //==============================================================================
 
-/// Parses a [SocketAddrV4] into a [SockAddr].
-fn parse_addr(endpoint: SocketAddrV4) -> SockAddr {
-    let addr: &Ipv4Addr = endpoint.ip();
-    let port: u16 = endpoint.port().into();
-    let ipv4: SocketAddrV4 = SocketAddrV4::new(*addr, port);
-    SockAddr::from(ipv4)
+/// Parses a [SocketAddr] into a [SockAddr].
+fn parse_addr(endpoint: SocketAddr) -> SockAddr {
+    SockAddr::from(endpoint)
+}
+
+/// Checks if a socket is a listening one, with the SO_ACCEPTCONN option.
+fn is_listening(socket: SOCKET) -> bool {
+    let mut value: i32 = 0;
+    let mut option_len: i32 = mem::size_of::<i32>() as i32;
+    match unsafe {
+        getsockopt(
+            socket,
+            SOL_SOCKET as i32,
+            SO_ACCEPTCONN as i32,
+            PSTR(&mut value as *mut i32 as *mut u8),
+            &mut option_len,
+        )
+    } {
+        0 => value != 0,
+        _ => false,
+    }
 }
 
 /// Packs a [OperationResult] into a [demi_qresult_t].
-fn pack_result(rt: &PosixRuntime, result: OperationResult, qd: QDesc, qt: u64) -> demi_qresult_t {
-    match result {
+fn pack_result(rt: &PosixRuntime, result: OperationResult, qd: QDesc, qt: u64) -> (demi_qresult_t, Option<Fail>) {
+    let qr: demi_qresult_t = match result {
         OperationResult::Connect => demi_qresult_t {
             qr_opcode: demi_opcode_t::DEMI_OPC_CONNECT,
             qr_qd: qd.into(),
             qr_qt: qt,
+            qr_ret: 0,
             qr_value: unsafe { mem::zeroed() },
         },
-        OperationResult::Accept(new_qd) => {
-            let sin: sockaddr = unsafe { mem::zeroed() };
+        OperationResult::Close => demi_qresult_t {
+            qr_opcode: demi_opcode_t::DEMI_OPC_CLOSE,
+            qr_qd: qd.into(),
+            qr_qt: qt,
+            qr_ret: 0,
+            qr_value: unsafe { mem::zeroed() },
+        },
+        OperationResult::Accept((new_qd, addr)) => {
             let qr_value: demi_qr_value_t = demi_qr_value_t {
                 ares: demi_accept_result_t {
                     qd: new_qd.into(),
-                    addr: sin,
+                    addr: create_sock_addr_storage(&addr).0,
                 },
             };
             demi_qresult_t {
                 qr_opcode: demi_opcode_t::DEMI_OPC_ACCEPT,
                 qr_qd: qd.into(),
                 qr_qt: qt,
+                qr_ret: 0,
                 qr_value,
             }
         },
@@ -414,52 +828,53 @@ fn pack_result(rt: &PosixRuntime, result: OperationResult, qd: QDesc, qt: u64) -
             qr_opcode: demi_opcode_t::DEMI_OPC_PUSH,
             qr_qd: qd.into(),
             qr_qt: qt,
+            qr_ret: 0,
             qr_value: unsafe { mem::zeroed() },
         },
         OperationResult::Pop(addr, bytes) => match rt.into_sgarray(bytes) {
             Ok(mut sga) => {
                 if let Some(endpoint) = addr {
-                    let saddr: SockAddrIn = {
-                        // TODO: check the following byte order conversion.
-                        SockAddrIn {
-                            sin_family: AF_INET as u16,
-                            sin_port: endpoint.port().into(),
-                            sin_addr: IN_ADDR {
-                                S_un: (WinSock::IN_ADDR_0 {
-                                    S_addr: u32::from_le_bytes(endpoint.ip().octets()),
-                                }),
-                            },
-                            sin_zero: [CHAR(0); 8],
-                        }
-                    };
-                    sga.sga_addr = unsafe { mem::transmute::<SockAddrIn, SOCKADDR>(saddr) };
+                    sga.sga_addr = create_sock_addr_storage(&endpoint).0;
                 }
                 let qr_value: demi_qr_value_t = demi_qr_value_t { sga };
                 demi_qresult_t {
                     qr_opcode: demi_opcode_t::DEMI_OPC_POP,
                     qr_qd: qd.into(),
                     qr_qt: qt,
+                    qr_ret: 0,
                     qr_value,
                 }
             },
             Err(e) => {
                 warn!("Operation Failed: {:?}", e);
+                return (
+                    demi_qresult_t {
+                        qr_opcode: demi_opcode_t::DEMI_OPC_FAILED,
+                        qr_qd: qd.into(),
+                        qr_qt: qt,
+                        qr_ret: e.errno,
+                        qr_value: unsafe { mem::zeroed() },
+                    },
+                    Some(e),
+                );
+            },
+        },
+        OperationResult::AcceptUnix(..) | OperationResult::PopUnix(..) => {
+            unreachable!("unix domain sockets are not supported")
+        },
+        OperationResult::Failed(e) => {
+            warn!("Operation Failed: {:?}", e);
+            return (
                 demi_qresult_t {
                     qr_opcode: demi_opcode_t::DEMI_OPC_FAILED,
                     qr_qd: qd.into(),
                     qr_qt: qt,
+                    qr_ret: e.errno,
                     qr_value: unsafe { mem::zeroed() },
-                }
-            },
-        },
-        OperationResult::Failed(e) => {
-            warn!("Operation Failed: {:?}", e);
-            demi_qresult_t {
-                qr_opcode: demi_opcode_t::DEMI_OPC_FAILED,
-                qr_qd: qd.into(),
-                qr_qt: qt,
-                qr_value: unsafe { mem::zeroed() },
-            }
+                },
+                Some(e),
+            );
         },
-    }
+    };
+    (qr, None)
 }
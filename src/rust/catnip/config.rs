@@ -6,99 +6,157 @@
 //======================================================================================================================
 
 use crate::{
-    demikernel::config::Config,
-    runtime::network::types::MacAddress,
+    demikernel::config::{
+        Config,
+        Section,
+    },
+    runtime::{
+        fail::Fail,
+        network::{
+            consts::{
+                DEFAULT_MSS,
+                MAX_MSS,
+                MIN_MSS,
+            },
+            types::MacAddress,
+        },
+    },
 };
-use ::anyhow::Error;
 use ::std::{
     collections::HashMap,
+    env,
     ffi::CString,
     net::Ipv4Addr,
+    str::FromStr,
 };
-use ::yaml_rust::Yaml;
+
+//======================================================================================================================
+// Constants
+//======================================================================================================================
+
+/// Keys of the "catnip" configuration section.
+const KEYS: &[&str] = &[
+    "my_ipv4_addr",
+    "my_link_addr",
+    "my_interface_name",
+    "arp_table",
+    "disable_arp",
+    "mtu",
+    "mss",
+    "tcp_checksum_offload",
+    "udp_checksum_offload",
+    "use_jumbo_frames",
+];
+
+/// Default MTU.
+const DEFAULT_MTU: u16 = 1500;
+
+//======================================================================================================================
+// Structures
+//======================================================================================================================
+
+/// Catnip configuration.
+pub struct CatnipConfig {
+    /// Local IPv4 address.
+    pub local_ipv4_addr: Ipv4Addr,
+    /// Arguments for initializing the DPDK EAL.
+    pub eal_init_args: Vec<CString>,
+    /// Static entries of the ARP table.
+    pub arp_table: HashMap<Ipv4Addr, MacAddress>,
+    /// Disable ARP?
+    pub disable_arp: bool,
+    /// Use jumbo frames?
+    pub use_jumbo_frames: bool,
+    /// Maximum transmission unit.
+    pub mtu: u16,
+    /// Maximum segment size for TCP.
+    pub mss: usize,
+    /// Offload TCP checksums?
+    pub tcp_checksum_offload: bool,
+    /// Offload UDP checksums?
+    pub udp_checksum_offload: bool,
+}
 
 //======================================================================================================================
 // Associated Functions
 //======================================================================================================================
 
-/// Catnip associated functions for Demikernel configuration object.
-impl Config {
-    /// Reads the "ARP table" parameter from the underlying configuration file.
-    pub fn arp_table(&self) -> HashMap<Ipv4Addr, MacAddress> {
-        // FIXME: this function should return a Result.
-        let mut arp_table: HashMap<Ipv4Addr, MacAddress> = HashMap::new();
-        if let Some(arp_table_obj) = self.0["catnip"]["arp_table"].as_hash() {
-            for (k, v) in arp_table_obj {
-                let link_addr_str: &str = k
-                    .as_str()
-                    .ok_or_else(|| anyhow::format_err!("Couldn't find ARP table link_addr in config"))
-                    .unwrap();
-                let link_addr = MacAddress::parse_str(link_addr_str).unwrap();
-                let ipv4_addr: Ipv4Addr = v
-                    .as_str()
-                    .ok_or_else(|| anyhow::format_err!("Couldn't find ARP table link_addr in config"))
-                    .unwrap()
-                    .parse()
-                    .unwrap();
-                arp_table.insert(ipv4_addr, link_addr);
-            }
-        }
-        arp_table
-    }
+/// Associated functions for Catnip configuration.
+impl CatnipConfig {
+    /// Reads and validates the "catnip" and "dpdk" sections of a Demikernel configuration.
+    ///
+    /// For backwards compatibility, the `MTU`, `MSS`, `TCP_CHECKSUM_OFFLOAD`, `UDP_CHECKSUM_OFFLOAD` and `USE_JUMBO`
+    /// environment variables are still honored, if the corresponding keys are missing.
+    pub fn new(config: &Config) -> Result<Self, Fail> {
+        let section: Section = config.section("catnip")?;
+        section.warn_unknown_keys(KEYS);
+        let dpdk: Section = config.section("dpdk")?;
+        dpdk.warn_unknown_keys(&["eal_init"]);
 
-    /// Reads the "DPDK EAL" parameter from the underlying configuration file.
-    pub fn eal_init_args(&self) -> Vec<CString> {
-        // FIXME: this function should return a Result.
-
-        match self.0["dpdk"]["eal_init"] {
-            Yaml::Array(ref arr) => arr
-                .iter()
-                .map(|a| {
-                    a.as_str()
-                        .ok_or_else(|| anyhow::format_err!("Non string argument"))
-                        .and_then(|s| CString::new(s).map_err(|e| e.into()))
-                })
-                .collect::<Result<Vec<_>, Error>>()
-                .unwrap(),
-            _ => panic!("Malformed YAML config"),
+        let local_ipv4_addr: Ipv4Addr = section.require("my_ipv4_addr")?;
+        if local_ipv4_addr.is_unspecified() || local_ipv4_addr.is_broadcast() {
+            return Err(section.invalid("my_ipv4_addr", "expected a unicast address"));
         }
-    }
 
-    /// Reads the "ARP Disable" parameter from the underlying configuration file.
-    pub fn disable_arp(&self) -> bool {
-        // TODO: this should be unified with arp_table().
-        // FIXME: this function should return a Result.
-        let mut disable_arp: bool = false;
-        if let Some(arp_disabled) = self.0["catnip"]["disable_arp"].as_bool() {
-            disable_arp = arp_disabled;
-        }
-        disable_arp
-    }
+        let eal_init_args: Vec<CString> = match dpdk.get_list::<String>("eal_init")? {
+            Some(args) => args
+                .into_iter()
+                .map(|arg| CString::new(arg).map_err(|_| dpdk.invalid("eal_init", "unexpected nul byte")))
+                .collect::<Result<_, _>>()?,
+            None => return Err(dpdk.invalid("eal_init", "missing arguments")),
+        };
 
-    /// Gets the "MTU" parameter from environment variables.
-    pub fn mtu(&self) -> u16 {
-        // FIXME: this function should return a Result.
-        ::std::env::var("MTU").unwrap().parse().unwrap()
-    }
+        let arp_table: HashMap<Ipv4Addr, MacAddress> = section
+            .get_map::<MacAddress, Ipv4Addr>("arp_table")?
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(link_addr, ipv4_addr)| (ipv4_addr, link_addr))
+            .collect();
+        let disable_arp: bool = section.get_or("disable_arp", false)?;
 
-    /// Gets the "MSS" parameter from environment variables.
-    pub fn mss(&self) -> usize {
-        // FIXME: this function should return a Result.
-        ::std::env::var("MSS").unwrap().parse().unwrap()
-    }
+        let mtu: u16 = match section.get("mtu")? {
+            Some(mtu) => mtu,
+            None => legacy_env(&section, "mtu", "MTU")?.unwrap_or(DEFAULT_MTU),
+        };
+        let mss: usize = match section.get("mss")? {
+            Some(mss) => mss,
+            None => legacy_env(&section, "mss", "MSS")?.unwrap_or(DEFAULT_MSS),
+        };
+        if !(MIN_MSS..=MAX_MSS).contains(&mss) {
+            return Err(section.invalid("mss", "out of range"));
+        }
 
-    /// Gets the "TCP_CHECKSUM_OFFLOAD" parameter from environment variables.
-    pub fn tcp_checksum_offload(&self) -> bool {
-        ::std::env::var("TCP_CHECKSUM_OFFLOAD").is_ok()
-    }
+        let tcp_checksum_offload: bool =
+            section.get_or("tcp_checksum_offload", env::var("TCP_CHECKSUM_OFFLOAD").is_ok())?;
+        let udp_checksum_offload: bool =
+            section.get_or("udp_checksum_offload", env::var("UDP_CHECKSUM_OFFLOAD").is_ok())?;
+        let use_jumbo_frames: bool = section.get_or("use_jumbo_frames", env::var("USE_JUMBO").is_ok())?;
 
-    /// Gets the "UDP_CHECKSUM_OFFLOAD" parameter from environment variables.
-    pub fn udp_checksum_offload(&self) -> bool {
-        ::std::env::var("UDP_CHECKSUM_OFFLOAD").is_ok()
+        Ok(Self {
+            local_ipv4_addr,
+            eal_init_args,
+            arp_table,
+            disable_arp,
+            use_jumbo_frames,
+            mtu,
+            mss,
+            tcp_checksum_offload,
+            udp_checksum_offload,
+        })
     }
+}
+
+//======================================================================================================================
+// Standalone Functions
+//======================================================================================================================
 
-    /// Gets the "USE_JUMBO" parameter from environment variables.
-    pub fn use_jumbo_frames(&self) -> bool {
-        ::std::env::var("USE_JUMBO").is_ok()
+/// Reads a legacy environment variable for a key of a configuration section, if it is set.
+fn legacy_env<T: FromStr>(section: &Section, key: &str, var: &str) -> Result<Option<T>, Fail> {
+    match env::var(var) {
+        Ok(value) => match value.parse() {
+            Ok(value) => Ok(Some(value)),
+            Err(_) => Err(section.invalid(key, &format!("failed to parse {} environment variable", var))),
+        },
+        Err(_) => Ok(None),
     }
 }
@@ -3,18 +3,9 @@
 
 use crate::{
     catnip::DPDKRuntime,
-    pal::{
-        constants::AF_INET,
-        data_structures::{
-            SockAddr,
-            SockAddrIn,
-        },
-        functions::{
-            create_sin_addr,
-            create_sin_zero,
-        },
-    },
+    pal::functions::create_sock_addr_storage,
     runtime::{
+        fail::Fail,
         memory::MemoryRuntime,
         types::{
             demi_accept_result_t,
@@ -31,26 +22,34 @@ use ::std::{
     rc::Rc,
 };
 
-pub fn pack_result(rt: Rc<DPDKRuntime>, result: OperationResult, qd: QDesc, qt: u64) -> demi_qresult_t {
-    match result {
+pub fn pack_result(rt: Rc<DPDKRuntime>, result: OperationResult, qd: QDesc, qt: u64) -> (demi_qresult_t, Option<Fail>) {
+    let qr: demi_qresult_t = match result {
         OperationResult::Connect => demi_qresult_t {
             qr_opcode: demi_opcode_t::DEMI_OPC_CONNECT,
             qr_qd: qd.into(),
             qr_qt: qt,
+            qr_ret: 0,
             qr_value: unsafe { mem::zeroed() },
         },
-        OperationResult::Accept(new_qd) => {
-            let sin = unsafe { mem::zeroed() };
+        OperationResult::Close => demi_qresult_t {
+            qr_opcode: demi_opcode_t::DEMI_OPC_CLOSE,
+            qr_qd: qd.into(),
+            qr_qt: qt,
+            qr_ret: 0,
+            qr_value: unsafe { mem::zeroed() },
+        },
+        OperationResult::Accept((new_qd, addr)) => {
             let qr_value = demi_qr_value_t {
                 ares: demi_accept_result_t {
                     qd: new_qd.into(),
-                    addr: sin,
+                    addr: create_sock_addr_storage(&addr).0,
                 },
             };
             demi_qresult_t {
                 qr_opcode: demi_opcode_t::DEMI_OPC_ACCEPT,
                 qr_qd: qd.into(),
                 qr_qt: qt,
+                qr_ret: 0,
                 qr_value,
             }
         },
@@ -58,48 +57,53 @@ pub fn pack_result(rt: Rc<DPDKRuntime>, result: OperationResult, qd: QDesc, qt:
             qr_opcode: demi_opcode_t::DEMI_OPC_PUSH,
             qr_qd: qd.into(),
             qr_qt: qt,
+            qr_ret: 0,
             qr_value: unsafe { mem::zeroed() },
         },
         OperationResult::Pop(addr, bytes) => match rt.into_sgarray(bytes) {
             Ok(mut sga) => {
                 if let Some(endpoint) = addr {
-                    let saddr: SockAddrIn = {
-                        // TODO: check the following byte order conversion.
-                        SockAddrIn {
-                            sin_family: AF_INET as u16,
-                            sin_port: endpoint.port().into(),
-                            sin_addr: create_sin_addr(&endpoint.ip().octets()),
-                            sin_zero: create_sin_zero(),
-                        }
-                    };
-                    sga.sga_addr = unsafe { mem::transmute::<SockAddrIn, SockAddr>(saddr) };
+                    sga.sga_addr = create_sock_addr_storage(&endpoint).0;
                 }
                 let qr_value = demi_qr_value_t { sga };
                 demi_qresult_t {
                     qr_opcode: demi_opcode_t::DEMI_OPC_POP,
                     qr_qd: qd.into(),
                     qr_qt: qt,
+                    qr_ret: 0,
                     qr_value,
                 }
             },
             Err(e) => {
                 warn!("Operation Failed: {:?}", e);
+                return (
+                    demi_qresult_t {
+                        qr_opcode: demi_opcode_t::DEMI_OPC_FAILED,
+                        qr_qd: qd.into(),
+                        qr_qt: qt,
+                        qr_ret: e.errno,
+                        qr_value: unsafe { mem::zeroed() },
+                    },
+                    Some(e),
+                );
+            },
+        },
+        OperationResult::AcceptUnix(..) | OperationResult::PopUnix(..) => {
+            unreachable!("unix domain sockets are not supported")
+        },
+        OperationResult::Failed(e) => {
+            warn!("Operation Failed: {:?}", e);
+            return (
                 demi_qresult_t {
                     qr_opcode: demi_opcode_t::DEMI_OPC_FAILED,
                     qr_qd: qd.into(),
                     qr_qt: qt,
+                    qr_ret: e.errno,
                     qr_value: unsafe { mem::zeroed() },
-                }
-            },
-        },
-        OperationResult::Failed(e) => {
-            warn!("Operation Failed: {:?}", e);
-            demi_qresult_t {
-                qr_opcode: demi_opcode_t::DEMI_OPC_FAILED,
-                qr_qd: qd.into(),
-                qr_qt: qt,
-                qr_value: unsafe { mem::zeroed() },
-            }
+                },
+                Some(e),
+            );
         },
-    }
+    };
+    (qr, None)
 }
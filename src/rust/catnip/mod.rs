@@ -10,11 +10,18 @@ pub mod runtime;
 //==============================================================================
 
 use self::{
+    config::CatnipConfig,
     interop::pack_result,
     runtime::DPDKRuntime,
 };
 use crate::{
-    demikernel::config::Config,
+    demikernel::{
+        config::Config,
+        libos::backend::{
+            require_ipv4,
+            LibOSBackend,
+        },
+    },
     inetstack::{
         operations::OperationResult,
         InetStack,
@@ -29,6 +36,7 @@ use crate::{
         },
         types::{
             demi_qresult_t,
+            demi_qstats_t,
             demi_sgarray_t,
         },
         QDesc,
@@ -40,13 +48,19 @@ use crate::{
     },
 };
 use ::std::{
-    net::SocketAddrV4,
+    net::{
+        SocketAddr,
+        SocketAddrV4,
+    },
     ops::{
         Deref,
         DerefMut,
     },
     rc::Rc,
-    time::Instant,
+    time::{
+        Duration,
+        Instant,
+    },
 };
 
 #[cfg(feature = "profiler")]
@@ -69,18 +83,19 @@ pub struct CatnipLibOS {
 
 /// Associate Functions for Catnip LibOS
 impl CatnipLibOS {
-    pub fn new(config: &Config) -> Self {
+    pub fn new(config: &Config) -> Result<Self, Fail> {
+        let config: CatnipConfig = CatnipConfig::new(config)?;
         load_mlx_driver();
         let rt: Rc<DPDKRuntime> = Rc::new(DPDKRuntime::new(
-            config.local_ipv4_addr(),
-            &config.eal_init_args(),
-            config.arp_table(),
-            config.disable_arp(),
-            config.use_jumbo_frames(),
-            config.mtu(),
-            config.mss(),
-            config.tcp_checksum_offload(),
-            config.udp_checksum_offload(),
+            config.local_ipv4_addr,
+            &config.eal_init_args,
+            config.arp_table,
+            config.disable_arp,
+            config.use_jumbo_frames,
+            config.mtu,
+            config.mss,
+            config.tcp_checksum_offload,
+            config.udp_checksum_offload,
         ));
         let now: Instant = Instant::now();
         let clock: TimerRc = TimerRc(Rc::new(Timer::new(now)));
@@ -98,11 +113,11 @@ impl CatnipLibOS {
             rt.arp_options.clone(),
         )
         .unwrap();
-        CatnipLibOS {
+        Ok(CatnipLibOS {
             inetstack,
             scheduler,
             rt,
-        }
+        })
     }
 
     /// Create a push request for Demikernel to asynchronously write data from `sga` to the
@@ -114,7 +129,7 @@ impl CatnipLibOS {
         trace!("push(): qd={:?}", qd);
         match self.rt.clone_sgarray(sga) {
             Ok(buf) => {
-                if buf.len() == 0 {
+                if buf.total_len() == 0 {
                     return Err(Fail::new(libc::EINVAL, "zero-length buffer"));
                 }
                 let future = self.do_push(qd, buf)?;
@@ -135,7 +150,7 @@ impl CatnipLibOS {
         trace!("pushto2(): qd={:?}", qd);
         match self.rt.clone_sgarray(sga) {
             Ok(buf) => {
-                if buf.len() == 0 {
+                if buf.total_len() == 0 {
                     return Err(Fail::new(libc::EINVAL, "zero-length buffer"));
                 }
                 let future = self.do_pushto(qd, buf, to)?;
@@ -157,11 +172,24 @@ impl CatnipLibOS {
         }
     }
 
-    pub fn pack_result(&mut self, handle: SchedulerHandle, qt: QToken) -> Result<demi_qresult_t, Fail> {
+    /// Returns the queue tokens of all operations that have completed, but whose results were not taken out yet.
+    pub fn completed(&self) -> Vec<QToken> {
+        self.scheduler.completed().into_iter().map(QToken::from).collect()
+    }
+
+    pub fn pack_result(&mut self, handle: SchedulerHandle, qt: QToken) -> Result<(demi_qresult_t, Option<Fail>), Fail> {
         let (qd, r): (QDesc, OperationResult) = self.take_operation(handle);
         Ok(pack_result(self.rt.clone(), r, qd, qt.into()))
     }
 
+    /// Cancels the operation associated with the target [SchedulerHandle]. If the operation has already completed, its
+    /// result is packed as is. Otherwise, it completes with `ECANCELED`.
+    pub fn cancel(&mut self, handle: SchedulerHandle, qt: QToken) -> Result<(demi_qresult_t, Option<Fail>), Fail> {
+        trace!("cancel() qt={:?}", qt);
+        let (qd, r): (QDesc, OperationResult) = self.cancel_operation(handle);
+        Ok(pack_result(self.rt.clone(), r, qd, qt.into()))
+    }
+
     /// Allocates a scatter-gather array.
     pub fn sgaalloc(&self, size: usize) -> Result<demi_sgarray_t, Fail> {
         self.rt.alloc_sgarray(size)
@@ -171,6 +199,11 @@ impl CatnipLibOS {
     pub fn sgafree(&self, sga: demi_sgarray_t) -> Result<(), Fail> {
         self.rt.free_sgarray(sga)
     }
+
+    /// Appends the segments of a scatter-gather array to another one, and releases the former.
+    pub fn sgaappend(&self, sga: &mut demi_sgarray_t, tail: demi_sgarray_t) -> Result<(), Fail> {
+        self.rt.append_sgarray(sga, tail)
+    }
 }
 
 //==============================================================================
@@ -192,3 +225,126 @@ impl DerefMut for CatnipLibOS {
         &mut self.inetstack
     }
 }
+
+/// LibOS Backend Trait Implementation for Catnip LibOS
+impl LibOSBackend for CatnipLibOS {
+    fn socket(&mut self, domain: libc::c_int, socket_type: libc::c_int, protocol: libc::c_int) -> Result<QDesc, Fail> {
+        self.inetstack.socket(domain, socket_type, protocol)
+    }
+
+    fn bind(&mut self, sockqd: QDesc, local: SocketAddr) -> Result<(), Fail> {
+        self.inetstack.bind(sockqd, require_ipv4(local)?)
+    }
+
+    fn listen(&mut self, sockqd: QDesc, backlog: usize) -> Result<(), Fail> {
+        self.inetstack.listen(sockqd, backlog)
+    }
+
+    fn accept(&mut self, sockqd: QDesc) -> Result<QToken, Fail> {
+        self.inetstack.accept(sockqd)
+    }
+
+    fn connect(&mut self, sockqd: QDesc, remote: SocketAddr) -> Result<QToken, Fail> {
+        self.inetstack.connect(sockqd, require_ipv4(remote)?)
+    }
+
+    fn setsockopt(
+        &mut self,
+        sockqd: QDesc,
+        level: libc::c_int,
+        optname: libc::c_int,
+        optval: &[u8],
+    ) -> Result<(), Fail> {
+        self.inetstack.setsockopt(sockqd, level, optname, optval)
+    }
+
+    fn getsockopt(
+        &mut self,
+        sockqd: QDesc,
+        level: libc::c_int,
+        optname: libc::c_int,
+        optval: &mut [u8],
+    ) -> Result<usize, Fail> {
+        self.inetstack.getsockopt(sockqd, level, optname, optval)
+    }
+
+    fn getsockname(&mut self, sockqd: QDesc) -> Result<SocketAddr, Fail> {
+        Ok(SocketAddr::V4(self.inetstack.getsockname(sockqd)?))
+    }
+
+    fn getpeername(&mut self, sockqd: QDesc) -> Result<SocketAddr, Fail> {
+        Ok(SocketAddr::V4(self.inetstack.getpeername(sockqd)?))
+    }
+
+    fn pushto(&mut self, sockqd: QDesc, sga: &demi_sgarray_t, to: SocketAddr) -> Result<QToken, Fail> {
+        CatnipLibOS::pushto(self, sockqd, sga, require_ipv4(to)?)
+    }
+
+    fn shutdown(&mut self, sockqd: QDesc, how: libc::c_int) -> Result<(), Fail> {
+        self.inetstack.shutdown(sockqd, how)
+    }
+
+    fn close(&mut self, qd: QDesc) -> Result<(), Fail> {
+        self.inetstack.close(qd)
+    }
+
+    fn async_close(&mut self, qd: QDesc) -> Result<QToken, Fail> {
+        self.inetstack.async_close(qd)
+    }
+
+    fn get_stats(&mut self, qd: QDesc) -> Result<demi_qstats_t, Fail> {
+        self.inetstack.get_stats(qd)
+    }
+
+    fn readiness(&mut self, qd: QDesc) -> Result<u32, Fail> {
+        self.inetstack.readiness(qd)
+    }
+
+    fn push(&mut self, qd: QDesc, sga: &demi_sgarray_t) -> Result<QToken, Fail> {
+        CatnipLibOS::push(self, qd, sga)
+    }
+
+    fn pop(&mut self, qd: QDesc, min_size: usize, max_size: Option<usize>) -> Result<QToken, Fail> {
+        self.inetstack.pop(qd, min_size, max_size)
+    }
+
+    fn poll(&mut self) {
+        self.inetstack.poll_bg_work()
+    }
+
+    fn wait_for_events(&mut self, timeout: Duration) {
+        self.inetstack.wait_for_events(timeout)
+    }
+
+    fn schedule(&mut self, qt: QToken) -> Result<SchedulerHandle, Fail> {
+        CatnipLibOS::schedule(self, qt)
+    }
+
+    fn completed(&self) -> Vec<QToken> {
+        CatnipLibOS::completed(self)
+    }
+
+    fn pack_result(&mut self, handle: SchedulerHandle, qt: QToken) -> Result<(demi_qresult_t, Option<Fail>), Fail> {
+        CatnipLibOS::pack_result(self, handle, qt)
+    }
+
+    fn cancel(&mut self, handle: SchedulerHandle, qt: QToken) -> Result<(demi_qresult_t, Option<Fail>), Fail> {
+        CatnipLibOS::cancel(self, handle, qt)
+    }
+
+    fn sgaalloc(&self, size: usize) -> Result<demi_sgarray_t, Fail> {
+        CatnipLibOS::sgaalloc(self, size)
+    }
+
+    fn sgafree(&self, sga: demi_sgarray_t) -> Result<(), Fail> {
+        CatnipLibOS::sgafree(self, sga)
+    }
+
+    fn sgaappend(&self, sga: &mut demi_sgarray_t, tail: demi_sgarray_t) -> Result<(), Fail> {
+        CatnipLibOS::sgaappend(self, sga, tail)
+    }
+
+    fn memory(&self) -> Rc<dyn MemoryRuntime> {
+        self.rt.clone()
+    }
+}
@@ -6,41 +6,76 @@
 //======================================================================================================================
 
 use crate::{
-    demikernel::config::Config,
-    runtime::network::types::MacAddress,
+    demikernel::config::{
+        Config,
+        Section,
+    },
+    runtime::{
+        fail::Fail,
+        network::types::MacAddress,
+    },
+};
+use ::std::{
+    collections::HashMap,
+    net::Ipv4Addr,
 };
 
 //======================================================================================================================
-// Associated Functions
+// Constants
 //======================================================================================================================
 
-impl Config {
-    /// Reads the "local interface name" parameter from the underlying configuration file.
-    pub fn local_interface_name(&self) -> String {
-        // FIXME: this function should return a Result.
+/// Keys of the "catpowder" configuration section.
+const KEYS: &[&str] = &["my_ipv4_addr", "my_link_addr", "my_interface_name", "arp_table"];
 
-        // FIXME: Change the follow key from "catnip" to "catpowder".
-        let local_interface_name: &str = self.0["catnip"]["my_interface_name"]
-            .as_str()
-            .ok_or_else(|| anyhow::format_err!("Couldn't find my_interface_name config"))
-            .unwrap();
+//======================================================================================================================
+// Structures
+//======================================================================================================================
 
-        local_interface_name.to_string()
-    }
+/// Catpowder configuration.
+pub struct CatpowderConfig {
+    /// Local IPv4 address.
+    pub local_ipv4_addr: Ipv4Addr,
+    /// Local link address.
+    pub local_link_addr: MacAddress,
+    /// Name of the network interface to use.
+    pub local_interface_name: String,
+    /// Static entries of the ARP table.
+    pub arp_table: HashMap<Ipv4Addr, MacAddress>,
+}
+
+//======================================================================================================================
+// Associated Functions
+//======================================================================================================================
+
+/// Associated functions for Catpowder configuration.
+impl CatpowderConfig {
+    /// Reads and validates the "catpowder" section of a Demikernel configuration. The "catnip" section is read instead
+    /// if the former is missing, as it used to be shared by both LibOSes.
+    pub fn new(config: &Config) -> Result<Self, Fail> {
+        let section: Section = config.section_or("catpowder", "catnip")?;
+        section.warn_unknown_keys(KEYS);
+
+        let local_ipv4_addr: Ipv4Addr = section.require("my_ipv4_addr")?;
+        if local_ipv4_addr.is_unspecified() || local_ipv4_addr.is_broadcast() {
+            return Err(section.invalid("my_ipv4_addr", "expected a unicast address"));
+        }
+        let local_link_addr: MacAddress = section.require("my_link_addr")?;
+        if !local_link_addr.is_unicast() {
+            return Err(section.invalid("my_link_addr", "expected a unicast address"));
+        }
+        let local_interface_name: String = section.require("my_interface_name")?;
+        let arp_table: HashMap<Ipv4Addr, MacAddress> = section
+            .get_map::<MacAddress, Ipv4Addr>("arp_table")?
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(link_addr, ipv4_addr)| (ipv4_addr, link_addr))
+            .collect();
 
-    /// Reads the "local link address" parameter from the underlying configuration file.
-    pub fn local_link_addr(&self) -> MacAddress {
-        // FIXME: this function should return a Result.
-
-        // Parse local IPv4 address.
-        // FIXME: Change the follow key from "catnip" to "catpowder".
-        let local_link_addr: MacAddress = MacAddress::parse_str(
-            self.0["catnip"]["my_link_addr"]
-                .as_str()
-                .ok_or_else(|| anyhow::format_err!("Couldn't find my_link_addr in config"))
-                .unwrap(),
-        )
-        .unwrap();
-        local_link_addr
+        Ok(Self {
+            local_ipv4_addr,
+            local_link_addr,
+            local_interface_name,
+            arp_table,
+        })
     }
 }
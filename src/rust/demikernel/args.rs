@@ -0,0 +1,306 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//======================================================================================================================
+// Imports
+//======================================================================================================================
+
+use crate::{
+    demikernel::{
+        config::Config,
+        libos::name::LibOSName,
+    },
+    runtime::fail::Fail,
+};
+use ::clap::{
+    Arg,
+    ArgAction,
+    ArgMatches,
+    Command,
+};
+use ::std::env;
+
+//======================================================================================================================
+// Constants
+//======================================================================================================================
+
+/// Options that take a value.
+const OPTIONS: [&str; 3] = ["--libos", "--config", "--set"];
+
+/// Flags that select a LibOS.
+const LIBOS_FLAGS: [&str; 6] = ["catpowder", "catnap", "catnapw", "catcollar", "catnip", "catmem"];
+
+//======================================================================================================================
+// Structures
+//======================================================================================================================
+
+/// Demikernel arguments.
+pub struct Args {
+    /// Names of the LibOSes to instantiate.
+    pub libos_names: Vec<LibOSName>,
+    /// Configuration for the LibOS.
+    pub config: Config,
+}
+
+//======================================================================================================================
+// Associated Functions
+//======================================================================================================================
+
+/// Associated functions for Demikernel arguments.
+impl Args {
+    /// Parses Demikernel arguments out of the command line of a program, whose first item is the program name.
+    ///
+    /// The following options are recognized, while any other argument is ignored, as it is likely to be meant for the
+    /// program itself:
+    /// - `--libos <NAME>`, or any of `--catpowder`, `--catnap`, `--catnapw`, `--catcollar`, `--catnip` and `--catmem`,
+    ///   selects the LibOSes to instantiate. Many names are joined with `+` (e.g. `catnap+catmem`). It falls back to
+    ///   the `LIBOS` environment variable.
+    /// - `--config <PATH>` sets the path to the configuration file. It falls back to the `CONFIG_PATH` environment
+    ///   variable.
+    /// - `--set <SECTION.KEY=VALUE>` overrides a key of the configuration file. It may be repeated.
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Self, Fail> {
+        let matches: ArgMatches = match Self::command().try_get_matches_from(filter(args)) {
+            Ok(matches) => matches,
+            Err(e) => {
+                let cause: String = format!(
+                    "invalid arguments: {}",
+                    e.to_string().lines().next().unwrap_or_default()
+                );
+                error!("parse(): {}", cause);
+                return Err(Fail::new(libc::EINVAL, &cause));
+            },
+        };
+
+        // Select LibOS.
+        let flags: Vec<&str> = LIBOS_FLAGS.into_iter().filter(|flag| matches.get_flag(flag)).collect();
+        let libos_names: Vec<LibOSName> = match matches.get_one::<String>("libos") {
+            Some(names) => parse_names(names)?,
+            None if !flags.is_empty() => flags.into_iter().map(str::parse).collect::<Result<_, _>>()?,
+            None => match env::var("LIBOS") {
+                Ok(names) => parse_names(&names)?,
+                Err(_) => return Err(Fail::new(libc::EINVAL, "missing value for LIBOS environment variable")),
+            },
+        };
+
+        // Read in configuration file.
+        let config_path: String = match matches.get_one::<String>("config") {
+            Some(config_path) => config_path.clone(),
+            None => match env::var("CONFIG_PATH") {
+                Ok(config_path) => config_path,
+                Err(_) => {
+                    return Err(Fail::new(
+                        libc::EINVAL,
+                        "missing value for --config argument or CONFIG_PATH environment variable",
+                    ))
+                },
+            },
+        };
+        let mut config: Config = Config::new(&config_path)?;
+
+        // Override configuration keys.
+        if let Some(overrides) = matches.get_many::<String>("set") {
+            for key_value in overrides {
+                match key_value.split_once('=') {
+                    Some((key, value)) => config.set_override(key, value)?,
+                    None => {
+                        return Err(Fail::new(
+                            libc::EINVAL,
+                            &format!(
+                                "invalid value for --set argument {:?}: expected SECTION.KEY=VALUE",
+                                key_value
+                            ),
+                        ))
+                    },
+                }
+            }
+        }
+
+        Ok(Self { libos_names, config })
+    }
+
+    /// Builds the command line parser.
+    fn command() -> Command {
+        Command::new("demikernel")
+            .disable_help_flag(true)
+            .disable_version_flag(true)
+            .arg(
+                Arg::new("libos")
+                    .long("libos")
+                    .value_parser(clap::value_parser!(String))
+                    .value_name("NAME")
+                    .conflicts_with_all(LIBOS_FLAGS)
+                    .help("Sets the LibOSes to instantiate"),
+            )
+            .arg(
+                Arg::new("config")
+                    .long("config")
+                    .value_parser(clap::value_parser!(String))
+                    .value_name("PATH")
+                    .help("Sets the path to the configuration file"),
+            )
+            .arg(
+                Arg::new("set")
+                    .long("set")
+                    .value_parser(clap::value_parser!(String))
+                    .value_name("SECTION.KEY=VALUE")
+                    .action(ArgAction::Append)
+                    .help("Overrides a key of the configuration file"),
+            )
+            .args(
+                LIBOS_FLAGS
+                    .iter()
+                    .map(|flag| Arg::new(flag).long(flag).action(ArgAction::SetTrue)),
+            )
+    }
+}
+
+//======================================================================================================================
+// Standalone Functions
+//======================================================================================================================
+
+/// Parses names of LibOSes joined with `+`.
+fn parse_names(names: &str) -> Result<Vec<LibOSName>, Fail> {
+    names.split('+').map(str::parse).collect()
+}
+
+/// Filters out the arguments that are not recognized by Demikernel, but the program name.
+fn filter<I: IntoIterator<Item = String>>(args: I) -> Vec<String> {
+    let mut args = args.into_iter();
+    let mut filtered: Vec<String> = vec![args.next().unwrap_or_else(|| "demikernel".to_string())];
+    while let Some(arg) = args.next() {
+        let (name, has_value): (&str, bool) = match arg.split_once('=') {
+            Some((name, _)) => (name, true),
+            None => (arg.as_str(), false),
+        };
+        if OPTIONS.contains(&name) {
+            filtered.push(arg);
+            if !has_value {
+                if let Some(value) = args.next() {
+                    filtered.push(value);
+                }
+            }
+        } else if name
+            .strip_prefix("--")
+            .map_or(false, |flag| LIBOS_FLAGS.contains(&flag))
+        {
+            filtered.push(arg);
+        }
+    }
+    filtered
+}
+
+//======================================================================================================================
+// Unit Tests
+//======================================================================================================================
+
+#[cfg(test)]
+mod test {
+    use super::Args;
+    use crate::{
+        demikernel::libos::name::LibOSName,
+        runtime::fail::Fail,
+    };
+
+    /// Path to a configuration file.
+    const CONFIG_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/scripts/config/default.yaml");
+
+    /// Builds a command line.
+    fn command_line(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    /// Tests if Demikernel arguments are picked among those of a program.
+    #[test]
+    fn args_parse() -> Result<(), Fail> {
+        let args: Args = Args::parse(command_line(&[
+            "program",
+            "--server",
+            "127.0.0.1:12345",
+            "--libos",
+            "catmem",
+            "-v",
+            &format!("--config={}", CONFIG_PATH),
+            "--set",
+            "catnip.my_interface_name=eth0",
+            "--set=catpowder.my_interface_name=eth1",
+            "--",
+            "trailing",
+        ]))?;
+
+        assert_eq!(args.libos_names, [LibOSName::Catmem]);
+        assert_eq!(
+            args.config.section("catnip")?.require::<String>("my_interface_name")?,
+            "eth0"
+        );
+        assert_eq!(
+            args.config
+                .section("catpowder")?
+                .require::<String>("my_interface_name")?,
+            "eth1"
+        );
+
+        Ok(())
+    }
+
+    /// Tests if a LibOS may be selected with a flag.
+    #[test]
+    fn args_parse_flag() -> Result<(), Fail> {
+        let args: Args = Args::parse(command_line(&["program", "--catnap", "--config", CONFIG_PATH]))?;
+        assert_eq!(args.libos_names, [LibOSName::Catnap]);
+
+        let args: Args = Args::parse(command_line(&[
+            "program",
+            "--catnap",
+            "--catmem",
+            "--config",
+            CONFIG_PATH,
+        ]))?;
+        assert_eq!(args.libos_names, [LibOSName::Catnap, LibOSName::Catmem]);
+
+        let args: Args = Args::parse(command_line(&[
+            "program",
+            "--libos",
+            "catnap+catmem",
+            "--config",
+            CONFIG_PATH,
+        ]))?;
+        assert_eq!(args.libos_names, [LibOSName::Catnap, LibOSName::Catmem]);
+
+        Ok(())
+    }
+
+    /// Tests if bad arguments are reported as failures.
+    #[test]
+    fn args_parse_bad() {
+        let bad_args: [&[&str]; 5] = [
+            &["program", "--libos", "catfish", "--config", CONFIG_PATH],
+            &["program", "--libos", "catnap", "--catmem", "--config", CONFIG_PATH],
+            &["program", "--libos", "catnap", "--config", "/nonexistent/config.yaml"],
+            &[
+                "program",
+                "--libos",
+                "catnap",
+                "--config",
+                CONFIG_PATH,
+                "--set",
+                "catnip.mtu",
+            ],
+            &[
+                "program",
+                "--libos",
+                "catnap",
+                "--config",
+                CONFIG_PATH,
+                "--set",
+                "mtu=1500",
+            ],
+        ];
+        for args in bad_args {
+            assert!(
+                Args::parse(command_line(args)).is_err(),
+                "parse() should fail for {:?}",
+                args
+            );
+        }
+    }
+}
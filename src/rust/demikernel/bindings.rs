@@ -6,27 +6,40 @@
 //======================================================================================================================
 
 use crate::{
-    demikernel::libos::{
-        name::LibOSName,
-        LibOS,
+    demikernel::{
+        args::Args,
+        libos::LibOS,
     },
     pal::{
-        constants::AF_INET,
+        constants::{
+            AF_INET,
+            AF_INET6,
+        },
         data_structures::{
             SockAddrIn,
+            SockAddrIn6,
+            SockAddrStorage,
             Socklen,
         },
-        functions::get_addr_from_sock_addr_in,
+        functions::{
+            create_sock_addr_storage,
+            get_socketaddr_from_sock_addr_storage,
+        },
     },
     runtime::{
         fail::Fail,
         logging,
+        network::types::UnixSocketAddr,
         types::{
+            demi_libos_t,
             demi_qresult_t,
+            demi_qstats_t,
             demi_qtoken_t,
             demi_sgarray_t,
             demi_sgaseg_t,
+            DEMI_SGARRAY_MAXLEN,
         },
+        QDesc,
         QToken,
     },
 };
@@ -38,20 +51,35 @@ use ::libc::{
 };
 use ::std::{
     cell::RefCell,
-    ffi::CStr,
-    mem,
-    net::{
-        Ipv4Addr,
-        SocketAddrV4,
+    ffi::{
+        CStr,
+        CString,
     },
+    mem,
+    net::SocketAddr,
     ptr,
     slice,
+    thread::{
+        self,
+        ThreadId,
+    },
     time::{
         Duration,
         SystemTime,
     },
 };
 
+#[cfg(target_os = "linux")]
+use crate::pal::linux;
+
+#[cfg(test)]
+use ::std::net::{
+    Ipv4Addr,
+    Ipv6Addr,
+    SocketAddrV4,
+    SocketAddrV6,
+};
+
 //======================================================================================================================
 // DEMIKERNEL
 //======================================================================================================================
@@ -59,6 +87,47 @@ use ::std::{
 /// Demikernel state.
 static mut DEMIKERNEL: RefCell<Option<LibOS>> = RefCell::new(None);
 
+/// Demikernel instance, which is handed out as an opaque [demi_libos_t] handle.
+struct Instance {
+    /// Thread that owns the instance.
+    thread: ThreadId,
+    /// Underlying LibOS.
+    libos: RefCell<LibOS>,
+}
+
+/// Socket address that is passed to the C bindings, in any of the communication domains that they support.
+enum Endpoint {
+    /// IPv4 or IPv6 socket address.
+    Inet(SocketAddr),
+    /// Unix domain socket address.
+    Unix(UnixSocketAddr),
+}
+
+/// Associated functions for socket addresses, which issue operations on a socket with the API of their communication
+/// domain.
+impl Endpoint {
+    fn bind(&self, libos: &mut LibOS, qd: QDesc) -> Result<(), Fail> {
+        match self {
+            Endpoint::Inet(local) => libos.bind(qd, *local),
+            Endpoint::Unix(local) => libos.bind_unix(qd, local),
+        }
+    }
+
+    fn connect(&self, libos: &mut LibOS, qd: QDesc) -> Result<QToken, Fail> {
+        match self {
+            Endpoint::Inet(remote) => libos.connect(qd, *remote),
+            Endpoint::Unix(remote) => libos.connect_unix(qd, remote),
+        }
+    }
+
+    fn pushto(&self, libos: &mut LibOS, qd: QDesc, sga: &demi_sgarray_t) -> Result<QToken, Fail> {
+        match self {
+            Endpoint::Inet(to) => libos.pushto(qd, sga, *to),
+            Endpoint::Unix(to) => libos.pushto_unix(qd, sga, to),
+        }
+    }
+}
+
 //======================================================================================================================
 // init
 //======================================================================================================================
@@ -69,17 +138,11 @@ pub extern "C" fn demi_init(argc: c_int, argv: *mut *mut c_char) -> c_int {
     logging::initialize();
     trace!("demi_init()");
 
-    let libos_name: LibOSName = match LibOSName::from_env() {
-        Ok(libos_name) => libos_name.into(),
-        Err(e) => panic!("{:?}", e),
-    };
-
-    // TODO: Pass arguments to the underlying libOS.
-    let libos: LibOS = match LibOS::new(libos_name) {
+    let libos: LibOS = match new_libos(argc, argv) {
         Ok(libos) => libos,
         Err(e) => {
             trace!("demi_init() failed: {:?}", e);
-            return -e.errno;
+            return e.errno;
         },
     };
 
@@ -88,12 +151,78 @@ pub extern "C" fn demi_init(argc: c_int, argv: *mut *mut c_char) -> c_int {
     0
 }
 
+//======================================================================================================================
+// init_instance
+//======================================================================================================================
+
+#[no_mangle]
+pub unsafe extern "C" fn demi_init_instance(
+    libos_out: *mut *mut demi_libos_t,
+    argc: c_int,
+    argv: *mut *mut c_char,
+) -> c_int {
+    logging::initialize();
+    trace!("demi_init_instance()");
+
+    // Check if storage location for the instance handle is invalid.
+    if libos_out.is_null() {
+        return libc::EINVAL;
+    }
+
+    let libos: LibOS = match new_libos(argc, argv) {
+        Ok(libos) => libos,
+        Err(e) => {
+            trace!("demi_init_instance() failed: {:?}", e);
+            return e.errno;
+        },
+    };
+
+    unsafe { *libos_out = into_instance(libos) };
+
+    0
+}
+
+//======================================================================================================================
+// destroy_instance
+//======================================================================================================================
+
+#[no_mangle]
+pub unsafe extern "C" fn demi_destroy_instance(libos: *mut demi_libos_t) -> c_int {
+    trace!("demi_destroy_instance() {:?}", libos);
+
+    // Check if instance handle is invalid.
+    if libos.is_null() {
+        return libc::EINVAL;
+    }
+
+    // Safety: We have to trust that our user is providing a handle returned by `demi_init_instance()`.
+    let instance: &Instance = unsafe { &*(libos as *const Instance) };
+    if instance.thread != thread::current().id() {
+        return libc::EPERM;
+    }
+    if instance.libos.try_borrow_mut().is_err() {
+        return libc::EBUSY;
+    }
+    drop(unsafe { Box::from_raw(libos as *mut Instance) });
+
+    0
+}
+
 //======================================================================================================================
 // create
 //======================================================================================================================
 
 #[no_mangle]
 pub extern "C" fn demi_create_pipe(memqd_out: *mut c_int, name: *const libc::c_char) -> c_int {
+    unsafe { demi_create_pipe_ex(ptr::null_mut(), memqd_out, name) }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn demi_create_pipe_ex(
+    libos: *mut demi_libos_t,
+    memqd_out: *mut c_int,
+    name: *const libc::c_char,
+) -> c_int {
     trace!("demi_create_pipe() memqd_out={:?}, name={:?}", memqd_out, name);
 
     // Convert C string to a Rust one.
@@ -103,7 +232,7 @@ pub extern "C" fn demi_create_pipe(memqd_out: *mut c_int, name: *const libc::c_c
     };
 
     // Issue socket operation.
-    let ret: Result<i32, Fail> = do_syscall(|libos| match libos.create_pipe(name) {
+    let ret: Result<i32, Fail> = do_syscall(libos, |libos| match libos.create_pipe(name) {
         Ok(qd) => {
             unsafe { *memqd_out = qd.into() };
             0
@@ -126,6 +255,15 @@ pub extern "C" fn demi_create_pipe(memqd_out: *mut c_int, name: *const libc::c_c
 
 #[no_mangle]
 pub extern "C" fn demi_open_pipe(memqd_out: *mut c_int, name: *const libc::c_char) -> c_int {
+    unsafe { demi_open_pipe_ex(ptr::null_mut(), memqd_out, name) }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn demi_open_pipe_ex(
+    libos: *mut demi_libos_t,
+    memqd_out: *mut c_int,
+    name: *const libc::c_char,
+) -> c_int {
     trace!("demi_open_pipe() memqd_out={:?}, name={:?}", memqd_out, name);
 
     // Convert C string to a Rust one.
@@ -135,7 +273,7 @@ pub extern "C" fn demi_open_pipe(memqd_out: *mut c_int, name: *const libc::c_cha
     };
 
     // Issue socket operation.
-    let ret: Result<i32, Fail> = do_syscall(|libos| match libos.open_pipe(name) {
+    let ret: Result<i32, Fail> = do_syscall(libos, |libos| match libos.open_pipe(name) {
         Ok(qd) => {
             unsafe { *memqd_out = qd.into() };
             0
@@ -158,10 +296,21 @@ pub extern "C" fn demi_open_pipe(memqd_out: *mut c_int, name: *const libc::c_cha
 
 #[no_mangle]
 pub extern "C" fn demi_socket(qd_out: *mut c_int, domain: c_int, socket_type: c_int, protocol: c_int) -> c_int {
+    unsafe { demi_socket_ex(ptr::null_mut(), qd_out, domain, socket_type, protocol) }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn demi_socket_ex(
+    libos: *mut demi_libos_t,
+    qd_out: *mut c_int,
+    domain: c_int,
+    socket_type: c_int,
+    protocol: c_int,
+) -> c_int {
     trace!("demi_socket()");
 
     // Issue socket operation.
-    let ret: Result<i32, Fail> = do_syscall(|libos| match libos.socket(domain, socket_type, protocol) {
+    let ret: Result<i32, Fail> = do_syscall(libos, |libos| match libos.socket(domain, socket_type, protocol) {
         Ok(qd) => {
             unsafe { *qd_out = qd.into() };
             0
@@ -184,6 +333,16 @@ pub extern "C" fn demi_socket(qd_out: *mut c_int, domain: c_int, socket_type: c_
 
 #[no_mangle]
 pub extern "C" fn demi_bind(qd: c_int, saddr: *const sockaddr, size: Socklen) -> c_int {
+    unsafe { demi_bind_ex(ptr::null_mut(), qd, saddr, size) }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn demi_bind_ex(
+    libos: *mut demi_libos_t,
+    qd: c_int,
+    saddr: *const sockaddr,
+    size: Socklen,
+) -> c_int {
     trace!("demi_bind()");
 
     // Check if socket address is invalid.
@@ -191,13 +350,8 @@ pub extern "C" fn demi_bind(qd: c_int, saddr: *const sockaddr, size: Socklen) ->
         return libc::EINVAL;
     }
 
-    // Check if socket address length is invalid.
-    if size as usize != mem::size_of::<SockAddrIn>() {
-        return libc::EINVAL;
-    }
-
     // Get socket address.
-    let endpoint: SocketAddrV4 = match sockaddr_to_socketaddrv4(saddr) {
+    let endpoint: Endpoint = match sockaddr_to_endpoint(saddr, size) {
         Ok(endpoint) => endpoint,
         Err(e) => {
             trace!("demi_bind() failed: {:?}", e);
@@ -206,7 +360,7 @@ pub extern "C" fn demi_bind(qd: c_int, saddr: *const sockaddr, size: Socklen) ->
     };
 
     // Issue bind operation.
-    let ret: Result<i32, Fail> = do_syscall(|libos| match libos.bind(qd.into(), endpoint) {
+    let ret: Result<i32, Fail> = do_syscall(libos, |libos| match endpoint.bind(libos, qd.into()) {
         Ok(..) => 0,
         Err(e) => {
             trace!("demi_bind() failed: {:?}", e);
@@ -226,6 +380,11 @@ pub extern "C" fn demi_bind(qd: c_int, saddr: *const sockaddr, size: Socklen) ->
 
 #[no_mangle]
 pub extern "C" fn demi_listen(sockqd: c_int, backlog: c_int) -> c_int {
+    unsafe { demi_listen_ex(ptr::null_mut(), sockqd, backlog) }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn demi_listen_ex(libos: *mut demi_libos_t, sockqd: c_int, backlog: c_int) -> c_int {
     trace!("demi_listen()");
 
     // Check if socket backlog is invalid.
@@ -234,7 +393,7 @@ pub extern "C" fn demi_listen(sockqd: c_int, backlog: c_int) -> c_int {
     }
 
     // Issue listen operation.
-    let ret: Result<i32, Fail> = do_syscall(|libos| match libos.listen(sockqd.into(), backlog as usize) {
+    let ret: Result<i32, Fail> = do_syscall(libos, |libos| match libos.listen(sockqd.into(), backlog as usize) {
         Ok(..) => 0,
         Err(e) => {
             trace!("demi_listen() failed: {:?}", e);
@@ -254,10 +413,19 @@ pub extern "C" fn demi_listen(sockqd: c_int, backlog: c_int) -> c_int {
 
 #[no_mangle]
 pub extern "C" fn demi_accept(qtok_out: *mut demi_qtoken_t, sockqd: c_int) -> c_int {
+    unsafe { demi_accept_ex(ptr::null_mut(), qtok_out, sockqd) }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn demi_accept_ex(
+    libos: *mut demi_libos_t,
+    qtok_out: *mut demi_qtoken_t,
+    sockqd: c_int,
+) -> c_int {
     trace!("demi_accept()");
 
     // Issue accept operation.
-    let ret: Result<i32, Fail> = do_syscall(|libos| {
+    let ret: Result<i32, Fail> = do_syscall(libos, |libos| {
         unsafe {
             *qtok_out = match libos.accept(sockqd.into()) {
                 Ok(qt) => qt.into(),
@@ -286,6 +454,17 @@ pub extern "C" fn demi_connect(
     sockqd: c_int,
     saddr: *const sockaddr,
     size: Socklen,
+) -> c_int {
+    unsafe { demi_connect_ex(ptr::null_mut(), qtok_out, sockqd, saddr, size) }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn demi_connect_ex(
+    libos: *mut demi_libos_t,
+    qtok_out: *mut demi_qtoken_t,
+    sockqd: c_int,
+    saddr: *const sockaddr,
+    size: Socklen,
 ) -> c_int {
     trace!("demi_connect()");
 
@@ -294,13 +473,8 @@ pub extern "C" fn demi_connect(
         return libc::EINVAL;
     }
 
-    // Check if socket address length is invalid.
-    if size as usize != mem::size_of::<SockAddrIn>() {
-        return libc::EINVAL;
-    }
-
     // Get socket address.
-    let endpoint: SocketAddrV4 = match sockaddr_to_socketaddrv4(saddr) {
+    let endpoint: Endpoint = match sockaddr_to_endpoint(saddr, size) {
         Ok(endpoint) => endpoint,
         Err(e) => {
             trace!("demi_connect() failed: {:?}", e);
@@ -309,7 +483,7 @@ pub extern "C" fn demi_connect(
     };
 
     // Issue connect operation.
-    let ret: Result<i32, Fail> = do_syscall(|libos| match libos.connect(sockqd.into(), endpoint) {
+    let ret: Result<i32, Fail> = do_syscall(libos, |libos| match endpoint.connect(libos, sockqd.into()) {
         Ok(qt) => {
             unsafe { *qtok_out = qt.into() };
             0
@@ -326,16 +500,49 @@ pub extern "C" fn demi_connect(
     }
 }
 
+//======================================================================================================================
+// shutdown
+//======================================================================================================================
+
+#[no_mangle]
+pub extern "C" fn demi_shutdown(sockqd: c_int, how: c_int) -> c_int {
+    unsafe { demi_shutdown_ex(ptr::null_mut(), sockqd, how) }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn demi_shutdown_ex(libos: *mut demi_libos_t, sockqd: c_int, how: c_int) -> c_int {
+    trace!("demi_shutdown()");
+
+    // Issue shutdown operation.
+    let ret: Result<i32, Fail> = do_syscall(libos, |libos| match libos.shutdown(sockqd.into(), how) {
+        Ok(..) => 0,
+        Err(e) => {
+            trace!("demi_shutdown() failed: {:?}", e);
+            e.errno
+        },
+    });
+
+    match ret {
+        Ok(ret) => ret,
+        Err(e) => e.errno,
+    }
+}
+
 //======================================================================================================================
 // close
 //======================================================================================================================
 
 #[no_mangle]
 pub extern "C" fn demi_close(qd: c_int) -> c_int {
+    unsafe { demi_close_ex(ptr::null_mut(), qd) }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn demi_close_ex(libos: *mut demi_libos_t, qd: c_int) -> c_int {
     trace!("demi_close()");
 
     // Issue close operation.
-    let ret: Result<i32, Fail> = do_syscall(|libos| match libos.close(qd.into()) {
+    let ret: Result<i32, Fail> = do_syscall(libos, |libos| match libos.close(qd.into()) {
         Ok(..) => 0,
         Err(e) => {
             trace!("demi_close() failed: {:?}", e);
@@ -349,6 +556,41 @@ pub extern "C" fn demi_close(qd: c_int) -> c_int {
     }
 }
 
+//======================================================================================================================
+// async_close
+//======================================================================================================================
+
+#[no_mangle]
+pub extern "C" fn demi_async_close(qtok_out: *mut demi_qtoken_t, qd: c_int) -> c_int {
+    unsafe { demi_async_close_ex(ptr::null_mut(), qtok_out, qd) }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn demi_async_close_ex(
+    libos: *mut demi_libos_t,
+    qtok_out: *mut demi_qtoken_t,
+    qd: c_int,
+) -> c_int {
+    trace!("demi_async_close()");
+
+    // Issue close operation.
+    let ret: Result<i32, Fail> = do_syscall(libos, |libos| match libos.async_close(qd.into()) {
+        Ok(qt) => {
+            unsafe { *qtok_out = qt.into() };
+            0
+        },
+        Err(e) => {
+            trace!("demi_async_close() failed: {:?}", e);
+            e.errno
+        },
+    });
+
+    match ret {
+        Ok(ret) => ret,
+        Err(e) => e.errno,
+    }
+}
+
 //======================================================================================================================
 // pushto
 //======================================================================================================================
@@ -360,6 +602,18 @@ pub extern "C" fn demi_pushto(
     sga: *const demi_sgarray_t,
     saddr: *const sockaddr,
     size: Socklen,
+) -> c_int {
+    unsafe { demi_pushto_ex(ptr::null_mut(), qtok_out, sockqd, sga, saddr, size) }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn demi_pushto_ex(
+    libos: *mut demi_libos_t,
+    qtok_out: *mut demi_qtoken_t,
+    sockqd: c_int,
+    sga: *const demi_sgarray_t,
+    saddr: *const sockaddr,
+    size: Socklen,
 ) -> c_int {
     trace!("demi_pushto()");
 
@@ -373,15 +627,10 @@ pub extern "C" fn demi_pushto(
         return libc::EINVAL;
     }
 
-    // Check if socket address length is invalid.
-    if size as usize != mem::size_of::<SockAddrIn>() {
-        return libc::EINVAL;
-    }
-
     let sga: &demi_sgarray_t = unsafe { &*sga };
 
     // Get socket address.
-    let endpoint: SocketAddrV4 = match sockaddr_to_socketaddrv4(saddr) {
+    let endpoint: Endpoint = match sockaddr_to_endpoint(saddr, size) {
         Ok(endpoint) => endpoint,
         Err(e) => {
             trace!("demi_pushto() failed: {:?}", e);
@@ -389,7 +638,7 @@ pub extern "C" fn demi_pushto(
         },
     };
 
-    let ret: Result<i32, Fail> = do_syscall(|libos| match libos.pushto(sockqd.into(), sga, endpoint) {
+    let ret: Result<i32, Fail> = do_syscall(libos, |libos| match endpoint.pushto(libos, sockqd.into(), sga) {
         Ok(qt) => {
             unsafe { *qtok_out = qt.into() };
             0
@@ -412,6 +661,16 @@ pub extern "C" fn demi_pushto(
 
 #[no_mangle]
 pub extern "C" fn demi_push(qtok_out: *mut demi_qtoken_t, qd: c_int, sga: *const demi_sgarray_t) -> c_int {
+    unsafe { demi_push_ex(ptr::null_mut(), qtok_out, qd, sga) }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn demi_push_ex(
+    libos: *mut demi_libos_t,
+    qtok_out: *mut demi_qtoken_t,
+    qd: c_int,
+    sga: *const demi_sgarray_t,
+) -> c_int {
     trace!("demi_push()");
 
     // Check if scatter-gather array is invalid.
@@ -422,7 +681,7 @@ pub extern "C" fn demi_push(qtok_out: *mut demi_qtoken_t, qd: c_int, sga: *const
     let sga: &demi_sgarray_t = unsafe { &*sga };
 
     // Issue push operation.
-    let ret: Result<i32, Fail> = do_syscall(|libos| match libos.push(qd.into(), sga) {
+    let ret: Result<i32, Fail> = do_syscall(libos, |libos| match libos.push(qd.into(), sga) {
         Ok(qt) => {
             unsafe { *qtok_out = qt.into() };
             0
@@ -445,10 +704,15 @@ pub extern "C" fn demi_push(qtok_out: *mut demi_qtoken_t, qd: c_int, sga: *const
 
 #[no_mangle]
 pub extern "C" fn demi_pop(qtok_out: *mut demi_qtoken_t, qd: c_int) -> c_int {
+    unsafe { demi_pop_ex(ptr::null_mut(), qtok_out, qd) }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn demi_pop_ex(libos: *mut demi_libos_t, qtok_out: *mut demi_qtoken_t, qd: c_int) -> c_int {
     trace!("demi_pop()");
 
     // Issue pop operation.
-    let ret: Result<i32, Fail> = do_syscall(|libos| match libos.pop(qd.into()) {
+    let ret: Result<i32, Fail> = do_syscall(libos, |libos| match libos.pop(qd.into()) {
         Ok(qt) => {
             unsafe { *qtok_out = qt.into() };
             0
@@ -465,6 +729,84 @@ pub extern "C" fn demi_pop(qtok_out: *mut demi_qtoken_t, qd: c_int) -> c_int {
     }
 }
 
+#[no_mangle]
+pub extern "C" fn demi_pop_bounded(qtok_out: *mut demi_qtoken_t, qd: c_int, min_size: usize, max_size: usize) -> c_int {
+    unsafe { demi_pop_bounded_ex(ptr::null_mut(), qtok_out, qd, min_size, max_size) }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn demi_pop_bounded_ex(
+    libos: *mut demi_libos_t,
+    qtok_out: *mut demi_qtoken_t,
+    qd: c_int,
+    min_size: usize,
+    max_size: usize,
+) -> c_int {
+    trace!("demi_pop_bounded()");
+
+    // Issue pop operation.
+    let ret: Result<i32, Fail> = do_syscall(libos, |libos| match libos.pop_bounded(qd.into(), min_size, max_size) {
+        Ok(qt) => {
+            unsafe { *qtok_out = qt.into() };
+            0
+        },
+        Err(e) => {
+            trace!("demi_pop_bounded() failed: {:?}", e);
+            e.errno
+        },
+    });
+
+    match ret {
+        Ok(ret) => ret,
+        Err(e) => e.errno,
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn demi_pop_into(
+    qtok_out: *mut demi_qtoken_t,
+    qd: c_int,
+    sga: *const demi_sgarray_t,
+    min_size: usize,
+) -> c_int {
+    unsafe { demi_pop_into_ex(ptr::null_mut(), qtok_out, qd, sga, min_size) }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn demi_pop_into_ex(
+    libos: *mut demi_libos_t,
+    qtok_out: *mut demi_qtoken_t,
+    qd: c_int,
+    sga: *const demi_sgarray_t,
+    min_size: usize,
+) -> c_int {
+    trace!("demi_pop_into()");
+
+    // Check if scatter-gather array is invalid.
+    if sga.is_null() {
+        return libc::EINVAL;
+    }
+
+    let sga: demi_sgarray_t = unsafe { *sga };
+
+    // Issue pop operation.
+    let ret: Result<i32, Fail> = do_syscall(libos, |libos| match libos.pop_into(qd.into(), sga, min_size) {
+        Ok(qt) => {
+            unsafe { *qtok_out = qt.into() };
+            0
+        },
+        Err(e) => {
+            trace!("demi_pop_into() failed: {:?}", e);
+            e.errno
+        },
+    });
+
+    match ret {
+        Ok(ret) => ret,
+        Err(e) => e.errno,
+    }
+}
+
 //======================================================================================================================
 // timedwait
 //======================================================================================================================
@@ -474,6 +816,16 @@ pub extern "C" fn demi_timedwait(
     qr_out: *mut demi_qresult_t,
     qt: demi_qtoken_t,
     abstime: *const libc::timespec,
+) -> c_int {
+    unsafe { demi_timedwait_ex(ptr::null_mut(), qr_out, qt, abstime) }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn demi_timedwait_ex(
+    libos: *mut demi_libos_t,
+    qr_out: *mut demi_qresult_t,
+    qt: demi_qtoken_t,
+    abstime: *const libc::timespec,
 ) -> c_int {
     trace!("demi_timedwait() {:?} {:?} {:?}", qr_out, qt, abstime);
 
@@ -495,7 +847,7 @@ pub extern "C" fn demi_timedwait(
     };
 
     // Issue operation.
-    let ret: Result<i32, Fail> = do_syscall(|libos| match libos.timedwait(qt.into(), abstime) {
+    let ret: Result<i32, Fail> = do_syscall(libos, |libos| match libos.timedwait(qt.into(), abstime) {
         Ok(r) => {
             if !qr_out.is_null() {
                 unsafe { *qr_out = r };
@@ -520,6 +872,16 @@ pub extern "C" fn demi_timedwait(
 
 #[no_mangle]
 pub extern "C" fn demi_wait(qr_out: *mut demi_qresult_t, qt: demi_qtoken_t, timeout: *const libc::timespec) -> c_int {
+    unsafe { demi_wait_ex(ptr::null_mut(), qr_out, qt, timeout) }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn demi_wait_ex(
+    libos: *mut demi_libos_t,
+    qr_out: *mut demi_qresult_t,
+    qt: demi_qtoken_t,
+    timeout: *const libc::timespec,
+) -> c_int {
     trace!("demi_wait() {:?} {:?} {:?}", qr_out, qt, timeout);
 
     // Convert timespec to Duration.
@@ -531,7 +893,7 @@ pub extern "C" fn demi_wait(qr_out: *mut demi_qresult_t, qt: demi_qtoken_t, time
     };
 
     // Issue wait operation.
-    let ret: Result<i32, Fail> = do_syscall(|libos| match libos.wait(qt.into(), duration) {
+    let ret: Result<i32, Fail> = do_syscall(libos, |libos| match libos.wait(qt.into(), duration) {
         Ok(r) => {
             if !qr_out.is_null() {
                 unsafe { *qr_out = r };
@@ -561,6 +923,18 @@ pub extern "C" fn demi_wait_any(
     qts: *mut demi_qtoken_t,
     num_qts: c_int,
     timeout: *const libc::timespec,
+) -> c_int {
+    unsafe { demi_wait_any_ex(ptr::null_mut(), qr_out, ready_offset, qts, num_qts, timeout) }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn demi_wait_any_ex(
+    libos: *mut demi_libos_t,
+    qr_out: *mut demi_qresult_t,
+    ready_offset: *mut c_int,
+    qts: *mut demi_qtoken_t,
+    num_qts: c_int,
+    timeout: *const libc::timespec,
 ) -> c_int {
     trace!(
         "demi_wait_any() {:?} {:?} {:?} {:?} {:?}",
@@ -591,7 +965,7 @@ pub extern "C" fn demi_wait_any(
     };
 
     // Issue wait_any operation.
-    let ret: Result<i32, Fail> = do_syscall(|libos| match libos.wait_any(&qts, duration) {
+    let ret: Result<i32, Fail> = do_syscall(libos, |libos| match libos.wait_any(&qts, duration) {
         Ok((ix, qr)) => {
             unsafe {
                 *qr_out = qr;
@@ -612,11 +986,224 @@ pub extern "C" fn demi_wait_any(
 }
 
 //======================================================================================================================
-// sgaalloc
+// wait_many
+//======================================================================================================================
+
+#[no_mangle]
+pub extern "C" fn demi_wait_many(
+    qrs_out: *mut demi_qresult_t,
+    max_qrs: c_int,
+    nqrs_out: *mut c_int,
+    qts: *const demi_qtoken_t,
+    num_qts: c_int,
+    timeout: *const libc::timespec,
+) -> c_int {
+    unsafe { demi_wait_many_ex(ptr::null_mut(), qrs_out, max_qrs, nqrs_out, qts, num_qts, timeout) }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn demi_wait_many_ex(
+    libos: *mut demi_libos_t,
+    qrs_out: *mut demi_qresult_t,
+    max_qrs: c_int,
+    nqrs_out: *mut c_int,
+    qts: *const demi_qtoken_t,
+    num_qts: c_int,
+    timeout: *const libc::timespec,
+) -> c_int {
+    trace!(
+        "demi_wait_many() {:?} {:?} {:?} {:?} {:?} {:?}",
+        qrs_out,
+        max_qrs,
+        nqrs_out,
+        qts,
+        num_qts,
+        timeout
+    );
+
+    // Check arguments.
+    if num_qts <= 0 || qts.is_null() {
+        return libc::EINVAL;
+    }
+    if qrs_out.is_null() || nqrs_out.is_null() {
+        warn!("qrs_out or nqrs_out is a null pointer");
+        return libc::EINVAL;
+    }
+    if max_qrs <= 0 {
+        warn!("qrs_out should have room for some result");
+        return libc::EINVAL;
+    }
+
+    // Get queue tokens.
+    let qts: Vec<QToken> = {
+        let raw_qts: &[u64] = unsafe { slice::from_raw_parts(qts, num_qts as usize) };
+        raw_qts.iter().map(|i| QToken::from(*i)).collect()
+    };
+
+    // Convert timespec to Duration.
+    let duration: Option<Duration> = if timeout.is_null() {
+        None
+    } else {
+        // Safety: We have to trust that our user is providing a valid timeout pointer for us to dereference.
+        Some(unsafe { Duration::new((*timeout).tv_sec as u64, (*timeout).tv_nsec as u32) })
+    };
+
+    // Issue wait_many operation.
+    let ret: Result<i32, Fail> = do_syscall(libos, |libos| match libos.wait_many(&qts, max_qrs as usize, duration) {
+        Ok(qrs) => {
+            let nqrs: c_int = qrs.len() as c_int;
+            // Safety: We have to trust that our user is providing room for `max_qrs` results, and no more are returned.
+            for (i, qr) in qrs.into_iter().enumerate() {
+                unsafe { qrs_out.add(i).write(qr) };
+            }
+            unsafe { *nqrs_out = nqrs };
+            0
+        },
+        Err(e) => {
+            trace!("demi_wait_many() failed: {:?}", e);
+            e.errno
+        },
+    });
+
+    match ret {
+        Ok(ret) => ret,
+        Err(e) => e.errno,
+    }
+}
+
+//======================================================================================================================
+// poll
+//======================================================================================================================
+
+#[no_mangle]
+pub extern "C" fn demi_poll(
+    nready_out: *mut c_int,
+    revents_out: *mut u32,
+    qds: *const c_int,
+    events: *const u32,
+    num_qds: c_int,
+    timeout: *const libc::timespec,
+) -> c_int {
+    unsafe { demi_poll_ex(ptr::null_mut(), nready_out, revents_out, qds, events, num_qds, timeout) }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn demi_poll_ex(
+    libos: *mut demi_libos_t,
+    nready_out: *mut c_int,
+    revents_out: *mut u32,
+    qds: *const c_int,
+    events: *const u32,
+    num_qds: c_int,
+    timeout: *const libc::timespec,
+) -> c_int {
+    trace!(
+        "demi_poll() {:?} {:?} {:?} {:?} {:?} {:?}",
+        nready_out,
+        revents_out,
+        qds,
+        events,
+        num_qds,
+        timeout
+    );
+
+    // Check arguments.
+    if num_qds <= 0 || qds.is_null() || events.is_null() {
+        return libc::EINVAL;
+    }
+    if nready_out.is_null() || revents_out.is_null() {
+        warn!("nready_out or revents_out is a null pointer");
+        return libc::EINVAL;
+    }
+
+    // Get queue descriptors and the events that are asked for.
+    let qds: Vec<QDesc> = {
+        let raw_qds: &[c_int] = unsafe { slice::from_raw_parts(qds, num_qds as usize) };
+        raw_qds.iter().map(|&qd| QDesc::from(qd)).collect()
+    };
+    let events: &[u32] = unsafe { slice::from_raw_parts(events, num_qds as usize) };
+
+    // Convert timespec to Duration.
+    let duration: Option<Duration> = if timeout.is_null() {
+        None
+    } else {
+        // Safety: We have to trust that our user is providing a valid timeout pointer for us to dereference.
+        Some(unsafe { Duration::new((*timeout).tv_sec as u64, (*timeout).tv_nsec as u32) })
+    };
+
+    // Issue poll operation.
+    let ret: Result<i32, Fail> = do_syscall(libos, |libos| match libos.poll_events(&qds, events, duration) {
+        Ok(revents) => {
+            // Safety: We have to trust that our user is providing room for as many events as queue descriptors.
+            let revents_out: &mut [u32] = unsafe { slice::from_raw_parts_mut(revents_out, revents.len()) };
+            revents_out.copy_from_slice(&revents);
+            unsafe { *nready_out = revents.iter().filter(|&&e| e != 0).count() as c_int };
+            0
+        },
+        Err(e) => {
+            trace!("demi_poll() failed: {:?}", e);
+            e.errno
+        },
+    });
+
+    match ret {
+        Ok(ret) => ret,
+        Err(e) => e.errno,
+    }
+}
+
+//======================================================================================================================
+// cancel
+//======================================================================================================================
+
+#[no_mangle]
+pub extern "C" fn demi_cancel(qr_out: *mut demi_qresult_t, qt: demi_qtoken_t) -> c_int {
+    unsafe { demi_cancel_ex(ptr::null_mut(), qr_out, qt) }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn demi_cancel_ex(
+    libos: *mut demi_libos_t,
+    qr_out: *mut demi_qresult_t,
+    qt: demi_qtoken_t,
+) -> c_int {
+    trace!("demi_cancel() {:?} {:?}", qr_out, qt);
+
+    // Check for invalid storage location for the result.
+    if qr_out.is_null() {
+        warn!("qr_out is a null pointer");
+        return libc::EINVAL;
+    }
+
+    // Issue cancel operation.
+    let ret: Result<i32, Fail> = do_syscall(libos, |libos| match libos.cancel(qt.into()) {
+        Ok(r) => {
+            unsafe { *qr_out = r };
+            0
+        },
+        Err(e) => {
+            trace!("demi_cancel() failed: {:?}", e);
+            e.errno
+        },
+    });
+
+    match ret {
+        Ok(ret) => ret,
+        Err(e) => e.errno,
+    }
+}
+
+//======================================================================================================================
+// sgaalloc
 //======================================================================================================================
 
 #[no_mangle]
 pub extern "C" fn demi_sgaalloc(size: libc::size_t) -> demi_sgarray_t {
+    unsafe { demi_sgaalloc_ex(ptr::null_mut(), size) }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn demi_sgaalloc_ex(libos: *mut demi_libos_t, size: libc::size_t) -> demi_sgarray_t {
     trace!("demi_sgaalloc()");
 
     let null_sga: demi_sgarray_t = {
@@ -626,13 +1213,13 @@ pub extern "C" fn demi_sgaalloc(size: libc::size_t) -> demi_sgarray_t {
             sga_segs: [demi_sgaseg_t {
                 sgaseg_buf: ptr::null_mut() as *mut c_void,
                 sgaseg_len: 0,
-            }; 1],
+            }; DEMI_SGARRAY_MAXLEN],
             sga_addr: unsafe { mem::zeroed() },
         }
     };
 
     // Issue sgaalloc operation.
-    let ret: Result<demi_sgarray_t, Fail> = do_syscall(|libos| -> demi_sgarray_t {
+    let ret: Result<demi_sgarray_t, Fail> = do_syscall(libos, |libos| -> demi_sgarray_t {
         match libos.sgaalloc(size) {
             Ok(sga) => sga,
             Err(e) => {
@@ -657,6 +1244,11 @@ pub extern "C" fn demi_sgaalloc(size: libc::size_t) -> demi_sgarray_t {
 
 #[no_mangle]
 pub extern "C" fn demi_sgafree(sga: *mut demi_sgarray_t) -> c_int {
+    unsafe { demi_sgafree_ex(ptr::null_mut(), sga) }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn demi_sgafree_ex(libos: *mut demi_libos_t, sga: *mut demi_sgarray_t) -> c_int {
     trace!("demi_sgfree()");
 
     // Check if scatter-gather array is invalid.
@@ -665,7 +1257,7 @@ pub extern "C" fn demi_sgafree(sga: *mut demi_sgarray_t) -> c_int {
     }
 
     // Issue sgafree operation.
-    let ret: Result<i32, Fail> = do_syscall(|libos| match libos.sgafree(unsafe { *sga }) {
+    let ret: Result<i32, Fail> = do_syscall(libos, |libos| match libos.sgafree(unsafe { *sga }) {
         Ok(()) => 0,
         Err(e) => {
             trace!("demi_sgafree() failed: {:?}", e);
@@ -680,93 +1272,1222 @@ pub extern "C" fn demi_sgafree(sga: *mut demi_sgarray_t) -> c_int {
 }
 
 //======================================================================================================================
-// getsockname
+// sgaappend
 //======================================================================================================================
 
-#[allow(unused)]
 #[no_mangle]
-pub extern "C" fn demi_getsockname(qd: c_int, saddr: *mut sockaddr, size: *mut Socklen) -> c_int {
-    // TODO: Implement this system call.
-    libc::ENOSYS
+pub extern "C" fn demi_sgaappend(sga: *mut demi_sgarray_t, tail: *mut demi_sgarray_t) -> c_int {
+    unsafe { demi_sgaappend_ex(ptr::null_mut(), sga, tail) }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn demi_sgaappend_ex(
+    libos: *mut demi_libos_t,
+    sga: *mut demi_sgarray_t,
+    tail: *mut demi_sgarray_t,
+) -> c_int {
+    trace!("demi_sgaappend()");
+
+    // Check if scatter-gather arrays are invalid.
+    if sga.is_null() || tail.is_null() {
+        return libc::EINVAL;
+    }
+
+    // Issue sgaappend operation.
+    let ret: Result<i32, Fail> = do_syscall(libos, |libos| {
+        match libos.sgaappend(unsafe { &mut *sga }, unsafe { *tail }) {
+            Ok(()) => 0,
+            Err(e) => {
+                trace!("demi_sgaappend() failed: {:?}", e);
+                e.errno
+            },
+        }
+    });
+
+    match ret {
+        Ok(ret) => ret,
+        Err(e) => e.errno,
+    }
 }
 
 //======================================================================================================================
-// setsockopt
+// getsockname
 //======================================================================================================================
 
-#[allow(unused)]
 #[no_mangle]
-pub extern "C" fn demi_setsockopt(
+pub extern "C" fn demi_getsockname(qd: c_int, saddr: *mut sockaddr, size: *mut Socklen) -> c_int {
+    unsafe { demi_getsockname_ex(ptr::null_mut(), qd, saddr, size) }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn demi_getsockname_ex(
+    libos: *mut demi_libos_t,
     qd: c_int,
-    level: c_int,
-    optname: c_int,
-    optval: *const c_void,
-    optlen: Socklen,
+    saddr: *mut sockaddr,
+    size: *mut Socklen,
 ) -> c_int {
-    // TODO: Implement this system call.
-    libc::ENOSYS
+    trace!("demi_getsockname()");
+
+    // Check if socket address or socket address length are invalid.
+    if saddr.is_null() || size.is_null() {
+        return libc::EINVAL;
+    }
+
+    // Check if socket address length is too small.
+    if (unsafe { *size } as usize) < mem::size_of::<SockAddrIn>() {
+        return libc::EINVAL;
+    }
+
+    // Issue getsockname operation.
+    let ret: Result<i32, Fail> = do_syscall(libos, |libos| match libos.getsockname(qd.into()) {
+        Ok(endpoint) => {
+            unsafe { socketaddr_to_sockaddr(&endpoint, saddr, size) };
+            0
+        },
+        Err(e) => {
+            trace!("demi_getsockname() failed: {:?}", e);
+            e.errno
+        },
+    });
+
+    match ret {
+        Ok(ret) => ret,
+        Err(e) => e.errno,
+    }
 }
 
 //======================================================================================================================
-// getsockopt
+// getpeername
 //======================================================================================================================
 
-#[allow(unused)]
 #[no_mangle]
-pub extern "C" fn demi_getsockopt(
+pub extern "C" fn demi_getpeername(qd: c_int, saddr: *mut sockaddr, size: *mut Socklen) -> c_int {
+    unsafe { demi_getpeername_ex(ptr::null_mut(), qd, saddr, size) }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn demi_getpeername_ex(
+    libos: *mut demi_libos_t,
     qd: c_int,
-    level: c_int,
-    optname: c_int,
-    optval: *mut c_void,
-    optlen: *mut Socklen,
+    saddr: *mut sockaddr,
+    size: *mut Socklen,
 ) -> c_int {
-    // TODO: Implement this system call.
-    libc::ENOSYS
-}
+    trace!("demi_getpeername()");
 
-//======================================================================================================================
-// Standalone Functions
-//======================================================================================================================
+    // Check if socket address or socket address length are invalid.
+    if saddr.is_null() || size.is_null() {
+        return libc::EINVAL;
+    }
 
-/// Issues a system call.
-fn do_syscall<T>(f: impl FnOnce(&mut LibOS) -> T) -> Result<T, Fail> {
-    match unsafe { DEMIKERNEL.try_borrow_mut() } {
-        Ok(mut libos) => match libos.as_mut() {
-            Some(libos) => Ok(f(libos)),
-            None => Err(Fail::new(libc::ENOSYS, "Demikernel is not initialized")),
+    // Check if socket address length is too small.
+    if (unsafe { *size } as usize) < mem::size_of::<SockAddrIn>() {
+        return libc::EINVAL;
+    }
+
+    // Issue getpeername operation.
+    let ret: Result<i32, Fail> = do_syscall(libos, |libos| match libos.getpeername(qd.into()) {
+        Ok(endpoint) => {
+            unsafe { socketaddr_to_sockaddr(&endpoint, saddr, size) };
+            0
         },
-        Err(_) => Err(Fail::new(libc::EBUSY, "Demikernel is busy")),
+        Err(e) => {
+            trace!("demi_getpeername() failed: {:?}", e);
+            e.errno
+        },
+    });
+
+    match ret {
+        Ok(ret) => ret,
+        Err(e) => e.errno,
     }
 }
 
-/// Converts a [sockaddr] into a [SocketAddrV4].
-fn sockaddr_to_socketaddrv4(saddr: *const sockaddr) -> Result<SocketAddrV4, Fail> {
-    // TODO: Change the logic bellow and rename this function once we support V6 addresses as well.
-    let sin: SockAddrIn = unsafe { *mem::transmute::<*const sockaddr, *const SockAddrIn>(saddr) };
-    if sin.sin_family != AF_INET as u16 {
-        return Err(Fail::new(libc::ENOTSUP, "communication domain not supported"));
-    };
-    let addr: Ipv4Addr = Ipv4Addr::from(u32::from_be(get_addr_from_sock_addr_in(&sin)));
-    let port: u16 = u16::from_be(sin.sin_port);
-    Ok(SocketAddrV4::new(addr, port))
+//======================================================================================================================
+// getstats
+//======================================================================================================================
+
+#[no_mangle]
+pub extern "C" fn demi_getstats(qd: c_int, stats_out: *mut demi_qstats_t, size: libc::size_t) -> c_int {
+    unsafe { demi_getstats_ex(ptr::null_mut(), qd, stats_out, size) }
 }
 
-#[test]
-fn test_sockaddr_to_socketaddrv4() {
-    // TODO: assign something meaningful to sa_family and check it once we support V6 addresses as well.
+#[no_mangle]
+pub unsafe extern "C" fn demi_getstats_ex(
+    libos: *mut demi_libos_t,
+    qd: c_int,
+    stats_out: *mut demi_qstats_t,
+    size: libc::size_t,
+) -> c_int {
+    trace!("demi_getstats() {:?} {:?} {:?}", qd, stats_out, size);
 
-    // SocketAddrV4: 127.0.0.1:80
-    let saddr: libc::sockaddr = {
-        sockaddr {
-            sa_family: AF_INET as u16,
-            sa_data: [0, 80, 127, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0],
-        }
-    };
-    match sockaddr_to_socketaddrv4(&saddr) {
-        Ok(addr) => {
-            assert_eq!(addr.port(), 80);
-            assert_eq!(addr.ip(), &Ipv4Addr::new(127, 0, 0, 1));
+    // Check if statistics output is invalid. It should at least have room for the version and the flags.
+    if stats_out.is_null() || size < 2 * mem::size_of::<u32>() {
+        return libc::EINVAL;
+    }
+
+    // Issue getstats operation.
+    let ret: Result<i32, Fail> = do_syscall(libos, |libos| match libos.get_stats(qd.into()) {
+        Ok(stats) => {
+            // Callers that were built against an older version of the structure have less room for it, so only copy
+            // as much as they have. Fields are only appended in later versions.
+            let len: usize = size.min(mem::size_of::<demi_qstats_t>());
+            unsafe { ptr::copy_nonoverlapping(&stats as *const demi_qstats_t as *const u8, stats_out as *mut u8, len) };
+            0
         },
-        _ => panic!("failed to convert"),
+        Err(e) => {
+            trace!("demi_getstats() failed: {:?}", e);
+            e.errno
+        },
+    });
+
+    match ret {
+        Ok(ret) => ret,
+        Err(e) => e.errno,
     }
 }
+
+//======================================================================================================================
+// setsockopt
+//======================================================================================================================
+
+#[no_mangle]
+pub extern "C" fn demi_setsockopt(
+    qd: c_int,
+    level: c_int,
+    optname: c_int,
+    optval: *const c_void,
+    optlen: Socklen,
+) -> c_int {
+    unsafe { demi_setsockopt_ex(ptr::null_mut(), qd, level, optname, optval, optlen) }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn demi_setsockopt_ex(
+    libos: *mut demi_libos_t,
+    qd: c_int,
+    level: c_int,
+    optname: c_int,
+    optval: *const c_void,
+    optlen: Socklen,
+) -> c_int {
+    trace!("demi_setsockopt()");
+
+    // Check if option value is invalid.
+    if optval.is_null() {
+        return libc::EINVAL;
+    }
+
+    // Check if option length is invalid.
+    if optlen as i32 <= 0 {
+        return libc::EINVAL;
+    }
+
+    let optval: &[u8] = unsafe { slice::from_raw_parts(optval as *const u8, optlen as usize) };
+
+    // Issue setsockopt operation.
+    let ret: Result<i32, Fail> = do_syscall(libos, |libos| {
+        match libos.setsockopt(qd.into(), level, optname, optval) {
+            Ok(()) => 0,
+            Err(e) => {
+                trace!("demi_setsockopt() failed: {:?}", e);
+                e.errno
+            },
+        }
+    });
+
+    match ret {
+        Ok(ret) => ret,
+        Err(e) => e.errno,
+    }
+}
+
+//======================================================================================================================
+// getsockopt
+//======================================================================================================================
+
+#[no_mangle]
+pub extern "C" fn demi_getsockopt(
+    qd: c_int,
+    level: c_int,
+    optname: c_int,
+    optval: *mut c_void,
+    optlen: *mut Socklen,
+) -> c_int {
+    unsafe { demi_getsockopt_ex(ptr::null_mut(), qd, level, optname, optval, optlen) }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn demi_getsockopt_ex(
+    libos: *mut demi_libos_t,
+    qd: c_int,
+    level: c_int,
+    optname: c_int,
+    optval: *mut c_void,
+    optlen: *mut Socklen,
+) -> c_int {
+    trace!("demi_getsockopt()");
+
+    // Check if option value or option length are invalid.
+    if optval.is_null() || optlen.is_null() {
+        return libc::EINVAL;
+    }
+
+    // Check if option length is invalid.
+    if unsafe { *optlen } as i32 <= 0 {
+        return libc::EINVAL;
+    }
+
+    let optval: &mut [u8] = unsafe { slice::from_raw_parts_mut(optval as *mut u8, *optlen as usize) };
+
+    // Issue getsockopt operation.
+    let ret: Result<i32, Fail> = do_syscall(libos, |libos| {
+        match libos.getsockopt(qd.into(), level, optname, optval) {
+            Ok(len) => {
+                unsafe { *optlen = len as Socklen };
+                0
+            },
+            Err(e) => {
+                trace!("demi_getsockopt() failed: {:?}", e);
+                e.errno
+            },
+        }
+    });
+
+    match ret {
+        Ok(ret) => ret,
+        Err(e) => e.errno,
+    }
+}
+
+//======================================================================================================================
+// strerror
+//======================================================================================================================
+
+thread_local! {
+    /// Storage for the description returned by the last call to `demi_strerror()`.
+    static STRERROR: RefCell<CString> = RefCell::new(CString::default());
+}
+
+#[no_mangle]
+pub extern "C" fn demi_strerror(qr: *const demi_qresult_t) -> *const c_char {
+    unsafe { demi_strerror_ex(ptr::null_mut(), qr) }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn demi_strerror_ex(libos: *mut demi_libos_t, qr: *const demi_qresult_t) -> *const c_char {
+    trace!("demi_strerror()");
+
+    // Check if result is invalid.
+    if qr.is_null() {
+        return ptr::null();
+    }
+    let qr: &demi_qresult_t = unsafe { &*qr };
+
+    // Prefer the cause reported by the failed operation, if it is still known.
+    let cause: Option<String> = match do_syscall(libos, |libos| libos.failure(qr).map(|e| e.cause.clone())) {
+        Ok(cause) => cause,
+        Err(_) => None,
+    };
+    let cause: String = match cause {
+        Some(cause) => cause,
+        None => unsafe { CStr::from_ptr(libc::strerror(qr.qr_ret)) }
+            .to_string_lossy()
+            .into_owned(),
+    };
+
+    STRERROR.with(|strerror| {
+        *strerror.borrow_mut() = CString::new(cause).unwrap_or_default();
+        strerror.borrow().as_ptr()
+    })
+}
+
+//======================================================================================================================
+// Standalone Functions
+//======================================================================================================================
+
+/// Instantiates a LibOS with the arguments passed to Demikernel.
+fn new_libos(argc: c_int, argv: *mut *mut c_char) -> Result<LibOS, Fail> {
+    // Parse arguments. Missing ones fall back to environment variables.
+    let args: Vec<String> = if argc <= 0 || argv.is_null() {
+        Vec::new()
+    } else {
+        (0..argc as usize)
+            .map(|i| unsafe { *argv.add(i) })
+            .take_while(|arg| !arg.is_null())
+            .map(|arg| unsafe { CStr::from_ptr(arg) }.to_string_lossy().into_owned())
+            .collect()
+    };
+    let args: Args = Args::parse(args)?;
+
+    LibOS::with_config_many(&args.libos_names, &args.config)
+}
+
+/// Hands out a LibOS as a Demikernel instance, which is owned by the calling thread.
+fn into_instance(libos: LibOS) -> *mut demi_libos_t {
+    let instance: Box<Instance> = Box::new(Instance {
+        thread: thread::current().id(),
+        libos: RefCell::new(libos),
+    });
+    Box::into_raw(instance) as *mut demi_libos_t
+}
+
+/// Issues a system call on a Demikernel instance, or on the one initialized with `demi_init()` if `libos` is null.
+fn do_syscall<T>(libos: *mut demi_libos_t, f: impl FnOnce(&mut LibOS) -> T) -> Result<T, Fail> {
+    if !libos.is_null() {
+        // Safety: We have to trust that our user is providing a handle returned by `demi_init_instance()`.
+        let instance: &Instance = unsafe { &*(libos as *const Instance) };
+        if instance.thread != thread::current().id() {
+            return Err(Fail::new(libc::EPERM, "Demikernel instance is owned by another thread"));
+        }
+        return match instance.libos.try_borrow_mut() {
+            Ok(mut libos) => Ok(f(&mut libos)),
+            Err(_) => Err(Fail::new(libc::EBUSY, "Demikernel is busy")),
+        };
+    }
+
+    match unsafe { DEMIKERNEL.try_borrow_mut() } {
+        Ok(mut libos) => match libos.as_mut() {
+            Some(libos) => Ok(f(libos)),
+            None => Err(Fail::new(libc::ENOSYS, "Demikernel is not initialized")),
+        },
+        Err(_) => Err(Fail::new(libc::EBUSY, "Demikernel is busy")),
+    }
+}
+
+/// Converts a [sockaddr] of a given length into an [Endpoint], based on its communication domain.
+fn sockaddr_to_endpoint(saddr: *const sockaddr, size: Socklen) -> Result<Endpoint, Fail> {
+    #[cfg(target_os = "linux")]
+    {
+        // Check if socket address length is too small to hold the communication domain.
+        if (size as usize) < mem::size_of::<libc::sa_family_t>() {
+            return Err(Fail::new(libc::EINVAL, "invalid socket address length"));
+        }
+        if unsafe { (*saddr).sa_family } as i32 == libc::AF_UNIX {
+            return unsafe { linux::sockaddr_to_unixsocketaddr(saddr, size) }.map(Endpoint::Unix);
+        }
+    }
+    sockaddr_to_socketaddr(saddr, size).map(Endpoint::Inet)
+}
+
+/// Converts a [sockaddr] of a given length into a [SocketAddr].
+pub(super) fn sockaddr_to_socketaddr(saddr: *const sockaddr, size: Socklen) -> Result<SocketAddr, Fail> {
+    // Check if socket address length is too small for any communication domain.
+    let len: usize = size as usize;
+    if len < mem::size_of::<SockAddrIn>() {
+        return Err(Fail::new(libc::EINVAL, "invalid socket address length"));
+    }
+
+    // Check if socket address length matches the communication domain.
+    let expected_len: usize = match unsafe { (*saddr).sa_family } as i32 {
+        AF_INET => mem::size_of::<SockAddrIn>(),
+        AF_INET6 => mem::size_of::<SockAddrIn6>(),
+        _ => return Err(Fail::new(libc::ENOTSUP, "communication domain not supported")),
+    };
+    if len != expected_len {
+        return Err(Fail::new(libc::EINVAL, "invalid socket address length"));
+    }
+
+    let mut storage: SockAddrStorage = unsafe { mem::zeroed() };
+    unsafe { ptr::copy_nonoverlapping(saddr as *const u8, &mut storage as *mut SockAddrStorage as *mut u8, len) };
+    match get_socketaddr_from_sock_addr_storage(&storage) {
+        Some(endpoint) => Ok(endpoint),
+        None => Err(Fail::new(libc::ENOTSUP, "communication domain not supported")),
+    }
+}
+
+/// Writes a [SocketAddr] into a [sockaddr] whose capacity is `size`. The socket address is truncated if it does not
+/// fit, and `size` is set to its actual length.
+pub(super) unsafe fn socketaddr_to_sockaddr(endpoint: &SocketAddr, saddr: *mut sockaddr, size: *mut Socklen) {
+    let (storage, len): (SockAddrStorage, Socklen) = create_sock_addr_storage(endpoint);
+    let count: usize = (*size as usize).min(len as usize);
+    ptr::copy_nonoverlapping(&storage as *const SockAddrStorage as *const u8, saddr as *mut u8, count);
+    *size = len;
+}
+
+#[test]
+fn test_sockaddr_to_socketaddr() {
+    // SocketAddrV4: 127.0.0.1:80
+    let saddr: libc::sockaddr = {
+        sockaddr {
+            sa_family: AF_INET as u16,
+            sa_data: [0, 80, 127, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0],
+        }
+    };
+    match sockaddr_to_socketaddr(&saddr, mem::size_of::<SockAddrIn>() as Socklen) {
+        Ok(SocketAddr::V4(addr)) => {
+            assert_eq!(addr.port(), 80);
+            assert_eq!(addr.ip(), &Ipv4Addr::new(127, 0, 0, 1));
+        },
+        _ => panic!("failed to convert"),
+    }
+
+    // Socket address length must match the communication domain.
+    match sockaddr_to_socketaddr(&saddr, mem::size_of::<SockAddrIn6>() as Socklen) {
+        Err(e) => assert_eq!(e.errno, libc::EINVAL),
+        _ => panic!("conversion should fail"),
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_sockaddr_to_endpoint_unix() {
+    // Abstract name: "\0demikernel"
+    let mut sun: libc::sockaddr_un = unsafe { mem::zeroed() };
+    sun.sun_family = libc::AF_UNIX as libc::sa_family_t;
+    for (i, b) in b"\0demikernel".iter().enumerate() {
+        sun.sun_path[i] = *b as c_char;
+    }
+    let saddr: *const sockaddr = &sun as *const libc::sockaddr_un as *const sockaddr;
+    let size: Socklen = (mem::size_of::<libc::sa_family_t>() + 11) as Socklen;
+    match sockaddr_to_endpoint(saddr, size) {
+        Ok(Endpoint::Unix(UnixSocketAddr::Abstract(name))) => assert_eq!(name, b"demikernel"),
+        _ => panic!("failed to convert"),
+    }
+
+    // The communication domain is not read past the length of the socket address.
+    assert_eq!(
+        sockaddr_to_endpoint(saddr, 1).err().map(|e| e.errno),
+        Some(libc::EINVAL)
+    );
+
+    // Socket addresses of other communication domains are still converted.
+    let saddr: sockaddr = sockaddr {
+        sa_family: AF_INET as u16,
+        sa_data: [0, 80, 127, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0],
+    };
+    match sockaddr_to_endpoint(&saddr, mem::size_of::<SockAddrIn>() as Socklen) {
+        Ok(Endpoint::Inet(addr)) => assert_eq!(addr.port(), 80),
+        _ => panic!("failed to convert"),
+    }
+}
+
+#[test]
+fn test_socketaddr_to_sockaddr() {
+    // SocketAddrV4: 127.0.0.1:80
+    let endpoint: SocketAddr = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(127, 0, 0, 1), 80));
+    let mut saddr: sockaddr = unsafe { mem::zeroed() };
+    let mut size: Socklen = mem::size_of::<sockaddr>() as Socklen;
+    unsafe { socketaddr_to_sockaddr(&endpoint, &mut saddr, &mut size) };
+    assert_eq!(size as usize, mem::size_of::<SockAddrIn>());
+    assert_eq!(saddr.sa_data[..6], [0, 80, 127, 0, 0, 1]);
+    match sockaddr_to_socketaddr(&saddr, size) {
+        Ok(addr) => assert_eq!(addr, endpoint),
+        _ => panic!("failed to convert"),
+    }
+}
+
+#[test]
+fn test_socketaddr_to_sockaddr_ipv6() {
+    // SocketAddrV6: [::1]:80
+    let endpoint: SocketAddr = SocketAddr::V6(SocketAddrV6::new(Ipv6Addr::LOCALHOST, 80, 0, 0));
+    let mut storage: SockAddrStorage = unsafe { mem::zeroed() };
+    let saddr: *mut sockaddr = &mut storage as *mut SockAddrStorage as *mut sockaddr;
+    let mut size: Socklen = mem::size_of::<SockAddrStorage>() as Socklen;
+    unsafe { socketaddr_to_sockaddr(&endpoint, saddr, &mut size) };
+    assert_eq!(size as usize, mem::size_of::<SockAddrIn6>());
+    assert_eq!(storage.ss_family as i32, AF_INET6);
+    match sockaddr_to_socketaddr(saddr, size) {
+        Ok(addr) => assert_eq!(addr, endpoint),
+        _ => panic!("failed to convert"),
+    }
+
+    // Socket addresses are truncated to fit.
+    let mut saddr: sockaddr = unsafe { mem::zeroed() };
+    let mut size: Socklen = mem::size_of::<SockAddrIn>() as Socklen;
+    unsafe { socketaddr_to_sockaddr(&endpoint, &mut saddr, &mut size) };
+    assert_eq!(size as usize, mem::size_of::<SockAddrIn6>());
+    assert_eq!(saddr.sa_family as i32, AF_INET6);
+}
+
+/// Instantiates Demikernel on top of Catnap, with an inline configuration.
+#[cfg(all(test, feature = "catnap-libos", target_os = "linux"))]
+fn new_catnap_instance() -> *mut demi_libos_t {
+    use crate::demikernel::{
+        config::Config,
+        libos::name::LibOSName,
+    };
+
+    let config: Config = "catnap: {}\n".parse().unwrap();
+    into_instance(LibOS::with_config(LibOSName::Catnap, &config).unwrap())
+}
+
+/// Waits for an operation to complete on a Demikernel instance.
+#[cfg(all(test, feature = "catnap-libos", target_os = "linux"))]
+fn wait_qt(handle: *mut demi_libos_t, qt: demi_qtoken_t) -> demi_qresult_t {
+    let mut qr: demi_qresult_t = unsafe { mem::zeroed() };
+    assert_eq!(unsafe { demi_wait_ex(handle, &mut qr, qt, ptr::null()) }, 0);
+    qr
+}
+
+/// Gets the local address of a socket on a Demikernel instance.
+#[cfg(all(test, feature = "catnap-libos", target_os = "linux"))]
+fn local_addr(handle: *mut demi_libos_t, qd: c_int) -> SocketAddr {
+    let mut saddr: SockAddrStorage = unsafe { mem::zeroed() };
+    let mut size: Socklen = mem::size_of::<SockAddrStorage>() as Socklen;
+    assert_eq!(
+        unsafe {
+            demi_getsockname_ex(
+                handle,
+                qd,
+                &mut saddr as *mut SockAddrStorage as *mut sockaddr,
+                &mut size,
+            )
+        },
+        0
+    );
+    get_socketaddr_from_sock_addr_storage(&saddr).unwrap()
+}
+
+/// Binds a socket on a Demikernel instance to a local address whose port is zero, so that the system picks a free port,
+/// and returns the address that the socket was bound to.
+#[cfg(all(test, feature = "catnap-libos", target_os = "linux"))]
+fn bind_ephemeral(handle: *mut demi_libos_t, qd: c_int, local: SocketAddr) -> SocketAddr {
+    let (saddr, size): (SockAddrStorage, Socklen) = create_sock_addr_storage(&local);
+    assert_eq!(
+        unsafe { demi_bind_ex(handle, qd, &saddr as *const SockAddrStorage as *const sockaddr, size) },
+        0
+    );
+    local_addr(handle, qd)
+}
+
+/// Writes a [UnixSocketAddr] into a `sockaddr_storage` structure, along with its length.
+#[cfg(all(test, feature = "catnap-libos", target_os = "linux"))]
+fn create_unix_sock_addr_storage(addr: &UnixSocketAddr) -> (SockAddrStorage, Socklen) {
+    use ::nix::sys::socket::{
+        SockaddrLike,
+        UnixAddr,
+    };
+
+    let unix_addr: UnixAddr = linux::unixsocketaddr_to_unixaddr(addr).unwrap();
+    (linux::unixaddr_to_sockaddr_storage(&unix_addr), unix_addr.len())
+}
+
+/// Asserts that a `sockaddr_storage` structure holds the address of a given Unix domain socket.
+#[cfg(all(test, feature = "catnap-libos", target_os = "linux"))]
+fn assert_unix_sock_addr_storage(storage: &SockAddrStorage, addr: &UnixSocketAddr) {
+    let expected: SockAddrStorage = linux::unixsocketaddr_to_sockaddr_storage(addr);
+    let (sun, expected_sun): (&libc::sockaddr_un, &libc::sockaddr_un) = unsafe {
+        (
+            &*(storage as *const SockAddrStorage as *const libc::sockaddr_un),
+            &*(&expected as *const SockAddrStorage as *const libc::sockaddr_un),
+        )
+    };
+    assert_eq!(sun.sun_family as i32, libc::AF_UNIX);
+    assert_eq!(sun.sun_path, expected_sun.sun_path);
+}
+
+#[cfg(all(feature = "catnap-libos", target_os = "linux"))]
+#[test]
+fn test_demi_strerror() {
+    let handle: *mut demi_libos_t = new_catnap_instance();
+
+    // Connect to a port where nobody listens, while another operation is pending.
+    let mut qd: c_int = 0;
+    assert_eq!(
+        unsafe { demi_socket_ex(handle, &mut qd, AF_INET, libc::SOCK_DGRAM, 0) },
+        0
+    );
+    let mut pop_qt: demi_qtoken_t = 0;
+    assert_eq!(unsafe { demi_pop_ex(handle, &mut pop_qt, qd) }, 0);
+    assert_eq!(
+        unsafe { demi_socket_ex(handle, &mut qd, AF_INET, libc::SOCK_STREAM, 0) },
+        0
+    );
+    let remote: SocketAddr = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 1));
+    let mut saddr: SockAddrStorage = unsafe { mem::zeroed() };
+    let mut size: Socklen = mem::size_of::<SockAddrStorage>() as Socklen;
+    unsafe { socketaddr_to_sockaddr(&remote, &mut saddr as *mut SockAddrStorage as *mut sockaddr, &mut size) };
+    let mut connect_qt: demi_qtoken_t = 0;
+    assert_eq!(
+        unsafe {
+            demi_connect_ex(
+                handle,
+                &mut connect_qt,
+                qd,
+                &saddr as *const SockAddrStorage as *const sockaddr,
+                size,
+            )
+        },
+        0
+    );
+    let mut connect_qr: demi_qresult_t = unsafe { mem::zeroed() };
+    assert_eq!(
+        unsafe { demi_wait_ex(handle, &mut connect_qr, connect_qt, ptr::null()) },
+        0
+    );
+    assert_eq!(connect_qr.qr_ret, libc::ECONNREFUSED);
+
+    // Cancel the other operation, which fails on its own.
+    let mut cancel_qr: demi_qresult_t = unsafe { mem::zeroed() };
+    assert_eq!(unsafe { demi_cancel_ex(handle, &mut cancel_qr, pop_qt) }, 0);
+    assert_eq!(cancel_qr.qr_ret, libc::ECANCELED);
+
+    // Each failure is described by its own cause.
+    let cause: &CStr = unsafe { CStr::from_ptr(demi_strerror_ex(handle, &connect_qr)) };
+    assert_eq!(cause.to_str().unwrap(), "operation failed");
+    let cause: &CStr = unsafe { CStr::from_ptr(demi_strerror_ex(handle, &cancel_qr)) };
+    assert_eq!(cause.to_str().unwrap(), "operation cancelled");
+
+    // Other results fall back to the system description.
+    connect_qr.qr_ret = libc::ETIMEDOUT;
+    let cause: &CStr = unsafe { CStr::from_ptr(demi_strerror_ex(handle, &connect_qr)) };
+    assert!(!cause.to_bytes().is_empty() && cause.to_str().unwrap() != "operation failed");
+
+    assert_eq!(unsafe { demi_destroy_instance(handle) }, 0);
+}
+
+#[cfg(all(feature = "catnap-libos", target_os = "linux"))]
+#[test]
+fn test_demi_init_instance() {
+    // The storage location for the instance handle is checked.
+    let arg0: CString = CString::new("test").unwrap();
+    let mut argv: [*mut c_char; 1] = [arg0.as_ptr() as *mut c_char];
+    assert_eq!(
+        unsafe { demi_init_instance(ptr::null_mut(), 1, argv.as_mut_ptr()) },
+        libc::EINVAL
+    );
+
+    // Instantiate two independent instances.
+    let libos1: *mut demi_libos_t = new_catnap_instance();
+    let libos2: *mut demi_libos_t = new_catnap_instance();
+    assert_ne!(libos1, libos2);
+
+    // Each instance serves its own system calls.
+    let mut sga1: demi_sgarray_t = unsafe { demi_sgaalloc_ex(libos1, 64) };
+    let mut sga2: demi_sgarray_t = unsafe { demi_sgaalloc_ex(libos2, 64) };
+    assert!(!sga1.sga_buf.is_null() && !sga2.sga_buf.is_null());
+    assert_eq!(unsafe { demi_sgafree_ex(libos1, &mut sga1) }, 0);
+    assert_eq!(unsafe { demi_sgafree_ex(libos2, &mut sga2) }, 0);
+
+    // Instances may only be used by the thread that owns them.
+    let handle: usize = libos1 as usize;
+    let ret: c_int = thread::spawn(move || unsafe { demi_destroy_instance(handle as *mut demi_libos_t) })
+        .join()
+        .unwrap();
+    assert_eq!(ret, libc::EPERM);
+
+    assert_eq!(unsafe { demi_destroy_instance(libos1) }, 0);
+    assert_eq!(unsafe { demi_destroy_instance(libos2) }, 0);
+}
+
+#[cfg(all(feature = "catnap-libos", target_os = "linux"))]
+#[test]
+fn test_demi_init_env() {
+    let config_path: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/scripts/config/default.yaml");
+    let saved: Vec<(&str, Option<String>)> = ["LIBOS", "CONFIG_PATH"]
+        .into_iter()
+        .map(|key| (key, ::std::env::var(key).ok()))
+        .collect();
+    ::std::env::set_var("LIBOS", "catnap");
+    ::std::env::set_var("CONFIG_PATH", config_path);
+
+    // Without arguments, the LibOS and its configuration file are taken from the environment.
+    assert_eq!(demi_init(0, ptr::null_mut()), 0);
+    let mut sga: demi_sgarray_t = demi_sgaalloc(64);
+    assert!(!sga.sga_buf.is_null());
+    assert_eq!(demi_sgafree(&mut sga), 0);
+    unsafe { DEMIKERNEL = RefCell::new(None) };
+
+    // The same goes for arguments that do not select any of them.
+    let arg0: CString = CString::new("test").unwrap();
+    let mut argv: [*mut c_char; 1] = [arg0.as_ptr() as *mut c_char];
+    let mut handle: *mut demi_libos_t = ptr::null_mut();
+    assert_eq!(unsafe { demi_init_instance(&mut handle, 1, argv.as_mut_ptr()) }, 0);
+    assert_eq!(unsafe { demi_destroy_instance(handle) }, 0);
+
+    for (key, value) in saved {
+        match value {
+            Some(value) => ::std::env::set_var(key, value),
+            None => ::std::env::remove_var(key),
+        }
+    }
+}
+
+#[cfg(all(feature = "catnap-libos", target_os = "linux"))]
+#[test]
+fn test_demi_pop_source_addr() {
+    let handle: *mut demi_libos_t = new_catnap_instance();
+
+    // Send a datagram from one socket to another.
+    let (mut localqd, mut remoteqd, mut qt): (c_int, c_int, demi_qtoken_t) = (0, 0, 0);
+    for qd in [&mut localqd, &mut remoteqd] {
+        assert_eq!(unsafe { demi_socket_ex(handle, qd, AF_INET, libc::SOCK_DGRAM, 0) }, 0);
+    }
+    let local: SocketAddr = bind_ephemeral(handle, localqd, "127.0.0.1:0".parse().unwrap());
+    let remote: SocketAddr = bind_ephemeral(handle, remoteqd, "127.0.0.1:0".parse().unwrap());
+    let (saddr, size): (SockAddrStorage, Socklen) = create_sock_addr_storage(&local);
+    let mut sga: demi_sgarray_t = unsafe { demi_sgaalloc_ex(handle, 32) };
+    assert_eq!(
+        unsafe {
+            demi_pushto_ex(
+                handle,
+                &mut qt,
+                remoteqd,
+                &sga,
+                &saddr as *const SockAddrStorage as *const sockaddr,
+                size,
+            )
+        },
+        0
+    );
+    wait_qt(handle, qt);
+    assert_eq!(unsafe { demi_sgafree_ex(handle, &mut sga) }, 0);
+
+    // The source address is in network byte order.
+    assert_eq!(unsafe { demi_pop_ex(handle, &mut qt, localqd) }, 0);
+    let mut qr: demi_qresult_t = wait_qt(handle, qt);
+    let sga: &mut demi_sgarray_t = unsafe { &mut qr.qr_value.sga };
+    assert_eq!(get_socketaddr_from_sock_addr_storage(&sga.sga_addr), Some(remote));
+    assert_eq!(unsafe { demi_sgafree_ex(handle, sga) }, 0);
+
+    assert_eq!(unsafe { demi_close_ex(handle, localqd) }, 0);
+    assert_eq!(unsafe { demi_close_ex(handle, remoteqd) }, 0);
+    assert_eq!(unsafe { demi_destroy_instance(handle) }, 0);
+}
+
+#[cfg(all(feature = "catnap-libos", target_os = "linux"))]
+#[test]
+fn test_demi_wait_many() {
+    let handle: *mut demi_libos_t = new_catnap_instance();
+    let mut qd: c_int = 0;
+    assert_eq!(
+        unsafe { demi_socket_ex(handle, &mut qd, AF_INET, libc::SOCK_DGRAM, 0) },
+        0
+    );
+    let local: SocketAddr = bind_ephemeral(handle, qd, "127.0.0.1:0".parse().unwrap());
+    let (saddr, size): (SockAddrStorage, Socklen) = create_sock_addr_storage(&local);
+
+    // Send a few datagrams to ourselves.
+    let mut sga: demi_sgarray_t = unsafe { demi_sgaalloc_ex(handle, 32) };
+    let mut qts: [demi_qtoken_t; 3] = [0; 3];
+    for qt in qts.iter_mut() {
+        assert_eq!(
+            unsafe {
+                demi_pushto_ex(
+                    handle,
+                    qt,
+                    qd,
+                    &sga,
+                    &saddr as *const SockAddrStorage as *const sockaddr,
+                    size,
+                )
+            },
+            0
+        );
+    }
+
+    // The number of results is checked.
+    let mut qrs: [demi_qresult_t; 3] = unsafe { mem::zeroed() };
+    let mut nqrs: c_int = 0;
+    assert_eq!(
+        unsafe { demi_wait_many_ex(handle, qrs.as_mut_ptr(), 0, &mut nqrs, qts.as_ptr(), 3, ptr::null()) },
+        libc::EINVAL
+    );
+
+    // Results never overrun the room that was given for them, and those left out are returned later on.
+    let mut completed: Vec<demi_qtoken_t> = Vec::new();
+    while completed.len() < qts.len() {
+        let pending: Vec<demi_qtoken_t> = qts.iter().copied().filter(|qt| !completed.contains(qt)).collect();
+        assert_eq!(
+            unsafe {
+                demi_wait_many_ex(
+                    handle,
+                    qrs.as_mut_ptr(),
+                    2,
+                    &mut nqrs,
+                    pending.as_ptr(),
+                    pending.len() as c_int,
+                    ptr::null(),
+                )
+            },
+            0
+        );
+        assert!(nqrs >= 1 && nqrs <= 2);
+        assert_eq!(qrs[2].qr_qt, 0);
+        for qr in &qrs[..nqrs as usize] {
+            assert_eq!(qr.qr_ret, 0);
+            completed.push(qr.qr_qt);
+        }
+        // Results come in the order of the list.
+        assert_eq!(&completed[completed.len() - nqrs as usize..], &pending[..nqrs as usize]);
+    }
+    assert_eq!(unsafe { demi_sgafree_ex(handle, &mut sga) }, 0);
+
+    assert_eq!(unsafe { demi_close_ex(handle, qd) }, 0);
+    assert_eq!(unsafe { demi_destroy_instance(handle) }, 0);
+}
+
+#[cfg(all(feature = "catnap-libos", target_os = "linux"))]
+#[test]
+fn test_demi_getstats() {
+    use crate::runtime::types::DEMI_QSTATS_VERSION;
+
+    let handle: *mut demi_libos_t = new_catnap_instance();
+    let mut qd: c_int = 0;
+    assert_eq!(
+        unsafe { demi_socket_ex(handle, &mut qd, AF_INET, libc::SOCK_DGRAM, 0) },
+        0
+    );
+
+    // The structure should at least have room for the version and the flags.
+    let mut stats: demi_qstats_t = demi_qstats_t::default();
+    assert_eq!(unsafe { demi_getstats_ex(handle, qd, &mut stats, 4) }, libc::EINVAL);
+
+    // Callers that know of fewer fields only get those.
+    let mut buf: [u8; mem::size_of::<demi_qstats_t>()] = [0xaa; mem::size_of::<demi_qstats_t>()];
+    assert_eq!(
+        unsafe { demi_getstats_ex(handle, qd, buf.as_mut_ptr() as *mut demi_qstats_t, 8) },
+        0
+    );
+    assert_eq!(&buf[..4], &DEMI_QSTATS_VERSION.to_ne_bytes());
+    assert!(buf[8..].iter().all(|&b| b == 0xaa));
+
+    // Others get the whole structure.
+    stats.qs_pending = u64::MAX;
+    assert_eq!(
+        unsafe { demi_getstats_ex(handle, qd, &mut stats, mem::size_of::<demi_qstats_t>()) },
+        0
+    );
+    assert_eq!((stats.qs_version, stats.qs_pending), (DEMI_QSTATS_VERSION, 0));
+
+    assert_eq!(unsafe { demi_close_ex(handle, qd) }, 0);
+    assert_eq!(unsafe { demi_destroy_instance(handle) }, 0);
+}
+
+#[cfg(all(feature = "catnap-libos", target_os = "linux"))]
+#[test]
+fn test_demi_ipv6_loopback() {
+    use crate::runtime::types::demi_opcode_t;
+
+    let handle: *mut demi_libos_t = new_catnap_instance();
+
+    // Set up a connection, and check that the remote peer is reported on accept.
+    let (mut listenqd, mut clientqd, mut qt): (c_int, c_int, demi_qtoken_t) = (0, 0, 0);
+    assert_eq!(
+        unsafe { demi_socket_ex(handle, &mut listenqd, AF_INET6, libc::SOCK_STREAM, 0) },
+        0
+    );
+    let listen: SocketAddr = bind_ephemeral(handle, listenqd, "[::1]:0".parse().unwrap());
+    let (saddr, size): (SockAddrStorage, Socklen) = create_sock_addr_storage(&listen);
+    let saddr: *const sockaddr = &saddr as *const SockAddrStorage as *const sockaddr;
+    assert_eq!(unsafe { demi_listen_ex(handle, listenqd, 16) }, 0);
+    assert_eq!(
+        unsafe { demi_socket_ex(handle, &mut clientqd, AF_INET6, libc::SOCK_STREAM, 0) },
+        0
+    );
+    assert_eq!(unsafe { demi_connect_ex(handle, &mut qt, clientqd, saddr, size) }, 0);
+    assert_eq!(wait_qt(handle, qt).qr_opcode, demi_opcode_t::DEMI_OPC_CONNECT);
+    let client: SocketAddr = local_addr(handle, clientqd);
+    assert!(client.is_ipv6());
+    assert_eq!(unsafe { demi_accept_ex(handle, &mut qt, listenqd) }, 0);
+    let qr: demi_qresult_t = wait_qt(handle, qt);
+    let serverqd: c_int = unsafe { qr.qr_value.ares.qd };
+    assert_eq!(
+        get_socketaddr_from_sock_addr_storage(unsafe { &qr.qr_value.ares.addr }),
+        Some(client)
+    );
+
+    // Data flows over the connection.
+    let mut sga: demi_sgarray_t = unsafe { demi_sgaalloc_ex(handle, 32) };
+    assert_eq!(unsafe { demi_push_ex(handle, &mut qt, clientqd, &sga) }, 0);
+    assert_eq!(wait_qt(handle, qt).qr_opcode, demi_opcode_t::DEMI_OPC_PUSH);
+    assert_eq!(unsafe { demi_pop_bounded_ex(handle, &mut qt, serverqd, 32, 32) }, 0);
+    let mut qr: demi_qresult_t = wait_qt(handle, qt);
+    assert_eq!(unsafe { qr.qr_value.sga.sga_segs[0].sgaseg_len }, 32);
+    assert_eq!(unsafe { demi_sgafree_ex(handle, &mut qr.qr_value.sga) }, 0);
+    for qd in [serverqd, clientqd, listenqd] {
+        assert_eq!(unsafe { demi_close_ex(handle, qd) }, 0);
+    }
+
+    // Send a datagram, and check that its source is reported on pop.
+    let (mut localqd, mut remoteqd): (c_int, c_int) = (0, 0);
+    for qd in [&mut localqd, &mut remoteqd] {
+        assert_eq!(unsafe { demi_socket_ex(handle, qd, AF_INET6, libc::SOCK_DGRAM, 0) }, 0);
+    }
+    let local: SocketAddr = bind_ephemeral(handle, localqd, "[::1]:0".parse().unwrap());
+    let remote: SocketAddr = bind_ephemeral(handle, remoteqd, "[::1]:0".parse().unwrap());
+    let (saddr, size): (SockAddrStorage, Socklen) = create_sock_addr_storage(&local);
+    assert_eq!(
+        unsafe {
+            demi_pushto_ex(
+                handle,
+                &mut qt,
+                remoteqd,
+                &sga,
+                &saddr as *const SockAddrStorage as *const sockaddr,
+                size,
+            )
+        },
+        0
+    );
+    wait_qt(handle, qt);
+    assert_eq!(unsafe { demi_sgafree_ex(handle, &mut sga) }, 0);
+    assert_eq!(unsafe { demi_pop_ex(handle, &mut qt, localqd) }, 0);
+    let mut qr: demi_qresult_t = wait_qt(handle, qt);
+    let sga: &mut demi_sgarray_t = unsafe { &mut qr.qr_value.sga };
+    assert_eq!(sga.sga_segs[0].sgaseg_len, 32);
+    assert_eq!(get_socketaddr_from_sock_addr_storage(&sga.sga_addr), Some(remote));
+    assert_eq!(unsafe { demi_sgafree_ex(handle, sga) }, 0);
+
+    assert_eq!(unsafe { demi_close_ex(handle, localqd) }, 0);
+    assert_eq!(unsafe { demi_close_ex(handle, remoteqd) }, 0);
+    assert_eq!(unsafe { demi_destroy_instance(handle) }, 0);
+}
+
+#[cfg(all(feature = "catnap-libos", target_os = "linux"))]
+#[test]
+fn test_demi_unix_stream() {
+    use crate::runtime::types::demi_opcode_t;
+    use ::std::{
+        env,
+        fs,
+        path::PathBuf,
+        process,
+    };
+
+    let handle: *mut demi_libos_t = new_catnap_instance();
+    let path: PathBuf = env::temp_dir().join(format!("demikernel-{}-stream.sock", process::id()));
+    let client_path: PathBuf = env::temp_dir().join(format!("demikernel-{}-stream-client.sock", process::id()));
+    let name: Vec<u8> = format!("demikernel-{}-stream", process::id()).into_bytes();
+    let client_name: Vec<u8> = format!("demikernel-{}-stream-client", process::id()).into_bytes();
+
+    // Connect over a path name and over an abstract name, from a client that binds its socket.
+    for (local, remote) in [
+        (
+            UnixSocketAddr::Pathname(path.clone()),
+            UnixSocketAddr::Pathname(client_path.clone()),
+        ),
+        (UnixSocketAddr::Abstract(name), UnixSocketAddr::Abstract(client_name)),
+    ] {
+        let _ = fs::remove_file(&path);
+        let _ = fs::remove_file(&client_path);
+        let (saddr, size): (SockAddrStorage, Socklen) = create_unix_sock_addr_storage(&local);
+        let saddr: *const sockaddr = &saddr as *const SockAddrStorage as *const sockaddr;
+        let (client_saddr, client_size): (SockAddrStorage, Socklen) = create_unix_sock_addr_storage(&remote);
+        let client_saddr: *const sockaddr = &client_saddr as *const SockAddrStorage as *const sockaddr;
+
+        let (mut listenqd, mut clientqd, mut qt): (c_int, c_int, demi_qtoken_t) = (0, 0, 0);
+        assert_eq!(
+            unsafe { demi_socket_ex(handle, &mut listenqd, libc::AF_UNIX, libc::SOCK_STREAM, 0) },
+            0
+        );
+        assert_eq!(unsafe { demi_bind_ex(handle, listenqd, saddr, size) }, 0);
+        assert_eq!(unsafe { demi_listen_ex(handle, listenqd, 16) }, 0);
+        assert_eq!(
+            unsafe { demi_socket_ex(handle, &mut clientqd, libc::AF_UNIX, libc::SOCK_STREAM, 0) },
+            0
+        );
+        assert_eq!(unsafe { demi_bind_ex(handle, clientqd, client_saddr, client_size) }, 0);
+        assert_eq!(unsafe { demi_connect_ex(handle, &mut qt, clientqd, saddr, size) }, 0);
+        assert_eq!(wait_qt(handle, qt).qr_opcode, demi_opcode_t::DEMI_OPC_CONNECT);
+
+        // The remote peer is reported on accept.
+        assert_eq!(unsafe { demi_accept_ex(handle, &mut qt, listenqd) }, 0);
+        let qr: demi_qresult_t = wait_qt(handle, qt);
+        assert_eq!(qr.qr_opcode, demi_opcode_t::DEMI_OPC_ACCEPT);
+        let serverqd: c_int = unsafe { qr.qr_value.ares.qd };
+        assert_unix_sock_addr_storage(unsafe { &qr.qr_value.ares.addr }, &remote);
+
+        // Data flows over the connection.
+        let mut sga: demi_sgarray_t = unsafe { demi_sgaalloc_ex(handle, 32) };
+        assert_eq!(unsafe { demi_push_ex(handle, &mut qt, clientqd, &sga) }, 0);
+        assert_eq!(wait_qt(handle, qt).qr_opcode, demi_opcode_t::DEMI_OPC_PUSH);
+        assert_eq!(unsafe { demi_sgafree_ex(handle, &mut sga) }, 0);
+        assert_eq!(unsafe { demi_pop_bounded_ex(handle, &mut qt, serverqd, 32, 32) }, 0);
+        let mut qr: demi_qresult_t = wait_qt(handle, qt);
+        assert_eq!(qr.qr_opcode, demi_opcode_t::DEMI_OPC_POP);
+        assert_eq!(unsafe { qr.qr_value.sga.sga_segs[0].sgaseg_len }, 32);
+        assert_eq!(unsafe { demi_sgafree_ex(handle, &mut qr.qr_value.sga) }, 0);
+
+        for qd in [serverqd, clientqd, listenqd] {
+            assert_eq!(unsafe { demi_close_ex(handle, qd) }, 0);
+        }
+    }
+
+    let _ = fs::remove_file(&path);
+    let _ = fs::remove_file(&client_path);
+    assert_eq!(unsafe { demi_destroy_instance(handle) }, 0);
+}
+
+#[cfg(all(feature = "catnap-libos", target_os = "linux"))]
+#[test]
+fn test_demi_unix_dgram() {
+    use crate::runtime::types::demi_opcode_t;
+    use ::std::{
+        env,
+        fs,
+        process,
+    };
+
+    let handle: *mut demi_libos_t = new_catnap_instance();
+    let local: UnixSocketAddr =
+        UnixSocketAddr::Pathname(env::temp_dir().join(format!("demikernel-{}-dgram.sock", process::id())));
+    let remote: UnixSocketAddr = UnixSocketAddr::Abstract(format!("demikernel-{}-dgram", process::id()).into_bytes());
+    if let UnixSocketAddr::Pathname(ref path) = local {
+        let _ = fs::remove_file(path);
+    }
+
+    // Bind two sockets, one to a path name and the other to an abstract name.
+    let (mut localqd, mut remoteqd, mut qt): (c_int, c_int, demi_qtoken_t) = (0, 0, 0);
+    for (qd, addr) in [(&mut localqd, &local), (&mut remoteqd, &remote)] {
+        let (saddr, size): (SockAddrStorage, Socklen) = create_unix_sock_addr_storage(addr);
+        assert_eq!(
+            unsafe { demi_socket_ex(handle, qd, libc::AF_UNIX, libc::SOCK_DGRAM, 0) },
+            0
+        );
+        assert_eq!(
+            unsafe { demi_bind_ex(handle, *qd, &saddr as *const SockAddrStorage as *const sockaddr, size) },
+            0
+        );
+    }
+
+    // Send a datagram from one socket to the other, and check that its source is reported on pop.
+    let (saddr, size): (SockAddrStorage, Socklen) = create_unix_sock_addr_storage(&local);
+    let mut sga: demi_sgarray_t = unsafe { demi_sgaalloc_ex(handle, 32) };
+    assert_eq!(
+        unsafe {
+            demi_pushto_ex(
+                handle,
+                &mut qt,
+                remoteqd,
+                &sga,
+                &saddr as *const SockAddrStorage as *const sockaddr,
+                size,
+            )
+        },
+        0
+    );
+    assert_eq!(wait_qt(handle, qt).qr_opcode, demi_opcode_t::DEMI_OPC_PUSH);
+    assert_eq!(unsafe { demi_sgafree_ex(handle, &mut sga) }, 0);
+    assert_eq!(unsafe { demi_pop_ex(handle, &mut qt, localqd) }, 0);
+    let mut qr: demi_qresult_t = wait_qt(handle, qt);
+    assert_eq!(qr.qr_opcode, demi_opcode_t::DEMI_OPC_POP);
+    let sga: &mut demi_sgarray_t = unsafe { &mut qr.qr_value.sga };
+    assert_eq!(sga.sga_segs[0].sgaseg_len, 32);
+    assert_unix_sock_addr_storage(&sga.sga_addr, &remote);
+    assert_eq!(unsafe { demi_sgafree_ex(handle, sga) }, 0);
+
+    assert_eq!(unsafe { demi_close_ex(handle, localqd) }, 0);
+    assert_eq!(unsafe { demi_close_ex(handle, remoteqd) }, 0);
+    if let UnixSocketAddr::Pathname(ref path) = local {
+        let _ = fs::remove_file(path);
+    }
+    assert_eq!(unsafe { demi_destroy_instance(handle) }, 0);
+}
+
+#[cfg(all(feature = "catnap-libos", target_os = "linux"))]
+#[test]
+fn test_demi_udp_batches() {
+    use crate::runtime::types::demi_opcode_t;
+
+    let handle: *mut demi_libos_t = new_catnap_instance();
+
+    // Connect one socket to another.
+    let (mut localqd, mut remoteqd, mut qt): (c_int, c_int, demi_qtoken_t) = (0, 0, 0);
+    for qd in [&mut localqd, &mut remoteqd] {
+        assert_eq!(unsafe { demi_socket_ex(handle, qd, AF_INET, libc::SOCK_DGRAM, 0) }, 0);
+    }
+    let local: SocketAddr = bind_ephemeral(handle, localqd, "127.0.0.1:0".parse().unwrap());
+    let remote: SocketAddr = bind_ephemeral(handle, remoteqd, "127.0.0.1:0".parse().unwrap());
+    let (saddr, size): (SockAddrStorage, Socklen) = create_sock_addr_storage(&local);
+    assert_eq!(
+        unsafe {
+            demi_connect_ex(
+                handle,
+                &mut qt,
+                remoteqd,
+                &saddr as *const SockAddrStorage as *const sockaddr,
+                size,
+            )
+        },
+        0
+    );
+    assert_eq!(wait_qt(handle, qt).qr_opcode, demi_opcode_t::DEMI_OPC_CONNECT);
+
+    // Push datagrams of increasing sizes over the connection, and wait for all of them at once.
+    let mut qts: Vec<demi_qtoken_t> = Vec::new();
+    for len in [8, 16, 32, 4] {
+        let mut sga: demi_sgarray_t = unsafe { demi_sgaalloc_ex(handle, len) };
+        assert_eq!(unsafe { demi_push_ex(handle, &mut qt, remoteqd, &sga) }, 0);
+        assert_eq!(unsafe { demi_sgafree_ex(handle, &mut sga) }, 0);
+        qts.push(qt);
+    }
+    for qt in qts {
+        let qr: demi_qresult_t = wait_qt(handle, qt);
+        assert_eq!((qr.qr_opcode, qr.qr_ret), (demi_opcode_t::DEMI_OPC_PUSH, 0));
+    }
+
+    // Datagrams are popped one at a time, and the bytes that exceed the maximum size are discarded.
+    for expected in [8, 16, 16, 4] {
+        assert_eq!(unsafe { demi_pop_bounded_ex(handle, &mut qt, localqd, 0, 16) }, 0);
+        let mut qr: demi_qresult_t = wait_qt(handle, qt);
+        assert_eq!(qr.qr_opcode, demi_opcode_t::DEMI_OPC_POP);
+        let sga: &mut demi_sgarray_t = unsafe { &mut qr.qr_value.sga };
+        assert_eq!(sga.sga_segs[0].sgaseg_len, expected);
+        assert_eq!(get_socketaddr_from_sock_addr_storage(&sga.sga_addr), Some(remote));
+        assert_eq!(unsafe { demi_sgafree_ex(handle, sga) }, 0);
+    }
+
+    assert_eq!(unsafe { demi_close_ex(handle, localqd) }, 0);
+    assert_eq!(unsafe { demi_close_ex(handle, remoteqd) }, 0);
+    assert_eq!(unsafe { demi_destroy_instance(handle) }, 0);
+}
+
+#[cfg(all(feature = "catnap-libos", target_os = "linux"))]
+#[test]
+fn test_demi_async_close() {
+    use crate::runtime::types::demi_opcode_t;
+
+    let handle: *mut demi_libos_t = new_catnap_instance();
+
+    // Set up a connection.
+    let (mut listenqd, mut clientqd, mut qt): (c_int, c_int, demi_qtoken_t) = (0, 0, 0);
+    assert_eq!(
+        unsafe { demi_socket_ex(handle, &mut listenqd, AF_INET, libc::SOCK_STREAM, 0) },
+        0
+    );
+    let listen: SocketAddr = bind_ephemeral(handle, listenqd, "127.0.0.1:0".parse().unwrap());
+    let (saddr, size): (SockAddrStorage, Socklen) = create_sock_addr_storage(&listen);
+    let saddr: *const sockaddr = &saddr as *const SockAddrStorage as *const sockaddr;
+    assert_eq!(unsafe { demi_listen_ex(handle, listenqd, 16) }, 0);
+    assert_eq!(
+        unsafe { demi_socket_ex(handle, &mut clientqd, AF_INET, libc::SOCK_STREAM, 0) },
+        0
+    );
+    assert_eq!(unsafe { demi_connect_ex(handle, &mut qt, clientqd, saddr, size) }, 0);
+    assert_eq!(wait_qt(handle, qt).qr_opcode, demi_opcode_t::DEMI_OPC_CONNECT);
+    assert_eq!(unsafe { demi_accept_ex(handle, &mut qt, listenqd) }, 0);
+    let serverqd: c_int = unsafe { wait_qt(handle, qt).qr_value.ares.qd };
+
+    // Closing a listening socket completes right away.
+    assert_eq!(unsafe { demi_async_close_ex(handle, &mut qt, listenqd) }, 0);
+    let qr: demi_qresult_t = wait_qt(handle, qt);
+    assert_eq!((qr.qr_opcode, qr.qr_qd), (demi_opcode_t::DEMI_OPC_CLOSE, listenqd));
+
+    // Closing a connected socket completes once pushed data is acknowledged, and the remote peer gets all of it.
+    let mut sga: demi_sgarray_t = unsafe { demi_sgaalloc_ex(handle, 1024) };
+    assert_eq!(unsafe { demi_push_ex(handle, &mut qt, clientqd, &sga) }, 0);
+    assert_eq!(wait_qt(handle, qt).qr_opcode, demi_opcode_t::DEMI_OPC_PUSH);
+    assert_eq!(unsafe { demi_sgafree_ex(handle, &mut sga) }, 0);
+    assert_eq!(unsafe { demi_async_close_ex(handle, &mut qt, clientqd) }, 0);
+    let qr: demi_qresult_t = wait_qt(handle, qt);
+    assert_eq!((qr.qr_opcode, qr.qr_qd), (demi_opcode_t::DEMI_OPC_CLOSE, clientqd));
+    let mut len: u32 = 0;
+    loop {
+        assert_eq!(unsafe { demi_pop_ex(handle, &mut qt, serverqd) }, 0);
+        let mut qr: demi_qresult_t = wait_qt(handle, qt);
+        assert_eq!(qr.qr_opcode, demi_opcode_t::DEMI_OPC_POP);
+        let popped: u32 = unsafe { qr.qr_value.sga.sga_segs[0].sgaseg_len };
+        assert_eq!(unsafe { demi_sgafree_ex(handle, &mut qr.qr_value.sga) }, 0);
+        if popped == 0 {
+            break;
+        }
+        len += popped;
+    }
+    assert_eq!(len, 1024);
+
+    // Closed queue descriptors may not be used anymore.
+    assert_eq!(unsafe { demi_async_close_ex(handle, &mut qt, clientqd) }, libc::EBADF);
+    assert_eq!(unsafe { demi_close_ex(handle, serverqd) }, 0);
+    assert_eq!(unsafe { demi_destroy_instance(handle) }, 0);
+}
@@ -5,9 +5,12 @@
 // Imports
 //======================================================================================================================
 
+use crate::runtime::fail::Fail;
 use ::std::{
-    fs::File,
-    io::Read,
+    collections::HashMap,
+    env,
+    fs,
+    str::FromStr,
 };
 use ::yaml_rust::{
     Yaml,
@@ -19,7 +22,27 @@ use ::yaml_rust::{
 //======================================================================================================================
 
 /// Demikernel configuration.
-pub struct Config(pub Yaml);
+///
+/// A configuration is a single YAML document with one section per LibOS (e.g. `catnip` or `catpowder`). Sections are
+/// only parsed and validated by the LibOS that reads them, so that a configuration file may be shared across LibOSes.
+/// Any key of a section may be overridden by an environment variable named `DEMI_<SECTION>_<KEY>`, in upper case (e.g.
+/// `DEMI_CATNIP_MY_IPV4_ADDR`), and these are in turn superseded by overrides set with [Config::set_override].
+pub struct Config {
+    /// Underlying YAML document.
+    yaml: Yaml,
+    /// Overridden values, indexed by `<section>.<key>`.
+    overrides: HashMap<String, String>,
+}
+
+/// Section of a Demikernel configuration.
+pub struct Section<'a> {
+    /// Name of the section.
+    name: &'static str,
+    /// Underlying YAML mapping.
+    yaml: &'a Yaml,
+    /// Overridden values of the whole configuration.
+    overrides: &'a HashMap<String, String>,
+}
 
 //======================================================================================================================
 // Associated Functions
@@ -28,35 +51,389 @@ pub struct Config(pub Yaml);
 /// Common associated functions for Demikernel configuration object.
 impl Config {
     /// Reads a configuration file into a [Config] object.
-    pub fn new(config_path: String) -> Self {
-        // FIXME: this function should return a Result.
-        let mut config_s: String = String::new();
-        File::open(config_path).unwrap().read_to_string(&mut config_s).unwrap();
-        let config: Vec<Yaml> = YamlLoader::load_from_str(&config_s).unwrap();
-        let config_obj: &Yaml = match &config[..] {
-            &[ref c] => c,
-            _ => Err(anyhow::format_err!("Wrong number of config objects")).unwrap(),
+    pub fn new(config_path: &str) -> Result<Self, Fail> {
+        let config_s: String = match fs::read_to_string(config_path) {
+            Ok(config_s) => config_s,
+            Err(e) => {
+                let cause: String = format!("failed to read configuration file {:?}: {}", config_path, e);
+                error!("new(): {}", cause);
+                return Err(Fail::new(e.raw_os_error().unwrap_or(libc::EIO), &cause));
+            },
         };
+        config_s.parse()
+    }
+
+    /// Borrows a section of the target configuration. The section is empty if it is missing.
+    pub fn section(&self, name: &'static str) -> Result<Section, Fail> {
+        match &self.yaml[name] {
+            yaml @ (Yaml::Hash(_) | Yaml::BadValue | Yaml::Null) => Ok(Section {
+                name,
+                yaml,
+                overrides: &self.overrides,
+            }),
+            _ => Err(Fail::new(
+                libc::EINVAL,
+                &format!("invalid configuration section {:?}: expected a mapping", name),
+            )),
+        }
+    }
+
+    /// Borrows a section of the target configuration, falling back to a legacy section if the former is missing.
+    /// Environment variable overrides still refer to the name of the requested section.
+    pub fn section_or(&self, name: &'static str, legacy: &'static str) -> Result<Section, Fail> {
+        let section: Section = self.section(name)?;
+        if section.is_empty() && !self.section(legacy)?.is_empty() {
+            warn!("section_or(): reading {:?} section instead of {:?}", legacy, name);
+            return Ok(Section {
+                name,
+                yaml: self.section(legacy)?.yaml,
+                overrides: &self.overrides,
+            });
+        }
+        Ok(section)
+    }
+
+    /// Overrides the value of a key, which is given as `<section>.<key>`.
+    pub fn set_override(&mut self, key: &str, value: &str) -> Result<(), Fail> {
+        match key.split_once('.') {
+            Some((section, name)) if !section.is_empty() && !name.is_empty() => {
+                self.overrides.insert(key.to_lowercase(), value.to_string());
+                Ok(())
+            },
+            _ => Err(Fail::new(
+                libc::EINVAL,
+                &format!("invalid configuration key {:?}: expected <section>.<key>", key),
+            )),
+        }
+    }
+}
+
+/// Associated functions for sections of a Demikernel configuration.
+impl<'a> Section<'a> {
+    /// Checks if the target section is empty.
+    pub fn is_empty(&self) -> bool {
+        match self.yaml.as_hash() {
+            Some(hash) => hash.is_empty(),
+            None => true,
+        }
+    }
+
+    /// Reads the value of a key in the target section, if any.
+    pub fn get<T: FromStr>(&self, key: &str) -> Result<Option<T>, Fail> {
+        if let Some(value) = self.get_override(key) {
+            return self.parse(key, &value).map(Some);
+        }
+        match &self.yaml[key] {
+            Yaml::BadValue | Yaml::Null => Ok(None),
+            yaml => match scalar(yaml) {
+                Some(value) => self.parse(key, &value).map(Some),
+                None => Err(self.invalid(key, "expected a scalar")),
+            },
+        }
+    }
+
+    /// Reads the value of a key in the target section, or returns `default` if the key is missing.
+    pub fn get_or<T: FromStr>(&self, key: &str, default: T) -> Result<T, Fail> {
+        Ok(self.get(key)?.unwrap_or(default))
+    }
+
+    /// Reads the value of a mandatory key in the target section.
+    pub fn require<T: FromStr>(&self, key: &str) -> Result<T, Fail> {
+        match self.get(key)? {
+            Some(value) => Ok(value),
+            None => Err(self.missing(key)),
+        }
+    }
+
+    /// Reads the list of values of a key in the target section, if any. When overridden, values are separated by
+    /// whitespaces.
+    pub fn get_list<T: FromStr>(&self, key: &str) -> Result<Option<Vec<T>>, Fail> {
+        if let Some(value) = self.get_override(key) {
+            return value
+                .split_whitespace()
+                .map(|item| self.parse(key, item))
+                .collect::<Result<_, _>>()
+                .map(Some);
+        }
+        match &self.yaml[key] {
+            Yaml::BadValue | Yaml::Null => Ok(None),
+            Yaml::Array(array) => array
+                .iter()
+                .map(|yaml| match scalar(yaml) {
+                    Some(value) => self.parse(key, &value),
+                    None => Err(self.invalid(key, "expected a list of scalars")),
+                })
+                .collect::<Result<_, _>>()
+                .map(Some),
+            _ => Err(self.invalid(key, "expected a list")),
+        }
+    }
+
+    /// Reads the key-value pairs of a key in the target section, if any. When overridden, pairs are separated by
+    /// whitespaces and written as `key=value`.
+    pub fn get_map<K: FromStr, V: FromStr>(&self, key: &str) -> Result<Option<Vec<(K, V)>>, Fail> {
+        if let Some(value) = self.get_override(key) {
+            return value
+                .split_whitespace()
+                .map(|pair| match pair.split_once('=') {
+                    Some((k, v)) => Ok((self.parse(key, k)?, self.parse(key, v)?)),
+                    None => Err(self.invalid(key, "expected key=value pairs")),
+                })
+                .collect::<Result<_, _>>()
+                .map(Some);
+        }
+        match &self.yaml[key] {
+            Yaml::BadValue | Yaml::Null => Ok(None),
+            Yaml::Hash(hash) => hash
+                .iter()
+                .map(|(k, v)| match (scalar(k), scalar(v)) {
+                    (Some(k), Some(v)) => Ok((self.parse(key, &k)?, self.parse(key, &v)?)),
+                    _ => Err(self.invalid(key, "expected a mapping of scalars")),
+                })
+                .collect::<Result<_, _>>()
+                .map(Some),
+            _ => Err(self.invalid(key, "expected a mapping")),
+        }
+    }
+
+    /// Warns about keys in the target section that are not in `known`, as these are likely to be typos.
+    pub fn warn_unknown_keys(&self, known: &[&str]) {
+        if let Some(hash) = self.yaml.as_hash() {
+            for key in hash.keys() {
+                match key.as_str() {
+                    Some(key) if known.contains(&key) => (),
+                    _ => warn!("warn_unknown_keys(): unknown key {:?} in {:?} section", key, self.name),
+                }
+            }
+        }
+    }
+
+    /// Builds the failure for an invalid value of a key in the target section.
+    pub fn invalid(&self, key: &str, reason: &str) -> Fail {
+        let cause: String = format!("invalid value for {}.{} in configuration: {}", self.name, key, reason);
+        Fail::new(libc::EINVAL, &cause)
+    }
+
+    /// Reads the value that overrides a key in the target section, if any.
+    fn get_override(&self, key: &str) -> Option<String> {
+        if let Some(value) = self.overrides.get(&format!("{}.{}", self.name, key)) {
+            return Some(value.clone());
+        }
+        let var: String = format!("DEMI_{}_{}", self.name, key).to_uppercase();
+        env::var(var).ok()
+    }
+
+    /// Parses the value of a key in the target section.
+    fn parse<T: FromStr>(&self, key: &str, value: &str) -> Result<T, Fail> {
+        match value.parse() {
+            Ok(value) => Ok(value),
+            Err(_) => Err(self.invalid(key, &format!("failed to parse {:?}", value))),
+        }
+    }
 
-        Self { 0: config_obj.clone() }
+    /// Builds the failure for a missing key in the target section.
+    fn missing(&self, key: &str) -> Fail {
+        let cause: String = format!("missing value for {}.{} in configuration", self.name, key);
+        Fail::new(libc::EINVAL, &cause)
     }
+}
+
+//======================================================================================================================
+// Trait Implementations
+//======================================================================================================================
 
-    /// Reads the local IPv4 address parameter from the underlying configuration file.
-    #[cfg(any(feature = "catnip-libos", feature = "catpowder-libos"))]
-    pub fn local_ipv4_addr(&self) -> ::std::net::Ipv4Addr {
-        // FIXME: this function should return a result.
-        use ::std::net::Ipv4Addr;
+/// From String Trait Implementation for Demikernel Configuration
+impl FromStr for Config {
+    type Err = Fail;
 
-        // FIXME: Change the follow key from "catnip" to "demikernel".
-        let local_ipv4_addr: Ipv4Addr = self.0["catnip"]["my_ipv4_addr"]
-            .as_str()
-            .ok_or_else(|| anyhow::format_err!("Couldn't find my_ipv4_addr in config"))
-            .unwrap()
-            .parse()
-            .unwrap();
-        if local_ipv4_addr.is_unspecified() || local_ipv4_addr.is_broadcast() {
-            panic!("Invalid IPv4 address");
+    /// Parses a YAML document into a [Config] object.
+    fn from_str(s: &str) -> Result<Self, Fail> {
+        let mut config: Vec<Yaml> = match YamlLoader::load_from_str(s) {
+            Ok(config) => config,
+            Err(e) => {
+                let cause: String = format!("failed to parse configuration: {}", e);
+                error!("from_str(): {}", cause);
+                return Err(Fail::new(libc::EINVAL, &cause));
+            },
+        };
+        match config.pop() {
+            Some(yaml @ Yaml::Hash(_)) if config.is_empty() => Ok(Self {
+                yaml,
+                overrides: HashMap::new(),
+            }),
+            _ => Err(Fail::new(
+                libc::EINVAL,
+                "configuration must be a single YAML document holding a mapping",
+            )),
         }
-        local_ipv4_addr
+    }
+}
+
+//======================================================================================================================
+// Standalone Functions
+//======================================================================================================================
+
+/// Converts a YAML scalar into a string.
+fn scalar(yaml: &Yaml) -> Option<String> {
+    match yaml {
+        Yaml::String(s) | Yaml::Real(s) => Some(s.clone()),
+        Yaml::Integer(i) => Some(i.to_string()),
+        Yaml::Boolean(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+//======================================================================================================================
+// Unit Tests
+//======================================================================================================================
+
+#[cfg(test)]
+mod test {
+    use super::{
+        Config,
+        Section,
+    };
+    use crate::runtime::fail::Fail;
+    use ::std::{
+        env,
+        net::Ipv4Addr,
+    };
+
+    /// Configuration used by the tests below.
+    const CONFIG: &str = r#"
+client:
+  connect_to:
+    host: 127.0.0.1
+section:
+  addr: 192.0.2.1
+  port: 8080
+  enabled: true
+  args: ["-c", "0xff"]
+  table:
+    "a": 1
+    "b": 2
+  nested:
+    key: value
+"#;
+
+    /// Tests if values are read from a section.
+    #[test]
+    fn config_get() -> Result<(), Fail> {
+        let config: Config = CONFIG.parse()?;
+        let section: Section = config.section("section")?;
+
+        assert_eq!(section.require::<Ipv4Addr>("addr")?, Ipv4Addr::new(192, 0, 2, 1));
+        assert_eq!(section.require::<u16>("port")?, 8080);
+        assert_eq!(section.require::<bool>("enabled")?, true);
+        assert_eq!(section.get_or::<u16>("mtu", 1500)?, 1500);
+        assert_eq!(section.get::<u16>("mtu")?, None);
+        assert_eq!(
+            section.get_list::<String>("args")?,
+            Some(vec!["-c".to_string(), "0xff".to_string()])
+        );
+        assert_eq!(
+            section.get_map::<String, u8>("table")?,
+            Some(vec![("a".to_string(), 1), ("b".to_string(), 2)])
+        );
+
+        Ok(())
+    }
+
+    /// Tests if missing sections are read as empty ones.
+    #[test]
+    fn config_missing_section() -> Result<(), Fail> {
+        let config: Config = CONFIG.parse()?;
+        let section: Section = config.section("missing")?;
+
+        assert!(section.is_empty());
+        assert_eq!(section.get::<u16>("port")?, None);
+        assert!(section.require::<u16>("port").is_err());
+
+        Ok(())
+    }
+
+    /// Tests if invalid values are reported as failures.
+    #[test]
+    fn config_invalid_values() -> Result<(), Fail> {
+        let config: Config = CONFIG.parse()?;
+        let section: Section = config.section("section")?;
+
+        assert_eq!(
+            section.get::<Ipv4Addr>("port").err().map(|e| e.errno),
+            Some(libc::EINVAL)
+        );
+        assert_eq!(section.get::<u8>("port").err().map(|e| e.errno), Some(libc::EINVAL));
+        assert_eq!(
+            section.get::<String>("nested").err().map(|e| e.errno),
+            Some(libc::EINVAL)
+        );
+        assert_eq!(
+            section.get_list::<String>("addr").err().map(|e| e.errno),
+            Some(libc::EINVAL)
+        );
+        assert!(config.section("client")?.get::<String>("connect_to").is_err());
+
+        Ok(())
+    }
+
+    /// Tests if malformed configurations are reported as failures.
+    #[test]
+    fn config_malformed() {
+        assert!("key: [".parse::<Config>().is_err());
+        assert!("- a\n- b\n".parse::<Config>().is_err());
+        assert!("a: 1\n---\nb: 2\n".parse::<Config>().is_err());
+        assert!("".parse::<Config>().is_err());
+        assert!(Config::new("/nonexistent/config.yaml").is_err());
+    }
+
+    /// Tests if keys may be overridden by environment variables.
+    #[test]
+    fn config_env_override() -> Result<(), Fail> {
+        let config: Config = "override:\n  port: 80\n".parse()?;
+        let section: Section = config.section("override")?;
+
+        env::set_var("DEMI_OVERRIDE_PORT", "8080");
+        env::set_var("DEMI_OVERRIDE_ARGS", "-c 0xff");
+        let port: Result<u16, Fail> = section.require("port");
+        let args: Result<Option<Vec<String>>, Fail> = section.get_list("args");
+        env::set_var("DEMI_OVERRIDE_PORT", "not-a-port");
+        let invalid: Result<u16, Fail> = section.require("port");
+        env::remove_var("DEMI_OVERRIDE_PORT");
+        env::remove_var("DEMI_OVERRIDE_ARGS");
+
+        assert_eq!(port?, 8080);
+        assert_eq!(args?, Some(vec!["-c".to_string(), "0xff".to_string()]));
+        assert!(invalid.is_err());
+        assert_eq!(section.require::<u16>("port")?, 80);
+
+        Ok(())
+    }
+
+    /// Tests if overrides supersede environment variables.
+    #[test]
+    fn config_set_override() -> Result<(), Fail> {
+        let mut config: Config = "overridden:\n  port: 80\n".parse()?;
+        config.set_override("overridden.port", "8080")?;
+        assert!(config.set_override("port", "8080").is_err());
+        assert!(config.set_override(".port", "8080").is_err());
+
+        env::set_var("DEMI_OVERRIDDEN_PORT", "443");
+        let port: Result<u16, Fail> = config.section("overridden")?.require("port");
+        env::remove_var("DEMI_OVERRIDDEN_PORT");
+
+        assert_eq!(port?, 8080);
+
+        Ok(())
+    }
+
+    /// Tests if a legacy section is read when the requested one is missing.
+    #[test]
+    fn config_legacy_section() -> Result<(), Fail> {
+        let config: Config = CONFIG.parse()?;
+
+        assert_eq!(config.section_or("new", "section")?.require::<u16>("port")?, 8080);
+        assert_eq!(config.section_or("section", "client")?.require::<u16>("port")?, 8080);
+
+        Ok(())
     }
 }
@@ -0,0 +1,387 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//======================================================================================================================
+// Imports
+//======================================================================================================================
+
+use crate::{
+    demikernel::libos::LibOS,
+    pal::functions::get_socketaddr_from_sock_addr_storage,
+    runtime::{
+        fail::Fail,
+        memory::Sga,
+        types::{
+            demi_accept_result_t,
+            demi_opcode_t,
+            demi_qresult_t,
+            demi_sgarray_t,
+        },
+        QDesc,
+        QToken,
+    },
+};
+use ::std::{
+    cell::{
+        RefCell,
+        RefMut,
+    },
+    future::Future,
+    net::SocketAddr,
+    pin::Pin,
+    rc::Rc,
+    task::{
+        Context,
+        Poll,
+    },
+};
+
+//======================================================================================================================
+// Structures
+//======================================================================================================================
+
+/// Asynchronous LibOS
+///
+/// This wraps a [LibOS] and exposes its I/O operations as Rust futures that resolve to typed results. Futures make
+/// progress only when they are polled, by driving the underlying [LibOS]. Dropping a future before it completes
+/// cancels the corresponding I/O operation.
+///
+/// The [LibOS] has no means to wake futures up, as its operations only make progress while it is polled. Thus, these
+/// futures busy-poll: a pending future asks to be polled again right away, every time it is polled. An executor that
+/// runs them never goes idle while some operation is pending, and it keeps a core busy, as Demikernel applications do.
+#[derive(Clone)]
+pub struct AsyncLibOS {
+    /// Underlying LibOS.
+    libos: Rc<RefCell<LibOS>>,
+}
+
+/// Result of an Accept Operation
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Accepted {
+    /// I/O queue descriptor of the accepted connection.
+    pub qd: QDesc,
+    /// Address of the remote peer.
+    pub remote: SocketAddr,
+}
+
+/// Result of a Pop Operation
+pub struct Popped {
+    /// Scatter-gather array that was popped.
+    pub buf: Sga,
+    /// Source address of the data, if known.
+    pub from: Option<SocketAddr>,
+}
+
+/// Operation Future
+///
+/// This resolves to the result of an I/O operation that was issued on the underlying [LibOS].
+struct OperationFuture {
+    /// Underlying LibOS.
+    libos: Rc<RefCell<LibOS>>,
+    /// Queue token of the I/O operation, until it completes.
+    qt: Option<QToken>,
+}
+
+//======================================================================================================================
+// Associated Functions
+//======================================================================================================================
+
+/// Associated Functions for Asynchronous LibOSes
+impl AsyncLibOS {
+    /// Wraps a [LibOS].
+    pub fn new(libos: LibOS) -> Self {
+        Self {
+            libos: Rc::new(RefCell::new(libos)),
+        }
+    }
+
+    /// Creates a new memory queue.
+    pub fn create_pipe(&self, name: &str) -> Result<QDesc, Fail> {
+        self.libos.borrow_mut().create_pipe(name)
+    }
+
+    /// Opens an existing memory queue.
+    pub fn open_pipe(&self, name: &str) -> Result<QDesc, Fail> {
+        self.libos.borrow_mut().open_pipe(name)
+    }
+
+    /// Creates a socket.
+    pub fn socket(&self, domain: libc::c_int, socket_type: libc::c_int, protocol: libc::c_int) -> Result<QDesc, Fail> {
+        self.libos.borrow_mut().socket(domain, socket_type, protocol)
+    }
+
+    /// Binds a socket to a local address.
+    pub fn bind(&self, sockqd: QDesc, local: SocketAddr) -> Result<(), Fail> {
+        self.libos.borrow_mut().bind(sockqd, local)
+    }
+
+    /// Marks a socket as a passive one.
+    pub fn listen(&self, sockqd: QDesc, backlog: usize) -> Result<(), Fail> {
+        self.libos.borrow_mut().listen(sockqd, backlog)
+    }
+
+    /// Accepts an incoming connection on a TCP socket.
+    pub async fn accept(&self, sockqd: QDesc) -> Result<Accepted, Fail> {
+        let qt: QToken = self.libos.borrow_mut().accept(sockqd)?;
+        let qr: demi_qresult_t = self.wait(qt).await?;
+        match qr.qr_opcode {
+            demi_opcode_t::DEMI_OPC_ACCEPT => {
+                let ares: demi_accept_result_t = unsafe { qr.qr_value.ares };
+                let remote: SocketAddr = match get_socketaddr_from_sock_addr_storage(&ares.addr) {
+                    Some(remote) => remote,
+                    None => return Err(Fail::new(libc::EAFNOSUPPORT, "communication domain not supported")),
+                };
+                Ok(Accepted {
+                    qd: QDesc::from(ares.qd),
+                    remote,
+                })
+            },
+            _ => Err(Fail::new(libc::EIO, "unexpected operation result")),
+        }
+    }
+
+    /// Initiates a connection with a remote TCP peer.
+    pub async fn connect(&self, sockqd: QDesc, remote: SocketAddr) -> Result<(), Fail> {
+        let qt: QToken = self.libos.borrow_mut().connect(sockqd, remote)?;
+        let qr: demi_qresult_t = self.wait(qt).await?;
+        match qr.qr_opcode {
+            demi_opcode_t::DEMI_OPC_CONNECT => Ok(()),
+            _ => Err(Fail::new(libc::EIO, "unexpected operation result")),
+        }
+    }
+
+    /// Pushes a scatter-gather array to an I/O queue.
+    pub async fn push<S: AsRef<demi_sgarray_t>>(&self, qd: QDesc, sga: &S) -> Result<(), Fail> {
+        let qt: QToken = self.libos.borrow_mut().push(qd, sga)?;
+        let qr: demi_qresult_t = self.wait(qt).await?;
+        match qr.qr_opcode {
+            demi_opcode_t::DEMI_OPC_PUSH => Ok(()),
+            _ => Err(Fail::new(libc::EIO, "unexpected operation result")),
+        }
+    }
+
+    /// Pushes a scatter-gather array to a UDP socket.
+    pub async fn pushto<S: AsRef<demi_sgarray_t>>(&self, qd: QDesc, sga: &S, to: SocketAddr) -> Result<(), Fail> {
+        let qt: QToken = self.libos.borrow_mut().pushto(qd, sga, to)?;
+        let qr: demi_qresult_t = self.wait(qt).await?;
+        match qr.qr_opcode {
+            demi_opcode_t::DEMI_OPC_PUSH => Ok(()),
+            _ => Err(Fail::new(libc::EIO, "unexpected operation result")),
+        }
+    }
+
+    /// Pops data from an I/O queue.
+    pub async fn pop(&self, qd: QDesc) -> Result<Popped, Fail> {
+        let qt: QToken = self.libos.borrow_mut().pop(qd)?;
+        self.popped(qt).await
+    }
+
+    /// Pops data from an I/O queue, within size bounds (see [LibOS::pop_bounded]).
+    pub async fn pop_bounded(&self, qd: QDesc, min_size: usize, max_size: usize) -> Result<Popped, Fail> {
+        let qt: QToken = self.libos.borrow_mut().pop_bounded(qd, min_size, max_size)?;
+        self.popped(qt).await
+    }
+
+    /// Closes an I/O queue.
+    pub fn close(&self, qd: QDesc) -> Result<(), Fail> {
+        self.libos.borrow_mut().close(qd)
+    }
+
+    /// Allocates a scatter-gather array.
+    pub fn sgaalloc(&self, size: usize) -> Result<demi_sgarray_t, Fail> {
+        self.libos.borrow().sgaalloc(size)
+    }
+
+    /// Allocates an owned scatter-gather array, which is released when dropped.
+    pub fn alloc_sga(&self, size: usize) -> Result<Sga, Fail> {
+        self.libos.borrow().alloc_sga(size)
+    }
+
+    /// Releases a scatter-gather array.
+    pub fn sgafree(&self, sga: demi_sgarray_t) -> Result<(), Fail> {
+        self.libos.borrow().sgafree(sga)
+    }
+
+    /// Appends the segments of a scatter-gather array to another one, and releases the former.
+    pub fn sgaappend(&self, sga: &mut demi_sgarray_t, tail: demi_sgarray_t) -> Result<(), Fail> {
+        self.libos.borrow().sgaappend(sga, tail)
+    }
+
+    /// Waits for a pop operation to complete, and takes out the data that it popped.
+    async fn popped(&self, qt: QToken) -> Result<Popped, Fail> {
+        let qr: demi_qresult_t = self.wait(qt).await?;
+        match qr.qr_opcode {
+            demi_opcode_t::DEMI_OPC_POP => {
+                let sga: demi_sgarray_t = unsafe { qr.qr_value.sga };
+                let from: Option<SocketAddr> = get_socketaddr_from_sock_addr_storage(&sga.sga_addr);
+                let buf: Sga = unsafe { Sga::from_raw(sga, self.libos.borrow().memory()) };
+                Ok(Popped { buf, from })
+            },
+            _ => Err(Fail::new(libc::EIO, "unexpected operation result")),
+        }
+    }
+
+    /// Waits for the I/O operation associated with the queue token `qt` to complete. Failed operations are converted
+    /// into errors.
+    async fn wait(&self, qt: QToken) -> Result<demi_qresult_t, Fail> {
+        let qr: demi_qresult_t = OperationFuture {
+            libos: self.libos.clone(),
+            qt: Some(qt),
+        }
+        .await?;
+        match qr.qr_opcode {
+            demi_opcode_t::DEMI_OPC_FAILED => match self.libos.borrow().failure(&qr) {
+                Some(e) => Err(e.clone()),
+                None => Err(Fail::new(qr.qr_ret, "operation failed")),
+            },
+            _ => Ok(qr),
+        }
+    }
+}
+
+//======================================================================================================================
+// Trait Implementations
+//======================================================================================================================
+
+/// Future Trait Implementation for Operation Futures
+impl Future for OperationFuture {
+    type Output = Result<demi_qresult_t, Fail>;
+
+    /// Polls the underlying LibOS once and checks if the target I/O operation has completed. If it has not, the waker
+    /// is woken up right away, so that the executor polls again (see [AsyncLibOS] on busy-polling).
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let self_: &mut OperationFuture = self.get_mut();
+        let qt: QToken = self_.qt.expect("operation polled after completion");
+        let result: Result<Option<demi_qresult_t>, Fail> = self_.libos.borrow_mut().try_wait(qt);
+        match result {
+            Ok(None) => {
+                // The underlying LibOS is not able to wake us up, so busy-poll.
+                ctx.waker().wake_by_ref();
+                Poll::Pending
+            },
+            Ok(Some(qr)) => {
+                self_.qt = None;
+                Poll::Ready(Ok(qr))
+            },
+            Err(e) => {
+                self_.qt = None;
+                Poll::Ready(Err(e))
+            },
+        }
+    }
+}
+
+/// Drop Trait Implementation for Operation Futures
+impl Drop for OperationFuture {
+    /// Cancels the target I/O operation, if it has not completed yet. Resources of operations that have completed in
+    /// the meantime are released.
+    fn drop(&mut self) {
+        if let Some(qt) = self.qt.take() {
+            let mut libos: RefMut<LibOS> = self.libos.borrow_mut();
+            let result: Result<(), Fail> = match libos.cancel(qt) {
+                Ok(qr) if qr.qr_opcode == demi_opcode_t::DEMI_OPC_ACCEPT => {
+                    libos.close(unsafe { qr.qr_value.ares.qd }.into())
+                },
+                Ok(qr) if qr.qr_opcode == demi_opcode_t::DEMI_OPC_POP => libos.sgafree(unsafe { qr.qr_value.sga }),
+                Ok(_) => Ok(()),
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                warn!("failed to cancel operation ({:?})", e);
+            }
+        }
+    }
+}
+
+//======================================================================================================================
+// Unit Tests
+//======================================================================================================================
+
+#[cfg(all(test, feature = "catmem-libos"))]
+mod tests {
+    use super::{
+        AsyncLibOS,
+        Popped,
+    };
+    use crate::{
+        catmem::CatmemLibOS,
+        demikernel::libos::LibOS,
+        runtime::{
+            memory::Sga,
+            types::demi_sgarray_t,
+            QDesc,
+        },
+    };
+    use ::futures::{
+        executor,
+        FutureExt,
+    };
+
+    /// Creates an asynchronous LibOS on top of Catmem.
+    fn new_libos() -> AsyncLibOS {
+        AsyncLibOS::new(LibOS::from_backend(Box::new(CatmemLibOS::new())))
+    }
+
+    /// Tests if data pushed to a memory queue may be popped asynchronously.
+    #[test]
+    fn async_libos_push_pop() {
+        let libos: AsyncLibOS = new_libos();
+        let qd: QDesc = libos.create_pipe("async-libos-push-pop").expect("create_pipe() failed");
+
+        let mut sga: Sga = libos.alloc_sga(32).expect("alloc_sga() failed");
+        sga.segments_mut().for_each(|data| data.fill(0x65));
+
+        executor::block_on(async {
+            libos.push(qd, &sga).await.expect("push() failed");
+            let popped: Popped = libos.pop(qd).await.expect("pop() failed");
+            assert!(popped.buf.segments().flatten().eq(sga.segments().flatten()));
+            assert!(popped.from.is_none());
+        });
+
+        libos.close(qd).expect("close() failed");
+    }
+
+    /// Tests if a scatter-gather array with multiple segments may be pushed to a memory queue.
+    #[test]
+    fn async_libos_push_pop_chain() {
+        let libos: AsyncLibOS = new_libos();
+        let qd: QDesc = libos
+            .create_pipe("async-libos-push-pop-chain")
+            .expect("create_pipe() failed");
+
+        // Build a scatter-gather array out of a header and a body.
+        let mut header: Sga = libos.alloc_sga(8).expect("alloc_sga() failed");
+        header.segments_mut().for_each(|data| data.fill(0x68));
+        let mut body: Sga = libos.alloc_sga(24).expect("alloc_sga() failed");
+        body.segments_mut().for_each(|data| data.fill(0x62));
+        let mut sga: demi_sgarray_t = header.into_raw();
+        libos.sgaappend(&mut sga, body.into_raw()).expect("sgaappend() failed");
+        let sga: Sga = unsafe { Sga::from_raw(sga, libos.libos.borrow().memory()) };
+        let expected: Vec<u8> = sga.segments().flatten().copied().collect();
+        assert_eq!(sga.segments().count(), 2);
+        assert_eq!(expected.len(), 32);
+
+        executor::block_on(async {
+            libos.push(qd, &sga).await.expect("push() failed");
+            let popped: Popped = libos.pop(qd).await.expect("pop() failed");
+            let data: Vec<u8> = popped.buf.segments().flatten().copied().collect();
+            assert_eq!(data, expected);
+        });
+
+        libos.close(qd).expect("close() failed");
+    }
+
+    /// Tests if dropping a pending operation cancels it.
+    #[test]
+    fn async_libos_drop_pending() {
+        let libos: AsyncLibOS = new_libos();
+        let qd: QDesc = libos
+            .create_pipe("async-libos-drop-pending")
+            .expect("create_pipe() failed");
+
+        // Nothing was pushed, so the pop operation cannot complete.
+        assert!(libos.pop(qd).now_or_never().is_none());
+
+        libos.close(qd).expect("close() failed");
+    }
+}
@@ -0,0 +1,369 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//======================================================================================================================
+// Imports
+//======================================================================================================================
+
+use super::name::LibOSName;
+use crate::{
+    demikernel::config::Config,
+    runtime::{
+        fail::Fail,
+        memory::MemoryRuntime,
+        network::types::UnixSocketAddr,
+        types::{
+            demi_qresult_t,
+            demi_qstats_t,
+            demi_sgarray_t,
+        },
+        QDesc,
+        QToken,
+    },
+    scheduler::SchedulerHandle,
+};
+use ::std::{
+    net::{
+        SocketAddr,
+        SocketAddrV4,
+    },
+    rc::Rc,
+    sync::{
+        Mutex,
+        MutexGuard,
+    },
+    thread,
+    time::Duration,
+};
+
+#[cfg(feature = "catcollar-libos")]
+use crate::catcollar::CatcollarLibOS;
+#[cfg(feature = "catmem-libos")]
+use crate::catmem::CatmemLibOS;
+#[cfg(all(feature = "catnap-libos", target_os = "linux"))]
+use crate::catnap::CatnapLibOS;
+#[cfg(all(feature = "catnapw-libos", target_os = "windows"))]
+use crate::catnapw::CatnapWLibOS;
+#[cfg(feature = "catnip-libos")]
+use crate::catnip::CatnipLibOS;
+#[cfg(feature = "catpowder-libos")]
+use crate::catpowder::CatpowderLibOS;
+
+//======================================================================================================================
+// Structures
+//======================================================================================================================
+
+/// Instantiates a LibOS backend with a given configuration.
+pub type BackendFactory = fn(&Config) -> Result<Box<dyn LibOSBackend>, Fail>;
+
+/// LibOS backends that were registered at runtime, by name.
+static REGISTRY: Mutex<Vec<(&'static str, BackendFactory)>> = Mutex::new(Vec::new());
+
+//======================================================================================================================
+// Traits
+//======================================================================================================================
+
+/// LibOS Backend
+///
+/// This is implemented by every LibOS, and may be implemented out of this crate to provide a custom one (see
+/// [register_backend]). Operations on sockets and memory queues fail with `ENOTSUP` unless they are overridden, so
+/// that each backend only implements those that it supports.
+pub trait LibOSBackend {
+    /// Creates a memory queue.
+    fn create_pipe(&mut self, _name: &str) -> Result<QDesc, Fail> {
+        Err(not_supported("create_pipe"))
+    }
+
+    /// Opens an existing memory queue.
+    fn open_pipe(&mut self, _name: &str) -> Result<QDesc, Fail> {
+        Err(not_supported("open_pipe"))
+    }
+
+    /// Creates a socket.
+    fn socket(
+        &mut self,
+        _domain: libc::c_int,
+        _socket_type: libc::c_int,
+        _protocol: libc::c_int,
+    ) -> Result<QDesc, Fail> {
+        Err(not_supported("socket"))
+    }
+
+    /// Binds a socket to a local address.
+    fn bind(&mut self, _sockqd: QDesc, _local: SocketAddr) -> Result<(), Fail> {
+        Err(not_supported("bind"))
+    }
+
+    /// Marks a socket as a passive one.
+    fn listen(&mut self, _sockqd: QDesc, _backlog: usize) -> Result<(), Fail> {
+        Err(not_supported("listen"))
+    }
+
+    /// Accepts an incoming connection on a TCP socket.
+    fn accept(&mut self, _sockqd: QDesc) -> Result<QToken, Fail> {
+        Err(not_supported("accept"))
+    }
+
+    /// Initiates a connection with a remote TCP socket.
+    fn connect(&mut self, _sockqd: QDesc, _remote: SocketAddr) -> Result<QToken, Fail> {
+        Err(not_supported("connect"))
+    }
+
+    /// Binds a Unix domain socket to a local address.
+    fn bind_unix(&mut self, _sockqd: QDesc, _local: &UnixSocketAddr) -> Result<(), Fail> {
+        Err(not_supported("bind_unix"))
+    }
+
+    /// Initiates a connection with a remote Unix domain socket.
+    fn connect_unix(&mut self, _sockqd: QDesc, _remote: &UnixSocketAddr) -> Result<QToken, Fail> {
+        Err(not_supported("connect_unix"))
+    }
+
+    /// Sets an option on a socket.
+    fn setsockopt(
+        &mut self,
+        _sockqd: QDesc,
+        _level: libc::c_int,
+        _optname: libc::c_int,
+        _optval: &[u8],
+    ) -> Result<(), Fail> {
+        Err(not_supported("setsockopt"))
+    }
+
+    /// Gets the value of an option on a socket. On success, the number of bytes written to `optval` is returned.
+    fn getsockopt(
+        &mut self,
+        _sockqd: QDesc,
+        _level: libc::c_int,
+        _optname: libc::c_int,
+        _optval: &mut [u8],
+    ) -> Result<usize, Fail> {
+        Err(not_supported("getsockopt"))
+    }
+
+    /// Gets the local address of a socket.
+    fn getsockname(&mut self, _sockqd: QDesc) -> Result<SocketAddr, Fail> {
+        Err(not_supported("getsockname"))
+    }
+
+    /// Gets the remote address of a connected socket.
+    fn getpeername(&mut self, _sockqd: QDesc) -> Result<SocketAddr, Fail> {
+        Err(not_supported("getpeername"))
+    }
+
+    /// Shuts down the sending half, the receiving half, or both halves of a connected socket, as selected by `how`.
+    fn shutdown(&mut self, _sockqd: QDesc, _how: libc::c_int) -> Result<(), Fail> {
+        Err(not_supported("shutdown"))
+    }
+
+    /// Pushes a scatter-gather array to a UDP socket.
+    fn pushto(&mut self, _sockqd: QDesc, _sga: &demi_sgarray_t, _to: SocketAddr) -> Result<QToken, Fail> {
+        Err(not_supported("pushto"))
+    }
+
+    /// Pushes a scatter-gather array to a Unix domain datagram socket.
+    fn pushto_unix(&mut self, _sockqd: QDesc, _sga: &demi_sgarray_t, _to: &UnixSocketAddr) -> Result<QToken, Fail> {
+        Err(not_supported("pushto_unix"))
+    }
+
+    /// Closes an I/O queue.
+    fn close(&mut self, qd: QDesc) -> Result<(), Fail>;
+
+    /// Asynchronously closes an I/O queue. The operation completes once the I/O queue is fully shut down.
+    fn async_close(&mut self, qd: QDesc) -> Result<QToken, Fail>;
+
+    /// Gets the statistics of an I/O queue that the backend holds, such as those of the underlying protocol. Counters
+    /// of completed operations are kept by the LibOS itself.
+    fn get_stats(&mut self, qd: QDesc) -> Result<demi_qstats_t, Fail>;
+
+    /// Checks which operations on an I/O queue would complete right away, from the state that the backend holds. The
+    /// readiness events (`DEMI_POLLIN`, `DEMI_POLLOUT`, `DEMI_POLLACCEPT` and `DEMI_POLLHUP`) are returned as a mask.
+    fn readiness(&mut self, qd: QDesc) -> Result<u32, Fail>;
+
+    /// Pushes a scatter-gather array to an I/O queue.
+    fn push(&mut self, qd: QDesc, sga: &demi_sgarray_t) -> Result<QToken, Fail>;
+
+    /// Pops data from an I/O queue. If `max_size` is set, at most that many bytes are popped, and the operation does
+    /// not complete until at least `min_size` bytes are available or the end of the stream is reached. Otherwise,
+    /// `min_size` is zero and whatever data is available first is popped.
+    fn pop(&mut self, qd: QDesc, min_size: usize, max_size: Option<usize>) -> Result<QToken, Fail>;
+
+    /// Makes progress on pending I/O operations. This should never block.
+    fn poll(&mut self);
+
+    /// Blocks until some pending I/O operation may make progress, or a timeout expires. This is called by the LibOS
+    /// once it has busy-polled for a while, and it may return early. By default, it sleeps for the whole timeout.
+    fn wait_for_events(&mut self, timeout: Duration) {
+        thread::sleep(timeout)
+    }
+
+    /// Retrieves the schedule handle of a pending I/O operation.
+    fn schedule(&mut self, qt: QToken) -> Result<SchedulerHandle, Fail>;
+
+    /// Returns the queue tokens of all operations that have completed, but whose results were not taken out yet.
+    fn completed(&self) -> Vec<QToken>;
+
+    /// Takes out the result of a completed I/O operation, along with the failure that it reported, if any. The cause of
+    /// a failure does not fit in a [demi_qresult_t], so it is handed out aside.
+    fn pack_result(&mut self, handle: SchedulerHandle, qt: QToken) -> Result<(demi_qresult_t, Option<Fail>), Fail>;
+
+    /// Cancels a pending I/O operation. If the operation has already completed, its result is packed as is.
+    /// Otherwise, it completes with `ECANCELED`. Failures are handed out as in [LibOSBackend::pack_result].
+    fn cancel(&mut self, handle: SchedulerHandle, qt: QToken) -> Result<(demi_qresult_t, Option<Fail>), Fail>;
+
+    /// Allocates a scatter-gather array.
+    fn sgaalloc(&self, size: usize) -> Result<demi_sgarray_t, Fail>;
+
+    /// Releases a scatter-gather array.
+    fn sgafree(&self, sga: demi_sgarray_t) -> Result<(), Fail>;
+
+    /// Appends the segments of a scatter-gather array to another one, and releases the former.
+    fn sgaappend(&self, sga: &mut demi_sgarray_t, tail: demi_sgarray_t) -> Result<(), Fail>;
+
+    /// Returns the memory runtime that releases the scatter-gather arrays handed out by the target backend. It may
+    /// outlive the backend.
+    fn memory(&self) -> Rc<dyn MemoryRuntime>;
+}
+
+//======================================================================================================================
+// Standalone Functions
+//======================================================================================================================
+
+/// Registers a custom LibOS backend under a given name, so that it may be selected as any other LibOS (e.g. with the
+/// `--libos` argument of `demi_init()`). Names must be lowercase alphanumeric, and may not be taken already.
+pub fn register_backend(name: &'static str, factory: BackendFactory) -> Result<(), Fail> {
+    trace!("register_backend(): name={:?}", name);
+
+    if name.is_empty() || !name.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit()) {
+        return Err(Fail::new(libc::EINVAL, "invalid libos name"));
+    }
+
+    // Check names of built-in LibOSes first, as parsing them also looks up the registry.
+    if name.parse::<LibOSName>().is_ok() {
+        return Err(Fail::new(libc::EEXIST, "libos name is already taken"));
+    }
+    let mut registry: MutexGuard<Vec<(&'static str, BackendFactory)>> =
+        REGISTRY.lock().expect("registry should not be poisoned");
+    if registry.iter().any(|&(other, _)| other == name) {
+        return Err(Fail::new(libc::EEXIST, "libos name is already taken"));
+    }
+    registry.push((name, factory));
+
+    Ok(())
+}
+
+/// Looks up a custom LibOS backend that was registered at runtime, by name.
+pub(super) fn lookup_backend(name: &str) -> Option<(&'static str, BackendFactory)> {
+    let registry: MutexGuard<Vec<(&'static str, BackendFactory)>> =
+        REGISTRY.lock().expect("registry should not be poisoned");
+    registry.iter().find(|&&(other, _)| other == name).copied()
+}
+
+/// Instantiates the LibOS backend of a given name, with a given configuration.
+#[allow(unused_variables)]
+pub(super) fn new_backend(libos_name: LibOSName, config: &Config) -> Result<Box<dyn LibOSBackend>, Fail> {
+    #[allow(unreachable_patterns)]
+    match libos_name {
+        #[cfg(all(feature = "catnap-libos", target_os = "linux"))]
+        LibOSName::Catnap => Ok(Box::new(CatnapLibOS::new(config))),
+        #[cfg(all(feature = "catnapw-libos", target_os = "windows"))]
+        LibOSName::CatnapW => Ok(Box::new(CatnapWLibOS::new(config))),
+        #[cfg(feature = "catcollar-libos")]
+        LibOSName::Catcollar => Ok(Box::new(CatcollarLibOS::new(config))),
+        #[cfg(feature = "catpowder-libos")]
+        LibOSName::Catpowder => Ok(Box::new(CatpowderLibOS::new(config)?)),
+        #[cfg(feature = "catnip-libos")]
+        LibOSName::Catnip => Ok(Box::new(CatnipLibOS::new(config)?)),
+        #[cfg(feature = "catmem-libos")]
+        LibOSName::Catmem => Ok(Box::new(CatmemLibOS::new())),
+        LibOSName::Custom(name) => match lookup_backend(name) {
+            Some((_, factory)) => factory(config),
+            None => Err(Fail::new(libc::EINVAL, &format!("unknown libos {:?}", name))),
+        },
+        _ => Err(Fail::new(libc::ENOTSUP, "unsupported libos")),
+    }
+}
+
+/// Narrows down a socket address to an IPv4 one, for LibOS backends that do not support IPv6.
+pub(crate) fn require_ipv4(addr: SocketAddr) -> Result<SocketAddrV4, Fail> {
+    match addr {
+        SocketAddr::V4(addr) => Ok(addr),
+        SocketAddr::V6(_) => Err(Fail::new(libc::EAFNOSUPPORT, "ipv6 is not supported by this libos")),
+    }
+}
+
+/// Builds the failure of an operation that is not supported by a LibOS backend.
+fn not_supported(syscall: &str) -> Fail {
+    Fail::new(libc::ENOTSUP, &format!("{}() is not supported by this libos", syscall))
+}
+
+//======================================================================================================================
+// Unit Tests
+//======================================================================================================================
+
+#[cfg(test)]
+mod test {
+    use super::{
+        lookup_backend,
+        register_backend,
+        LibOSBackend,
+    };
+    use crate::{
+        demikernel::{
+            config::Config,
+            libos::name::LibOSName,
+        },
+        runtime::fail::Fail,
+    };
+
+    #[cfg(feature = "catmem-libos")]
+    use crate::{
+        catmem::CatmemLibOS,
+        demikernel::libos::LibOS,
+        runtime::QDesc,
+    };
+
+    /// Fails to instantiate a LibOS backend.
+    fn no_backend(_config: &Config) -> Result<Box<dyn LibOSBackend>, Fail> {
+        Err(Fail::new(libc::ENODEV, "no backend"))
+    }
+
+    /// Tests if custom LibOS backends are registered by name.
+    #[test]
+    fn backend_register() -> Result<(), Fail> {
+        register_backend("catfood", no_backend)?;
+        assert!(lookup_backend("catfood").is_some());
+        assert_eq!("catfood".parse::<LibOSName>()?, LibOSName::Custom("catfood"));
+
+        // Names may not be taken twice, nor be malformed.
+        for (name, errno) in [
+            ("catfood", libc::EEXIST),
+            ("catnap", libc::EEXIST),
+            ("", libc::EINVAL),
+            ("cat+food", libc::EINVAL),
+        ] {
+            assert_eq!(register_backend(name, no_backend).err().map(|e| e.errno), Some(errno));
+        }
+
+        Ok(())
+    }
+
+    /// Tests if a custom LibOS backend is instantiated as any other LibOS.
+    #[cfg(feature = "catmem-libos")]
+    #[test]
+    fn backend_custom_libos() -> Result<(), Fail> {
+        register_backend("catbowl", |_| Ok(Box::new(CatmemLibOS::new())))?;
+        let config: Config = "catbowl: {}\n".parse()?;
+        let mut libos: LibOS = LibOS::with_config("catbowl".parse()?, &config)?;
+        let qd: QDesc = libos.create_pipe("backend-custom-libos")?;
+        libos.close(qd)?;
+
+        let libos_name: LibOSName = LibOSName::Custom("catfish");
+        assert_eq!(
+            LibOS::with_config(libos_name, &config).err().map(|e| e.errno),
+            Some(libc::EINVAL)
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,454 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//======================================================================================================================
+// Imports
+//======================================================================================================================
+
+use super::backend::LibOSBackend;
+use crate::{
+    runtime::{
+        fail::Fail,
+        memory::MemoryRuntime,
+        network::types::UnixSocketAddr,
+        types::{
+            demi_qresult_t,
+            demi_qstats_t,
+            demi_sgarray_t,
+        },
+        QDesc,
+        QToken,
+    },
+    scheduler::SchedulerHandle,
+};
+use ::std::{
+    net::SocketAddr,
+    rc::Rc,
+    time::Duration,
+};
+
+//======================================================================================================================
+// Constants
+//======================================================================================================================
+
+/// Tag of I/O queue descriptors that are owned by the memory LibOS. This keeps them positive once converted to C.
+const MEMORY_QDESC_TAG: usize = 1 << 30;
+
+/// Tag of queue tokens that are owned by the memory LibOS.
+const MEMORY_QTOKEN_TAG: u64 = 1 << 63;
+
+//======================================================================================================================
+// Structures
+//======================================================================================================================
+
+/// Composite LibOS
+///
+/// This hosts a memory LibOS alongside a network LibOS. Each backend has its own I/O queue descriptors and queue
+/// tokens, so those handed out by the memory LibOS are tagged, and requests are routed to the owning backend based on
+/// that tag. Scatter-gather arrays are always allocated by the network LibOS, so that they may be pushed to either.
+pub struct CompositeLibOS {
+    /// Network LibOS.
+    network: Box<dyn LibOSBackend>,
+    /// Memory LibOS.
+    memory: Box<dyn LibOSBackend>,
+}
+
+/// Owner of an I/O queue descriptor or a queue token.
+enum Owner<T> {
+    /// Network LibOS, along with the untagged value.
+    Network(T),
+    /// Memory LibOS, along with the untagged value.
+    Memory(T),
+}
+
+//======================================================================================================================
+// Associated Functions
+//======================================================================================================================
+
+/// Associated functions for composite LibOSes.
+impl CompositeLibOS {
+    /// Composes a network LibOS and a memory LibOS.
+    pub fn new(network: Box<dyn LibOSBackend>, memory: Box<dyn LibOSBackend>) -> Self {
+        Self { network, memory }
+    }
+}
+
+//======================================================================================================================
+// Trait Implementations
+//======================================================================================================================
+
+/// LibOS Backend Trait Implementation for Composite LibOS
+impl LibOSBackend for CompositeLibOS {
+    /// Creates a memory queue.
+    fn create_pipe(&mut self, name: &str) -> Result<QDesc, Fail> {
+        Ok(tag_qd(self.memory.create_pipe(name)?))
+    }
+
+    /// Opens an existing memory queue.
+    fn open_pipe(&mut self, name: &str) -> Result<QDesc, Fail> {
+        Ok(tag_qd(self.memory.open_pipe(name)?))
+    }
+
+    /// Creates a socket.
+    fn socket(&mut self, domain: libc::c_int, socket_type: libc::c_int, protocol: libc::c_int) -> Result<QDesc, Fail> {
+        let sockqd: QDesc = self.network.socket(domain, socket_type, protocol)?;
+        if usize::from(sockqd) & MEMORY_QDESC_TAG != 0 {
+            warn!("socket(): too many I/O queues");
+            self.network.close(sockqd)?;
+            return Err(Fail::new(libc::EMFILE, "too many I/O queues"));
+        }
+        Ok(sockqd)
+    }
+
+    /// Binds a socket to a local address.
+    fn bind(&mut self, sockqd: QDesc, local: SocketAddr) -> Result<(), Fail> {
+        self.network.bind(socket(sockqd, "bind")?, local)
+    }
+
+    /// Marks a socket as a passive one.
+    fn listen(&mut self, sockqd: QDesc, backlog: usize) -> Result<(), Fail> {
+        self.network.listen(socket(sockqd, "listen")?, backlog)
+    }
+
+    /// Accepts an incoming connection on a TCP socket.
+    fn accept(&mut self, sockqd: QDesc) -> Result<QToken, Fail> {
+        self.network.accept(socket(sockqd, "accept")?)
+    }
+
+    /// Initiates a connection with a remote TCP socket.
+    fn connect(&mut self, sockqd: QDesc, remote: SocketAddr) -> Result<QToken, Fail> {
+        self.network.connect(socket(sockqd, "connect")?, remote)
+    }
+
+    /// Binds a Unix domain socket to a local address.
+    fn bind_unix(&mut self, sockqd: QDesc, local: &UnixSocketAddr) -> Result<(), Fail> {
+        self.network.bind_unix(socket(sockqd, "bind_unix")?, local)
+    }
+
+    /// Initiates a connection with a remote Unix domain socket.
+    fn connect_unix(&mut self, sockqd: QDesc, remote: &UnixSocketAddr) -> Result<QToken, Fail> {
+        self.network.connect_unix(socket(sockqd, "connect_unix")?, remote)
+    }
+
+    /// Closes an I/O queue.
+    fn close(&mut self, qd: QDesc) -> Result<(), Fail> {
+        match owner_of_qd(qd) {
+            Owner::Network(sockqd) => self.network.close(sockqd),
+            Owner::Memory(memqd) => self.memory.close(memqd),
+        }
+    }
+
+    /// Asynchronously closes an I/O queue.
+    fn async_close(&mut self, qd: QDesc) -> Result<QToken, Fail> {
+        match owner_of_qd(qd) {
+            Owner::Network(sockqd) => self.network.async_close(sockqd),
+            Owner::Memory(memqd) => Ok(tag_qt(self.memory.async_close(memqd)?)),
+        }
+    }
+
+    /// Gets the statistics of an I/O queue.
+    fn get_stats(&mut self, qd: QDesc) -> Result<demi_qstats_t, Fail> {
+        match owner_of_qd(qd) {
+            Owner::Network(sockqd) => self.network.get_stats(sockqd),
+            Owner::Memory(memqd) => self.memory.get_stats(memqd),
+        }
+    }
+
+    /// Checks which operations on an I/O queue would complete right away.
+    fn readiness(&mut self, qd: QDesc) -> Result<u32, Fail> {
+        match owner_of_qd(qd) {
+            Owner::Network(sockqd) => self.network.readiness(sockqd),
+            Owner::Memory(memqd) => self.memory.readiness(memqd),
+        }
+    }
+
+    /// Sets an option on a socket.
+    fn setsockopt(
+        &mut self,
+        sockqd: QDesc,
+        level: libc::c_int,
+        optname: libc::c_int,
+        optval: &[u8],
+    ) -> Result<(), Fail> {
+        self.network
+            .setsockopt(socket(sockqd, "setsockopt")?, level, optname, optval)
+    }
+
+    /// Gets the value of an option on a socket.
+    fn getsockopt(
+        &mut self,
+        sockqd: QDesc,
+        level: libc::c_int,
+        optname: libc::c_int,
+        optval: &mut [u8],
+    ) -> Result<usize, Fail> {
+        self.network
+            .getsockopt(socket(sockqd, "getsockopt")?, level, optname, optval)
+    }
+
+    /// Gets the local address of a socket.
+    fn getsockname(&mut self, sockqd: QDesc) -> Result<SocketAddr, Fail> {
+        self.network.getsockname(socket(sockqd, "getsockname")?)
+    }
+
+    /// Gets the remote address of a connected socket.
+    fn getpeername(&mut self, sockqd: QDesc) -> Result<SocketAddr, Fail> {
+        self.network.getpeername(socket(sockqd, "getpeername")?)
+    }
+
+    /// Shuts down a socket.
+    fn shutdown(&mut self, sockqd: QDesc, how: libc::c_int) -> Result<(), Fail> {
+        self.network.shutdown(socket(sockqd, "shutdown")?, how)
+    }
+
+    /// Pushes a scatter-gather array to an I/O queue.
+    fn push(&mut self, qd: QDesc, sga: &demi_sgarray_t) -> Result<QToken, Fail> {
+        match owner_of_qd(qd) {
+            Owner::Network(sockqd) => self.network.push(sockqd, sga),
+            Owner::Memory(memqd) => Ok(tag_qt(self.memory.push(memqd, sga)?)),
+        }
+    }
+
+    /// Pushes a scatter-gather array to a UDP socket.
+    fn pushto(&mut self, sockqd: QDesc, sga: &demi_sgarray_t, to: SocketAddr) -> Result<QToken, Fail> {
+        self.network.pushto(socket(sockqd, "pushto")?, sga, to)
+    }
+
+    /// Pushes a scatter-gather array to a Unix domain datagram socket.
+    fn pushto_unix(&mut self, sockqd: QDesc, sga: &demi_sgarray_t, to: &UnixSocketAddr) -> Result<QToken, Fail> {
+        self.network.pushto_unix(socket(sockqd, "pushto_unix")?, sga, to)
+    }
+
+    /// Pops data from an I/O queue.
+    fn pop(&mut self, qd: QDesc, min_size: usize, max_size: Option<usize>) -> Result<QToken, Fail> {
+        match owner_of_qd(qd) {
+            Owner::Network(sockqd) => self.network.pop(sockqd, min_size, max_size),
+            Owner::Memory(memqd) => Ok(tag_qt(self.memory.pop(memqd, min_size, max_size)?)),
+        }
+    }
+
+    /// Polls both LibOSes.
+    fn poll(&mut self) {
+        self.network.poll();
+        self.memory.poll();
+    }
+
+    /// Blocks on the notification source of the network LibOS. Memory queues have none, so they are only checked
+    /// again once the timeout expires.
+    fn wait_for_events(&mut self, timeout: Duration) {
+        self.network.wait_for_events(timeout)
+    }
+
+    /// Retrieves the schedule handle of an operation from the LibOS that owns it.
+    fn schedule(&mut self, qt: QToken) -> Result<SchedulerHandle, Fail> {
+        match owner_of_qt(qt) {
+            Owner::Network(qt) => self.network.schedule(qt),
+            Owner::Memory(qt) => self.memory.schedule(qt),
+        }
+    }
+
+    /// Returns the queue tokens of all completed operations of both LibOSes.
+    fn completed(&self) -> Vec<QToken> {
+        let mut completed: Vec<QToken> = self.network.completed();
+        completed.extend(self.memory.completed().into_iter().map(tag_qt));
+        completed
+    }
+
+    /// Takes out the result of a completed operation from the LibOS that owns it.
+    fn pack_result(&mut self, handle: SchedulerHandle, qt: QToken) -> Result<(demi_qresult_t, Option<Fail>), Fail> {
+        match owner_of_qt(qt) {
+            Owner::Network(qt) => self.network.pack_result(handle, qt),
+            Owner::Memory(memqt) => Ok(tag_result(self.memory.pack_result(handle, memqt)?, qt)),
+        }
+    }
+
+    /// Cancels an operation on the LibOS that owns it.
+    fn cancel(&mut self, handle: SchedulerHandle, qt: QToken) -> Result<(demi_qresult_t, Option<Fail>), Fail> {
+        match owner_of_qt(qt) {
+            Owner::Network(qt) => self.network.cancel(handle, qt),
+            Owner::Memory(memqt) => Ok(tag_result(self.memory.cancel(handle, memqt)?, qt)),
+        }
+    }
+
+    /// Allocates a scatter-gather array.
+    fn sgaalloc(&self, size: usize) -> Result<demi_sgarray_t, Fail> {
+        self.network.sgaalloc(size)
+    }
+
+    /// Releases a scatter-gather array.
+    fn sgafree(&self, sga: demi_sgarray_t) -> Result<(), Fail> {
+        self.network.sgafree(sga)
+    }
+
+    /// Appends the segments of a scatter-gather array to another one.
+    fn sgaappend(&self, sga: &mut demi_sgarray_t, tail: demi_sgarray_t) -> Result<(), Fail> {
+        self.network.sgaappend(sga, tail)
+    }
+
+    /// Returns the memory runtime of the network LibOS, which releases all scatter-gather arrays.
+    fn memory(&self) -> Rc<dyn MemoryRuntime> {
+        self.network.memory()
+    }
+}
+
+//======================================================================================================================
+// Standalone Functions
+//======================================================================================================================
+
+/// Tags an I/O queue descriptor of the memory LibOS.
+fn tag_qd(memqd: QDesc) -> QDesc {
+    QDesc::from(usize::from(memqd) | MEMORY_QDESC_TAG)
+}
+
+/// Tags a queue token of the memory LibOS.
+fn tag_qt(memqt: QToken) -> QToken {
+    QToken::from(u64::from(memqt) | MEMORY_QTOKEN_TAG)
+}
+
+/// Tags the result of an operation of the memory LibOS, whose tagged queue token is `qt`.
+fn tag_result((mut qr, failure): (demi_qresult_t, Option<Fail>), qt: QToken) -> (demi_qresult_t, Option<Fail>) {
+    qr.qr_qd = tag_qd(QDesc::from(qr.qr_qd)).into();
+    qr.qr_qt = qt.into();
+    (qr, failure)
+}
+
+/// Finds out which LibOS owns an I/O queue descriptor.
+fn owner_of_qd(qd: QDesc) -> Owner<QDesc> {
+    let qd: usize = qd.into();
+    if qd & MEMORY_QDESC_TAG != 0 {
+        Owner::Memory(QDesc::from(qd & !MEMORY_QDESC_TAG))
+    } else {
+        Owner::Network(QDesc::from(qd))
+    }
+}
+
+/// Finds out which LibOS owns a queue token.
+fn owner_of_qt(qt: QToken) -> Owner<QToken> {
+    let qt: u64 = qt.into();
+    if qt & MEMORY_QTOKEN_TAG != 0 {
+        Owner::Memory(QToken::from(qt & !MEMORY_QTOKEN_TAG))
+    } else {
+        Owner::Network(QToken::from(qt))
+    }
+}
+
+/// Checks if an I/O queue descriptor refers to a socket.
+fn socket(qd: QDesc, syscall: &str) -> Result<QDesc, Fail> {
+    match owner_of_qd(qd) {
+        Owner::Network(sockqd) => Ok(sockqd),
+        Owner::Memory(_) => Err(Fail::new(
+            libc::ENOTSUP,
+            &format!("{}() is not supported on memory queues", syscall),
+        )),
+    }
+}
+
+//======================================================================================================================
+// Unit Tests
+//======================================================================================================================
+
+#[cfg(test)]
+mod test {
+    use super::{
+        owner_of_qd,
+        owner_of_qt,
+        tag_qd,
+        tag_qt,
+        Owner,
+    };
+    use crate::runtime::{
+        QDesc,
+        QToken,
+    };
+
+    #[cfg(all(feature = "catnap-libos", feature = "catmem-libos", target_os = "linux"))]
+    use crate::{
+        demikernel::{
+            config::Config,
+            libos::{
+                name::LibOSName,
+                LibOS,
+            },
+        },
+        runtime::{
+            fail::Fail,
+            memory::Sga,
+            types::{
+                demi_opcode_t,
+                demi_qresult_t,
+            },
+        },
+    };
+    #[cfg(all(feature = "catnap-libos", feature = "catmem-libos", target_os = "linux"))]
+    use ::std::net::{
+        Ipv4Addr,
+        SocketAddr,
+        SocketAddrV4,
+    };
+
+    /// Tests if tagged I/O queue descriptors and queue tokens are routed to the memory LibOS.
+    #[test]
+    fn composite_tags() {
+        let memqd: QDesc = QDesc::from(3);
+        let memqt: QToken = QToken::from(7);
+
+        match owner_of_qd(tag_qd(memqd)) {
+            Owner::Memory(qd) => assert_eq!(qd, memqd),
+            Owner::Network(_) => panic!("tagged queue descriptor should be routed to the memory libos"),
+        }
+        match owner_of_qd(memqd) {
+            Owner::Network(qd) => assert_eq!(qd, memqd),
+            Owner::Memory(_) => panic!("untagged queue descriptor should be routed to the network libos"),
+        }
+        match owner_of_qt(tag_qt(memqt)) {
+            Owner::Memory(qt) => assert_eq!(qt, memqt),
+            Owner::Network(_) => panic!("tagged queue token should be routed to the memory libos"),
+        }
+        match owner_of_qt(memqt) {
+            Owner::Network(qt) => assert_eq!(qt, memqt),
+            Owner::Memory(_) => panic!("untagged queue token should be routed to the network libos"),
+        }
+
+        // Tagged I/O queue descriptors remain positive once converted to C.
+        assert!(i32::from(tag_qd(memqd)) > 0);
+    }
+
+    /// Tests if a composite LibOS serves memory queues and sockets at once.
+    #[cfg(all(feature = "catnap-libos", feature = "catmem-libos", target_os = "linux"))]
+    #[test]
+    fn composite_pipe_and_socket() -> Result<(), Fail> {
+        let config: Config = "catnap: {}\n".parse()?;
+        let mut libos: LibOS = LibOS::with_config_many(&[LibOSName::Catnap, LibOSName::Catmem], &config)?;
+        let local: SocketAddr = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 23456));
+
+        let memqd: QDesc = libos.create_pipe("composite-pipe-and-socket")?;
+        let sockqd: QDesc = libos.socket(libc::AF_INET, libc::SOCK_DGRAM, 0)?;
+        libos.bind(sockqd, local)?;
+        assert_ne!(memqd, sockqd);
+        assert_eq!(libos.bind(memqd, local).err().map(|e| e.errno), Some(libc::ENOTSUP));
+
+        // Push to both queues.
+        let sga: Sga = libos.alloc_sga(32)?;
+        let qt: QToken = libos.push(memqd, &sga)?;
+        libos.wait(qt, None)?;
+        let qt: QToken = libos.pushto(sockqd, &sga, local)?;
+        libos.wait(qt, None)?;
+
+        // Pop from both queues, in whichever order they complete.
+        let mut qts: Vec<QToken> = vec![libos.pop(memqd)?, libos.pop(sockqd)?];
+        let mut qds: Vec<QDesc> = Vec::new();
+        while !qts.is_empty() {
+            let (i, qr): (usize, demi_qresult_t) = libos.wait_any(&qts, None)?;
+            assert_eq!(qr.qr_opcode, demi_opcode_t::DEMI_OPC_POP);
+            assert_eq!(QToken::from(qr.qr_qt), qts.remove(i));
+            qds.push(QDesc::from(qr.qr_qd));
+            libos.sgafree(unsafe { qr.qr_value.sga })?;
+        }
+        assert!(qds.contains(&memqd) && qds.contains(&sockqd));
+
+        libos.close(memqd)?;
+        libos.close(sockqd)?;
+
+        Ok(())
+    }
+}
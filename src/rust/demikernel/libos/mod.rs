@@ -1,27 +1,39 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT license.
 
-pub mod memory;
+pub mod asynchronous;
+pub mod backend;
+pub mod composite;
 pub mod name;
-pub mod network;
+pub mod policy;
 
 //======================================================================================================================
 // Imports
 //======================================================================================================================
 
 use self::{
-    memory::MemoryLibOS,
+    backend::LibOSBackend,
+    composite::CompositeLibOS,
     name::LibOSName,
-    network::NetworkLibOS,
+    policy::WaitPolicy,
 };
 use crate::{
     demikernel::config::Config,
     runtime::{
         fail::Fail,
         logging,
+        memory::{
+            MemoryRuntime,
+            Sga,
+        },
+        network::types::UnixSocketAddr,
         types::{
+            demi_opcode_t,
             demi_qresult_t,
+            demi_qstats_t,
             demi_sgarray_t,
+            DEMI_POLLHUP,
+            DEMI_QSTATS_VERSION,
         },
         QDesc,
         QToken,
@@ -29,8 +41,14 @@ use crate::{
     scheduler::SchedulerHandle,
 };
 use ::std::{
+    collections::{
+        HashMap,
+        HashSet,
+    },
     env,
-    net::SocketAddrV4,
+    net::SocketAddr,
+    rc::Rc,
+    slice,
     time::{
         Duration,
         Instant,
@@ -38,29 +56,35 @@ use ::std::{
     },
 };
 
-#[cfg(feature = "catcollar-libos")]
-use crate::catcollar::CatcollarLibOS;
-#[cfg(feature = "catmem-libos")]
-use crate::catmem::CatmemLibOS;
-#[cfg(all(feature = "catnap-libos", target_os = "linux"))]
-use crate::catnap::CatnapLibOS;
-#[cfg(all(feature = "catnapw-libos", target_os = "windows"))]
-use crate::catnapw::CatnapWLibOS;
-#[cfg(feature = "catnip-libos")]
-use crate::catnip::CatnipLibOS;
-#[cfg(feature = "catpowder-libos")]
-use crate::catpowder::CatpowderLibOS;
+//======================================================================================================================
+// Constants
+//======================================================================================================================
+
+/// Maximum number of bytes that a single pop operation may be bounded to. Popped data is handed out in a single
+/// [DemiBuffer](crate::runtime::memory::DemiBuffer), whose length is a `u16` that must stay below `u16::MAX`.
+pub const POP_SIZE_MAX: usize = u16::MAX as usize - 1;
 
 //======================================================================================================================
 // Structures
 //======================================================================================================================
 
 /// LibOS
-pub enum LibOS {
-    /// Network LibOS
-    NetworkLibOS(NetworkLibOS),
-    /// Memory LibOS
-    MemoryLibOS(MemoryLibOS),
+pub struct LibOS {
+    /// Backend that serves all operations.
+    backend: Box<dyn LibOSBackend>,
+    /// Memory runtime of the backend, which releases owned scatter-gather arrays.
+    memory: Rc<dyn MemoryRuntime>,
+    /// Scatter-gather arrays provided by the caller to pending pop operations.
+    targets: HashMap<QToken, (QDesc, demi_sgarray_t)>,
+    /// Pending operations, along with their I/O queue and the number of bytes that they push.
+    pending: HashMap<QToken, (QDesc, usize)>,
+    /// Counters of completed operations on each I/O queue.
+    stats: HashMap<QDesc, demi_qstats_t>,
+    /// Failures reported by the last operations whose results were taken out, along with their I/O queue, indexed by
+    /// queue token.
+    failures: HashMap<QToken, (QDesc, Fail)>,
+    /// Policy for waiting on pending operations.
+    wait_policy: WaitPolicy,
 }
 
 //======================================================================================================================
@@ -69,7 +93,8 @@ pub enum LibOS {
 
 /// Associated functions for LibOS.
 impl LibOS {
-    /// Instantiates a new LibOS.
+    /// Instantiates a new LibOS, reading its configuration from the file pointed to by the `CONFIG_PATH` environment
+    /// variable.
     pub fn new(libos_name: LibOSName) -> Result<Self, Fail> {
         logging::initialize();
 
@@ -83,49 +108,74 @@ impl LibOS {
                 ))
             },
         };
-        let config: Config = Config::new(config_path);
+        let config: Config = Config::new(&config_path)?;
+
+        Self::with_config(libos_name, &config)
+    }
+
+    /// Instantiates a new LibOS with a given configuration.
+    pub fn with_config(libos_name: LibOSName, config: &Config) -> Result<Self, Fail> {
+        logging::initialize();
 
         // Instantiate LibOS.
-        #[allow(unreachable_patterns)]
-        let libos: LibOS = match libos_name {
-            #[cfg(all(feature = "catnap-libos", target_os = "linux"))]
-            LibOSName::Catnap => Self::NetworkLibOS(NetworkLibOS::Catnap(CatnapLibOS::new(&config))),
-            #[cfg(all(feature = "catnapw-libos", target_os = "windows"))]
-            LibOSName::CatnapW => Self::NetworkLibOS(NetworkLibOS::CatnapW(CatnapWLibOS::new(&config))),
-            #[cfg(feature = "catcollar-libos")]
-            LibOSName::Catcollar => Self::NetworkLibOS(NetworkLibOS::Catcollar(CatcollarLibOS::new(&config))),
-            #[cfg(feature = "catpowder-libos")]
-            LibOSName::Catpowder => Self::NetworkLibOS(NetworkLibOS::Catpowder(CatpowderLibOS::new(&config))),
-            #[cfg(feature = "catnip-libos")]
-            LibOSName::Catnip => Self::NetworkLibOS(NetworkLibOS::Catnip(CatnipLibOS::new(&config))),
-            #[cfg(feature = "catmem-libos")]
-            LibOSName::Catmem => Self::MemoryLibOS(MemoryLibOS::Catmem(CatmemLibOS::new())),
-            _ => panic!("unsupported libos"),
-        };
+        let wait_policy: WaitPolicy = WaitPolicy::new(config)?;
+        let backend: Box<dyn LibOSBackend> = backend::new_backend(libos_name, config)?;
 
+        let mut libos: Self = Self::from_backend(backend);
+        libos.set_wait_policy(wait_policy);
         Ok(libos)
     }
 
-    /// Creates a new memory queue.
-    pub fn create_pipe(&mut self, name: &str) -> Result<QDesc, Fail> {
-        match self {
-            LibOS::NetworkLibOS(_) => Err(Fail::new(
-                libc::ENOTSUP,
-                "create_pipe() is not supported on network liboses",
+    /// Instantiates a new LibOS on top of a given backend. The LibOS busy-polls while waiting, until another wait policy
+    /// is set.
+    pub fn from_backend(backend: Box<dyn LibOSBackend>) -> Self {
+        logging::initialize();
+        let memory: Rc<dyn MemoryRuntime> = backend.memory();
+        Self {
+            backend,
+            memory,
+            targets: HashMap::new(),
+            pending: HashMap::new(),
+            stats: HashMap::new(),
+            failures: HashMap::new(),
+            wait_policy: WaitPolicy::default(),
+        }
+    }
+
+    /// Instantiates a new LibOS for each of the given names, with a given configuration. Many LibOSes are hosted
+    /// together in a composite LibOS, but only Catmem may be hosted alongside a network LibOS.
+    pub fn with_config_many(libos_names: &[LibOSName], config: &Config) -> Result<Self, Fail> {
+        match *libos_names {
+            [libos_name] => Self::with_config(libos_name, config),
+            [LibOSName::Catmem, libos_name] | [libos_name, LibOSName::Catmem] if libos_name != LibOSName::Catmem => {
+                logging::initialize();
+                let wait_policy: WaitPolicy = WaitPolicy::new(config)?;
+                let network: Box<dyn LibOSBackend> = backend::new_backend(libos_name, config)?;
+                let memory: Box<dyn LibOSBackend> = backend::new_backend(LibOSName::Catmem, config)?;
+                let mut libos: Self = Self::from_backend(Box::new(CompositeLibOS::new(network, memory)));
+                libos.set_wait_policy(wait_policy);
+                Ok(libos)
+            },
+            _ => Err(Fail::new(
+                libc::EINVAL,
+                "only catmem may be hosted alongside a network libos",
             )),
-            LibOS::MemoryLibOS(libos) => libos.create_pipe(name),
         }
     }
 
+    /// Sets the policy for waiting on pending operations.
+    pub fn set_wait_policy(&mut self, wait_policy: WaitPolicy) {
+        self.wait_policy = wait_policy;
+    }
+
+    /// Creates a new memory queue.
+    pub fn create_pipe(&mut self, name: &str) -> Result<QDesc, Fail> {
+        self.backend.create_pipe(name)
+    }
+
     /// Opens an existing memory queue.
     pub fn open_pipe(&mut self, name: &str) -> Result<QDesc, Fail> {
-        match self {
-            LibOS::NetworkLibOS(_) => Err(Fail::new(
-                libc::ENOTSUP,
-                "open_pipe() is not supported on network liboses",
-            )),
-            LibOS::MemoryLibOS(libos) => libos.open_pipe(name),
-        }
+        self.backend.open_pipe(name)
     }
 
     /// Creates a socket.
@@ -135,74 +185,172 @@ impl LibOS {
         socket_type: libc::c_int,
         protocol: libc::c_int,
     ) -> Result<QDesc, Fail> {
-        match self {
-            LibOS::NetworkLibOS(libos) => libos.socket(domain, socket_type, protocol),
-            LibOS::MemoryLibOS(_) => Err(Fail::new(libc::ENOTSUP, "socket() is not supported on memory liboses")),
-        }
+        self.backend.socket(domain, socket_type, protocol)
     }
 
     /// Binds a socket to a local address.
-    pub fn bind(&mut self, sockqd: QDesc, local: SocketAddrV4) -> Result<(), Fail> {
-        match self {
-            LibOS::NetworkLibOS(libos) => libos.bind(sockqd, local),
-            LibOS::MemoryLibOS(_) => Err(Fail::new(libc::ENOTSUP, "bind() is not supported on memory liboses")),
-        }
+    pub fn bind(&mut self, sockqd: QDesc, local: SocketAddr) -> Result<(), Fail> {
+        self.backend.bind(sockqd, local)
     }
 
     /// Marks a socket as a passive one.
     pub fn listen(&mut self, sockqd: QDesc, backlog: usize) -> Result<(), Fail> {
-        match self {
-            LibOS::NetworkLibOS(libos) => libos.listen(sockqd, backlog),
-            LibOS::MemoryLibOS(_) => Err(Fail::new(libc::ENOTSUP, "listen() is not supported on memory liboses")),
-        }
+        self.backend.listen(sockqd, backlog)
     }
 
     /// Accepts an incoming connection on a TCP socket.
     pub fn accept(&mut self, sockqd: QDesc) -> Result<QToken, Fail> {
-        match self {
-            LibOS::NetworkLibOS(libos) => libos.accept(sockqd),
-            LibOS::MemoryLibOS(_) => Err(Fail::new(libc::ENOTSUP, "accept() is not supported on memory liboses")),
-        }
+        let qt: QToken = self.backend.accept(sockqd)?;
+        Ok(self.track(qt, sockqd, 0))
     }
 
     /// Initiates a connection with a remote TCP socket.
-    pub fn connect(&mut self, sockqd: QDesc, remote: SocketAddrV4) -> Result<QToken, Fail> {
-        match self {
-            LibOS::NetworkLibOS(libos) => libos.connect(sockqd, remote),
-            LibOS::MemoryLibOS(_) => Err(Fail::new(libc::ENOTSUP, "connect() is not supported on memory liboses")),
-        }
+    pub fn connect(&mut self, sockqd: QDesc, remote: SocketAddr) -> Result<QToken, Fail> {
+        let qt: QToken = self.backend.connect(sockqd, remote)?;
+        Ok(self.track(qt, sockqd, 0))
+    }
+
+    /// Binds a Unix domain socket to a local address, either in the filesystem or in the abstract namespace.
+    pub fn bind_unix(&mut self, sockqd: QDesc, local: &UnixSocketAddr) -> Result<(), Fail> {
+        self.backend.bind_unix(sockqd, local)
+    }
+
+    /// Initiates a connection with a remote Unix domain socket.
+    pub fn connect_unix(&mut self, sockqd: QDesc, remote: &UnixSocketAddr) -> Result<QToken, Fail> {
+        let qt: QToken = self.backend.connect_unix(sockqd, remote)?;
+        Ok(self.track(qt, sockqd, 0))
     }
 
     /// Closes an I/O queue.
     pub fn close(&mut self, qd: QDesc) -> Result<(), Fail> {
-        match self {
-            LibOS::NetworkLibOS(libos) => libos.close(qd),
-            LibOS::MemoryLibOS(libos) => libos.close(qd),
-        }
+        self.backend.close(qd)?;
+        self.forget(qd);
+        Ok(())
     }
 
-    /// Pushes a scatter-gather array to an I/O queue.
-    pub fn push(&mut self, qd: QDesc, sga: &demi_sgarray_t) -> Result<QToken, Fail> {
-        match self {
-            LibOS::NetworkLibOS(libos) => libos.push(qd, sga),
-            LibOS::MemoryLibOS(libos) => libos.push(qd, sga),
-        }
+    /// Asynchronously closes an I/O queue. The operation completes once the I/O queue is fully shut down, that is, for
+    /// TCP sockets, once pushed data is flushed and acknowledged by the remote peer. The I/O queue descriptor may not
+    /// be used after this call, even if the operation fails.
+    pub fn async_close(&mut self, qd: QDesc) -> Result<QToken, Fail> {
+        let qt: QToken = self.backend.async_close(qd)?;
+        self.forget(qd);
+        Ok(qt)
     }
 
-    /// Pushes a scatter-gather array to a UDP socket.
-    pub fn pushto(&mut self, qd: QDesc, sga: &demi_sgarray_t, to: SocketAddrV4) -> Result<QToken, Fail> {
-        match self {
-            LibOS::NetworkLibOS(libos) => libos.pushto(qd, sga, to),
-            LibOS::MemoryLibOS(_) => Err(Fail::new(libc::ENOTSUP, "pushto() is not supported on memory liboses")),
-        }
+    /// Sets an option on a socket.
+    pub fn setsockopt(
+        &mut self,
+        sockqd: QDesc,
+        level: libc::c_int,
+        optname: libc::c_int,
+        optval: &[u8],
+    ) -> Result<(), Fail> {
+        self.backend.setsockopt(sockqd, level, optname, optval)
+    }
+
+    /// Gets the value of an option on a socket. On success, the number of bytes written to `optval` is returned.
+    pub fn getsockopt(
+        &mut self,
+        sockqd: QDesc,
+        level: libc::c_int,
+        optname: libc::c_int,
+        optval: &mut [u8],
+    ) -> Result<usize, Fail> {
+        self.backend.getsockopt(sockqd, level, optname, optval)
+    }
+
+    /// Gets the local address of a socket.
+    pub fn getsockname(&mut self, sockqd: QDesc) -> Result<SocketAddr, Fail> {
+        self.backend.getsockname(sockqd)
+    }
+
+    /// Gets the remote address of a connected socket.
+    pub fn getpeername(&mut self, sockqd: QDesc) -> Result<SocketAddr, Fail> {
+        self.backend.getpeername(sockqd)
+    }
+
+    /// Shuts down the sending half (`SHUT_WR`), the receiving half (`SHUT_RD`), or both halves (`SHUT_RDWR`) of a
+    /// connected socket, without releasing it. Once the sending half is shut down, the remote peer sees the end of
+    /// the stream, while data may still be popped from it.
+    pub fn shutdown(&mut self, sockqd: QDesc, how: libc::c_int) -> Result<(), Fail> {
+        self.backend.shutdown(sockqd, how)
+    }
+
+    /// Pushes a scatter-gather array to an I/O queue. Both raw and owned scatter-gather arrays may be pushed.
+    pub fn push<S: AsRef<demi_sgarray_t>>(&mut self, qd: QDesc, sga: &S) -> Result<QToken, Fail> {
+        let sga: &demi_sgarray_t = sga.as_ref();
+        let qt: QToken = self.backend.push(qd, sga)?;
+        Ok(self.track(qt, qd, sgarray_len(sga)))
+    }
+
+    /// Pushes a scatter-gather array to a UDP socket. Both raw and owned scatter-gather arrays may be pushed.
+    pub fn pushto<S: AsRef<demi_sgarray_t>>(&mut self, qd: QDesc, sga: &S, to: SocketAddr) -> Result<QToken, Fail> {
+        let sga: &demi_sgarray_t = sga.as_ref();
+        let qt: QToken = self.backend.pushto(qd, sga, to)?;
+        Ok(self.track(qt, qd, sgarray_len(sga)))
+    }
+
+    /// Pushes a scatter-gather array to a Unix domain datagram socket.
+    pub fn pushto_unix<S: AsRef<demi_sgarray_t>>(
+        &mut self,
+        qd: QDesc,
+        sga: &S,
+        to: &UnixSocketAddr,
+    ) -> Result<QToken, Fail> {
+        let sga: &demi_sgarray_t = sga.as_ref();
+        let qt: QToken = self.backend.pushto_unix(qd, sga, to)?;
+        Ok(self.track(qt, qd, sgarray_len(sga)))
     }
 
     /// Pops data from a an I/O queue.
     pub fn pop(&mut self, qd: QDesc) -> Result<QToken, Fail> {
-        match self {
-            LibOS::NetworkLibOS(libos) => libos.pop(qd),
-            LibOS::MemoryLibOS(libos) => libos.pop(qd),
+        let qt: QToken = self.backend.pop(qd, 0, None)?;
+        Ok(self.track(qt, qd, 0))
+    }
+
+    /// Pops at most `max_size` bytes from an I/O queue. On stream queues, the operation does not complete until at
+    /// least `min_size` bytes are available or the end of the stream is reached. On datagram sockets, `min_size` is
+    /// ignored, and the bytes of a datagram that exceed `max_size` are discarded.
+    pub fn pop_bounded(&mut self, qd: QDesc, min_size: usize, max_size: usize) -> Result<QToken, Fail> {
+        trace!(
+            "pop_bounded(): qd={:?}, min_size={:?}, max_size={:?}",
+            qd,
+            min_size,
+            max_size
+        );
+
+        // Check size bounds.
+        if max_size == 0 || max_size > POP_SIZE_MAX {
+            return Err(Fail::new(libc::EINVAL, "invalid maximum size for pop"));
         }
+        if min_size > max_size {
+            return Err(Fail::new(libc::EINVAL, "minimum size for pop exceeds maximum size"));
+        }
+
+        let qt: QToken = self.backend.pop(qd, min_size, Some(max_size))?;
+        Ok(self.track(qt, qd, 0))
+    }
+
+    /// Pops data from an I/O queue into a scatter-gather array provided by the caller, as many bytes as it holds at
+    /// most, as in [LibOS::pop_bounded]. The data is copied into the segments of `sga` when the operation completes,
+    /// and their lengths are set accordingly. The scatter-gather array is then handed back in the result of the
+    /// operation, and remains owned by the caller.
+    pub fn pop_into(&mut self, qd: QDesc, sga: demi_sgarray_t, min_size: usize) -> Result<QToken, Fail> {
+        trace!("pop_into(): qd={:?}, min_size={:?}", qd, min_size);
+
+        // Check scatter-gather array.
+        let numsegs: usize = sga.sga_numsegs as usize;
+        if numsegs == 0 || numsegs > sga.sga_segs.len() {
+            return Err(Fail::new(libc::EINVAL, "demi_sgarray_t has invalid segment count"));
+        }
+        if sga.sga_segs[..numsegs].iter().any(|seg| seg.sgaseg_buf.is_null()) {
+            return Err(Fail::new(libc::EINVAL, "demi_sgarray_t has invalid segment buffer"));
+        }
+        let capacity: usize = sga.sga_segs[..numsegs].iter().map(|seg| seg.sgaseg_len as usize).sum();
+
+        let qt: QToken = self.pop_bounded(qd, min_size, capacity.min(POP_SIZE_MAX))?;
+        self.targets.insert(qt, (qd, sga));
+        Ok(qt)
     }
 
     /// Waits for a pending I/O operation to complete or a timeout to expire.
@@ -226,6 +374,9 @@ impl LibOS {
         // Retrieve associated schedule handle.
         let mut handle: SchedulerHandle = self.schedule(qt)?;
 
+        // Get the wait start time, so as to know when the spin budget is exhausted.
+        let start: Instant = Instant::now();
+
         loop {
             // Poll first, so as to give pending operations a chance to complete.
             self.poll();
@@ -241,81 +392,693 @@ impl LibOS {
                 handle.take_key();
                 return Err(Fail::new(libc::ETIMEDOUT, "timer expired"));
             }
+
+            // Back off until the operation may make progress.
+            let remaining: Duration = abstime
+                .expect("abstime should still be set")
+                .duration_since(SystemTime::now())
+                .unwrap_or_default();
+            self.back_off(start, Some(remaining));
+        }
+    }
+
+    /// Polls pending I/O operations once and takes out the result of the target one, if it has completed. This never
+    /// blocks.
+    pub fn try_wait(&mut self, qt: QToken) -> Result<Option<demi_qresult_t>, Fail> {
+        trace!("try_wait(): qt={:?}", qt);
+
+        // Retrieve associated schedule handle.
+        let mut handle: SchedulerHandle = self.schedule(qt)?;
+
+        // Poll first, so as to give pending operations a chance to complete.
+        self.poll();
+
+        // The operation has completed, so extract the result and return.
+        if handle.has_completed() {
+            return Ok(Some(self.pack_result(handle, qt)?));
         }
+
+        // Return this operation to the scheduling queue by removing the associated key
+        // (which would otherwise cause the operation to be freed).
+        handle.take_key();
+        Ok(None)
     }
 
     /// Waits for any of the given pending I/O operations to complete or a timeout to expire.
     pub fn wait_any(&mut self, qts: &[QToken], timeout: Option<Duration>) -> Result<(usize, demi_qresult_t), Fail> {
         trace!("wait_any(): qts={:?}, timeout={:?}", qts, timeout);
 
-        // Get the wait start time, but only if we have a timeout.  We don't care when we started if we wait forever.
-        let start: Option<Instant> = if timeout.is_none() { None } else { Some(Instant::now()) };
+        let mut ready: Vec<(usize, demi_qresult_t)> = self.wait_completed(qts, 1, timeout)?;
+        Ok(ready.pop().expect("some operation should have completed"))
+    }
 
-        loop {
-            // Poll first, so as to give pending operations a chance to complete.
-            self.poll();
+    /// Waits for some of the given pending I/O operations to complete or a timeout to expire. On success, the results
+    /// of the operations in the list that have completed are returned at once, in the order of the list, up to
+    /// `max_qrs` of them. Operations beyond that are left pending, to be taken out by a later wait. If the result of
+    /// some operation fails to be taken out, the others are still returned, and the failure is only reported if there
+    /// are none.
+    pub fn wait_many(
+        &mut self,
+        qts: &[QToken],
+        max_qrs: usize,
+        timeout: Option<Duration>,
+    ) -> Result<Vec<demi_qresult_t>, Fail> {
+        trace!(
+            "wait_many(): qts={:?}, max_qrs={:?}, timeout={:?}",
+            qts,
+            max_qrs,
+            timeout
+        );
 
-            // Search for any operation that has completed.
-            for (i, &qt) in qts.iter().enumerate() {
-                // Retrieve associated schedule handle.
-                // TODO: move this out of the loop.
-                let mut handle: SchedulerHandle = self.schedule(qt)?;
+        if max_qrs == 0 {
+            return Err(Fail::new(libc::EINVAL, "should have room for some result"));
+        }
 
-                // Found one, so extract the result and return.
-                if handle.has_completed() {
-                    return Ok((i, self.pack_result(handle, qt)?));
-                }
+        let ready: Vec<(usize, demi_qresult_t)> = self.wait_completed(qts, max_qrs, timeout)?;
+        Ok(ready.into_iter().map(|(_, qr)| qr).collect())
+    }
 
-                // Return this operation to the scheduling queue by removing the associated key
-                // (which would otherwise cause the operation to be freed).
-                handle.take_key();
+    /// Waits for any of the given I/O queues to be ready for some of the given events, or for a timeout to expire.
+    /// On success, the readiness events of each I/O queue are returned in the order of the list, and `DEMI_POLLHUP` is
+    /// reported even if it was not asked for. If the timeout expires, no event is returned.
+    pub fn poll_events(&mut self, qds: &[QDesc], events: &[u32], timeout: Option<Duration>) -> Result<Vec<u32>, Fail> {
+        trace!(
+            "poll_events(): qds={:?}, events={:?}, timeout={:?}",
+            qds,
+            events,
+            timeout
+        );
+
+        if qds.len() != events.len() {
+            return Err(Fail::new(
+                libc::EINVAL,
+                "each queue descriptor should come with its events",
+            ));
+        }
+
+        // Get the wait start time, so as to check for expiration and to know when the spin budget is exhausted.
+        let start: Instant = Instant::now();
+
+        let mut revents: Vec<u32> = vec![0; qds.len()];
+        loop {
+            // Poll first, so as to give pending operations a chance to make progress.
+            self.poll();
+
+            // Check each I/O queue against the events that were asked for.
+            let mut ready: bool = false;
+            for (i, (&qd, &mask)) in qds.iter().zip(events).enumerate() {
+                revents[i] = self.backend.readiness(qd)? & (mask | DEMI_POLLHUP);
+                ready |= revents[i] != 0;
+            }
+            if ready {
+                return Ok(revents);
             }
 
             // If we have a timeout, check for expiration.
-            if timeout.is_some()
-                && Instant::now().duration_since(start.expect("start should be set if timeout is"))
-                    > timeout.expect("timeout should still be set")
-            {
-                return Err(Fail::new(libc::ETIMEDOUT, "timer expired"));
+            if timeout.is_some() && start.elapsed() >= timeout.expect("timeout should still be set") {
+                return Ok(revents);
             }
+
+            // Back off until some I/O queue may become ready.
+            self.back_off(start, timeout.map(|timeout| timeout.saturating_sub(start.elapsed())));
         }
     }
 
+    /// Blocks on the backend until some I/O queue may have become ready, or for at most `timeout`. As not every event
+    /// wakes the backend up, each blocking period is bounded by the wait policy, and callers should poll again once this
+    /// returns.
+    pub fn wait_for_events(&mut self, timeout: Duration) {
+        if !timeout.is_zero() {
+            self.backend.wait_for_events(timeout.min(self.wait_policy.max_block));
+        }
+    }
+
+    /// Cancels a pending I/O operation. If the operation has already completed, its result is returned as is.
+    /// Otherwise, the operation is aborted and completes with `ECANCELED`.
+    pub fn cancel(&mut self, qt: QToken) -> Result<demi_qresult_t, Fail> {
+        trace!("cancel() qt={:?}", qt);
+
+        // Retrieve associated schedule handle.
+        let handle: SchedulerHandle = self.schedule(qt)?;
+
+        // Poll first, so as to give the operation a chance to complete.
+        self.poll();
+
+        let (qr, failure): (demi_qresult_t, Option<Fail>) = self.backend.cancel(handle, qt)?;
+        let qr: demi_qresult_t = self.fill_target(qt, qr)?;
+        self.account(qt, &qr, failure);
+        Ok(qr)
+    }
+
+    /// Gets the failure reported by an operation, given its result. Nothing is returned if the operation did not fail,
+    /// or if its queue token was reused since then.
+    pub fn failure(&self, qr: &demi_qresult_t) -> Option<&Fail> {
+        if qr.qr_opcode != demi_opcode_t::DEMI_OPC_FAILED {
+            return None;
+        }
+        self.failures
+            .get(&QToken::from(qr.qr_qt))
+            .map(|(_, e)| e)
+            .filter(|e| e.errno == qr.qr_ret)
+    }
+
+    /// Gets the statistics of an I/O queue. Counters of pushed and popped data only account for operations whose
+    /// results were taken out, while protocol-specific fields are filled by the backend, if it holds them, and flagged
+    /// in `qs_flags`.
+    pub fn get_stats(&mut self, qd: QDesc) -> Result<demi_qstats_t, Fail> {
+        trace!("get_stats(): qd={:?}", qd);
+
+        let mut stats: demi_qstats_t = self.backend.get_stats(qd)?;
+        if let Some(counters) = self.stats.get(&qd) {
+            stats.qs_bytes_pushed = counters.qs_bytes_pushed;
+            stats.qs_bytes_popped = counters.qs_bytes_popped;
+            stats.qs_pushes = counters.qs_pushes;
+            stats.qs_pops = counters.qs_pops;
+        }
+        stats.qs_pending = self.pending.values().filter(|&&(other, _)| other == qd).count() as u64;
+        stats.qs_version = DEMI_QSTATS_VERSION;
+        Ok(stats)
+    }
+
     /// Allocates a scatter-gather array.
     pub fn sgaalloc(&self, size: usize) -> Result<demi_sgarray_t, Fail> {
-        match self {
-            LibOS::NetworkLibOS(libos) => libos.sgaalloc(size),
-            LibOS::MemoryLibOS(libos) => libos.sgaalloc(size),
+        self.backend.sgaalloc(size)
+    }
+
+    /// Allocates an owned scatter-gather array, which is released when dropped.
+    pub fn alloc_sga(&self, size: usize) -> Result<Sga, Fail> {
+        let sga: demi_sgarray_t = self.sgaalloc(size)?;
+        Ok(unsafe { Sga::from_raw(sga, self.memory.clone()) })
+    }
+
+    /// Takes ownership of the scatter-gather array popped by the operation whose result is `qr`, so that it is released
+    /// when dropped. The cause of a failed operation is retrieved as in [LibOS::failure].
+    pub fn take_sga(&self, qr: demi_qresult_t) -> Result<Sga, Fail> {
+        match qr.qr_opcode {
+            demi_opcode_t::DEMI_OPC_POP => Ok(unsafe { Sga::from_raw(qr.qr_value.sga, self.memory.clone()) }),
+            demi_opcode_t::DEMI_OPC_FAILED => Err(self
+                .failure(&qr)
+                .cloned()
+                .unwrap_or_else(|| Fail::new(qr.qr_ret, "operation failed"))),
+            _ => Err(Fail::new(libc::EINVAL, "not the result of a pop operation")),
         }
     }
 
+    /// Returns the memory runtime that releases the scatter-gather arrays handed out by the target LibOS. It may
+    /// outlive the LibOS.
+    pub fn memory(&self) -> Rc<dyn MemoryRuntime> {
+        self.memory.clone()
+    }
+
     /// Releases a scatter-gather array.
     pub fn sgafree(&self, sga: demi_sgarray_t) -> Result<(), Fail> {
-        match self {
-            LibOS::NetworkLibOS(libos) => libos.sgafree(sga),
-            LibOS::MemoryLibOS(libos) => libos.sgafree(sga),
-        }
+        self.backend.sgafree(sga)
+    }
+
+    /// Appends the segments of the `tail` scatter-gather array to `sga`, and releases `tail`. No data is copied.
+    pub fn sgaappend(&self, sga: &mut demi_sgarray_t, tail: demi_sgarray_t) -> Result<(), Fail> {
+        self.backend.sgaappend(sga, tail)
     }
 
     /// Waits for any operation in an I/O queue.
     fn schedule(&mut self, qt: QToken) -> Result<SchedulerHandle, Fail> {
-        match self {
-            LibOS::NetworkLibOS(libos) => libos.schedule(qt),
-            LibOS::MemoryLibOS(libos) => libos.schedule(qt),
+        self.backend.schedule(qt)
+    }
+
+    fn completed(&self) -> Vec<QToken> {
+        self.backend.completed()
+    }
+
+    /// Waits for at most `max` of the given pending I/O operations to complete, and takes out their results along with
+    /// their offsets in the list. Only the operations that have completed are looked up in the scheduler, so the cost
+    /// of each pass is a single scan of the list. The queue tokens are checked only once the wait is about to block.
+    fn wait_completed(
+        &mut self,
+        qts: &[QToken],
+        max: usize,
+        timeout: Option<Duration>,
+    ) -> Result<Vec<(usize, demi_qresult_t)>, Fail> {
+        // Get the wait start time, so as to check for expiration and to know when the spin budget is exhausted.
+        let start: Instant = Instant::now();
+        let mut checked: bool = false;
+
+        loop {
+            // Poll first, so as to give pending operations a chance to complete.
+            self.poll();
+
+            // Found some completed operations, so extract their results and return. Results that were taken out are
+            // returned even if others fail to be, as they cannot be waited for again. A failure is only reported if no
+            // result was taken out.
+            let ready: Vec<(usize, QToken)> = self.find_completed(qts, max);
+            if !ready.is_empty() {
+                let mut qrs: Vec<(usize, demi_qresult_t)> = Vec::with_capacity(ready.len());
+                let mut failure: Option<Fail> = None;
+                for (i, qt) in ready {
+                    match self.schedule(qt).and_then(|handle| self.pack_result(handle, qt)) {
+                        Ok(qr) => qrs.push((i, qr)),
+                        Err(e) => {
+                            warn!("failed to take out result (qt={:?}): {:?}", qt, e);
+                            failure.get_or_insert(e);
+                        },
+                    }
+                }
+                return match failure {
+                    Some(e) if qrs.is_empty() => Err(e),
+                    _ => Ok(qrs),
+                };
+            }
+
+            // Nothing has completed, so make sure that we are not waiting on some invalid queue token.
+            if !checked {
+                self.check_qtokens(qts)?;
+                checked = true;
+            }
+
+            // If we have a timeout, check for expiration.
+            if timeout.is_some() && start.elapsed() > timeout.expect("timeout should still be set") {
+                return Err(Fail::new(libc::ETIMEDOUT, "timer expired"));
+            }
+
+            // Back off until some operation may make progress.
+            self.back_off(start, timeout.map(|timeout| timeout.saturating_sub(start.elapsed())));
         }
     }
 
+    /// Finds the first `max` queue tokens in the list whose operations have completed, along with their offsets. If a
+    /// queue token shows up more than once, its first offset is kept.
+    fn find_completed(&self, qts: &[QToken], max: usize) -> Vec<(usize, QToken)> {
+        let mut completed: HashSet<QToken> = self.completed().into_iter().collect();
+        let mut ready: Vec<(usize, QToken)> = Vec::new();
+        for (i, qt) in qts.iter().enumerate() {
+            if completed.is_empty() || ready.len() == max {
+                break;
+            }
+            if completed.remove(qt) {
+                ready.push((i, *qt));
+            }
+        }
+        ready
+    }
+
+    /// Checks that each of the given queue tokens refers to a pending operation.
+    fn check_qtokens(&mut self, qts: &[QToken]) -> Result<(), Fail> {
+        for &qt in qts {
+            // Return this operation to the scheduling queue by removing the associated key
+            // (which would otherwise cause the operation to be freed).
+            self.schedule(qt)?.take_key();
+        }
+        Ok(())
+    }
+
     fn pack_result(&mut self, handle: SchedulerHandle, qt: QToken) -> Result<demi_qresult_t, Fail> {
-        match self {
-            LibOS::NetworkLibOS(libos) => libos.pack_result(handle, qt),
-            LibOS::MemoryLibOS(libos) => libos.pack_result(handle, qt),
+        let (qr, failure): (demi_qresult_t, Option<Fail>) = self.backend.pack_result(handle, qt)?;
+        let qr: demi_qresult_t = self.fill_target(qt, qr)?;
+        self.account(qt, &qr, failure);
+        Ok(qr)
+    }
+
+    /// Records a pending operation on an I/O queue, for statistics.
+    fn track(&mut self, qt: QToken, qd: QDesc, bytes: usize) -> QToken {
+        self.pending.insert(qt, (qd, bytes));
+        qt
+    }
+
+    /// Drops the statistics of an I/O queue that was closed, along with the pending operations and the scatter-gather
+    /// arrays of the pop operations that were recorded on it, as their results may never be taken out, and the failures
+    /// that its operations reported. The arrays remain owned by the caller.
+    fn forget(&mut self, qd: QDesc) {
+        self.stats.remove(&qd);
+        self.pending.retain(|_, &mut (other, _)| other != qd);
+        self.targets.retain(|_, &mut (other, _)| other != qd);
+        self.failures.retain(|_, (other, _)| *other != qd);
+    }
+
+    /// Accounts the result of an operation that was taken out in the statistics of its I/O queue, and keeps the
+    /// failure that it reported, if any, until its queue token is reused.
+    fn account(&mut self, qt: QToken, qr: &demi_qresult_t, failure: Option<Fail>) {
+        match failure {
+            Some(e) => self.failures.insert(qt, (QDesc::from(qr.qr_qd), e)),
+            None => self.failures.remove(&qt),
+        };
+
+        let (qd, bytes): (QDesc, usize) = match self.pending.remove(&qt) {
+            Some(pending) => pending,
+            None => return,
+        };
+        match qr.qr_opcode {
+            demi_opcode_t::DEMI_OPC_PUSH => {
+                let stats: &mut demi_qstats_t = self.stats.entry(qd).or_default();
+                stats.qs_pushes += 1;
+                stats.qs_bytes_pushed += bytes as u64;
+            },
+            demi_opcode_t::DEMI_OPC_POP => {
+                let stats: &mut demi_qstats_t = self.stats.entry(qd).or_default();
+                stats.qs_pops += 1;
+                stats.qs_bytes_popped += sgarray_len(unsafe { &qr.qr_value.sga }) as u64;
+            },
+            _ => (),
+        }
+    }
+
+    /// Copies the data popped by an operation into the scatter-gather array that the caller provided to it, if any,
+    /// and releases the one that the backend allocated.
+    fn fill_target(&mut self, qt: QToken, mut qr: demi_qresult_t) -> Result<demi_qresult_t, Fail> {
+        let mut target: demi_sgarray_t = match self.targets.remove(&qt) {
+            Some((_, target)) => target,
+            None => return Ok(qr),
+        };
+        if qr.qr_opcode != demi_opcode_t::DEMI_OPC_POP {
+            return Ok(qr);
+        }
+
+        let sga: demi_sgarray_t = unsafe { qr.qr_value.sga };
+        copy_sgarray(&sga, &mut target);
+        target.sga_addr = sga.sga_addr;
+        self.backend.sgafree(sga)?;
+        qr.qr_value.sga = target;
+        Ok(qr)
+    }
+
+    /// Blocks on the backend once the spin budget of the wait policy is exhausted, for a wait that was started at `start`
+    /// and that times out after `remaining`, if set. This returns right away while the LibOS should busy-poll.
+    fn back_off(&mut self, start: Instant, remaining: Option<Duration>) {
+        if let Some(timeout) = self.wait_policy.block_for(start.elapsed(), remaining) {
+            self.backend.wait_for_events(timeout);
         }
     }
 
     fn poll(&mut self) {
-        match self {
-            LibOS::NetworkLibOS(libos) => libos.poll(),
-            LibOS::MemoryLibOS(libos) => libos.poll(),
+        self.backend.poll()
+    }
+}
+
+//======================================================================================================================
+// Standalone Functions
+//======================================================================================================================
+
+/// Computes the number of bytes held in the segments of a scatter-gather array.
+fn sgarray_len(sga: &demi_sgarray_t) -> usize {
+    let numsegs: usize = (sga.sga_numsegs as usize).min(sga.sga_segs.len());
+    sga.sga_segs[..numsegs].iter().map(|seg| seg.sgaseg_len as usize).sum()
+}
+
+/// Copies the data of a scatter-gather array into the segments of another one, and shrinks each segment of the latter
+/// to the number of bytes copied into it. Data that does not fit in the target scatter-gather array is left out.
+fn copy_sgarray(src: &demi_sgarray_t, dst: &mut demi_sgarray_t) {
+    let mut chunks = src.sga_segs[..src.sga_numsegs as usize]
+        .iter()
+        .filter(|seg| seg.sgaseg_len > 0)
+        .map(|seg| unsafe { slice::from_raw_parts(seg.sgaseg_buf as *const u8, seg.sgaseg_len as usize) });
+    let mut chunk: &[u8] = &[];
+    for seg in dst.sga_segs[..dst.sga_numsegs as usize].iter_mut() {
+        let buf: &mut [u8] = unsafe { slice::from_raw_parts_mut(seg.sgaseg_buf as *mut u8, seg.sgaseg_len as usize) };
+        let mut len: usize = 0;
+        while len < buf.len() {
+            if chunk.is_empty() {
+                match chunks.next() {
+                    Some(next) => chunk = next,
+                    None => break,
+                }
+                continue;
+            }
+            let n: usize = chunk.len().min(buf.len() - len);
+            buf[len..len + n].copy_from_slice(&chunk[..n]);
+            chunk = &chunk[n..];
+            len += n;
+        }
+        seg.sgaseg_len = len as u32;
+    }
+}
+
+//======================================================================================================================
+// Unit Tests
+//======================================================================================================================
+
+#[cfg(all(test, feature = "catmem-libos"))]
+mod tests {
+    use super::{
+        policy::WaitPolicy,
+        LibOS,
+        POP_SIZE_MAX,
+    };
+    use crate::{
+        catmem::CatmemLibOS,
+        runtime::{
+            fail::Fail,
+            memory::Sga,
+            types::{
+                demi_opcode_t,
+                demi_qresult_t,
+                demi_qstats_t,
+                demi_sgarray_t,
+                DEMI_POLLIN,
+                DEMI_POLLOUT,
+                DEMI_QSTATS_VERSION,
+            },
+            QDesc,
+            QToken,
+        },
+    };
+    use ::std::time::{
+        Duration,
+        Instant,
+    };
+
+    /// Creates a LibOS on top of Catmem.
+    fn new_libos() -> LibOS {
+        LibOS::from_backend(Box::new(CatmemLibOS::new()))
+    }
+
+    /// Pushes some bytes to a memory queue and waits for them to be pushed.
+    fn push_bytes(libos: &mut LibOS, qd: QDesc, bytes: &[u8]) -> Result<(), Fail> {
+        let mut sga: Sga = libos.alloc_sga(bytes.len())?;
+        let mut bytes: &[u8] = bytes;
+        for data in sga.segments_mut() {
+            let (head, tail): (&[u8], &[u8]) = bytes.split_at(data.len());
+            data.copy_from_slice(head);
+            bytes = tail;
         }
+        let qt: QToken = libos.push(qd, &sga)?;
+        libos.wait(qt, None)?;
+        Ok(())
+    }
+
+    /// Collects the bytes of all segments in a scatter-gather array.
+    fn sga_bytes(sga: &Sga) -> Vec<u8> {
+        sga.segments().flatten().copied().collect()
+    }
+
+    /// Tests if pop operations wait for their minimum size, and do not exceed their maximum size.
+    #[test]
+    fn libos_pop_bounded() -> Result<(), Fail> {
+        let mut libos: LibOS = new_libos();
+        let qd: QDesc = libos.create_pipe("libos-pop-bounded")?;
+
+        // Not enough data is available for the pop operation to complete at first.
+        let qt: QToken = libos.pop_bounded(qd, 12, 12)?;
+        push_bytes(&mut libos, qd, &[1; 8])?;
+        assert!(libos.try_wait(qt)?.is_none());
+        push_bytes(&mut libos, qd, &[2; 8])?;
+        let qr: demi_qresult_t = libos.wait(qt, None)?;
+        let popped: Sga = libos.take_sga(qr)?;
+        assert_eq!(sga_bytes(&popped), &[1, 1, 1, 1, 1, 1, 1, 1, 2, 2, 2, 2]);
+
+        // Data beyond the maximum size is left for the next pop operation.
+        let qt: QToken = libos.pop_bounded(qd, 0, POP_SIZE_MAX)?;
+        let qr: demi_qresult_t = libos.wait(qt, None)?;
+        let popped: Sga = libos.take_sga(qr)?;
+        assert_eq!(sga_bytes(&popped), &[2, 2, 2, 2]);
+
+        // Size bounds are checked.
+        for (min_size, max_size) in [(0, 0), (9, 8), (0, POP_SIZE_MAX + 1)] {
+            assert_eq!(
+                libos.pop_bounded(qd, min_size, max_size).err().map(|e| e.errno),
+                Some(libc::EINVAL)
+            );
+        }
+
+        libos.close(qd)?;
+        Ok(())
+    }
+
+    /// Tests if data is popped into the segments of a scatter-gather array provided by the caller.
+    #[test]
+    fn libos_pop_into() -> Result<(), Fail> {
+        let mut libos: LibOS = new_libos();
+        let qd: QDesc = libos.create_pipe("libos-pop-into")?;
+        push_bytes(&mut libos, qd, &(0..16).collect::<Vec<u8>>())?;
+
+        // Pop into a scatter-gather array of two segments, which holds less data than was pushed.
+        let mut target: demi_sgarray_t = libos.sgaalloc(4)?;
+        libos.sgaappend(&mut target, libos.sgaalloc(8)?)?;
+        let qt: QToken = libos.pop_into(qd, target, 0)?;
+        let qr: demi_qresult_t = libos.wait(qt, None)?;
+        let popped: Sga = libos.take_sga(qr)?;
+        assert_eq!(popped.as_ref().sga_buf, target.sga_buf);
+        let segments: Vec<&[u8]> = popped.segments().collect();
+        assert_eq!(segments, [&[0, 1, 2, 3][..], &[4, 5, 6, 7, 8, 9, 10, 11][..]]);
+
+        // The remaining data only fills the first segment.
+        let qt: QToken = libos.pop_into(qd, popped.into_raw(), 0)?;
+        let qr: demi_qresult_t = libos.wait(qt, None)?;
+        let popped: Sga = libos.take_sga(qr)?;
+        let segments: Vec<&[u8]> = popped.segments().collect();
+        assert_eq!(segments, [&[12, 13, 14, 15][..], &[][..]]);
+
+        libos.close(qd)?;
+        Ok(())
+    }
+
+    /// Tests if an asynchronous close completes, and releases the queue descriptor.
+    #[test]
+    fn libos_async_close() -> Result<(), Fail> {
+        let mut libos: LibOS = new_libos();
+        let qd: QDesc = libos.create_pipe("libos-async-close")?;
+
+        let qt: QToken = libos.async_close(qd)?;
+        let qr: demi_qresult_t = libos.wait(qt, None)?;
+        assert_eq!(qr.qr_opcode, demi_opcode_t::DEMI_OPC_CLOSE);
+        assert_eq!(qr.qr_qd, qd.into());
+
+        // The queue descriptor may not be used anymore.
+        assert_eq!(libos.async_close(qd).err().map(|e| e.errno), Some(libc::EBADF));
+        Ok(())
+    }
+
+    /// Tests if the statistics of a memory queue account for completed and pending operations.
+    #[test]
+    fn libos_get_stats() -> Result<(), Fail> {
+        let mut libos: LibOS = new_libos();
+        let qd: QDesc = libos.create_pipe("libos-get-stats")?;
+        push_bytes(&mut libos, qd, &[1; 8])?;
+        push_bytes(&mut libos, qd, &[2; 8])?;
+
+        // Pop part of the data, and leave another pop operation pending.
+        let qt: QToken = libos.pop_bounded(qd, 0, 12)?;
+        libos.wait(qt, None)?;
+        let qt: QToken = libos.pop_bounded(qd, 8, 8)?;
+
+        let stats: demi_qstats_t = libos.get_stats(qd)?;
+        assert_eq!(stats.qs_version, DEMI_QSTATS_VERSION);
+        assert_eq!(stats.qs_flags, 0);
+        assert_eq!((stats.qs_pushes, stats.qs_bytes_pushed), (2, 16));
+        assert_eq!((stats.qs_pops, stats.qs_bytes_popped), (1, 12));
+        assert_eq!(stats.qs_pending, 1);
+
+        libos.cancel(qt)?;
+        assert_eq!(libos.get_stats(qd)?.qs_pending, 0);
+
+        // Statistics are gone along with the queue descriptor.
+        libos.close(qd)?;
+        assert_eq!(libos.get_stats(qd).err().map(|e| e.errno), Some(libc::EBADF));
+        Ok(())
+    }
+
+    /// Tests if closing a memory queue drops the operations that were recorded on it, for statistics, along with the
+    /// scatter-gather arrays of its pop operations.
+    #[test]
+    fn libos_close_forgets_pending() -> Result<(), Fail> {
+        let mut libos: LibOS = new_libos();
+        let qd1: QDesc = libos.create_pipe("libos-close-forgets-pending-1")?;
+        let qd2: QDesc = libos.create_pipe("libos-close-forgets-pending-2")?;
+
+        // Leave some pop operations pending, and never wait for them.
+        let target: demi_sgarray_t = libos.sgaalloc(8)?;
+        libos.pop_into(qd1, target, 0)?;
+        libos.pop(qd2)?;
+        libos.pop_into(qd2, target, 0)?;
+        assert_eq!(libos.pending.len(), 3);
+        assert_eq!(libos.targets.len(), 2);
+
+        libos.close(qd1)?;
+        assert_eq!(libos.pending.len(), 2);
+        assert_eq!(libos.targets.len(), 1);
+        let qt: QToken = libos.async_close(qd2)?;
+        assert!(libos.pending.is_empty() && libos.stats.is_empty() && libos.targets.is_empty());
+        libos.wait(qt, None)?;
+
+        // The scatter-gather array is still owned by the caller.
+        libos.sgafree(target)?;
+        Ok(())
+    }
+
+    /// Tests if closing a memory queue drops the failures that its operations reported.
+    #[test]
+    fn libos_close_forgets_failures() -> Result<(), Fail> {
+        let mut libos: LibOS = new_libos();
+        let qd: QDesc = libos.create_pipe("libos-close-forgets-failures")?;
+
+        // Cancel a pop operation, so that it reports a failure.
+        let qt: QToken = libos.pop(qd)?;
+        let qr: demi_qresult_t = libos.cancel(qt)?;
+        assert_eq!(libos.failure(&qr).map(|e| e.errno), Some(libc::ECANCELED));
+
+        libos.close(qd)?;
+        assert!(libos.failure(&qr).is_none() && libos.failures.is_empty());
+        Ok(())
+    }
+
+    /// Tests if memory queues are reported as readable and writable from the occupancy of their ring buffer.
+    #[test]
+    fn libos_poll_events() -> Result<(), Fail> {
+        let mut libos: LibOS = new_libos();
+        let qd: QDesc = libos.create_pipe("libos-poll-events")?;
+        let timeout: Option<Duration> = Some(Duration::ZERO);
+
+        // Empty queues are only writable, so waiting for them to be readable times out.
+        assert_eq!(
+            libos.poll_events(&[qd], &[DEMI_POLLIN | DEMI_POLLOUT], timeout)?,
+            [DEMI_POLLOUT]
+        );
+        let timeout_1ms: Option<Duration> = Some(Duration::from_millis(1));
+        assert_eq!(libos.poll_events(&[qd], &[DEMI_POLLIN], timeout_1ms)?, [0]);
+
+        // Queues holding some data are readable, until it is popped.
+        push_bytes(&mut libos, qd, &[1; 8])?;
+        assert_eq!(libos.poll_events(&[qd], &[DEMI_POLLIN], None)?, [DEMI_POLLIN]);
+        let qt: QToken = libos.pop(qd)?;
+        libos.wait(qt, None)?;
+        assert_eq!(libos.poll_events(&[qd], &[DEMI_POLLIN], timeout)?, [0]);
+
+        // Each queue descriptor comes with its events, and should be valid.
+        assert_eq!(
+            libos.poll_events(&[qd], &[], timeout).err().map(|e| e.errno),
+            Some(libc::EINVAL)
+        );
+        libos.close(qd)?;
+        assert_eq!(
+            libos.poll_events(&[qd], &[DEMI_POLLIN], timeout).err().map(|e| e.errno),
+            Some(libc::EBADF)
+        );
+        Ok(())
+    }
+
+    /// Tests if waits still complete and time out as expected once the LibOS backs off.
+    #[test]
+    fn libos_wait_policy() -> Result<(), Fail> {
+        let mut libos: LibOS = new_libos();
+        libos.set_wait_policy(WaitPolicy {
+            spin_budget: Some(Duration::ZERO),
+            max_block: Duration::from_millis(1),
+        });
+        let qd: QDesc = libos.create_pipe("libos-wait-policy")?;
+
+        // The timeout is honored, although the LibOS blocks in between polls.
+        let qt: QToken = libos.pop(qd)?;
+        let timeout: Duration = Duration::from_millis(20);
+        let start: Instant = Instant::now();
+        assert_eq!(
+            libos.wait(qt, Some(timeout)).err().map(|e| e.errno),
+            Some(libc::ETIMEDOUT)
+        );
+        assert!(start.elapsed() >= timeout);
+
+        // The operation completes once data is available.
+        push_bytes(&mut libos, qd, &[1; 8])?;
+        let qr: demi_qresult_t = libos.wait(qt, None)?;
+        let popped: Sga = libos.take_sga(qr)?;
+        assert_eq!(sga_bytes(&popped), &[1; 8]);
+        libos.close(qd)?;
+        Ok(())
     }
 }
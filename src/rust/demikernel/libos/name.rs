@@ -5,14 +5,19 @@
 // Imports
 //======================================================================================================================
 
+use super::backend;
 use crate::runtime::fail::Fail;
-use ::std::env;
+use ::std::{
+    env,
+    str::FromStr,
+};
 
 //======================================================================================================================
 // Structures
 //======================================================================================================================
 
 /// Names of LibOSes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum LibOSName {
     Catpowder,
     Catnap,
@@ -20,6 +25,8 @@ pub enum LibOSName {
     Catcollar,
     Catnip,
     Catmem,
+    /// Custom LibOS, that was registered with [backend::register_backend].
+    Custom(&'static str),
 }
 
 //======================================================================================================================
@@ -28,9 +35,10 @@ pub enum LibOSName {
 
 /// Associated functions for LibOSName.
 impl LibOSName {
+    /// Reads the name of a LibOS from the `LIBOS` environment variable.
     pub fn from_env() -> Result<Self, Fail> {
         match env::var("LIBOS") {
-            Ok(name) => Ok(name.into()),
+            Ok(name) => name.parse(),
             Err(_) => Err(Fail::new(libc::EINVAL, "missing value for LIBOS environment variable")),
         }
     }
@@ -40,17 +48,22 @@ impl LibOSName {
 // Trait Implementations
 //======================================================================================================================
 
-/// Conversion trait implementation for LibOSName.
-impl From<String> for LibOSName {
-    fn from(str: String) -> Self {
-        match str.to_lowercase().as_str() {
-            "catpowder" => LibOSName::Catpowder,
-            "catnap" => LibOSName::Catnap,
-            "catnapw" => LibOSName::CatnapW,
-            "catcollar" => LibOSName::Catcollar,
-            "catnip" => LibOSName::Catnip,
-            "catmem" => LibOSName::Catmem,
-            _ => panic!("unkown libos"),
+/// From String trait implementation for LibOSName.
+impl FromStr for LibOSName {
+    type Err = Fail;
+
+    fn from_str(s: &str) -> Result<Self, Fail> {
+        match s.to_lowercase().as_str() {
+            "catpowder" => Ok(LibOSName::Catpowder),
+            "catnap" => Ok(LibOSName::Catnap),
+            "catnapw" => Ok(LibOSName::CatnapW),
+            "catcollar" => Ok(LibOSName::Catcollar),
+            "catnip" => Ok(LibOSName::Catnip),
+            "catmem" => Ok(LibOSName::Catmem),
+            name => match backend::lookup_backend(name) {
+                Some((name, _)) => Ok(LibOSName::Custom(name)),
+                None => Err(Fail::new(libc::EINVAL, &format!("unknown libos {:?}", s))),
+            },
         }
     }
 }
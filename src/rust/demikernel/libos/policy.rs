@@ -0,0 +1,148 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//======================================================================================================================
+// Imports
+//======================================================================================================================
+
+use crate::{
+    demikernel::config::{
+        Config,
+        Section,
+    },
+    runtime::fail::Fail,
+};
+use ::std::time::Duration;
+
+//======================================================================================================================
+// Constants
+//======================================================================================================================
+
+/// Keys of the "wait_policy" configuration section.
+const KEYS: &[&str] = &["spin_budget_us", "max_block_us"];
+
+/// Default upper bound of a single blocking period, in microseconds.
+const DEFAULT_MAX_BLOCK_US: u64 = 1000;
+
+//======================================================================================================================
+// Structures
+//======================================================================================================================
+
+/// Wait Policy
+///
+/// This tells how the LibOS waits for pending operations to complete. It busy-polls the backend for a spin budget,
+/// and then backs off by blocking on the notification source of the backend between polls. Each blocking period is
+/// bounded, as some events (e.g. packets that are received by a user-space network stack) cannot wake it up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WaitPolicy {
+    /// Time to busy-poll for before backing off. The LibOS never blocks if this is not set.
+    pub spin_budget: Option<Duration>,
+    /// Upper bound of a single blocking period.
+    pub max_block: Duration,
+}
+
+//======================================================================================================================
+// Associated Functions
+//======================================================================================================================
+
+/// Associated functions for wait policies.
+impl WaitPolicy {
+    /// Reads and validates the "wait_policy" section of a Demikernel configuration. The LibOS busy-polls forever if
+    /// the section is missing.
+    pub fn new(config: &Config) -> Result<Self, Fail> {
+        let section: Section = config.section("wait_policy")?;
+        section.warn_unknown_keys(KEYS);
+
+        let spin_budget: Option<Duration> = section.get::<u64>("spin_budget_us")?.map(Duration::from_micros);
+        let max_block_us: u64 = section.get_or("max_block_us", DEFAULT_MAX_BLOCK_US)?;
+        if max_block_us == 0 {
+            return Err(section.invalid("max_block_us", "expected a positive duration"));
+        }
+
+        Ok(Self {
+            spin_budget,
+            max_block: Duration::from_micros(max_block_us),
+        })
+    }
+
+    /// Tells how long to block for, given the time that was spent waiting so far and the time that remains before the
+    /// wait times out, if any. Nothing is returned while the spin budget is not exhausted, nor when the wait is about
+    /// to time out.
+    pub fn block_for(&self, spent: Duration, remaining: Option<Duration>) -> Option<Duration> {
+        match self.spin_budget {
+            Some(spin_budget) if spent >= spin_budget => {
+                let timeout: Duration = match remaining {
+                    Some(remaining) => remaining.min(self.max_block),
+                    None => self.max_block,
+                };
+                if timeout.is_zero() {
+                    None
+                } else {
+                    Some(timeout)
+                }
+            },
+            _ => None,
+        }
+    }
+}
+
+//======================================================================================================================
+// Trait Implementations
+//======================================================================================================================
+
+/// Default trait implementation for wait policies. The LibOS busy-polls forever.
+impl Default for WaitPolicy {
+    fn default() -> Self {
+        Self {
+            spin_budget: None,
+            max_block: Duration::from_micros(DEFAULT_MAX_BLOCK_US),
+        }
+    }
+}
+
+//======================================================================================================================
+// Unit Tests
+//======================================================================================================================
+
+#[cfg(test)]
+mod test {
+    use super::WaitPolicy;
+    use crate::{
+        demikernel::config::Config,
+        runtime::fail::Fail,
+    };
+    use ::std::time::Duration;
+
+    /// Tests if wait policies are read from a configuration.
+    #[test]
+    fn wait_policy_config() -> Result<(), Fail> {
+        let config: Config = "catnap: {}\n".parse()?;
+        assert_eq!(WaitPolicy::new(&config)?, WaitPolicy::default());
+
+        let config: Config = "wait_policy:\n  spin_budget_us: 50\n  max_block_us: 200\n".parse()?;
+        let policy: WaitPolicy = WaitPolicy::new(&config)?;
+        assert_eq!(policy.spin_budget, Some(Duration::from_micros(50)));
+        assert_eq!(policy.max_block, Duration::from_micros(200));
+
+        let config: Config = "wait_policy:\n  max_block_us: 0\n".parse()?;
+        assert_eq!(WaitPolicy::new(&config).err().map(|e| e.errno), Some(libc::EINVAL));
+
+        Ok(())
+    }
+
+    /// Tests if the LibOS blocks only once the spin budget is exhausted, and for no longer than the remaining time.
+    #[test]
+    fn wait_policy_block_for() {
+        let ms = Duration::from_millis;
+        assert_eq!(WaitPolicy::default().block_for(ms(1000), None), None);
+
+        let policy: WaitPolicy = WaitPolicy {
+            spin_budget: Some(ms(10)),
+            max_block: ms(5),
+        };
+        assert_eq!(policy.block_for(ms(9), None), None);
+        assert_eq!(policy.block_for(ms(10), None), Some(ms(5)));
+        assert_eq!(policy.block_for(ms(10), Some(ms(2))), Some(ms(2)));
+        assert_eq!(policy.block_for(ms(10), Some(ms(0))), None);
+    }
+}
@@ -1,6 +1,13 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT license.
 
+pub mod args;
+// The C bindings take raw pointers from their callers, and their safety requirements are those stated in their manual
+// pages.
+#[allow(clippy::missing_safety_doc)]
 pub mod bindings;
 pub mod config;
 pub mod libos;
+
+#[cfg(all(feature = "preload", target_os = "linux"))]
+mod preload;
@@ -0,0 +1,134 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//======================================================================================================================
+// Imports
+//======================================================================================================================
+
+use crate::runtime::fail::Fail;
+use ::libc::{
+    c_int,
+    epoll_event,
+};
+use ::std::collections::{
+    hash_map::Entry,
+    HashMap,
+};
+
+//======================================================================================================================
+// Structures
+//======================================================================================================================
+
+/// Epoll Instance
+///
+/// This holds the interest list of an epoll instance for Demikernel sockets. Other file descriptors are registered in
+/// the kernel epoll instance that backs it.
+#[derive(Default)]
+pub struct Epoll {
+    /// Events of interest for each Demikernel socket.
+    interests: HashMap<c_int, epoll_event>,
+}
+
+//======================================================================================================================
+// Associated Functions
+//======================================================================================================================
+
+/// Associated Functions for Epoll Instances
+impl Epoll {
+    /// Adds (`EPOLL_CTL_ADD`), modifies (`EPOLL_CTL_MOD`) or removes (`EPOLL_CTL_DEL`) a socket in the interest list of
+    /// the target epoll instance.
+    pub fn ctl(&mut self, op: c_int, fd: c_int, event: Option<epoll_event>) -> Result<(), Fail> {
+        match (op, self.interests.entry(fd), event) {
+            (libc::EPOLL_CTL_ADD, Entry::Vacant(entry), Some(event)) => {
+                entry.insert(event);
+                Ok(())
+            },
+            (libc::EPOLL_CTL_MOD, Entry::Occupied(mut entry), Some(event)) => {
+                entry.insert(event);
+                Ok(())
+            },
+            (libc::EPOLL_CTL_DEL, Entry::Occupied(entry), _) => {
+                entry.remove();
+                Ok(())
+            },
+            (libc::EPOLL_CTL_ADD, Entry::Occupied(_), _) => {
+                Err(Fail::new(libc::EEXIST, "socket is already registered"))
+            },
+            (libc::EPOLL_CTL_MOD | libc::EPOLL_CTL_DEL, Entry::Vacant(_), _) => {
+                Err(Fail::new(libc::ENOENT, "socket is not registered"))
+            },
+            (libc::EPOLL_CTL_ADD | libc::EPOLL_CTL_MOD, _, None) => Err(Fail::new(libc::EFAULT, "invalid event")),
+            _ => Err(Fail::new(libc::EINVAL, "invalid epoll operation")),
+        }
+    }
+
+    /// Removes a socket from the interest list of the target epoll instance, once it is closed.
+    pub fn remove(&mut self, fd: c_int) {
+        self.interests.remove(&fd);
+    }
+
+    /// Disables a socket in the interest list of the target epoll instance, once it reported an event and it was
+    /// registered with `EPOLLONESHOT`.
+    pub fn disarm(&mut self, fd: c_int) {
+        if let Some(event) = self.interests.get_mut(&fd) {
+            event.events = 0;
+        }
+    }
+
+    /// Returns a snapshot of the interest list of the target epoll instance.
+    pub fn interests(&self) -> Vec<(c_int, epoll_event)> {
+        self.interests.iter().map(|(&fd, &event)| (fd, event)).collect()
+    }
+}
+
+//======================================================================================================================
+// Unit Tests
+//======================================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::Epoll;
+    use crate::runtime::fail::Fail;
+    use ::libc::epoll_event;
+
+    /// Tests if sockets are added to, modified in and removed from the interest list as `epoll_ctl()` would.
+    #[test]
+    fn epoll_ctl() -> Result<(), Fail> {
+        let mut epoll: Epoll = Epoll::default();
+        let event: epoll_event = epoll_event {
+            events: libc::EPOLLIN as u32,
+            u64: 42,
+        };
+
+        epoll.ctl(libc::EPOLL_CTL_ADD, 3, Some(event))?;
+        let errno = |r: Result<(), Fail>| r.err().map(|e| e.errno);
+        assert_eq!(
+            errno(epoll.ctl(libc::EPOLL_CTL_ADD, 3, Some(event))),
+            Some(libc::EEXIST)
+        );
+        assert_eq!(
+            errno(epoll.ctl(libc::EPOLL_CTL_MOD, 4, Some(event))),
+            Some(libc::ENOENT)
+        );
+        assert_eq!(errno(epoll.ctl(libc::EPOLL_CTL_MOD, 3, None)), Some(libc::EFAULT));
+
+        // Sockets registered with EPOLLONESHOT are disarmed until they are modified.
+        epoll.ctl(
+            libc::EPOLL_CTL_MOD,
+            3,
+            Some(epoll_event {
+                events: (libc::EPOLLIN | libc::EPOLLONESHOT) as u32,
+                u64: 42,
+            }),
+        )?;
+        epoll.disarm(3);
+        let interests: Vec<(i32, epoll_event)> = epoll.interests();
+        assert_eq!(interests.len(), 1);
+        assert_eq!((interests[0].0, interests[0].1.events, interests[0].1.u64), (3, 0, 42));
+
+        epoll.ctl(libc::EPOLL_CTL_DEL, 3, None)?;
+        assert!(epoll.interests().is_empty());
+        assert_eq!(errno(epoll.ctl(libc::EPOLL_CTL_DEL, 3, None)), Some(libc::ENOENT));
+        Ok(())
+    }
+}
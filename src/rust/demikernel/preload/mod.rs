@@ -0,0 +1,850 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! # POSIX Socket Interposition
+//!
+//! When Demikernel is built with the `preload` feature, its shared library interposes the POSIX socket and `epoll`
+//! functions of the C library, so that unmodified applications run on top of a LibOS if the library is preloaded:
+//!
+//! ```sh
+//! LD_PRELOAD=libdemikernel.so LIBOS=catnip CONFIG_PATH=config.yaml redis-server
+//! ```
+//!
+//! Each Demikernel socket is backed by a placeholder file descriptor, which is handed out to the application and mapped
+//! to the I/O queue descriptor of the socket. Calls on other file descriptors, as well as calls issued by Demikernel
+//! itself, are forwarded to the C library.
+//!
+//! The LibOS is not thread-safe, so it is owned by the thread that first creates a socket or an epoll instance. Only
+//! that thread runs on top of Demikernel: sockets and epoll instances that other threads create are kernel ones, and
+//! calls that other threads issue on Demikernel file descriptors fail with `EBADF`. Blocking calls, including
+//! `epoll_wait()`, block on the LibOS for at most the maximum blocking period of its wait policy at a time, so that
+//! kernel file descriptors registered in an epoll instance are checked again in between.
+
+mod epoll;
+mod real;
+mod socket;
+
+//======================================================================================================================
+// Imports
+//======================================================================================================================
+
+use self::{
+    epoll::Epoll,
+    socket::Socket,
+};
+use crate::{
+    demikernel::{
+        bindings::{
+            sockaddr_to_socketaddr,
+            socketaddr_to_sockaddr,
+        },
+        libos::{
+            name::LibOSName,
+            LibOS,
+        },
+    },
+    runtime::{
+        fail::Fail,
+        QDesc,
+    },
+};
+use ::libc::{
+    c_int,
+    c_void,
+    epoll_event,
+    size_t,
+    sockaddr,
+    socklen_t,
+    ssize_t,
+};
+use ::nix::errno::Errno;
+use ::std::{
+    cell::{
+        Cell,
+        RefCell,
+        RefMut,
+    },
+    collections::{
+        BTreeSet,
+        HashMap,
+    },
+    net::SocketAddr,
+    slice,
+    sync::{
+        atomic::{
+            AtomicBool,
+            Ordering,
+        },
+        PoisonError,
+        RwLock,
+    },
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+//======================================================================================================================
+// Structures
+//======================================================================================================================
+
+/// Has some thread taken ownership of the LibOS?
+static OWNED: AtomicBool = AtomicBool::new(false);
+
+/// File descriptors of Demikernel sockets and epoll instances. These are shared by all threads, so that calls that
+/// other threads issue on them are told apart from calls on kernel file descriptors.
+static FDS: RwLock<BTreeSet<c_int>> = RwLock::new(BTreeSet::new());
+
+thread_local! {
+    /// Is the current thread running Demikernel code?
+    static BUSY: Cell<bool> = const { Cell::new(false) };
+
+    /// State of the interposition library, which is initialized on the thread that first creates a socket or an epoll
+    /// instance. It is left unset on other threads.
+    static PRELOAD: RefCell<Option<Preload>> = const { RefCell::new(None) };
+}
+
+/// Interposition State
+struct Preload {
+    /// Underlying LibOS.
+    libos: LibOS,
+    /// Demikernel sockets, by placeholder file descriptor.
+    sockets: HashMap<c_int, Socket>,
+    /// Epoll instances, by file descriptor.
+    epolls: HashMap<c_int, Epoll>,
+}
+
+/// Busy Guard
+///
+/// This marks the current thread as running Demikernel code while it lives, so that calls to interposed functions
+/// are forwarded to the C library in the meantime.
+struct Busy;
+
+//======================================================================================================================
+// Associated Functions
+//======================================================================================================================
+
+/// Associated Functions for the Interposition State
+impl Preload {
+    /// Instantiates the LibOS named by the `LIBOS` environment variable.
+    fn new() -> Result<Self, Fail> {
+        Ok(Self {
+            libos: LibOS::new(LibOSName::from_env()?)?,
+            sockets: HashMap::new(),
+            epolls: HashMap::new(),
+        })
+    }
+
+    /// Checks if a file descriptor refers to a Demikernel socket or to an epoll instance.
+    fn owns(&self, fd: c_int) -> bool {
+        self.sockets.contains_key(&fd) || self.epolls.contains_key(&fd)
+    }
+
+    /// Looks up the Demikernel socket that a file descriptor refers to.
+    fn get_socket(sockets: &mut HashMap<c_int, Socket>, fd: c_int) -> Result<&mut Socket, Fail> {
+        match sockets.get_mut(&fd) {
+            Some(socket) => Ok(socket),
+            None => Err(Fail::new(libc::ENOTSOCK, "not a demikernel socket")),
+        }
+    }
+
+    /// Creates a Demikernel socket. `SOCK_NONBLOCK` and `SOCK_CLOEXEC` flags apply to its placeholder file descriptor.
+    fn socket(&mut self, domain: c_int, socket_type: c_int, protocol: c_int) -> Result<c_int, Fail> {
+        let flags: c_int = socket_type & (libc::SOCK_NONBLOCK | libc::SOCK_CLOEXEC);
+        let socket_type: c_int = socket_type & !flags;
+        let qd: QDesc = self.libos.socket(domain, socket_type, protocol)?;
+        self.attach(qd, flags, socket_type == libc::SOCK_DGRAM)
+    }
+
+    /// Binds a Demikernel socket to a local address.
+    fn bind(&mut self, fd: c_int, local: SocketAddr) -> Result<(), Fail> {
+        let qd: QDesc = Self::get_socket(&mut self.sockets, fd)?.qd();
+        self.libos.bind(qd, local)
+    }
+
+    /// Marks a Demikernel socket as a passive one.
+    fn listen(&mut self, fd: c_int, backlog: c_int) -> Result<(), Fail> {
+        let socket: &mut Socket = Self::get_socket(&mut self.sockets, fd)?;
+        // Linux accepts non-positive backlogs, so do we.
+        self.libos.listen(socket.qd(), backlog.max(1) as usize)?;
+        socket.listen();
+        Ok(())
+    }
+
+    /// Accepts a connection on a Demikernel socket. `SOCK_NONBLOCK` and `SOCK_CLOEXEC` flags apply to the placeholder
+    /// file descriptor of the new socket.
+    fn accept(&mut self, fd: c_int, flags: c_int) -> Result<(c_int, SocketAddr), Fail> {
+        let block: bool = !is_nonblocking(fd);
+        let (qd, remote): (QDesc, SocketAddr) =
+            Self::get_socket(&mut self.sockets, fd)?.accept(&mut self.libos, block)?;
+        Ok((self.attach(qd, flags, true)?, remote))
+    }
+
+    /// Connects a Demikernel socket to a remote peer.
+    fn connect(&mut self, fd: c_int, remote: SocketAddr) -> Result<(), Fail> {
+        let block: bool = !is_nonblocking(fd);
+        Self::get_socket(&mut self.sockets, fd)?.connect(&mut self.libos, remote, block)
+    }
+
+    /// Reads data from a Demikernel socket.
+    fn read(&mut self, fd: c_int, buf: &mut [u8], block: bool) -> Result<usize, Fail> {
+        let block: bool = block && !is_nonblocking(fd);
+        Self::get_socket(&mut self.sockets, fd)?.read(&mut self.libos, buf, block)
+    }
+
+    /// Writes data to a Demikernel socket.
+    fn write(&mut self, fd: c_int, buf: &[u8], block: bool) -> Result<usize, Fail> {
+        let block: bool = block && !is_nonblocking(fd);
+        Self::get_socket(&mut self.sockets, fd)?.write(&mut self.libos, buf, block)
+    }
+
+    /// Shuts down part of the connection of a Demikernel socket.
+    fn shutdown(&mut self, fd: c_int, how: c_int) -> Result<(), Fail> {
+        let qd: QDesc = Self::get_socket(&mut self.sockets, fd)?.qd();
+        self.libos.shutdown(qd, how)
+    }
+
+    /// Closes a Demikernel socket or an epoll instance, along with its file descriptor.
+    fn close(&mut self, fd: c_int) -> Result<(), Fail> {
+        let result: Result<(), Fail> = match self.sockets.remove(&fd) {
+            Some(socket) => {
+                for epoll in self.epolls.values_mut() {
+                    epoll.remove(fd);
+                }
+                socket.close(&mut self.libos)
+            },
+            None => {
+                self.epolls.remove(&fd);
+                Ok(())
+            },
+        };
+        FDS.write().unwrap_or_else(PoisonError::into_inner).remove(&fd);
+        match unsafe { real::close(fd) } {
+            0 => result,
+            _ => Err(Fail::new(Errno::last() as i32, "failed to close file descriptor")),
+        }
+    }
+
+    /// Gets the local address of a Demikernel socket.
+    fn getsockname(&mut self, fd: c_int) -> Result<SocketAddr, Fail> {
+        let qd: QDesc = Self::get_socket(&mut self.sockets, fd)?.qd();
+        self.libos.getsockname(qd)
+    }
+
+    /// Gets the remote address of a Demikernel socket.
+    fn getpeername(&mut self, fd: c_int) -> Result<SocketAddr, Fail> {
+        let qd: QDesc = Self::get_socket(&mut self.sockets, fd)?.qd();
+        self.libos.getpeername(qd)
+    }
+
+    /// Sets an option on a Demikernel socket. Options that the LibOS does not support are ignored, as applications
+    /// commonly tune sockets with options that do not matter to Demikernel.
+    fn setsockopt(&mut self, fd: c_int, level: c_int, optname: c_int, optval: &[u8]) -> Result<(), Fail> {
+        let qd: QDesc = Self::get_socket(&mut self.sockets, fd)?.qd();
+        match self.libos.setsockopt(qd, level, optname, optval) {
+            Err(e) if e.errno == libc::ENOTSUP || e.errno == libc::ENOPROTOOPT => {
+                warn!("ignoring socket option (level={:?}, optname={:?})", level, optname);
+                Ok(())
+            },
+            result => result,
+        }
+    }
+
+    /// Gets the value of an option on a Demikernel socket. The `SO_ERROR` option is served from the socket itself.
+    fn getsockopt(&mut self, fd: c_int, level: c_int, optname: c_int, optval: &mut [u8]) -> Result<usize, Fail> {
+        let socket: &mut Socket = Self::get_socket(&mut self.sockets, fd)?;
+        if (level, optname) == (libc::SOL_SOCKET, libc::SO_ERROR) {
+            let error: [u8; 4] = socket.take_error().unwrap_or(0).to_ne_bytes();
+            let len: usize = optval.len().min(error.len());
+            optval[..len].copy_from_slice(&error[..len]);
+            return Ok(len);
+        }
+        self.libos.getsockopt(socket.qd(), level, optname, optval)
+    }
+
+    /// Creates an epoll instance.
+    fn epoll_create(&mut self, flags: c_int) -> Result<c_int, Fail> {
+        let epfd: c_int = unsafe { real::epoll_create1(flags) };
+        if epfd < 0 {
+            return Err(Fail::new(Errno::last() as i32, "failed to create epoll instance"));
+        }
+        self.epolls.insert(epfd, Epoll::default());
+        FDS.write().unwrap_or_else(PoisonError::into_inner).insert(epfd);
+        Ok(epfd)
+    }
+
+    /// Registers a Demikernel socket in the interest list of an epoll instance.
+    fn epoll_ctl(&mut self, epfd: c_int, op: c_int, fd: c_int, event: Option<epoll_event>) -> Result<(), Fail> {
+        match self.epolls.get_mut(&epfd) {
+            Some(epoll) => epoll.ctl(op, fd, event),
+            None => Err(Fail::new(libc::EINVAL, "not an epoll instance")),
+        }
+    }
+
+    /// Waits for events on the Demikernel sockets and other file descriptors of an epoll instance, or for a timeout to
+    /// expire. In between polls, this blocks on the LibOS.
+    fn epoll_wait(
+        &mut self,
+        epfd: c_int,
+        events: &mut [epoll_event],
+        timeout: Option<Duration>,
+    ) -> Result<usize, Fail> {
+        let start: Instant = Instant::now();
+        loop {
+            let nevents: usize = self.epoll_poll(epfd, events)?;
+            let remaining: Option<Duration> = timeout.map(|timeout| timeout.saturating_sub(start.elapsed()));
+            if nevents > 0 || remaining == Some(Duration::ZERO) {
+                return Ok(nevents);
+            }
+            self.libos.wait_for_events(remaining.unwrap_or(Duration::MAX));
+        }
+    }
+
+    /// Polls Demikernel sockets and other file descriptors of an epoll instance once. Events are level-triggered.
+    fn epoll_poll(&mut self, epfd: c_int, events: &mut [epoll_event]) -> Result<usize, Fail> {
+        let mut nevents: usize = 0;
+
+        // Poll Demikernel sockets.
+        let epoll: &mut Epoll = match self.epolls.get_mut(&epfd) {
+            Some(epoll) => epoll,
+            None => return Err(Fail::new(libc::EINVAL, "not an epoll instance")),
+        };
+        for (fd, interest) in epoll.interests() {
+            if nevents == events.len() {
+                break;
+            }
+            let socket: &mut Socket = match self.sockets.get_mut(&fd) {
+                Some(socket) => socket,
+                None => continue,
+            };
+            let mask: u32 = interest.events | (libc::EPOLLERR | libc::EPOLLHUP) as u32;
+            let ready: u32 = socket.poll_events(&mut self.libos) & mask;
+            if ready != 0 {
+                events[nevents] = epoll_event {
+                    events: ready,
+                    u64: interest.u64,
+                };
+                nevents += 1;
+                if interest.events & libc::EPOLLONESHOT as u32 != 0 {
+                    epoll.disarm(fd);
+                }
+            }
+        }
+
+        // Poll other file descriptors.
+        if nevents < events.len() {
+            let room: c_int = (events.len() - nevents) as c_int;
+            match unsafe { real::epoll_wait(epfd, events[nevents..].as_mut_ptr(), room, 0) } {
+                n if n >= 0 => nevents += n as usize,
+                _ => return Err(Fail::new(Errno::last() as i32, "failed to wait on epoll instance")),
+            }
+        }
+
+        Ok(nevents)
+    }
+
+    /// Attaches an I/O queue to a new placeholder file descriptor, which is created with the given `SOCK_NONBLOCK` and
+    /// `SOCK_CLOEXEC` flags.
+    fn attach(&mut self, qd: QDesc, flags: c_int, connected: bool) -> Result<c_int, Fail> {
+        let mut efd_flags: c_int = 0;
+        if flags & libc::SOCK_NONBLOCK != 0 {
+            efd_flags |= libc::EFD_NONBLOCK;
+        }
+        if flags & libc::SOCK_CLOEXEC != 0 {
+            efd_flags |= libc::EFD_CLOEXEC;
+        }
+        let fd: c_int = unsafe { libc::eventfd(0, efd_flags) };
+        if fd < 0 {
+            let e: Fail = Fail::new(Errno::last() as i32, "failed to create placeholder file descriptor");
+            let _ = self.libos.close(qd);
+            return Err(e);
+        }
+        self.sockets.insert(fd, Socket::new(qd, connected));
+        FDS.write().unwrap_or_else(PoisonError::into_inner).insert(fd);
+        Ok(fd)
+    }
+}
+
+/// Associated Functions for Busy Guards
+impl Busy {
+    /// Marks the current thread as running Demikernel code. If it is already, `None` is returned instead.
+    fn enter() -> Option<Self> {
+        match BUSY.try_with(|busy| busy.replace(true)) {
+            Ok(false) => Some(Busy),
+            _ => None,
+        }
+    }
+}
+
+//======================================================================================================================
+// Trait Implementations
+//======================================================================================================================
+
+/// Drop Trait Implementation for Busy Guards
+impl Drop for Busy {
+    fn drop(&mut self) {
+        let _ = BUSY.try_with(|busy| busy.set(false));
+    }
+}
+
+//======================================================================================================================
+// Interposed Functions
+//======================================================================================================================
+
+#[no_mangle]
+pub unsafe extern "C" fn socket(domain: c_int, socket_type: c_int, protocol: c_int) -> c_int {
+    let stream_or_dgram: bool = matches!(
+        socket_type & !(libc::SOCK_NONBLOCK | libc::SOCK_CLOEXEC),
+        libc::SOCK_STREAM | libc::SOCK_DGRAM
+    );
+    if (domain == libc::AF_INET || domain == libc::AF_INET6) && stream_or_dgram {
+        if let Some(result) = intercept(None, |preload| preload.socket(domain, socket_type, protocol)) {
+            return to_c(result);
+        }
+    }
+    real::socket(domain, socket_type, protocol)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn bind(fd: c_int, addr: *const sockaddr, addrlen: socklen_t) -> c_int {
+    let result: Option<Result<(), Fail>> = intercept(Some(fd), |preload| {
+        if addr.is_null() {
+            return Err(Fail::new(libc::EFAULT, "invalid socket address"));
+        }
+        preload.bind(fd, sockaddr_to_socketaddr(addr, addrlen)?)
+    });
+    match result {
+        Some(result) => to_c(result.map(|_| 0)),
+        None => real::bind(fd, addr, addrlen),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn listen(fd: c_int, backlog: c_int) -> c_int {
+    match intercept(Some(fd), |preload| preload.listen(fd, backlog)) {
+        Some(result) => to_c(result.map(|_| 0)),
+        None => real::listen(fd, backlog),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn accept(fd: c_int, addr: *mut sockaddr, addrlen: *mut socklen_t) -> c_int {
+    match intercept(Some(fd), |preload| preload.accept(fd, 0)) {
+        Some(result) => to_c(result.map(|(newfd, remote)| write_sockaddr(&remote, addr, addrlen, newfd))),
+        None => real::accept(fd, addr, addrlen),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn accept4(fd: c_int, addr: *mut sockaddr, addrlen: *mut socklen_t, flags: c_int) -> c_int {
+    match intercept(Some(fd), |preload| preload.accept(fd, flags)) {
+        Some(result) => to_c(result.map(|(newfd, remote)| write_sockaddr(&remote, addr, addrlen, newfd))),
+        None => real::accept4(fd, addr, addrlen, flags),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn connect(fd: c_int, addr: *const sockaddr, addrlen: socklen_t) -> c_int {
+    let result: Option<Result<(), Fail>> = intercept(Some(fd), |preload| {
+        if addr.is_null() {
+            return Err(Fail::new(libc::EFAULT, "invalid socket address"));
+        }
+        preload.connect(fd, sockaddr_to_socketaddr(addr, addrlen)?)
+    });
+    match result {
+        Some(result) => to_c(result.map(|_| 0)),
+        None => real::connect(fd, addr, addrlen),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn read(fd: c_int, buf: *mut c_void, count: size_t) -> ssize_t {
+    match intercept(Some(fd), |preload| preload.read(fd, as_mut_slice(buf, count), true)) {
+        Some(result) => to_c(result.map(|len| len as ssize_t)),
+        None => real::read(fd, buf, count),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn write(fd: c_int, buf: *const c_void, count: size_t) -> ssize_t {
+    match intercept(Some(fd), |preload| preload.write(fd, as_slice(buf, count), true)) {
+        Some(result) => to_c(result.map(|len| len as ssize_t)),
+        None => real::write(fd, buf, count),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn recv(fd: c_int, buf: *mut c_void, len: size_t, flags: c_int) -> ssize_t {
+    let result: Option<Result<usize, Fail>> = intercept(Some(fd), |preload| {
+        if flags & libc::MSG_PEEK != 0 {
+            return Err(Fail::new(libc::EOPNOTSUPP, "cannot peek at demikernel sockets"));
+        }
+        preload.read(fd, as_mut_slice(buf, len), flags & libc::MSG_DONTWAIT == 0)
+    });
+    match result {
+        Some(result) => to_c(result.map(|len| len as ssize_t)),
+        None => real::recv(fd, buf, len, flags),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn send(fd: c_int, buf: *const c_void, len: size_t, flags: c_int) -> ssize_t {
+    match intercept(Some(fd), |preload| {
+        preload.write(fd, as_slice(buf, len), flags & libc::MSG_DONTWAIT == 0)
+    }) {
+        Some(result) => to_c(result.map(|len| len as ssize_t)),
+        None => real::send(fd, buf, len, flags),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn shutdown(fd: c_int, how: c_int) -> c_int {
+    match intercept(Some(fd), |preload| preload.shutdown(fd, how)) {
+        Some(result) => to_c(result.map(|_| 0)),
+        None => real::shutdown(fd, how),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn close(fd: c_int) -> c_int {
+    match intercept(Some(fd), |preload| preload.close(fd)) {
+        Some(result) => to_c(result.map(|_| 0)),
+        None => real::close(fd),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn getsockname(fd: c_int, addr: *mut sockaddr, addrlen: *mut socklen_t) -> c_int {
+    match intercept(Some(fd), |preload| preload.getsockname(fd)) {
+        Some(result) => to_c(result.and_then(|local| {
+            check_sockaddr(addr, addrlen)?;
+            Ok(write_sockaddr(&local, addr, addrlen, 0))
+        })),
+        None => real::getsockname(fd, addr, addrlen),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn getpeername(fd: c_int, addr: *mut sockaddr, addrlen: *mut socklen_t) -> c_int {
+    match intercept(Some(fd), |preload| preload.getpeername(fd)) {
+        Some(result) => to_c(result.and_then(|remote| {
+            check_sockaddr(addr, addrlen)?;
+            Ok(write_sockaddr(&remote, addr, addrlen, 0))
+        })),
+        None => real::getpeername(fd, addr, addrlen),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn setsockopt(
+    fd: c_int,
+    level: c_int,
+    optname: c_int,
+    optval: *const c_void,
+    optlen: socklen_t,
+) -> c_int {
+    let result: Option<Result<(), Fail>> = intercept(Some(fd), |preload| {
+        if optval.is_null() {
+            return Err(Fail::new(libc::EFAULT, "invalid option value"));
+        }
+        preload.setsockopt(fd, level, optname, as_slice(optval, optlen as usize))
+    });
+    match result {
+        Some(result) => to_c(result.map(|_| 0)),
+        None => real::setsockopt(fd, level, optname, optval, optlen),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn getsockopt(
+    fd: c_int,
+    level: c_int,
+    optname: c_int,
+    optval: *mut c_void,
+    optlen: *mut socklen_t,
+) -> c_int {
+    let result: Option<Result<usize, Fail>> = intercept(Some(fd), |preload| {
+        if optval.is_null() || optlen.is_null() {
+            return Err(Fail::new(libc::EFAULT, "invalid option value"));
+        }
+        preload.getsockopt(fd, level, optname, as_mut_slice(optval, *optlen as usize))
+    });
+    match result {
+        Some(result) => to_c(result.map(|len| {
+            *optlen = len as socklen_t;
+            0
+        })),
+        None => real::getsockopt(fd, level, optname, optval, optlen),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn epoll_create(size: c_int) -> c_int {
+    if size <= 0 {
+        return to_c(Err(Fail::new(libc::EINVAL, "invalid size")));
+    }
+    match intercept(None, |preload| preload.epoll_create(0)) {
+        Some(result) => to_c(result),
+        None => real::epoll_create(size),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn epoll_create1(flags: c_int) -> c_int {
+    match intercept(None, |preload| preload.epoll_create(flags)) {
+        Some(result) => to_c(result),
+        None => real::epoll_create1(flags),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn epoll_ctl(epfd: c_int, op: c_int, fd: c_int, event: *mut epoll_event) -> c_int {
+    // Only Demikernel sockets are registered here. Other file descriptors are registered in the kernel.
+    let event_: Option<epoll_event> = if event.is_null() { None } else { Some(*event) };
+    match intercept(Some(fd), |preload| {
+        if preload.sockets.contains_key(&fd) {
+            preload.epoll_ctl(epfd, op, fd, event_)
+        } else if real::epoll_ctl(epfd, op, fd, event) == 0 {
+            Ok(())
+        } else {
+            Err(Fail::new(Errno::last() as i32, "failed to control epoll instance"))
+        }
+    }) {
+        Some(result) => to_c(result.map(|_| 0)),
+        None => real::epoll_ctl(epfd, op, fd, event),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn epoll_wait(epfd: c_int, events: *mut epoll_event, maxevents: c_int, timeout: c_int) -> c_int {
+    let timeout_: Option<Duration> = if timeout < 0 {
+        None
+    } else {
+        Some(Duration::from_millis(timeout as u64))
+    };
+    let result: Option<Result<usize, Fail>> = intercept(Some(epfd), |preload| {
+        if events.is_null() || maxevents <= 0 {
+            return Err(Fail::new(libc::EINVAL, "invalid event list"));
+        }
+        let events: &mut [epoll_event] = slice::from_raw_parts_mut(events, maxevents as usize);
+        preload.epoll_wait(epfd, events, timeout_)
+    });
+    match result {
+        Some(result) => to_c(result.map(|nevents| nevents as c_int)),
+        None => real::epoll_wait(epfd, events, maxevents, timeout),
+    }
+}
+
+//======================================================================================================================
+// Standalone Functions
+//======================================================================================================================
+
+/// Runs `f` on the interposition state if `fd` refers to a Demikernel socket or an epoll instance, or if no file
+/// descriptor is given, in which case the state is initialized first unless another thread owns the LibOS. If the
+/// current thread is already running Demikernel code, or `fd` refers to some other file, `None` is returned and the
+/// call should be forwarded to the C library instead. Calls on Demikernel file descriptors fail from other threads
+/// than the owner of the LibOS.
+fn intercept<T>(fd: Option<c_int>, f: impl FnOnce(&mut Preload) -> Result<T, Fail>) -> Option<Result<T, Fail>> {
+    let _busy: Busy = Busy::enter()?;
+    if let Some(fd) = fd {
+        if !FDS.read().unwrap_or_else(PoisonError::into_inner).contains(&fd) {
+            return None;
+        }
+    }
+    let result: Result<Option<Result<T, Fail>>, _> = PRELOAD.try_with(|preload| {
+        let mut preload: RefMut<Option<Preload>> = preload.borrow_mut();
+        match fd {
+            Some(fd) if !preload.as_ref().map_or(false, |preload| preload.owns(fd)) => {
+                return Some(Err(Fail::new(
+                    libc::EBADF,
+                    "demikernel socket is owned by another thread",
+                )));
+            },
+            Some(_) => (),
+            None if preload.is_none() => {
+                // Only one thread may own the LibOS, so other threads get kernel sockets and epoll instances.
+                if OWNED.swap(true, Ordering::AcqRel) {
+                    return None;
+                }
+                match Preload::new() {
+                    Ok(state) => *preload = Some(state),
+                    Err(e) => {
+                        warn!("failed to initialize demikernel ({:?})", e);
+                        OWNED.store(false, Ordering::Release);
+                        return None;
+                    },
+                }
+            },
+            None => (),
+        }
+        Some(f(preload.as_mut()?))
+    });
+    result.ok().flatten()
+}
+
+/// Checks if a placeholder file descriptor is non-blocking, which is set with `SOCK_NONBLOCK` or later on with `fcntl()`.
+fn is_nonblocking(fd: c_int) -> bool {
+    let flags: c_int = unsafe { libc::fcntl(fd, libc::F_GETFL) };
+    flags >= 0 && flags & libc::O_NONBLOCK != 0
+}
+
+/// Converts the result of an intercepted call into the return value of the corresponding function of the C library.
+/// On failure, `errno` is set and -1 is returned.
+fn to_c<T: From<i8>>(result: Result<T, Fail>) -> T {
+    match result {
+        Ok(value) => value,
+        Err(e) => {
+            unsafe { *libc::__errno_location() = e.errno };
+            T::from(-1)
+        },
+    }
+}
+
+/// Checks if the storage location of a socket address is valid.
+fn check_sockaddr(addr: *mut sockaddr, addrlen: *mut socklen_t) -> Result<(), Fail> {
+    if addr.is_null() || addrlen.is_null() {
+        return Err(Fail::new(libc::EFAULT, "invalid socket address"));
+    }
+    Ok(())
+}
+
+/// Writes a socket address to a storage location, if one is given, and returns `ret`.
+unsafe fn write_sockaddr(endpoint: &SocketAddr, addr: *mut sockaddr, addrlen: *mut socklen_t, ret: c_int) -> c_int {
+    if !addr.is_null() && !addrlen.is_null() {
+        socketaddr_to_sockaddr(endpoint, addr, addrlen);
+    }
+    ret
+}
+
+/// Borrows a buffer that was provided by the application.
+unsafe fn as_slice<'a>(buf: *const c_void, len: size_t) -> &'a [u8] {
+    if buf.is_null() || len == 0 {
+        return &[];
+    }
+    slice::from_raw_parts(buf as *const u8, len)
+}
+
+/// Mutably borrows a buffer that was provided by the application.
+unsafe fn as_mut_slice<'a>(buf: *mut c_void, len: size_t) -> &'a mut [u8] {
+    if buf.is_null() || len == 0 {
+        return &mut [];
+    }
+    slice::from_raw_parts_mut(buf as *mut u8, len)
+}
+
+//======================================================================================================================
+// Unit Tests
+//======================================================================================================================
+
+#[cfg(all(test, feature = "catnap-libos"))]
+mod tests {
+    use super::{
+        accept,
+        bind,
+        close,
+        connect,
+        epoll_create1,
+        epoll_ctl,
+        epoll_wait,
+        getsockname,
+        listen,
+        read,
+        socket,
+        socketaddr_to_sockaddr,
+        write,
+        Preload,
+        OWNED,
+        PRELOAD,
+    };
+    use crate::demikernel::{
+        config::Config,
+        libos::{
+            name::LibOSName,
+            LibOS,
+        },
+    };
+    use ::libc::{
+        c_int,
+        c_void,
+        epoll_event,
+        sockaddr,
+        sockaddr_storage,
+        socklen_t,
+    };
+    use ::std::{
+        collections::HashMap,
+        mem,
+        net::SocketAddr,
+        ptr,
+        sync::atomic::Ordering,
+    };
+
+    /// Tests if a connection is served through the interposed functions, as an unmodified application would do it.
+    #[test]
+    fn serve_connection() {
+        let config: Config = "catnap: {}\n".parse().unwrap();
+        assert!(!OWNED.swap(true, Ordering::AcqRel));
+        PRELOAD.with(|preload| {
+            *preload.borrow_mut() = Some(Preload {
+                libos: LibOS::with_config(LibOSName::Catnap, &config).unwrap(),
+                sockets: HashMap::new(),
+                epolls: HashMap::new(),
+            })
+        });
+        let local: SocketAddr = "127.0.0.1:0".parse().unwrap();
+        let mut storage: sockaddr_storage = unsafe { mem::zeroed() };
+        let mut addrlen: socklen_t = mem::size_of::<sockaddr_storage>() as socklen_t;
+        let addr: *mut sockaddr = &mut storage as *mut sockaddr_storage as *mut sockaddr;
+        unsafe { socketaddr_to_sockaddr(&local, addr, &mut addrlen) };
+
+        unsafe {
+            // Set up a listening socket on an ephemeral port, and wait for incoming connections with epoll.
+            let listener: c_int = socket(libc::AF_INET, libc::SOCK_STREAM | libc::SOCK_NONBLOCK, 0);
+            assert!(listener >= 0);
+            assert_eq!(bind(listener, addr, addrlen), 0);
+            assert_eq!(listen(listener, 16), 0);
+            addrlen = mem::size_of::<sockaddr_storage>() as socklen_t;
+            assert_eq!(getsockname(listener, addr, &mut addrlen), 0);
+            let epfd: c_int = epoll_create1(0);
+            assert!(epfd >= 0);
+            let mut event: epoll_event = epoll_event {
+                events: libc::EPOLLIN as u32,
+                u64: 1,
+            };
+            assert_eq!(epoll_ctl(epfd, libc::EPOLL_CTL_ADD, listener, &mut event), 0);
+
+            // Connect, and accept the connection once the listening socket is ready.
+            let client: c_int = socket(libc::AF_INET, libc::SOCK_STREAM, 0);
+            assert!(client >= 0);
+            assert_eq!(connect(client, addr, addrlen), 0);
+            let mut events: [epoll_event; 4] = [epoll_event { events: 0, u64: 0 }; 4];
+            assert_eq!(epoll_wait(epfd, events.as_mut_ptr(), 4, 1000), 1);
+            assert_eq!({ events[0].u64 }, 1);
+            let server: c_int = accept(listener, ptr::null_mut(), ptr::null_mut());
+            assert!(server >= 0);
+
+            // Send data, and read it once the accepted socket is ready.
+            let data: &[u8] = b"hello";
+            assert_eq!(
+                write(client, data.as_ptr() as *const c_void, data.len()),
+                data.len() as isize
+            );
+            event.u64 = 2;
+            assert_eq!(epoll_ctl(epfd, libc::EPOLL_CTL_ADD, server, &mut event), 0);
+            loop {
+                let nevents: c_int = epoll_wait(epfd, events.as_mut_ptr(), 4, 1000);
+                assert!(nevents > 0);
+                if events[..nevents as usize].iter().any(|event| event.u64 == 2) {
+                    break;
+                }
+            }
+            let mut buf: [u8; 16] = [0; 16];
+            let len: isize = read(server, buf.as_mut_ptr() as *mut c_void, buf.len());
+            assert_eq!(&buf[..len as usize], data);
+
+            for fd in [server, client, epfd, listener] {
+                assert_eq!(close(fd), 0);
+            }
+        }
+    }
+}
@@ -0,0 +1,82 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! Functions of the C library that are interposed. Each of them forwards to the next definition of the corresponding
+//! symbol, that is, the one of the C library, which is looked up on first use.
+
+//======================================================================================================================
+// Imports
+//======================================================================================================================
+
+use ::libc::{
+    c_char,
+    c_int,
+    c_void,
+    epoll_event,
+    size_t,
+    sockaddr,
+    socklen_t,
+    ssize_t,
+};
+use ::std::{
+    mem,
+    sync::atomic::{
+        AtomicUsize,
+        Ordering,
+    },
+};
+
+//======================================================================================================================
+// Macros
+//======================================================================================================================
+
+/// Declares a function that forwards to the next definition of a symbol.
+macro_rules! real {
+    ($name:ident($($arg:ident: $ty:ty),*) -> $ret:ty) => {
+        pub unsafe fn $name($($arg: $ty),*) -> $ret {
+            static ADDR: AtomicUsize = AtomicUsize::new(0);
+            let mut addr: usize = ADDR.load(Ordering::Relaxed);
+            if addr == 0 {
+                addr = lookup(concat!(stringify!($name), "\0"));
+                ADDR.store(addr, Ordering::Relaxed);
+            }
+            let f: unsafe extern "C" fn($($ty),*) -> $ret = mem::transmute(addr);
+            f($($arg),*)
+        }
+    };
+}
+
+real!(socket(domain: c_int, socket_type: c_int, protocol: c_int) -> c_int);
+real!(bind(fd: c_int, addr: *const sockaddr, addrlen: socklen_t) -> c_int);
+real!(listen(fd: c_int, backlog: c_int) -> c_int);
+real!(accept(fd: c_int, addr: *mut sockaddr, addrlen: *mut socklen_t) -> c_int);
+real!(accept4(fd: c_int, addr: *mut sockaddr, addrlen: *mut socklen_t, flags: c_int) -> c_int);
+real!(connect(fd: c_int, addr: *const sockaddr, addrlen: socklen_t) -> c_int);
+real!(read(fd: c_int, buf: *mut c_void, count: size_t) -> ssize_t);
+real!(write(fd: c_int, buf: *const c_void, count: size_t) -> ssize_t);
+real!(recv(fd: c_int, buf: *mut c_void, len: size_t, flags: c_int) -> ssize_t);
+real!(send(fd: c_int, buf: *const c_void, len: size_t, flags: c_int) -> ssize_t);
+real!(shutdown(fd: c_int, how: c_int) -> c_int);
+real!(close(fd: c_int) -> c_int);
+real!(getsockname(fd: c_int, addr: *mut sockaddr, addrlen: *mut socklen_t) -> c_int);
+real!(getpeername(fd: c_int, addr: *mut sockaddr, addrlen: *mut socklen_t) -> c_int);
+real!(setsockopt(fd: c_int, level: c_int, optname: c_int, optval: *const c_void, optlen: socklen_t) -> c_int);
+real!(getsockopt(fd: c_int, level: c_int, optname: c_int, optval: *mut c_void, optlen: *mut socklen_t) -> c_int);
+real!(epoll_create(size: c_int) -> c_int);
+real!(epoll_create1(flags: c_int) -> c_int);
+real!(epoll_ctl(epfd: c_int, op: c_int, fd: c_int, event: *mut epoll_event) -> c_int);
+real!(epoll_wait(epfd: c_int, events: *mut epoll_event, maxevents: c_int, timeout: c_int) -> c_int);
+
+//======================================================================================================================
+// Standalone Functions
+//======================================================================================================================
+
+/// Looks up the next definition of a symbol, whose name is null-terminated. The process is aborted if there is none,
+/// as the interposed function could not be served otherwise.
+fn lookup(name: &str) -> usize {
+    let addr: *mut c_void = unsafe { libc::dlsym(libc::RTLD_NEXT, name.as_ptr() as *const c_char) };
+    if addr.is_null() {
+        unsafe { libc::abort() };
+    }
+    addr as usize
+}
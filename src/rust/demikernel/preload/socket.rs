@@ -0,0 +1,357 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//======================================================================================================================
+// Imports
+//======================================================================================================================
+
+use crate::{
+    demikernel::libos::LibOS,
+    pal::functions::get_socketaddr_from_sock_addr_storage,
+    runtime::{
+        fail::Fail,
+        memory::Sga,
+        types::{
+            demi_accept_result_t,
+            demi_opcode_t,
+            demi_qresult_t,
+        },
+        QDesc,
+        QToken,
+    },
+};
+use ::std::{
+    collections::VecDeque,
+    net::{
+        Ipv4Addr,
+        SocketAddr,
+    },
+};
+
+//======================================================================================================================
+// Constants
+//======================================================================================================================
+
+/// Maximum number of bytes that are pushed by a single operation.
+const PUSH_SIZE_MAX: usize = u16::MAX as usize - 1;
+
+//======================================================================================================================
+// Structures
+//======================================================================================================================
+
+/// Socket States
+enum State {
+    /// The socket is neither connected nor listening.
+    Unconnected,
+    /// The socket is listening for incoming connections.
+    Listening,
+    /// The socket is connecting to a remote peer.
+    Connecting(QToken),
+    /// Data may be pushed to and popped from the socket.
+    Connected,
+}
+
+/// Socket
+///
+/// This emulates the synchronous semantics of a POSIX socket on top of an I/O queue. Operations that would block are
+/// waited for, unless the socket is non-blocking. In this case, they are left pending and checked again whenever the
+/// socket is used.
+pub struct Socket {
+    /// Underlying I/O queue descriptor.
+    qd: QDesc,
+    /// State of the socket.
+    state: State,
+    /// Pending accept operation.
+    accept: Option<QToken>,
+    /// Connections that were accepted, but not taken out yet.
+    accepted: VecDeque<(QDesc, SocketAddr)>,
+    /// Pending pop operation.
+    pop: Option<QToken>,
+    /// Data that was popped, but not read yet.
+    unread: Vec<u8>,
+    /// Was the end of the stream reached?
+    eof: bool,
+    /// Pending error, which is reported by the `SO_ERROR` socket option.
+    error: Option<i32>,
+}
+
+//======================================================================================================================
+// Associated Functions
+//======================================================================================================================
+
+/// Associated Functions for Sockets
+impl Socket {
+    /// Creates a socket on top of an I/O queue. Datagram sockets and accepted connections are connected right away.
+    pub fn new(qd: QDesc, connected: bool) -> Self {
+        Self {
+            qd,
+            state: if connected {
+                State::Connected
+            } else {
+                State::Unconnected
+            },
+            accept: None,
+            accepted: VecDeque::new(),
+            pop: None,
+            unread: Vec::new(),
+            eof: false,
+            error: None,
+        }
+    }
+
+    /// Returns the I/O queue descriptor of the target socket.
+    pub fn qd(&self) -> QDesc {
+        self.qd
+    }
+
+    /// Marks the target socket as a listening one.
+    pub fn listen(&mut self) {
+        self.state = State::Listening;
+    }
+
+    /// Takes out the pending error of the target socket, if any.
+    pub fn take_error(&mut self) -> Option<i32> {
+        self.error.take()
+    }
+
+    /// Accepts a connection on the target socket.
+    pub fn accept(&mut self, libos: &mut LibOS, block: bool) -> Result<(QDesc, SocketAddr), Fail> {
+        if !matches!(self.state, State::Listening) {
+            return Err(Fail::new(libc::EINVAL, "socket is not listening"));
+        }
+        loop {
+            if let Some(accepted) = self.accepted.pop_front() {
+                return Ok(accepted);
+            }
+            self.poll_accept(libos, block)?;
+            if !block && self.accepted.is_empty() {
+                return Err(Fail::new(libc::EAGAIN, "no connection to accept"));
+            }
+        }
+    }
+
+    /// Connects the target socket to a remote peer. If the socket is non-blocking, this fails with `EINPROGRESS`, and
+    /// the connection is established in the background.
+    pub fn connect(&mut self, libos: &mut LibOS, remote: SocketAddr, block: bool) -> Result<(), Fail> {
+        match self.state {
+            State::Unconnected => (),
+            State::Connecting(_) => return Err(Fail::new(libc::EALREADY, "socket is already connecting")),
+            _ => return Err(Fail::new(libc::EISCONN, "socket is already connected")),
+        }
+        let qt: QToken = libos.connect(self.qd, remote)?;
+        self.state = State::Connecting(qt);
+        self.poll_connect(libos, block)?;
+        match self.state {
+            State::Connecting(_) => Err(Fail::new(libc::EINPROGRESS, "connection is in progress")),
+            _ => Ok(()),
+        }
+    }
+
+    /// Reads data from the target socket. Zero is returned once the end of the stream is reached.
+    pub fn read(&mut self, libos: &mut LibOS, buf: &mut [u8], block: bool) -> Result<usize, Fail> {
+        self.poll_connect(libos, block)?;
+        loop {
+            if !self.unread.is_empty() {
+                let len: usize = buf.len().min(self.unread.len());
+                buf[..len].copy_from_slice(&self.unread[..len]);
+                self.unread.drain(..len);
+                return Ok(len);
+            }
+            if self.eof || buf.is_empty() {
+                return Ok(0);
+            }
+            if let Some(errno) = self.error.take() {
+                return Err(Fail::new(errno, "socket has failed"));
+            }
+            match self.state {
+                State::Connected => self.poll_pop(libos, block)?,
+                State::Connecting(_) => return Err(Fail::new(libc::EAGAIN, "connection is in progress")),
+                _ => return Err(Fail::new(libc::ENOTCONN, "socket is not connected")),
+            }
+            if !block && self.unread.is_empty() && !self.eof {
+                return Err(Fail::new(libc::EAGAIN, "no data to read"));
+            }
+        }
+    }
+
+    /// Writes data to the target socket. This waits for each push operation to complete, but if one of them fails,
+    /// the number of bytes that were pushed before is returned.
+    pub fn write(&mut self, libos: &mut LibOS, buf: &[u8], block: bool) -> Result<usize, Fail> {
+        self.poll_connect(libos, block)?;
+        match self.state {
+            State::Connected => (),
+            State::Connecting(_) => return Err(Fail::new(libc::EAGAIN, "connection is in progress")),
+            _ => return Err(Fail::new(libc::ENOTCONN, "socket is not connected")),
+        }
+
+        let mut len: usize = 0;
+        for chunk in buf.chunks(PUSH_SIZE_MAX) {
+            if let Err(e) = push(libos, self.qd, chunk) {
+                return if len > 0 { Ok(len) } else { Err(e) };
+            }
+            len += chunk.len();
+        }
+        Ok(len)
+    }
+
+    /// Polls the target socket for readiness, as `epoll` would. Pending operations are issued as needed, and their
+    /// failures are reported with `EPOLLERR`.
+    pub fn poll_events(&mut self, libos: &mut LibOS) -> u32 {
+        match self.state {
+            State::Listening if self.accepted.is_empty() => {
+                if let Err(e) = self.poll_accept(libos, false) {
+                    self.error = Some(e.errno);
+                }
+            },
+            State::Connecting(_) => {
+                if let Err(e) = self.poll_connect(libos, false) {
+                    self.error = Some(e.errno);
+                }
+            },
+            State::Connected if self.unread.is_empty() && !self.eof && self.error.is_none() => {
+                if let Err(e) = self.poll_pop(libos, false) {
+                    self.error = Some(e.errno);
+                }
+            },
+            _ => (),
+        }
+
+        let mut events: i32 = 0;
+        match self.state {
+            State::Listening if !self.accepted.is_empty() => events |= libc::EPOLLIN,
+            State::Connected => {
+                if !self.unread.is_empty() || self.eof {
+                    events |= libc::EPOLLIN;
+                }
+                if self.eof {
+                    events |= libc::EPOLLRDHUP;
+                }
+                events |= libc::EPOLLOUT;
+            },
+            _ => (),
+        }
+        if self.error.is_some() {
+            events |= libc::EPOLLERR;
+        }
+        events as u32
+    }
+
+    /// Closes the target socket. Pending operations are cancelled, and connections that were accepted but not taken out
+    /// are closed as well.
+    pub fn close(self, libos: &mut LibOS) -> Result<(), Fail> {
+        let connect: Option<QToken> = match self.state {
+            State::Connecting(qt) => Some(qt),
+            _ => None,
+        };
+        for qt in [self.accept, self.pop, connect].into_iter().flatten() {
+            if let Ok(qr) = libos.cancel(qt) {
+                match qr.qr_opcode {
+                    demi_opcode_t::DEMI_OPC_POP => drop(libos.take_sga(qr)),
+                    demi_opcode_t::DEMI_OPC_ACCEPT => {
+                        let ares: demi_accept_result_t = unsafe { qr.qr_value.ares };
+                        let _ = libos.close(QDesc::from(ares.qd));
+                    },
+                    _ => (),
+                }
+            }
+        }
+        for (qd, _) in self.accepted {
+            let _ = libos.close(qd);
+        }
+        libos.close(self.qd)
+    }
+
+    /// Checks if a pending connect operation on the target socket has completed.
+    fn poll_connect(&mut self, libos: &mut LibOS, block: bool) -> Result<(), Fail> {
+        let qt: QToken = match self.state {
+            State::Connecting(qt) => qt,
+            _ => return Ok(()),
+        };
+        match complete(libos, qt, block) {
+            Ok(None) => Ok(()),
+            Ok(Some(_)) => {
+                self.state = State::Connected;
+                Ok(())
+            },
+            Err(e) => {
+                self.state = State::Unconnected;
+                Err(e)
+            },
+        }
+    }
+
+    /// Issues an accept operation on the target socket, if none is pending, and checks if it has completed.
+    fn poll_accept(&mut self, libos: &mut LibOS, block: bool) -> Result<(), Fail> {
+        let qt: QToken = match self.accept {
+            Some(qt) => qt,
+            None => *self.accept.insert(libos.accept(self.qd)?),
+        };
+        let result: Result<Option<demi_qresult_t>, Fail> = complete(libos, qt, block);
+        if !matches!(result, Ok(None)) {
+            self.accept = None;
+        }
+        if let Some(qr) = result? {
+            let ares: demi_accept_result_t = unsafe { qr.qr_value.ares };
+            let remote: SocketAddr = get_socketaddr_from_sock_addr_storage(&ares.addr)
+                .unwrap_or_else(|| SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)));
+            self.accepted.push_back((QDesc::from(ares.qd), remote));
+        }
+        Ok(())
+    }
+
+    /// Issues a pop operation on the target socket, if none is pending, and checks if it has completed.
+    fn poll_pop(&mut self, libos: &mut LibOS, block: bool) -> Result<(), Fail> {
+        let qt: QToken = match self.pop {
+            Some(qt) => qt,
+            None => *self.pop.insert(libos.pop(self.qd)?),
+        };
+        let result: Result<Option<demi_qresult_t>, Fail> = complete(libos, qt, block);
+        if !matches!(result, Ok(None)) {
+            self.pop = None;
+        }
+        if let Some(qr) = result? {
+            let sga: Sga = libos.take_sga(qr)?;
+            let len: usize = self.unread.len();
+            for segment in sga.segments() {
+                self.unread.extend_from_slice(segment);
+            }
+            // An empty scatter-gather array stands for the end of the stream.
+            self.eof = self.unread.len() == len;
+        }
+        Ok(())
+    }
+}
+
+//======================================================================================================================
+// Standalone Functions
+//======================================================================================================================
+
+/// Checks if an operation has completed, waiting for it if `block` is set. Failed operations are turned into errors.
+fn complete(libos: &mut LibOS, qt: QToken, block: bool) -> Result<Option<demi_qresult_t>, Fail> {
+    let qr: demi_qresult_t = if block {
+        libos.wait(qt, None)?
+    } else {
+        match libos.try_wait(qt)? {
+            Some(qr) => qr,
+            None => return Ok(None),
+        }
+    };
+    match qr.qr_opcode {
+        demi_opcode_t::DEMI_OPC_FAILED => Err(Fail::new(qr.qr_ret, "operation failed")),
+        _ => Ok(Some(qr)),
+    }
+}
+
+/// Pushes a copy of some data to an I/O queue, and waits for the operation to complete.
+fn push(libos: &mut LibOS, qd: QDesc, data: &[u8]) -> Result<(), Fail> {
+    let mut sga: Sga = libos.alloc_sga(data.len())?;
+    let mut rest: &[u8] = data;
+    for segment in sga.segments_mut() {
+        let (head, tail): (&[u8], &[u8]) = rest.split_at(segment.len());
+        segment.copy_from_slice(head);
+        rest = tail;
+    }
+    let qt: QToken = libos.push(qd, &sga)?;
+    complete(libos, qt, true)?;
+    Ok(())
+}
@@ -15,7 +15,11 @@ use crate::{
                 EtherType2,
                 Ethernet2Header,
             },
-            tcp::operations::ConnectFuture,
+            tcp::operations::{
+                CloseFuture,
+                ConnectFuture,
+                TcpOperation,
+            },
             udp::UdpOperation,
             Peer,
         },
@@ -34,11 +38,16 @@ use crate::{
                 TcpConfig,
                 UdpConfig,
             },
-            types::MacAddress,
+            types::{
+                MacAddress,
+                SocketOption,
+                SocketOptionName,
+            },
             NetworkRuntime,
         },
         queue::IoQueueTable,
         timer::TimerRc,
+        types::demi_qstats_t,
         QDesc,
         QToken,
         QType,
@@ -63,7 +72,11 @@ use ::std::{
         SocketAddrV4,
     },
     rc::Rc,
-    time::Instant,
+    thread,
+    time::{
+        Duration,
+        Instant,
+    },
 };
 
 #[cfg(feature = "profiler")]
@@ -362,6 +375,250 @@ impl InetStack {
         Ok(())
     }
 
+    ///
+    /// **Brief**
+    ///
+    /// Asynchronously closes a connection referred to by `qd`. The operation
+    /// completes once all data that was pushed to a TCP socket, including the
+    /// end-of-stream marker, is acknowledged by the remote peer. It fails if
+    /// the connection is reset beforehand, or if the linger timeout of the
+    /// socket expires. UDP sockets, as well as TCP sockets that are not
+    /// connected, are closed immediately. The queue
+    /// descriptor is released when the result of the operation is taken out.
+    ///
+    /// **Return Value**
+    ///
+    /// Upon successful completion, a queue token is returned. This token can be
+    /// used to wait for the close operation to complete. Upon failure, `Fail`
+    /// is returned instead.
+    ///
+    pub fn async_close(&mut self, qd: QDesc) -> Result<QToken, Fail> {
+        #[cfg(feature = "profiler")]
+        timer!("inetstack::async_close");
+        trace!("async_close(): qd={:?}", qd);
+
+        let future: FutureOperation = match self.file_table.get(qd) {
+            Some(qtype) => match QType::try_from(qtype) {
+                Ok(QType::TcpSocket) => {
+                    let fut: CloseFuture = self.ipv4.tcp.async_close(qd)?;
+                    Ok(FutureOperation::from(fut))
+                },
+                Ok(QType::UdpSocket) => {
+                    let udp_op = UdpOperation::Close(qd, self.ipv4.udp.do_close(qd));
+                    Ok(FutureOperation::Udp(udp_op))
+                },
+                _ => Err(Fail::new(EINVAL, "invalid queue type")),
+            },
+            _ => Err(Fail::new(EBADF, "bad queue descriptor")),
+        }?;
+
+        let handle: SchedulerHandle = match self.scheduler.insert(future) {
+            Some(handle) => handle,
+            None => return Err(Fail::new(libc::EAGAIN, "cannot schedule co-routine")),
+        };
+        let qt: QToken = handle.into_raw().into();
+        trace!("async_close() qt={:?}", qt);
+        Ok(qt)
+    }
+
+    ///
+    /// **Brief**
+    ///
+    /// Shuts down the sending half (`SHUT_WR`), the receiving half (`SHUT_RD`),
+    /// or both halves (`SHUT_RDWR`) of a connection referred to by `qd`,
+    /// without releasing it. Shutting down the sending half sends a FIN to the
+    /// remote peer, while data may still be received from it.
+    ///
+    /// **Return Value**
+    ///
+    /// Upon successful completion, `Ok(())` is returned. Upon failure, `Fail` is
+    /// returned instead.
+    ///
+    pub fn shutdown(&mut self, qd: QDesc, how: libc::c_int) -> Result<(), Fail> {
+        #[cfg(feature = "profiler")]
+        timer!("inetstack::shutdown");
+        trace!("shutdown(): qd={:?}, how={:?}", qd, how);
+
+        match self.file_table.get(qd) {
+            Some(qtype) => match QType::try_from(qtype) {
+                Ok(QType::TcpSocket) => self.ipv4.tcp.shutdown(qd, how),
+                Ok(QType::UdpSocket) => Err(Fail::new(libc::ENOTCONN, "udp sockets are not connected")),
+                _ => Err(Fail::new(EINVAL, "invalid queue type")),
+            },
+            _ => Err(Fail::new(EBADF, "bad queue descriptor")),
+        }
+    }
+
+    ///
+    /// **Brief**
+    ///
+    /// Sets the option `optname` at the protocol level `level` of the socket
+    /// referred to by `qd` to the value pointed to by `optval`. Options are not
+    /// forwarded to any kernel socket. Instead, they override the TCP or UDP
+    /// configuration of the target socket. Currently, the following options
+    /// are supported:
+    ///
+    /// - On TCP sockets, SO_REUSEADDR, SO_RCVBUF, SO_SNDBUF, SO_LINGER and
+    ///   SO_KEEPALIVE at the SOL_SOCKET level, and TCP_NODELAY and TCP_MAXSEG
+    ///   at the IPPROTO_TCP level. SO_SNDBUF bounds the amount of data in
+    ///   flight. Options take effect on the next connection.
+    /// - On UDP sockets, SO_RCVBUF only. Datagrams that arrive while the
+    ///   receive buffer is full are dropped.
+    ///
+    /// Other options fail with `ENOPROTOOPT`.
+    ///
+    /// **Return Value**
+    ///
+    /// Upon successful completion, `Ok(())` is returned. Upon failure, `Fail` is
+    /// returned instead.
+    ///
+    pub fn setsockopt(&mut self, qd: QDesc, level: c_int, optname: c_int, optval: &[u8]) -> Result<(), Fail> {
+        #[cfg(feature = "profiler")]
+        timer!("inetstack::setsockopt");
+        trace!("setsockopt(): qd={:?} level={:?} optname={:?}", qd, level, optname);
+        let name: SocketOptionName = SocketOptionName::try_from((level, optname))?;
+        let option: SocketOption = SocketOption::decode(name, optval)?;
+        match self.file_table.get(qd) {
+            Some(qtype) => match QType::try_from(qtype) {
+                Ok(QType::TcpSocket) => self.ipv4.tcp.setsockopt(qd, option),
+                Ok(QType::UdpSocket) => self.ipv4.udp.setsockopt(qd, option),
+                _ => Err(Fail::new(EINVAL, "invalid queue type")),
+            },
+            _ => Err(Fail::new(EBADF, "bad queue descriptor")),
+        }
+    }
+
+    ///
+    /// **Brief**
+    ///
+    /// Gets the value of the option `optname` at the protocol level `level` of
+    /// the socket referred to by `qd` and writes it to `optval`. See
+    /// [setsockopt](Self::setsockopt) for the list of supported options.
+    ///
+    /// **Return Value**
+    ///
+    /// Upon successful completion, the number of bytes written to `optval` is
+    /// returned. Upon failure, `Fail` is returned instead.
+    ///
+    pub fn getsockopt(&mut self, qd: QDesc, level: c_int, optname: c_int, optval: &mut [u8]) -> Result<usize, Fail> {
+        #[cfg(feature = "profiler")]
+        timer!("inetstack::getsockopt");
+        trace!("getsockopt(): qd={:?} level={:?} optname={:?}", qd, level, optname);
+        let name: SocketOptionName = SocketOptionName::try_from((level, optname))?;
+        let option: SocketOption = match self.file_table.get(qd) {
+            Some(qtype) => match QType::try_from(qtype) {
+                Ok(QType::TcpSocket) => self.ipv4.tcp.getsockopt(qd, name)?,
+                Ok(QType::UdpSocket) => self.ipv4.udp.getsockopt(qd, name)?,
+                _ => return Err(Fail::new(EINVAL, "invalid queue type")),
+            },
+            _ => return Err(Fail::new(EBADF, "bad queue descriptor")),
+        };
+        option.encode(optval)
+    }
+
+    ///
+    /// **Brief**
+    ///
+    /// Gets the local endpoint to which the socket referred to by `qd` is
+    /// bound. If the socket was bound to port zero, the ephemeral port that was
+    /// allocated to it is reported.
+    ///
+    /// **Return Value**
+    ///
+    /// Upon successful completion, the local endpoint is returned. Upon
+    /// failure, `Fail` is returned instead.
+    ///
+    pub fn getsockname(&mut self, qd: QDesc) -> Result<SocketAddrV4, Fail> {
+        #[cfg(feature = "profiler")]
+        timer!("inetstack::getsockname");
+        trace!("getsockname(): qd={:?}", qd);
+        match self.file_table.get(qd) {
+            Some(qtype) => match QType::try_from(qtype) {
+                Ok(QType::TcpSocket) => self.ipv4.tcp.getsockname(qd),
+                Ok(QType::UdpSocket) => self.ipv4.udp.getsockname(qd),
+                _ => Err(Fail::new(EINVAL, "invalid queue type")),
+            },
+            _ => Err(Fail::new(EBADF, "bad queue descriptor")),
+        }
+    }
+
+    ///
+    /// **Brief**
+    ///
+    /// Gets the remote endpoint to which the socket referred to by `qd` is
+    /// connected.
+    ///
+    /// **Return Value**
+    ///
+    /// Upon successful completion, the remote endpoint is returned. Upon
+    /// failure, `Fail` is returned instead.
+    ///
+    pub fn getpeername(&mut self, qd: QDesc) -> Result<SocketAddrV4, Fail> {
+        #[cfg(feature = "profiler")]
+        timer!("inetstack::getpeername");
+        trace!("getpeername(): qd={:?}", qd);
+        match self.file_table.get(qd) {
+            Some(qtype) => match QType::try_from(qtype) {
+                Ok(QType::TcpSocket) => self.ipv4.tcp.getpeername(qd),
+                Ok(QType::UdpSocket) => self.ipv4.udp.getpeername(qd),
+                _ => Err(Fail::new(EINVAL, "invalid queue type")),
+            },
+            _ => Err(Fail::new(EBADF, "bad queue descriptor")),
+        }
+    }
+
+    ///
+    /// **Brief**
+    ///
+    /// Gets the statistics of the socket referred to by `qd` that are held by
+    /// the network stack. For established TCP connections, these are the
+    /// congestion window, the retransmission timeout, the number of
+    /// retransmissions, and the receive and send windows. Nothing is held for
+    /// UDP sockets.
+    ///
+    /// **Return Value**
+    ///
+    /// Upon successful completion, the statistics are returned. Upon failure,
+    /// `Fail` is returned instead.
+    ///
+    pub fn get_stats(&mut self, qd: QDesc) -> Result<demi_qstats_t, Fail> {
+        trace!("get_stats(): qd={:?}", qd);
+        match self.file_table.get(qd) {
+            Some(qtype) => match QType::try_from(qtype) {
+                Ok(QType::TcpSocket) => self.ipv4.tcp.get_stats(qd),
+                Ok(QType::UdpSocket) => Ok(demi_qstats_t::default()),
+                _ => Err(Fail::new(EINVAL, "invalid queue type")),
+            },
+            _ => Err(Fail::new(EBADF, "bad queue descriptor")),
+        }
+    }
+
+    ///
+    /// **Brief**
+    ///
+    /// Checks which operations on the socket referred to by `qd` would
+    /// complete right away. TCP sockets are checked against the ready queue of
+    /// passive sockets, and against the receive queue and the send window of
+    /// established connections. UDP sockets are checked against their receive
+    /// queue.
+    ///
+    /// **Return Value**
+    ///
+    /// Upon successful completion, a mask of readiness events is returned.
+    /// Upon failure, `Fail` is returned instead.
+    ///
+    pub fn readiness(&mut self, qd: QDesc) -> Result<u32, Fail> {
+        trace!("readiness(): qd={:?}", qd);
+        match self.file_table.get(qd) {
+            Some(qtype) => match QType::try_from(qtype) {
+                Ok(QType::TcpSocket) => self.ipv4.tcp.readiness(qd),
+                Ok(QType::UdpSocket) => self.ipv4.udp.readiness(qd),
+                _ => Err(Fail::new(EINVAL, "invalid queue type")),
+            },
+            _ => Err(Fail::new(EBADF, "bad queue descriptor")),
+        }
+    }
+
     /// Pushes a buffer to a TCP socket.
     /// TODO: Rename this function to push() once we have a common representation across all libOSes.
     pub fn do_push(&mut self, qd: QDesc, buf: DemiBuffer) -> Result<FutureOperation, Fail> {
@@ -404,6 +661,15 @@ impl InetStack {
         match self.file_table.get(qd) {
             Some(qtype) => match QType::try_from(qtype) {
                 Ok(QType::UdpSocket) => {
+                    // Datagrams are transmitted from a single buffer, thus buffer chains are flattened.
+                    // TODO: Chain packet bodies instead, once the transmit path of the runtimes support it.
+                    let buf: DemiBuffer = if buf.num_segments() > 1 {
+                        let mut data: Vec<u8> = Vec::with_capacity(buf.total_len());
+                        buf.segments().for_each(|segment| data.extend_from_slice(segment));
+                        DemiBuffer::from_slice(&data)?
+                    } else {
+                        buf
+                    };
                     let udp_op = UdpOperation::Pushto(qd, self.ipv4.udp.do_pushto(qd, buf, to));
                     Ok(FutureOperation::Udp(udp_op))
                 },
@@ -438,18 +704,19 @@ impl InetStack {
     }
 
     /// Create a pop request to write data from IO connection represented by `qd` into a buffer
-    /// allocated by the application.
-    pub fn pop(&mut self, qd: QDesc) -> Result<QToken, Fail> {
+    /// allocated by the application. At most `max_size` bytes are popped if set, and TCP pops do not complete until at
+    /// least `min_size` bytes are available or the connection is closed.
+    pub fn pop(&mut self, qd: QDesc, min_size: usize, max_size: Option<usize>) -> Result<QToken, Fail> {
         #[cfg(feature = "profiler")]
         timer!("inetstack::pop");
 
-        trace!("pop(): qd={:?}", qd);
+        trace!("pop(): qd={:?}, min_size={:?}, max_size={:?}", qd, min_size, max_size);
 
         let future = match self.file_table.get(qd) {
             Some(qtype) => match QType::try_from(qtype) {
-                Ok(QType::TcpSocket) => Ok(FutureOperation::from(self.ipv4.tcp.pop(qd))),
+                Ok(QType::TcpSocket) => Ok(FutureOperation::from(self.ipv4.tcp.pop(qd, min_size, max_size))),
                 Ok(QType::UdpSocket) => {
-                    let udp_op = UdpOperation::Pop(FutureResult::new(self.ipv4.udp.do_pop(qd), None));
+                    let udp_op = UdpOperation::Pop(FutureResult::new(self.ipv4.udp.do_pop(qd, max_size), None));
                     Ok(FutureOperation::Udp(udp_op))
                 },
                 _ => Err(Fail::new(EINVAL, "invalid queue type")),
@@ -491,6 +758,22 @@ impl InetStack {
         }
     }
 
+    /// Cancels an operation. If the operation has already completed, its result is returned as is.
+    pub fn cancel2(&mut self, qt: QToken) -> Result<(QDesc, OperationResult), Fail> {
+        trace!("cancel2(): qt={:?}", qt);
+
+        // Retrieve associated schedule handle.
+        let handle: SchedulerHandle = match self.scheduler.from_raw_handle(qt.into()) {
+            Some(handle) => handle,
+            None => return Err(Fail::new(libc::EINVAL, "invalid queue token")),
+        };
+
+        // Poll first, so as to give the operation a chance to complete.
+        self.poll_bg_work();
+
+        Ok(self.cancel_operation(handle))
+    }
+
     /// Waits for any operation to complete.
     #[deprecated]
     pub fn wait_any2(&mut self, qts: &[QToken]) -> Result<(usize, QDesc, OperationResult), Fail> {
@@ -531,9 +814,30 @@ impl InetStack {
     pub fn take_operation(&mut self, handle: SchedulerHandle) -> (QDesc, OperationResult) {
         let boxed_future: Box<dyn Any> = self.scheduler.take(handle).as_any();
         let boxed_concrete_type: FutureOperation = *boxed_future.downcast::<FutureOperation>().expect("Wrong type!");
+        self.take_result(boxed_concrete_type)
+    }
 
+    /// Cancels the operation associated with the target [SchedulerHandle] and takes out its result. If the operation
+    /// has already completed, its result is taken as is. Otherwise, it completes with `ECANCELED`.
+    pub fn cancel_operation(&mut self, handle: SchedulerHandle) -> (QDesc, OperationResult) {
+        let boxed_future: Box<dyn Any> = self.scheduler.take(handle).as_any();
+        let mut boxed_concrete_type: FutureOperation =
+            *boxed_future.downcast::<FutureOperation>().expect("Wrong type!");
         match boxed_concrete_type {
+            FutureOperation::Tcp(ref mut f) => f.cancel(),
+            FutureOperation::Udp(ref mut f) => f.cancel(),
+            FutureOperation::Background(..) => {
+                panic!("`cancel_operation` attempted on background task!")
+            },
+        }
+        self.take_result(boxed_concrete_type)
+    }
+
+    /// Takes out the [OperationResult] associated with the target [FutureOperation].
+    fn take_result(&mut self, operation: FutureOperation) -> (QDesc, OperationResult) {
+        match operation {
             FutureOperation::Tcp(f) => {
+                let closing: bool = matches!(f, TcpOperation::Close(..));
                 let (qd, new_qd, qr): (QDesc, Option<QDesc>, OperationResult) = f.expect_result();
 
                 // Handle accept failures.
@@ -548,9 +852,24 @@ impl InetStack {
                     }
                 }
 
+                // Release queue descriptor of closed socket, whether or not the connection was shut down gracefully.
+                if closing {
+                    self.file_table.free(qd);
+                }
+
+                (qd, qr)
+            },
+            FutureOperation::Udp(f) => {
+                let closing: bool = matches!(f, UdpOperation::Close(..));
+                let (qd, qr): (QDesc, OperationResult) = f.get_result();
+
+                // Release queue descriptor of closed socket.
+                if closing {
+                    self.file_table.free(qd);
+                }
+
                 (qd, qr)
             },
-            FutureOperation::Udp(f) => f.get_result(),
             FutureOperation::Background(..) => {
                 panic!("`take_operation` attempted on background task!")
             },
@@ -578,6 +897,22 @@ impl InetStack {
         }
     }
 
+    ///
+    /// **Brief**
+    ///
+    /// Blocks the calling thread until the next timer of the network stack
+    /// expires or `timeout` expires, whichever happens first. Incoming packets
+    /// are not notified, so they are only received once this returns.
+    ///
+    pub fn wait_for_events(&mut self, timeout: Duration) {
+        trace!("wait_for_events(): timeout={:?}", timeout);
+        let timeout: Duration = match self.clock.next_expiry() {
+            Some(expiry) => timeout.min(expiry.saturating_duration_since(Instant::now())),
+            None => timeout,
+        };
+        thread::sleep(timeout);
+    }
+
     /// Scheduler will poll all futures that are ready to make progress.
     /// Then ask the runtime to receive new data which we will forward to the engine to parse and
     /// route to the correct protocol.
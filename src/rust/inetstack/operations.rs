@@ -8,11 +8,12 @@
 use crate::runtime::{
     fail::Fail,
     memory::DemiBuffer,
+    network::types::UnixSocketAddr,
     QDesc,
 };
 use ::std::{
     fmt,
-    net::SocketAddrV4,
+    net::SocketAddr,
 };
 
 //==============================================================================
@@ -21,10 +22,15 @@ use ::std::{
 
 pub enum OperationResult {
     Connect,
-    Accept(QDesc),
+    Close,
+    Accept((QDesc, SocketAddr)),
+    /// Connection accepted from a Unix domain socket.
+    AcceptUnix((QDesc, UnixSocketAddr)),
     Push,
     // TODO: Drop wrapping Option.
-    Pop(Option<SocketAddrV4>, DemiBuffer),
+    Pop(Option<SocketAddr>, DemiBuffer),
+    /// Data popped from a Unix domain socket.
+    PopUnix(UnixSocketAddr, DemiBuffer),
     Failed(Fail),
 }
 
@@ -36,9 +42,10 @@ impl fmt::Debug for OperationResult {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             OperationResult::Connect => write!(f, "Connect"),
-            OperationResult::Accept(..) => write!(f, "Accept"),
+            OperationResult::Close => write!(f, "Close"),
+            OperationResult::Accept(..) | OperationResult::AcceptUnix(..) => write!(f, "Accept"),
             OperationResult::Push => write!(f, "Push"),
-            OperationResult::Pop(..) => write!(f, "Pop"),
+            OperationResult::Pop(..) | OperationResult::PopUnix(..) => write!(f, "Pop"),
             OperationResult::Failed(ref e) => write!(f, "Failed({:?})", e),
         }
     }
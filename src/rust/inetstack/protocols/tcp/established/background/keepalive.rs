@@ -0,0 +1,58 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+use super::ControlBlock;
+use crate::runtime::{
+    fail::Fail,
+    network::consts::{
+        KEEPALIVE_IDLE,
+        KEEPALIVE_INTERVAL,
+        KEEPALIVE_PROBES,
+    },
+};
+use ::futures::future;
+use ::std::{
+    rc::Rc,
+    time::Instant,
+};
+
+pub async fn keepalive(cb: Rc<ControlBlock>) -> Result<!, Fail> {
+    // Keep-alive probes are only sent if the user asked for them.
+    if !cb.get_keepalive() {
+        return future::pending().await;
+    }
+
+    // Time since which we have not heard from our peer, and number of probes sent since then.
+    let mut idle_since: Instant = cb.get_last_receive();
+    let mut probes: u32 = 0;
+    loop {
+        let deadline: Instant = idle_since + KEEPALIVE_IDLE + KEEPALIVE_INTERVAL * probes;
+        cb.clock.wait_until(cb.clock.clone(), deadline).await;
+
+        // Our peer sent something in the meantime, so start over.
+        let last_receive: Instant = cb.get_last_receive();
+        if last_receive > idle_since {
+            idle_since = last_receive;
+            probes = 0;
+            continue;
+        }
+
+        // Outstanding data is already being retransmitted, so there is no need to probe our peer.
+        if !cb.is_idle() {
+            idle_since = cb.clock.now();
+            probes = 0;
+            continue;
+        }
+
+        // Our peer did not answer any probe, so give up on the connection.
+        if probes == KEEPALIVE_PROBES {
+            let cause: Fail = Fail::new(libc::ETIMEDOUT, "keep-alive probes were not answered");
+            cb.abort(cause.clone());
+            return Err(cause);
+        }
+
+        trace!("Sending keep-alive probe");
+        cb.send_keepalive_probe();
+        probes += 1;
+    }
+}
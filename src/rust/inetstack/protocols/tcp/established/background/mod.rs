@@ -2,11 +2,13 @@
 // Licensed under the MIT license.
 
 mod acknowledger;
+mod keepalive;
 mod retransmitter;
 mod sender;
 
 use self::{
     acknowledger::acknowledger,
+    keepalive::keepalive,
     retransmitter::retransmitter,
     sender::sender,
 };
@@ -34,10 +36,14 @@ pub fn background(cb: Rc<ControlBlock>, fd: QDesc, _dead_socket_tx: mpsc::Unboun
         let sender = sender(cb.clone()).fuse();
         futures::pin_mut!(sender);
 
+        let keepalive = keepalive(cb.clone()).fuse();
+        futures::pin_mut!(keepalive);
+
         let r = futures::select_biased! {
             r = acknowledger => r,
             r = retransmitter => r,
             r = sender => r,
+            r = keepalive => r,
         };
         error!("Connection (fd {:?}) terminated: {:?}", fd, r);
 
@@ -101,9 +101,18 @@ pub async fn sender(cb: Rc<ControlBlock>) -> Result<!, Fail> {
         let next_buf_size: usize = cb.unsent_top_size().expect("no buffer in unsent queue");
 
         let sent_data: u32 = (send_next - send_unacked).into();
+        let send_buffer_size: usize = cb.get_send_buffer_size();
+
+        // Nagle's algorithm: unless it is disabled, small segments are held back until all data in flight is
+        // acknowledged. The end-of-send marker is never held back.
+        let nagle_holds_back: bool =
+            !cb.get_nodelay() && sent_data > 0 && next_buf_size > 0 && next_buf_size < cb.get_mss();
+
         if win_sz <= (sent_data + next_buf_size as u32)
             || effective_cwnd <= sent_data
             || (effective_cwnd - sent_data) <= cb.get_mss() as u32
+            || sent_data as usize >= send_buffer_size
+            || nagle_holds_back
         {
             futures::select_biased! {
                 _ = send_unacked_changed => continue 'top,
@@ -116,7 +125,7 @@ pub async fn sender(cb: Rc<ControlBlock>) -> Result<!, Fail> {
 
         // Past this point we have data to send and it's valid to send it!
 
-        // TODO: Nagle's algorithm - We need to coalese small buffers together to send MSS sized packets.
+        // TODO: Nagle's algorithm - We should also coalese small buffers together to send MSS sized packets.
         // TODO: Silly window syndrome - See RFC 1122's discussion of the SWS avoidance algorithm.
 
         // ToDo: Link-level concerns don't belong here, we should call an IP-level send routine below.
@@ -125,7 +134,10 @@ pub async fn sender(cb: Rc<ControlBlock>) -> Result<!, Fail> {
         // Form an outgoing packet.
         let max_size: usize = cmp::min(
             cmp::min((win_sz - sent_data) as usize, cb.get_mss()),
-            (effective_cwnd - sent_data) as usize,
+            cmp::min(
+                (effective_cwnd - sent_data) as usize,
+                send_buffer_size - sent_data as usize,
+            ),
         );
         let segment_data: DemiBuffer = cb
             .pop_unsent_segment(max_size)
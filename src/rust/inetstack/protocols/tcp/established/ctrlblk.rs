@@ -38,6 +38,14 @@ use crate::{
             NetworkRuntime,
         },
         timer::TimerRc,
+        types::{
+            demi_qstats_t,
+            DEMI_POLLHUP,
+            DEMI_POLLIN,
+            DEMI_POLLOUT,
+            DEMI_QSTATS_TCP,
+            DEMI_SGARRAY_MAXLEN,
+        },
         watched::{
             WatchFuture,
             WatchedValue,
@@ -52,6 +60,7 @@ use ::std::{
     },
     collections::VecDeque,
     convert::TryInto,
+    mem,
     net::SocketAddrV4,
     rc::Rc,
     task::{
@@ -126,12 +135,74 @@ impl Receiver {
         }
     }
 
-    pub fn pop(&self) -> Option<DemiBuffer> {
-        let buf: DemiBuffer = self.recv_queue.borrow_mut().pop_front()?;
+    // Pops at least `min_size` bytes and at most `max_size` bytes (if any) from the receive queue, or less than that if
+    // the end of the stream comes first.  If `min_size` is zero, a single buffer is popped.  Nothing is popped until
+    // enough data is available.
+    pub fn pop(&self, min_size: usize, max_size: Option<usize>) -> Result<Option<DemiBuffer>, Fail> {
+        let mut recv_queue = self.recv_queue.borrow_mut();
+
+        // Check if enough data is available, or if the end of the stream (a zero-length buffer) is reached.
+        let mut available: usize = 0;
+        let mut eof: bool = false;
+        for buf in recv_queue.iter() {
+            if buf.is_empty() {
+                eof = true;
+                break;
+            }
+            available += buf.len();
+            if available >= min_size {
+                break;
+            }
+        }
+        if recv_queue.is_empty() || (available < min_size && !eof) {
+            return Ok(None);
+        }
+
+        // Take out buffers until enough data is popped. The end of the stream is left in the receive queue, unless it
+        // comes first.
+        let mut data: Option<DemiBuffer> = None;
+        let mut len: usize = 0;
+        while data.is_none() || len < min_size {
+            let front: &mut DemiBuffer = match recv_queue.front_mut() {
+                Some(front) if !front.is_empty() || data.is_none() => front,
+                _ => break,
+            };
+            let buf: DemiBuffer = match max_size {
+                // Leave the bytes beyond the maximum size in the receive queue.
+                Some(max_size) if len + front.len() > max_size => {
+                    let rest: DemiBuffer = front.split_off(max_size - len)?;
+                    mem::replace(front, rest)
+                },
+                _ => recv_queue.pop_front().expect("receive queue should not be empty"),
+            };
+            len += buf.len();
+            match data {
+                Some(ref mut data) => data.append(buf)?,
+                None => data = Some(buf),
+            }
+        }
         self.reader_next
-            .set(self.reader_next.get() + SeqNumber::from(buf.len() as u32));
+            .set(self.reader_next.get() + SeqNumber::from(len as u32));
 
-        Some(buf)
+        // Gather the data in a single buffer if it is spread across too many of them for a scatter-gather array.
+        let mut data: DemiBuffer = data.expect("some data should have been popped");
+        if data.num_segments() > DEMI_SGARRAY_MAXLEN {
+            let bytes: Vec<u8> = data.segments().flatten().copied().collect();
+            data = DemiBuffer::from_slice(&bytes)?;
+        }
+
+        Ok(Some(data))
+    }
+
+    // Checks if the receive queue holds neither data nor the end of the stream.
+    pub fn is_empty(&self) -> bool {
+        self.recv_queue.borrow().is_empty()
+    }
+
+    // Drops everything in the receive queue, as if it was popped, so that the receive window stays open.
+    pub fn discard(&self) {
+        self.recv_queue.borrow_mut().clear();
+        self.reader_next.set(self.receive_next.get());
     }
 
     pub fn push(&self, buf: DemiBuffer) {
@@ -197,6 +268,15 @@ pub struct ControlBlock {
     // Whether the user has called close.
     pub user_is_done_sending: Cell<bool>,
 
+    // Whether the user has shut down the receiving half of the connection.
+    user_is_done_receiving: Cell<bool>,
+
+    // Time at which the last segment was received from our peer.  This is used to tell if the connection is idle.
+    last_receive: Cell<Instant>,
+
+    // Error that aborted the connection, if any.  This is reported to pending and future pops and pushes.
+    error: RefCell<Option<Fail>>,
+
     // Congestion control trait implementation we're currently using.
     // ToDo: Consider switching this to a static implementation to avoid V-table call overhead.
     cc: Box<dyn congestion_control::CongestionControl>,
@@ -233,6 +313,7 @@ impl ControlBlock {
         congestion_control_options: Option<congestion_control::Options>,
     ) -> Self {
         let sender = Sender::new(sender_seq_no, sender_window_size, sender_window_scale, sender_mss);
+        let now: Instant = clock.now();
         Self {
             local,
             remote,
@@ -253,6 +334,9 @@ impl ControlBlock {
             out_of_order_fin: Cell::new(Option::None),
             receiver: Receiver::new(receiver_seq_no, receiver_seq_no),
             user_is_done_sending: Cell::new(false),
+            user_is_done_receiving: Cell::new(false),
+            last_receive: Cell::new(now),
+            error: RefCell::new(None),
             cc: cc_constructor(sender_mss, sender_seq_no, congestion_control_options),
             retransmit_deadline: WatchedValue::new(None),
             rto_calculator: RefCell::new(RtoCalculator::new()),
@@ -273,6 +357,13 @@ impl ControlBlock {
     }
 
     pub fn send(&self, buf: DemiBuffer) -> Result<(), Fail> {
+        // Once the user has called close (or shut down the sending half of the connection), no more data may be sent.
+        if self.user_is_done_sending.get() {
+            return Err(Fail::new(libc::EPIPE, "connection is shut down for sending"));
+        }
+        if let Some(e) = self.error.borrow().as_ref() {
+            return Err(e.clone());
+        }
         self.sender.send(buf, self)
     }
 
@@ -364,10 +455,95 @@ impl ControlBlock {
         self.rto_calculator.borrow().rto()
     }
 
+    /// Fills the TCP fields of the statistics of the connection.
+    pub fn fill_stats(&self, stats: &mut demi_qstats_t) {
+        stats.qs_flags |= DEMI_QSTATS_TCP;
+        stats.qs_tcp_cwnd = self.cc.get_cwnd() as u64;
+        stats.qs_tcp_rto_us = self.rto().as_micros() as u64;
+        stats.qs_tcp_retransmissions = self.sender.get_retransmissions();
+        stats.qs_tcp_recv_window = self.get_receive_window_size() as u64;
+        stats.qs_tcp_send_window = self.sender.get_send_window().0 as u64;
+    }
+
+    /// Checks if data (or the end of the stream) may be popped from and pushed to the connection, and if our peer has
+    /// hung up, that is, if it has sent a FIN or reset the connection.
+    pub fn readiness(&self) -> u32 {
+        let mut events: u32 = 0;
+        if self.user_is_done_receiving.get() || !self.receiver.is_empty() || self.error.borrow().is_some() {
+            events |= DEMI_POLLIN;
+        }
+        if !self.user_is_done_sending.get() && self.sender.is_writable() {
+            events |= DEMI_POLLOUT;
+        }
+        match self.state.get() {
+            State::CloseWait | State::Closing | State::LastAck | State::TimeWait | State::Closed => {
+                events |= DEMI_POLLHUP
+            },
+            State::Established | State::FinWait1 | State::FinWait2 => (),
+        }
+        events
+    }
+
     pub fn rto_back_off(&self) {
         self.rto_calculator.borrow_mut().back_off()
     }
 
+    pub fn get_send_buffer_size(&self) -> usize {
+        self.tcp_config.get_send_buffer_size()
+    }
+
+    pub fn get_nodelay(&self) -> bool {
+        self.tcp_config.get_nodelay()
+    }
+
+    pub fn get_keepalive(&self) -> bool {
+        self.tcp_config.get_keepalive()
+    }
+
+    pub fn get_last_receive(&self) -> Instant {
+        self.last_receive.get()
+    }
+
+    /// Checks if the connection is idle, that is, if it is in a data transfer state and all of the data that we have
+    /// sent was acknowledged by our peer.
+    pub fn is_idle(&self) -> bool {
+        match self.state.get() {
+            State::Established | State::CloseWait => {
+                let (send_unacknowledged, _): (SeqNumber, _) = self.sender.get_send_unacked();
+                let (send_next, _): (SeqNumber, _) = self.sender.get_send_next();
+                self.sender.top_size_unsent().is_none() && send_unacknowledged == send_next
+            },
+            _ => false,
+        }
+    }
+
+    /// Sends a keep-alive probe, that is, an ACK with an already acknowledged sequence number, so that our peer
+    /// acknowledges it back.
+    pub fn send_keepalive_probe(&self) {
+        let mut header: TcpHeader = self.tcp_header();
+        let (send_unacknowledged, _): (SeqNumber, _) = self.sender.get_send_unacked();
+        header.seq_num = send_unacknowledged - SeqNumber::from(1);
+        if let Some(remote_link_addr) = self.arp().try_query(*self.remote.ip()) {
+            self.emit(header, None, remote_link_addr);
+        }
+    }
+
+    /// Aborts the connection, that is, resets it and reports `cause` to pending and future pops and pushes.
+    pub fn abort(&self, cause: Fail) {
+        let mut header: TcpHeader = self.tcp_header();
+        let (send_next, _): (SeqNumber, _) = self.sender.get_send_next();
+        header.seq_num = send_next;
+        header.rst = true;
+        if let Some(remote_link_addr) = self.arp().try_query(*self.remote.ip()) {
+            self.emit(header, None, remote_link_addr);
+        }
+        self.state.set(State::Closed);
+        *self.error.borrow_mut() = Some(cause);
+        if let Some(w) = self.waker.borrow_mut().take() {
+            w.wake()
+        }
+    }
+
     pub fn unsent_top_size(&self) -> Option<usize> {
         self.sender.top_size_unsent()
     }
@@ -395,6 +571,7 @@ impl ControlBlock {
         // ToDo: We're probably getting "now" here in order to get a timestamp as close as possible to when we received
         // the packet.  However, this is wasteful if we don't take a path below that actually uses it.  Review this.
         let now: Instant = self.clock.now();
+        self.last_receive.set(now);
 
         // Check to see if the segment is acceptable sequence-wise (i.e. contains some data that fits within the receive
         // window, or is a non-data segment with a sequence number that falls within the window).  Unacceptable segments
@@ -791,6 +968,52 @@ impl ControlBlock {
         Ok(())
     }
 
+    /// Shuts down the receiving half of the connection.  Data that was received but not popped yet is dropped, as is
+    /// data that is received afterwards, and pending and future pops complete at the end of the stream.
+    pub fn shutdown_receive(&self) {
+        self.user_is_done_receiving.set(true);
+        self.receiver.discard();
+        if let Some(w) = self.waker.borrow_mut().take() {
+            w.wake()
+        }
+    }
+
+    /// Polls for the completion of the user's close request, that is, for all of our data (including the FIN) to be
+    /// acknowledged by our peer.  This fails if the connection is reset beforehand, or if `deadline` expires.
+    pub fn poll_close(&self, ctx: &mut Context, deadline: Option<Instant>) -> Poll<Result<(), Fail>> {
+        match self.state.get() {
+            // Our FIN is ACK'd.
+            State::FinWait2 | State::TimeWait => return Poll::Ready(Ok(())),
+            // We may have been closed either after our FIN was ACK'd (from LAST-ACK, or by a late reset) or by a reset
+            // before that.  In the latter case, some of our data is still outstanding.
+            State::Closed => {
+                let (send_unacknowledged, _): (SeqNumber, _) = self.sender.get_send_unacked();
+                let (send_next, _): (SeqNumber, _) = self.sender.get_send_next();
+                if self.sender.top_size_unsent().is_none() && send_unacknowledged == send_next {
+                    return Poll::Ready(Ok(()));
+                }
+                return match self.error.borrow().as_ref() {
+                    Some(e) => Poll::Ready(Err(e.clone())),
+                    None => Poll::Ready(Err(Fail::new(libc::ECONNRESET, "connection reset by peer"))),
+                };
+            },
+            _ => (),
+        }
+
+        if let Some(deadline) = deadline {
+            if self.clock.now() >= deadline {
+                return Poll::Ready(Err(Fail::new(
+                    libc::ETIMEDOUT,
+                    "timed out waiting for close to complete",
+                )));
+            }
+        }
+
+        // ToDo: Register a waker that is notified on state changes, instead of polling.
+        ctx.waker().wake_by_ref();
+        Poll::Pending
+    }
+
     /// Fetch a TCP header filling out various values based on our current state.
     /// ToDo: Fix the "filling out various values based on our current state" part to actually do that correctly.
     pub fn tcp_header(&self) -> TcpHeader {
@@ -901,7 +1124,12 @@ impl ControlBlock {
         hdr_window_size
     }
 
-    pub fn poll_recv(&self, ctx: &mut Context) -> Poll<Result<DemiBuffer, Fail>> {
+    pub fn poll_recv(
+        &self,
+        ctx: &mut Context,
+        min_size: usize,
+        max_size: Option<usize>,
+    ) -> Poll<Result<DemiBuffer, Fail>> {
         // ToDo: Need to add a way to indicate that the other side closed (i.e. that we've received a FIN).
         // Should we do this via a zero-sized buffer?  Same as with the unsent and unacked queues on the send side?
         //
@@ -909,17 +1137,23 @@ impl ControlBlock {
         //  if self.receiver.reader_next.get() == self.receiver.receive_next.get() {
         // But that will think data is available to be read once we've received a FIN, because FINs consume sequence
         // number space.  Now we call is_empty() on the receive queue instead.
-        if self.receiver.recv_queue.borrow().is_empty() {
-            *self.waker.borrow_mut() = Some(ctx.waker().clone());
-            return Poll::Pending;
+        // Once the user has shut down the receiving half of the connection, received data is dropped as it arrives.
+        if self.user_is_done_receiving.get() {
+            return Poll::Ready(Ok(DemiBuffer::new(0)));
         }
 
-        let segment: DemiBuffer = self
-            .receiver
-            .pop()
-            .expect("poll_recv failed to pop data from receive queue");
-
-        Poll::Ready(Ok(segment))
+        match self.receiver.pop(min_size, max_size) {
+            Ok(Some(segment)) => Poll::Ready(Ok(segment)),
+            Ok(None) => {
+                // Report why the connection was aborted once all of the data received beforehand is popped.
+                if let Some(e) = self.error.borrow().as_ref() {
+                    return Poll::Ready(Err(e.clone()));
+                }
+                *self.waker.borrow_mut() = Some(ctx.waker().clone());
+                Poll::Pending
+            },
+            Err(e) => Poll::Ready(Err(e)),
+        }
     }
 
     // This routine remembers that we have received an out-of-order FIN.
@@ -1068,6 +1302,11 @@ impl ControlBlock {
             }
         }
 
+        // Once the user has shut down the receiving half of the connection, nobody is going to pop this data.
+        if self.user_is_done_receiving.get() {
+            self.receiver.discard();
+        }
+
         // ToDo: Review recent change to update control block copy of recv_next upon each push to the receiver.
         // When receiving a retransmitted segment that fills a "hole" in the receive space, thus allowing a number
         // (potentially large number) of out-of-order segments to be added, we'll be modifying the TCB copy of
@@ -21,6 +21,7 @@ use crate::{
     runtime::{
         fail::Fail,
         memory::DemiBuffer,
+        types::demi_qstats_t,
         QDesc,
     },
     scheduler::SchedulerHandle,
@@ -69,14 +70,23 @@ impl EstablishedSocket {
         self.cb.send(buf)
     }
 
-    pub fn poll_recv(&self, ctx: &mut Context) -> Poll<Result<DemiBuffer, Fail>> {
-        self.cb.poll_recv(ctx)
+    pub fn poll_recv(
+        &self,
+        ctx: &mut Context,
+        min_size: usize,
+        max_size: Option<usize>,
+    ) -> Poll<Result<DemiBuffer, Fail>> {
+        self.cb.poll_recv(ctx, min_size, max_size)
     }
 
     pub fn close(&self) -> Result<(), Fail> {
         self.cb.close()
     }
 
+    pub fn shutdown_receive(&self) {
+        self.cb.shutdown_receive()
+    }
+
     pub fn remote_mss(&self) -> usize {
         self.cb.remote_mss()
     }
@@ -85,6 +95,14 @@ impl EstablishedSocket {
         self.cb.rto()
     }
 
+    pub fn fill_stats(&self, stats: &mut demi_qstats_t) {
+        self.cb.fill_stats(stats)
+    }
+
+    pub fn readiness(&self) -> u32 {
+        self.cb.readiness()
+    }
+
     pub fn endpoints(&self) -> (SocketAddrV4, SocketAddrV4) {
         (self.cb.get_local(), self.cb.get_remote())
     }
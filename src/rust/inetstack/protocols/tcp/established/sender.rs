@@ -91,6 +91,9 @@ pub struct Sender {
     // Maximum Segment Size currently in use for this connection.
     // ToDo: Revisit this once we support path MTU discovery.
     mss: usize,
+
+    // Number of segments retransmitted so far.
+    retransmissions: Cell<u64>,
 }
 
 impl fmt::Debug for Sender {
@@ -121,6 +124,7 @@ impl Sender {
 
             window_scale,
             mss,
+            retransmissions: Cell::new(0),
         }
     }
 
@@ -132,6 +136,15 @@ impl Sender {
         self.send_window.watch()
     }
 
+    pub fn get_retransmissions(&self) -> u64 {
+        self.retransmissions.get()
+    }
+
+    /// Checks if more data may be sent, that is, if our peer has an open window and the unsent queue has room left.
+    pub fn is_writable(&self) -> bool {
+        self.send_window.get() > 0 && self.unsent_queue.borrow().len() < UNSENT_QUEUE_CUTOFF
+    }
+
     pub fn get_send_unacked(&self) -> (SeqNumber, WatchFuture<SeqNumber>) {
         self.send_unacked.watch()
     }
@@ -210,7 +223,17 @@ impl Sender {
 
             let win_sz: u32 = self.send_window.get();
 
-            if win_sz > 0 && win_sz >= in_flight_after_send && effective_cwnd >= in_flight_after_send {
+            // Small segments are held back while some data is in flight, unless Nagle's algorithm is disabled. The
+            // end-of-send marker is never held back.
+            let nagle_allows_send: bool =
+                cb.get_nodelay() || sent_data == 0 || buf_len == 0 || buf_len as usize >= self.mss;
+
+            if win_sz > 0
+                && win_sz >= in_flight_after_send
+                && effective_cwnd >= in_flight_after_send
+                && in_flight_after_send as usize <= cb.get_send_buffer_size()
+                && nagle_allows_send
+            {
                 if let Some(remote_link_addr) = cb.arp().try_query(cb.get_remote().ip().clone()) {
                     // This hook is primarily intended to record the last time we sent data, so we can later tell if
                     // the connection has been idle.
@@ -291,6 +314,7 @@ impl Sender {
                     header.fin = true;
                 }
                 cb.emit(header, Some(data), first_hop_link_addr);
+                self.retransmissions.set(self.retransmissions.get() + 1);
             }
         } else {
             // We shouldn't enter the retransmit routine with an empty unacknowledged queue.  So maybe we should assert
@@ -1,9 +1,12 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT license.
 
-use super::peer::{
-    Inner,
-    TcpPeer,
+use super::{
+    established::ControlBlock,
+    peer::{
+        Inner,
+        TcpPeer,
+    },
 };
 use crate::{
     inetstack::operations::OperationResult,
@@ -18,17 +21,23 @@ use ::std::{
     cell::RefCell,
     fmt,
     future::Future,
+    net::{
+        SocketAddr,
+        SocketAddrV4,
+    },
     pin::Pin,
     rc::Rc,
     task::{
         Context,
         Poll,
     },
+    time::Instant,
 };
 
 pub enum TcpOperation {
     Accept(FutureResult<AcceptFuture>),
     Connect(FutureResult<ConnectFuture>),
+    Close(FutureResult<CloseFuture>),
     Pop(FutureResult<PopFuture>),
     Push(FutureResult<PushFuture>),
 }
@@ -45,6 +54,12 @@ impl From<ConnectFuture> for TcpOperation {
     }
 }
 
+impl From<CloseFuture> for TcpOperation {
+    fn from(f: CloseFuture) -> Self {
+        TcpOperation::Close(FutureResult::new(f, None))
+    }
+}
+
 impl From<PushFuture> for TcpOperation {
     fn from(f: PushFuture) -> Self {
         TcpOperation::Push(FutureResult::new(f, None))
@@ -64,6 +79,7 @@ impl Future for TcpOperation {
         match self.get_mut() {
             TcpOperation::Accept(ref mut f) => Future::poll(Pin::new(f), ctx),
             TcpOperation::Connect(ref mut f) => Future::poll(Pin::new(f), ctx),
+            TcpOperation::Close(ref mut f) => Future::poll(Pin::new(f), ctx),
             TcpOperation::Push(ref mut f) => Future::poll(Pin::new(f), ctx),
             TcpOperation::Pop(ref mut f) => Future::poll(Pin::new(f), ctx),
         }
@@ -83,11 +99,25 @@ impl TcpOperation {
                 done: Some(Err(e)),
             }) => (future.fd, None, OperationResult::Failed(e)),
 
+            // Close operation.
+            TcpOperation::Close(FutureResult {
+                future,
+                done: Some(Ok(())),
+            }) => (future.fd, None, OperationResult::Close),
+            TcpOperation::Close(FutureResult {
+                future,
+                done: Some(Err(e)),
+            }) => (future.fd, None, OperationResult::Failed(e)),
+
             // Accept operation.
             TcpOperation::Accept(FutureResult {
                 future,
-                done: Some(Ok(new_qd)),
-            }) => (future.qd, Some(future.new_qd), OperationResult::Accept(new_qd)),
+                done: Some(Ok((new_qd, remote))),
+            }) => (
+                future.qd,
+                Some(future.new_qd),
+                OperationResult::Accept((new_qd, SocketAddr::V4(remote))),
+            ),
             TcpOperation::Accept(FutureResult {
                 future,
                 done: Some(Err(e)),
@@ -116,6 +146,22 @@ impl TcpOperation {
             _ => panic!("Future not ready"),
         }
     }
+
+    /// Completes the target operation with `ECANCELED`, unless it has already completed.
+    pub fn cancel(&mut self) {
+        match self {
+            TcpOperation::Accept(ref mut f) => f.cancel(),
+            TcpOperation::Connect(ref mut f) => {
+                if f.done.is_none() {
+                    f.future.inner.borrow_mut().cancel_connect(f.future.fd);
+                }
+                f.cancel()
+            },
+            TcpOperation::Close(ref mut f) => f.cancel(),
+            TcpOperation::Push(ref mut f) => f.cancel(),
+            TcpOperation::Pop(ref mut f) => f.cancel(),
+        }
+    }
 }
 
 pub struct ConnectFuture {
@@ -138,6 +184,45 @@ impl Future for ConnectFuture {
     }
 }
 
+/// Close Operation Descriptor
+pub struct CloseFuture {
+    /// Queue descriptor of the closed socket.
+    pub fd: QDesc,
+    /// Control block of the connection that is shutting down, if any.
+    cb: Option<Rc<ControlBlock>>,
+    /// Time at which the operation times out, if any.
+    deadline: Option<Instant>,
+}
+
+/// Associated Functions for Close Operation Descriptors
+impl CloseFuture {
+    /// Creates a descriptor for a close operation. Sockets without a connection are closed right away.
+    pub fn new(fd: QDesc, cb: Option<Rc<ControlBlock>>, deadline: Option<Instant>) -> Self {
+        Self { fd, cb, deadline }
+    }
+}
+
+/// Debug Trait Implementation for Close Operation Descriptors
+impl fmt::Debug for CloseFuture {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "CloseFuture({:?})", self.fd)
+    }
+}
+
+/// Future Trait Implementation for Close Operation Descriptors
+impl Future for CloseFuture {
+    type Output = Result<(), Fail>;
+
+    /// Polls the underlying close operation.
+    fn poll(self: Pin<&mut Self>, context: &mut Context) -> Poll<Self::Output> {
+        let self_: &mut CloseFuture = self.get_mut();
+        match self_.cb {
+            Some(ref cb) => cb.poll_close(context, self_.deadline),
+            None => Poll::Ready(Ok(())),
+        }
+    }
+}
+
 /// Accept Operation Descriptor
 pub struct AcceptFuture {
     /// Queue descriptor of listening socket.
@@ -165,7 +250,7 @@ impl fmt::Debug for AcceptFuture {
 
 /// Future Trait Implementation for Accept Operation Descriptors
 impl Future for AcceptFuture {
-    type Output = Result<QDesc, Fail>;
+    type Output = Result<(QDesc, SocketAddrV4), Fail>;
 
     /// Polls the underlying accept operation.
     fn poll(self: Pin<&mut Self>, context: &mut Context) -> Poll<Self::Output> {
@@ -202,6 +287,8 @@ impl Future for PushFuture {
 
 pub struct PopFuture {
     pub fd: QDesc,
+    pub min_size: usize,
+    pub max_size: Option<usize>,
     pub inner: Rc<RefCell<Inner>>,
 }
 
@@ -219,6 +306,6 @@ impl Future for PopFuture {
         let peer = TcpPeer {
             inner: self_.inner.clone(),
         };
-        peer.poll_recv(self_.fd, ctx)
+        peer.poll_recv(self_.fd, ctx, self_.min_size, self_.max_size)
     }
 }
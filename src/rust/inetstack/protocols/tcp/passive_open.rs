@@ -175,6 +175,11 @@ impl PassiveSocket {
         self.ready.borrow_mut().poll(ctx)
     }
 
+    /// Checks if an accept operation would complete right away, be it with a new connection or a failed handshake.
+    pub fn has_ready(&self) -> bool {
+        self.ready.borrow().len() > 0
+    }
+
     pub fn receive(&mut self, ip_header: &Ipv4Header, header: &TcpHeader) -> Result<(), Fail> {
         let remote = SocketAddrV4::new(ip_header.get_src_addr(), header.src_port);
         if self.ready.borrow().endpoints.contains(&remote) {
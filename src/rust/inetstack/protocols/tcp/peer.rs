@@ -27,6 +27,7 @@ use crate::{
             established::ControlBlock,
             operations::{
                 AcceptFuture,
+                CloseFuture,
                 ConnectFuture,
                 PopFuture,
                 PushFuture,
@@ -38,21 +39,36 @@ use crate::{
             SeqNumber,
         },
     },
+    pal::constants::{
+        SHUT_RD,
+        SHUT_RDWR,
+        SHUT_WR,
+    },
     runtime::{
         fail::Fail,
         memory::DemiBuffer,
         network::{
             config::TcpConfig,
-            types::MacAddress,
+            types::{
+                MacAddress,
+                SocketOption,
+                SocketOptionName,
+            },
             NetworkRuntime,
         },
         timer::TimerRc,
+        types::{
+            demi_qstats_t,
+            DEMI_POLLACCEPT,
+            DEMI_POLLHUP,
+        },
         QDesc,
     },
     scheduler::scheduler::Scheduler,
 };
 use ::futures::channel::mpsc;
 use ::libc::{
+    c_int,
     EAGAIN,
     EBADF,
     EBUSY,
@@ -69,6 +85,7 @@ use ::rand::{
 };
 use ::std::{
     cell::{
+        Ref,
         RefCell,
         RefMut,
     },
@@ -82,7 +99,10 @@ use ::std::{
         Context,
         Poll,
     },
-    time::Duration,
+    time::{
+        Duration,
+        Instant,
+    },
 };
 
 #[cfg(feature = "profiler")]
@@ -111,6 +131,9 @@ pub struct Inner {
     // FD -> local port
     sockets: HashMap<QDesc, Socket>,
 
+    // FD -> socket options that override the default TCP configuration
+    configs: HashMap<QDesc, TcpConfig>,
+
     passive: HashMap<SocketAddrV4, PassiveSocket>,
     connecting: HashMap<(SocketAddrV4, SocketAddrV4), ActiveOpenSocket>,
     established: HashMap<(SocketAddrV4, SocketAddrV4), EstablishedSocket>,
@@ -180,14 +203,17 @@ impl TcpPeer {
     pub fn bind(&self, qd: QDesc, mut addr: SocketAddrV4) -> Result<(), Fail> {
         let mut inner: RefMut<Inner> = self.inner.borrow_mut();
 
-        // Check if address is already bound.
+        // Check if address is already bound. Sockets that allow address reuse only conflict with listening ones.
+        let reuse_address: bool = inner.get_config(qd).get_reuse_address();
         for (_, socket) in &inner.sockets {
             match socket {
+                Socket::Listening { local } if *local == addr => {
+                    return Err(Fail::new(libc::EADDRINUSE, "address already in use"))
+                },
                 Socket::Inactive { local: Some(local) }
-                | Socket::Listening { local }
                 | Socket::Connecting { local, remote: _ }
                 | Socket::Established { local, remote: _ }
-                    if *local == addr =>
+                    if *local == addr && !reuse_address =>
                 {
                     return Err(Fail::new(libc::EADDRINUSE, "address already in use"))
                 },
@@ -273,7 +299,7 @@ impl TcpPeer {
             inner.rt.clone(),
             inner.scheduler.clone(),
             inner.clock.clone(),
-            inner.tcp_config.clone(),
+            inner.get_config(qd),
             inner.local_link_addr,
             inner.arp.clone(),
             nonce,
@@ -289,7 +315,12 @@ impl TcpPeer {
     }
 
     /// Handles an incoming connection.
-    pub fn poll_accept(&self, qd: QDesc, new_qd: QDesc, ctx: &mut Context) -> Poll<Result<QDesc, Fail>> {
+    pub fn poll_accept(
+        &self,
+        qd: QDesc,
+        new_qd: QDesc,
+        ctx: &mut Context,
+    ) -> Poll<Result<(QDesc, SocketAddrV4), Fail>> {
         let mut inner_: RefMut<Inner> = self.inner.borrow_mut();
         let inner: &mut Inner = &mut *inner_;
 
@@ -307,6 +338,7 @@ impl TcpPeer {
         };
         let established: EstablishedSocket = EstablishedSocket::new(cb, new_qd, inner.dead_socket_tx.clone());
         let key: (SocketAddrV4, SocketAddrV4) = (established.cb.get_local(), established.cb.get_remote());
+        let remote: SocketAddrV4 = established.cb.get_remote();
 
         let socket: Socket = Socket::Established {
             local: established.cb.get_local(),
@@ -323,7 +355,12 @@ impl TcpPeer {
             panic!("duplicate queue descriptor in established sockets table");
         }
 
-        Poll::Ready(Ok(new_qd))
+        // Accepted sockets inherit socket options from the listening socket.
+        if let Some(config) = inner.configs.get(&qd).cloned() {
+            inner.configs.insert(new_qd, config);
+        }
+
+        Poll::Ready(Ok((new_qd, remote)))
     }
 
     pub fn connect(&self, qd: QDesc, remote: SocketAddrV4) -> Result<ConnectFuture, Fail> {
@@ -364,7 +401,7 @@ impl TcpPeer {
             local,
             remote,
             inner.rt.clone(),
-            inner.tcp_config.clone(),
+            inner.get_config(qd),
             inner.local_link_addr,
             inner.clock.clone(),
             inner.arp.clone(),
@@ -383,7 +420,13 @@ impl TcpPeer {
         })
     }
 
-    pub fn poll_recv(&self, fd: QDesc, ctx: &mut Context) -> Poll<Result<DemiBuffer, Fail>> {
+    pub fn poll_recv(
+        &self,
+        fd: QDesc,
+        ctx: &mut Context,
+        min_size: usize,
+        max_size: Option<usize>,
+    ) -> Poll<Result<DemiBuffer, Fail>> {
         let inner = self.inner.borrow_mut();
         let key = match inner.sockets.get(&fd) {
             Some(Socket::Established { local, remote }) => (*local, *remote),
@@ -393,22 +436,27 @@ impl TcpPeer {
             None => return Poll::Ready(Err(Fail::new(EBADF, "bad queue descriptor"))),
         };
         match inner.established.get(&key) {
-            Some(ref s) => s.poll_recv(ctx),
+            Some(ref s) => s.poll_recv(ctx, min_size, max_size),
             None => Poll::Ready(Err(Fail::new(ENOTCONN, "connection not established"))),
         }
     }
 
     pub fn push(&self, fd: QDesc, buf: DemiBuffer) -> PushFuture {
-        let err: Option<Fail> = match self.send(fd, buf) {
-            Ok(()) => None,
-            Err(e) => Some(e),
-        };
+        // Queue each non-empty segment of the buffer chain in order, so that the chain is sent without copying.
+        let err: Option<Fail> = buf
+            .into_segments()
+            .into_iter()
+            .filter(|segment| !segment.is_empty())
+            .try_for_each(|segment| self.send(fd, segment))
+            .err();
         PushFuture { fd, err }
     }
 
-    pub fn pop(&self, fd: QDesc) -> PopFuture {
+    pub fn pop(&self, fd: QDesc, min_size: usize, max_size: Option<usize>) -> PopFuture {
         PopFuture {
             fd,
+            min_size,
+            max_size,
             inner: self.inner.clone(),
         }
     }
@@ -429,6 +477,7 @@ impl TcpPeer {
     /// Closes a TCP socket.
     pub fn do_close(&self, qd: QDesc) -> Result<(), Fail> {
         let mut inner: RefMut<Inner> = self.inner.borrow_mut();
+        inner.configs.remove(&qd);
 
         match inner.sockets.remove(&qd) {
             Some(Socket::Established { local, remote }) => {
@@ -446,6 +495,144 @@ impl TcpPeer {
         Ok(())
     }
 
+    /// Closes a TCP socket, as in [TcpPeer::do_close], and returns a future that completes once the connection is shut
+    /// down, that is, once our unsent data and FIN are acknowledged by the remote peer. If lingering is enabled on the
+    /// socket, the linger timeout bounds the time that this may take. Sockets that are not connected are closed right
+    /// away: listening sockets stop accepting connections, and ongoing connection attempts are aborted.
+    pub fn async_close(&self, qd: QDesc) -> Result<CloseFuture, Fail> {
+        let mut inner_: RefMut<Inner> = self.inner.borrow_mut();
+        let inner: &mut Inner = &mut inner_;
+        let linger: Option<Duration> = inner.get_config(qd).get_linger();
+
+        let future: CloseFuture = match inner.sockets.get(&qd) {
+            Some(Socket::Established { local, remote }) => match inner.established.get(&(*local, *remote)) {
+                Some(s) => {
+                    s.close()?;
+                    let deadline: Option<Instant> = linger.map(|timeout| inner.clock.now() + timeout);
+                    CloseFuture::new(qd, Some(s.cb.clone()), deadline)
+                },
+                // The connection was torn down already.
+                None => CloseFuture::new(qd, None, None),
+            },
+            Some(Socket::Listening { local }) => {
+                inner.passive.remove(local);
+                CloseFuture::new(qd, None, None)
+            },
+            Some(Socket::Connecting { local, remote }) => {
+                // Dropping the active open socket also stops its background handshake.
+                inner.connecting.remove(&(*local, *remote));
+                CloseFuture::new(qd, None, None)
+            },
+            Some(Socket::Inactive { .. }) => CloseFuture::new(qd, None, None),
+            None => return Err(Fail::new(EBADF, "bad queue descriptor")),
+        };
+        inner.sockets.remove(&qd);
+        inner.configs.remove(&qd);
+
+        Ok(future)
+    }
+
+    /// Shuts down the sending half, the receiving half, or both halves of a TCP connection, as selected by `how`. The
+    /// socket is not released.
+    pub fn shutdown(&self, qd: QDesc, how: c_int) -> Result<(), Fail> {
+        let inner: Ref<Inner> = self.inner.borrow();
+        let key: (SocketAddrV4, SocketAddrV4) = match inner.sockets.get(&qd) {
+            Some(Socket::Established { local, remote }) => (*local, *remote),
+            Some(..) => return Err(Fail::new(ENOTCONN, "connection not established")),
+            None => return Err(Fail::new(EBADF, "bad queue descriptor")),
+        };
+        let established: &EstablishedSocket = match inner.established.get(&key) {
+            Some(s) => s,
+            None => return Err(Fail::new(ENOTCONN, "connection not established")),
+        };
+        match how {
+            SHUT_RD => established.shutdown_receive(),
+            SHUT_WR => established.close()?,
+            SHUT_RDWR => {
+                established.shutdown_receive();
+                established.close()?
+            },
+            _ => return Err(Fail::new(EINVAL, "invalid value for how")),
+        }
+
+        Ok(())
+    }
+
+    /// Gets the statistics of a TCP socket. TCP fields are filled only once the connection is established.
+    pub fn get_stats(&self, qd: QDesc) -> Result<demi_qstats_t, Fail> {
+        let inner: Ref<Inner> = self.inner.borrow();
+        let mut stats: demi_qstats_t = demi_qstats_t::default();
+        match inner.sockets.get(&qd) {
+            Some(Socket::Established { local, remote }) => {
+                if let Some(s) = inner.established.get(&(*local, *remote)) {
+                    s.fill_stats(&mut stats);
+                }
+            },
+            Some(..) => (),
+            None => return Err(Fail::new(EBADF, "bad queue descriptor")),
+        }
+        Ok(stats)
+    }
+
+    /// Checks which operations on a TCP socket would complete right away: accepting a connection on a passive socket,
+    /// or popping and pushing data on an established one. Sockets that are not connected yet are never ready.
+    pub fn readiness(&self, qd: QDesc) -> Result<u32, Fail> {
+        let inner: Ref<Inner> = self.inner.borrow();
+        match inner.sockets.get(&qd) {
+            Some(Socket::Listening { local }) => match inner.passive.get(local) {
+                Some(passive) if passive.has_ready() => Ok(DEMI_POLLACCEPT),
+                _ => Ok(0),
+            },
+            Some(Socket::Established { local, remote }) => match inner.established.get(&(*local, *remote)) {
+                Some(s) => Ok(s.readiness()),
+                // The connection was torn down already.
+                None => Ok(DEMI_POLLHUP),
+            },
+            Some(..) => Ok(0),
+            None => Err(Fail::new(EBADF, "bad queue descriptor")),
+        }
+    }
+
+    /// Sets a socket option on a TCP socket. Options take effect on the next connection established by the socket.
+    pub fn setsockopt(&self, qd: QDesc, option: SocketOption) -> Result<(), Fail> {
+        let mut inner: RefMut<Inner> = self.inner.borrow_mut();
+        if !inner.sockets.contains_key(&qd) {
+            return Err(Fail::new(EBADF, "bad queue descriptor"));
+        }
+        let mut config: TcpConfig = inner.get_config(qd);
+        config.set_socket_option(option)?;
+        inner.configs.insert(qd, config);
+        Ok(())
+    }
+
+    /// Gets the value of a socket option on a TCP socket.
+    pub fn getsockopt(&self, qd: QDesc, name: SocketOptionName) -> Result<SocketOption, Fail> {
+        let inner: Ref<Inner> = self.inner.borrow();
+        if !inner.sockets.contains_key(&qd) {
+            return Err(Fail::new(EBADF, "bad queue descriptor"));
+        }
+        inner.get_config(qd).get_socket_option(name)
+    }
+
+    /// Gets the local address of a TCP socket. Unbound sockets report the unspecified address.
+    pub fn getsockname(&self, qd: QDesc) -> Result<SocketAddrV4, Fail> {
+        let inner: Ref<Inner> = self.inner.borrow();
+        match inner.sockets.get(&qd) {
+            Some(Socket::Inactive { local: Some(local) })
+            | Some(Socket::Listening { local })
+            | Some(Socket::Connecting { local, remote: _ })
+            | Some(Socket::Established { local, remote: _ }) => Ok(*local),
+            Some(Socket::Inactive { local: None }) => Ok(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 0)),
+            None => Err(Fail::new(EBADF, "bad queue descriptor")),
+        }
+    }
+
+    /// Gets the remote address of a connected TCP socket.
+    pub fn getpeername(&self, qd: QDesc) -> Result<SocketAddrV4, Fail> {
+        let (_, remote): (SocketAddrV4, SocketAddrV4) = self.endpoints(qd)?;
+        Ok(remote)
+    }
+
     pub fn remote_mss(&self, fd: QDesc) -> Result<usize, Fail> {
         let inner = self.inner.borrow();
         let key = match inner.sockets.get(&fd) {
@@ -506,6 +693,7 @@ impl Inner {
             isn_generator: IsnGenerator::new(nonce),
             ephemeral_ports,
             sockets: HashMap::new(),
+            configs: HashMap::new(),
             passive: HashMap::new(),
             connecting: HashMap::new(),
             established: HashMap::new(),
@@ -521,6 +709,14 @@ impl Inner {
         }
     }
 
+    /// Gets the TCP configuration of a socket, including any socket option overrides.
+    fn get_config(&self, qd: QDesc) -> TcpConfig {
+        match self.configs.get(&qd) {
+            Some(config) => config.clone(),
+            None => self.tcp_config.clone(),
+        }
+    }
+
     fn receive(&mut self, ip_hdr: &Ipv4Header, buf: DemiBuffer) -> Result<(), Fail> {
         let (mut tcp_hdr, data) = TcpHeader::parse(ip_hdr, buf, self.tcp_config.get_rx_checksum_offload())?;
         debug!("TCP received {:?}", tcp_hdr);
@@ -603,4 +799,14 @@ impl Inner {
 
         Poll::Ready(Ok(()))
     }
+
+    /// Aborts an ongoing connection attempt on the socket `fd`. The socket is left bound to its local address.
+    pub(super) fn cancel_connect(&mut self, fd: QDesc) {
+        if let Some(Socket::Connecting { local, remote }) = self.sockets.get(&fd) {
+            let (local, remote): (SocketAddrV4, SocketAddrV4) = (*local, *remote);
+            // Dropping the active open socket also stops its background handshake.
+            self.connecting.remove(&(local, remote));
+            self.sockets.insert(fd, Socket::Inactive { local: Some(local) });
+        }
+    }
 }
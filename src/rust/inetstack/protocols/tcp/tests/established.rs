@@ -4,13 +4,18 @@
 use crate::{
     inetstack::{
         protocols::tcp::{
-            operations::PushFuture,
+            operations::{
+                PopFuture,
+                PushFuture,
+            },
+            segment::TcpHeader,
             tests::{
                 check_packet_data,
                 check_packet_pure_ack,
                 setup::{
                     advance_clock,
                     connection_setup,
+                    extract_headers,
                 },
             },
             SeqNumber,
@@ -22,6 +27,15 @@ use crate::{
     },
     runtime::{
         memory::DemiBuffer,
+        network::{
+            config::TcpConfig,
+            consts::{
+                KEEPALIVE_IDLE,
+                KEEPALIVE_INTERVAL,
+                KEEPALIVE_PROBES,
+            },
+            types::SocketOption,
+        },
         QDesc,
     },
 };
@@ -443,3 +457,240 @@ fn test_connect_disconnect() {
 
     connection_hangup(&mut ctx, &mut now, &mut server, &mut client, server_fd, client_fd);
 }
+
+//=============================================================================
+
+/// Tests that small segments are held back while some data is in flight, once
+/// Nagle's algorithm is enabled.
+#[test]
+fn test_send_nagle() {
+    let mut ctx = Context::from_waker(noop_waker_ref());
+    let mut now = Instant::now();
+
+    // Connection parameters
+    let listen_port: u16 = 80;
+    let listen_addr: SocketAddrV4 = SocketAddrV4::new(test_helpers::BOB_IPV4, listen_port);
+
+    // Setup peers.
+    let mut tcp_config: TcpConfig = TcpConfig::default();
+    tcp_config.set_socket_option(SocketOption::NoDelay(false)).unwrap();
+    let mut server: Engine = test_helpers::new_bob2(now);
+    let mut client: Engine = test_helpers::new_alice2_with_config(now, tcp_config);
+    let window_size: u16 = client.rt.tcp_config.get_receive_window_size();
+
+    let (_, client_fd): (QDesc, QDesc) =
+        connection_setup(&mut ctx, &mut now, &mut server, &mut client, listen_port, listen_addr);
+
+    // The first segment is sent right away, as no data is in flight.
+    let bufsize: usize = 64;
+    let _: PushFuture = client.tcp_push(client_fd, cook_buffer(bufsize, None));
+    let first: DemiBuffer = client.rt.pop_frame();
+
+    // The second one is held back until the first one is acknowledged.
+    let _: PushFuture = client.tcp_push(client_fd, cook_buffer(bufsize, None));
+    client.rt.poll_scheduler();
+    assert!(client.rt.pop_frame_unchecked().is_none());
+
+    server.receive(first).unwrap();
+    advance_clock(Some(&mut server), Some(&mut client), &mut now);
+    server.rt.poll_scheduler();
+    client.receive(server.rt.pop_frame()).unwrap();
+    client.rt.poll_scheduler();
+    let len: usize = check_packet_data(
+        client.rt.pop_frame(),
+        test_helpers::ALICE_MAC,
+        test_helpers::BOB_MAC,
+        test_helpers::ALICE_IPV4,
+        test_helpers::BOB_IPV4,
+        window_size,
+        SeqNumber::from(1 + bufsize as u32),
+        None,
+    );
+    assert_eq!(len, bufsize);
+}
+
+//=============================================================================
+
+/// Tests that the amount of data in flight is bounded by the send buffer size.
+#[test]
+fn test_send_buffer_size() {
+    let mut ctx = Context::from_waker(noop_waker_ref());
+    let mut now = Instant::now();
+
+    // Connection parameters
+    let listen_port: u16 = 80;
+    let listen_addr: SocketAddrV4 = SocketAddrV4::new(test_helpers::BOB_IPV4, listen_port);
+
+    // Setup peers.
+    let send_buffer_size: usize = 100;
+    let mut tcp_config: TcpConfig = TcpConfig::default();
+    tcp_config
+        .set_socket_option(SocketOption::SendBufferSize(send_buffer_size))
+        .unwrap();
+    let mut server: Engine = test_helpers::new_bob2(now);
+    let mut client: Engine = test_helpers::new_alice2_with_config(now, tcp_config);
+    let window_size: u16 = client.rt.tcp_config.get_receive_window_size();
+
+    let (_, client_fd): (QDesc, QDesc) =
+        connection_setup(&mut ctx, &mut now, &mut server, &mut client, listen_port, listen_addr);
+
+    // The first buffer fits in the send buffer.
+    let bufsize: usize = 64;
+    let _: PushFuture = client.tcp_push(client_fd, cook_buffer(bufsize, None));
+    let first: DemiBuffer = client.rt.pop_frame();
+
+    // Only the head of the second one does.
+    let _: PushFuture = client.tcp_push(client_fd, cook_buffer(bufsize, None));
+    client.rt.poll_scheduler();
+    let second: DemiBuffer = client.rt.pop_frame();
+    let len: usize = check_packet_data(
+        second.clone(),
+        test_helpers::ALICE_MAC,
+        test_helpers::BOB_MAC,
+        test_helpers::ALICE_IPV4,
+        test_helpers::BOB_IPV4,
+        window_size,
+        SeqNumber::from(1 + bufsize as u32),
+        None,
+    );
+    assert_eq!(len, send_buffer_size - bufsize);
+    client.rt.poll_scheduler();
+    assert!(client.rt.pop_frame_unchecked().is_none());
+
+    // The rest is sent once data in flight is acknowledged.
+    server.receive(first).unwrap();
+    server.receive(second).unwrap();
+    advance_clock(Some(&mut server), Some(&mut client), &mut now);
+    server.rt.poll_scheduler();
+    client.receive(server.rt.pop_frame()).unwrap();
+    client.rt.poll_scheduler();
+    let len: usize = check_packet_data(
+        client.rt.pop_frame(),
+        test_helpers::ALICE_MAC,
+        test_helpers::BOB_MAC,
+        test_helpers::ALICE_IPV4,
+        test_helpers::BOB_IPV4,
+        window_size,
+        SeqNumber::from(1 + send_buffer_size as u32),
+        None,
+    );
+    assert_eq!(len, 2 * bufsize - send_buffer_size);
+}
+
+//=============================================================================
+
+/// Tests that data received once the receiving half of a connection is shut
+/// down is dropped as it arrives, so that the receive window stays open.
+#[test]
+fn test_shutdown_receive() {
+    let mut ctx = Context::from_waker(noop_waker_ref());
+    let mut now = Instant::now();
+
+    // Connection parameters
+    let listen_port: u16 = 80;
+    let listen_addr: SocketAddrV4 = SocketAddrV4::new(test_helpers::BOB_IPV4, listen_port);
+
+    // Setup peers.
+    let mut server: Engine = test_helpers::new_bob2(now);
+    let mut client: Engine = test_helpers::new_alice2(now);
+    let window_size: u16 = client.rt.tcp_config.get_receive_window_size();
+
+    let (server_fd, client_fd): (QDesc, QDesc) =
+        connection_setup(&mut ctx, &mut now, &mut server, &mut client, listen_port, listen_addr);
+    let recv_window: u64 = server.ipv4.tcp.get_stats(server_fd).unwrap().qs_tcp_recv_window;
+
+    // Shut down the receiving half of the server, and send it some data.
+    server.tcp_shutdown(server_fd, libc::SHUT_RD).unwrap();
+    let bufsize: usize = 64;
+    let (bytes, _): (DemiBuffer, usize) = send_data(
+        &mut ctx,
+        &mut now,
+        &mut server,
+        &mut client,
+        client_fd,
+        window_size,
+        SeqNumber::from(1),
+        None,
+        cook_buffer(bufsize, None),
+    );
+    server.receive(bytes).unwrap();
+
+    // The data is acknowledged, but it does not take up the receive window.
+    assert_eq!(
+        server.ipv4.tcp.get_stats(server_fd).unwrap().qs_tcp_recv_window,
+        recv_window
+    );
+    recv_pure_ack(&mut now, &mut server, &mut client, SeqNumber::from(1 + bufsize as u32));
+
+    // Pops complete at the end of the stream.
+    let mut pop_future: PopFuture = server.tcp_pop(server_fd);
+    match Future::poll(Pin::new(&mut pop_future), &mut ctx) {
+        Poll::Ready(Ok(buf)) => assert_eq!(buf.len(), 0),
+        _ => panic!("pop should complete at the end of the stream"),
+    }
+}
+
+//=============================================================================
+
+/// Tests that keep-alive probes are sent on idle connections, and that the
+/// connection is dropped once our peer stops answering them.
+#[test]
+fn test_keepalive() {
+    let mut ctx = Context::from_waker(noop_waker_ref());
+    let mut now = Instant::now();
+
+    // Connection parameters
+    let listen_port: u16 = 80;
+    let listen_addr: SocketAddrV4 = SocketAddrV4::new(test_helpers::BOB_IPV4, listen_port);
+
+    // Setup peers.
+    let mut tcp_config: TcpConfig = TcpConfig::default();
+    tcp_config.set_socket_option(SocketOption::KeepAlive(true)).unwrap();
+    let mut server: Engine = test_helpers::new_bob2(now);
+    let mut client: Engine = test_helpers::new_alice2_with_config(now, tcp_config);
+
+    let (_, client_fd): (QDesc, QDesc) =
+        connection_setup(&mut ctx, &mut now, &mut server, &mut client, listen_port, listen_addr);
+
+    // Once the connection is idle, a probe is sent and answered by our peer.
+    now += KEEPALIVE_IDLE;
+    client.clock.advance_clock(now);
+    server.clock.advance_clock(now);
+    client.rt.poll_scheduler();
+    let probe: DemiBuffer = client.rt.pop_frame();
+    let (_, _, header): (_, _, TcpHeader) = extract_headers(probe.clone());
+    assert_eq!(header.seq_num, SeqNumber::from(0));
+    server.receive(probe).unwrap();
+    let ack: DemiBuffer = server.rt.pop_frame();
+    check_packet_pure_ack(
+        ack.clone(),
+        test_helpers::BOB_MAC,
+        test_helpers::ALICE_MAC,
+        test_helpers::BOB_IPV4,
+        test_helpers::ALICE_IPV4,
+        SeqNumber::from(1),
+    );
+    client.receive(ack).unwrap();
+
+    // Probes that go unanswered are sent again, until the connection is dropped.
+    now += KEEPALIVE_IDLE;
+    client.clock.advance_clock(now);
+    for _ in 0..KEEPALIVE_PROBES {
+        client.rt.poll_scheduler();
+        let (_, _, header): (_, _, TcpHeader) = extract_headers(client.rt.pop_frame());
+        assert_eq!(header.seq_num, SeqNumber::from(0));
+        assert!(!header.rst);
+        now += KEEPALIVE_INTERVAL;
+        client.clock.advance_clock(now);
+    }
+    client.rt.poll_scheduler();
+    let (_, _, header): (_, _, TcpHeader) = extract_headers(client.rt.pop_frame());
+    assert!(header.rst);
+
+    // The connection reports why it was dropped.
+    let mut pop_future: PopFuture = client.tcp_pop(client_fd);
+    match Future::poll(Pin::new(&mut pop_future), &mut ctx) {
+        Poll::Ready(Err(e)) => assert_eq!(e.errno, libc::ETIMEDOUT),
+        _ => panic!("pop should fail"),
+    }
+}
@@ -322,7 +322,7 @@ fn test_refuse_connection_missing_syn() {
 //=============================================================================
 
 /// Extracts headers of a TCP packet.
-fn extract_headers(bytes: DemiBuffer) -> (Ethernet2Header, Ipv4Header, TcpHeader) {
+pub fn extract_headers(bytes: DemiBuffer) -> (Ethernet2Header, Ipv4Header, TcpHeader) {
     let (eth2_header, eth2_payload) = Ethernet2Header::parse(bytes).unwrap();
     let (ipv4_header, ipv4_payload) = Ipv4Header::parse(eth2_payload).unwrap();
     let (tcp_header, _) = TcpHeader::parse(&ipv4_header, ipv4_payload, false).unwrap();
@@ -548,7 +548,7 @@ pub fn connection_setup(
     connection_setup_sync_rcvd_established(server, bytes);
 
     let server_fd = match Future::poll(Pin::new(&mut accept_future), ctx) {
-        Poll::Ready(Ok(server_fd)) => Ok(server_fd),
+        Poll::Ready(Ok((server_fd, _))) => Ok(server_fd),
         _ => Err(()),
     }
     .unwrap();
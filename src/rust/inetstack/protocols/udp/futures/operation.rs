@@ -18,6 +18,7 @@ use crate::{
 };
 use ::std::{
     future::Future,
+    net::SocketAddr,
     pin::Pin,
     task::{
         Context,
@@ -35,6 +36,8 @@ pub enum UdpOperation {
     Pushto(QDesc, Result<(), Fail>),
     /// Pop operation.
     Pop(FutureResult<UdpPopFuture>),
+    /// Close operation.
+    Close(QDesc, Result<(), Fail>),
 }
 
 //==============================================================================
@@ -53,15 +56,27 @@ impl UdpOperation {
             UdpOperation::Pop(FutureResult {
                 future,
                 done: Some(Ok((addr, bytes))),
-            }) => (future.get_qd(), OperationResult::Pop(Some(addr), bytes)),
+            }) => (future.get_qd(), OperationResult::Pop(Some(SocketAddr::V4(addr)), bytes)),
             UdpOperation::Pop(FutureResult {
                 future,
                 done: Some(Err(e)),
             }) => (future.get_qd(), OperationResult::Failed(e)),
 
+            // Close operation.
+            UdpOperation::Close(fd, Ok(())) => (fd, OperationResult::Close),
+            UdpOperation::Close(fd, Err(e)) => (fd, OperationResult::Failed(e)),
+
             _ => panic!("UDP Operation not ready"),
         }
     }
+
+    /// Completes the target UDP operation descriptor with `ECANCELED`, unless it has already completed.
+    pub fn cancel(&mut self) {
+        match self {
+            UdpOperation::Pop(ref mut f) => f.cancel(),
+            UdpOperation::Pushto(..) | UdpOperation::Close(..) => (),
+        }
+    }
 }
 
 //==============================================================================
@@ -76,7 +91,7 @@ impl Future for UdpOperation {
     fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<()> {
         match self.get_mut() {
             UdpOperation::Pop(ref mut f) => Future::poll(Pin::new(f), ctx),
-            UdpOperation::Pushto(..) => Poll::Ready(()),
+            UdpOperation::Pushto(..) | UdpOperation::Close(..) => Poll::Ready(()),
         }
     }
 }
@@ -37,6 +37,8 @@ pub struct UdpPopFuture {
     qd: QDesc,
     /// Shared receiving queue.
     recv_queue: SharedQueue<SharedQueueSlot<DemiBuffer>>,
+    /// Maximum number of bytes to pop. Bytes of a datagram beyond this size are discarded.
+    max_size: Option<usize>,
 }
 
 //==============================================================================
@@ -46,8 +48,12 @@ pub struct UdpPopFuture {
 /// Associate Functions for Pop Operation Descriptor
 impl UdpPopFuture {
     /// Creates a pop operation descritor.
-    pub fn new(qd: QDesc, recv_queue: SharedQueue<SharedQueueSlot<DemiBuffer>>) -> Self {
-        Self { qd, recv_queue }
+    pub fn new(qd: QDesc, recv_queue: SharedQueue<SharedQueueSlot<DemiBuffer>>, max_size: Option<usize>) -> Self {
+        Self {
+            qd,
+            recv_queue,
+            max_size,
+        }
     }
 
     /// Returns the queue descriptor that is associated to the target pop operation descriptor.
@@ -66,8 +72,18 @@ impl Future for UdpPopFuture {
 
     /// Polls the target pop operation descriptor.
     fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
-        match self.get_mut().recv_queue.try_pop() {
-            Ok(Some(msg)) => Poll::Ready(Ok((msg.remote, msg.data))),
+        let self_: &mut UdpPopFuture = self.get_mut();
+        match self_.recv_queue.try_pop() {
+            Ok(Some(mut msg)) => {
+                // Discard the bytes that do not fit.
+                if let Some(max_size) = self_.max_size {
+                    if msg.data.len() > max_size {
+                        let excess: usize = msg.data.len() - max_size;
+                        msg.data.trim(excess)?;
+                    }
+                }
+                Poll::Ready(Ok((msg.remote, msg.data)))
+            },
             Ok(None) => {
                 let waker: &Waker = ctx.waker();
                 waker.wake_by_ref();
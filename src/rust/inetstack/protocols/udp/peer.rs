@@ -36,9 +36,18 @@ use crate::{
         fail::Fail,
         memory::DemiBuffer,
         network::{
-            types::MacAddress,
+            config::UdpConfig,
+            types::{
+                MacAddress,
+                SocketOption,
+                SocketOptionName,
+            },
             NetworkRuntime,
         },
+        types::{
+            DEMI_POLLIN,
+            DEMI_POLLOUT,
+        },
         QDesc,
     },
     scheduler::{
@@ -92,8 +101,10 @@ pub struct UdpPeer {
     ephemeral_ports: EphemeralPorts,
     /// Opened sockets.
     sockets: HashMap<QDesc, Option<SocketAddrV4>>,
-    /// Bound sockets.
-    bound: HashMap<SocketAddrV4, SharedQueue<SharedQueueSlot<DemiBuffer>>>,
+    /// Socket options that override the default UDP configuration.
+    configs: HashMap<QDesc, UdpConfig>,
+    /// Bound sockets, along with their receive queue.
+    bound: HashMap<SocketAddrV4, (QDesc, SharedQueue<SharedQueueSlot<DemiBuffer>>)>,
     /// Queue of unset datagrams. This is shared across fast/slow paths.
     send_queue: SharedQueue<SharedQueueSlot<DemiBuffer>>,
     /// Local link address.
@@ -102,6 +113,8 @@ pub struct UdpPeer {
     local_ipv4_addr: Ipv4Addr,
     /// Offload checksum to hardware?
     checksum_offload: bool,
+    /// Default UDP configuration.
+    udp_config: UdpConfig,
 
     /// The background co-routine sends unset UDP packets.
     /// We annotate it as unused because the compiler believes that it is never called which is not the case.
@@ -122,9 +135,10 @@ impl UdpPeer {
         rng_seed: [u8; 32],
         local_link_addr: MacAddress,
         local_ipv4_addr: Ipv4Addr,
-        offload_checksum: bool,
+        udp_config: UdpConfig,
         arp: ArpPeer,
     ) -> Result<Self, Fail> {
+        let offload_checksum: bool = udp_config.get_tx_checksum_offload();
         let send_queue: SharedQueue<SharedQueueSlot<DemiBuffer>> =
             SharedQueue::<SharedQueueSlot<DemiBuffer>>::new(SEND_QUEUE_MAX_SIZE);
         let future = Self::background_sender(
@@ -151,11 +165,13 @@ impl UdpPeer {
             arp,
             ephemeral_ports,
             sockets: HashMap::new(),
+            configs: HashMap::new(),
             bound: HashMap::new(),
             send_queue,
             local_link_addr,
             local_ipv4_addr,
             checksum_offload: offload_checksum,
+            udp_config,
             background: handle,
         })
     }
@@ -244,7 +260,7 @@ impl UdpPeer {
                 let queue: SharedQueue<SharedQueueSlot<DemiBuffer>> =
                     SharedQueue::<SharedQueueSlot<DemiBuffer>>::new(RECV_QUEUE_MAX_SIZE);
 
-                if self.bound.insert(addr, queue).is_some() {
+                if self.bound.insert(addr, (qd, queue)).is_some() {
                     Err(Fail::new(libc::EADDRINUSE, "address in use"))
                 } else {
                     Ok(())
@@ -276,6 +292,7 @@ impl UdpPeer {
             Some(s) => s,
             None => return Err(Fail::new(EBADF, "invalid queue descriptor")),
         };
+        self.configs.remove(&qd);
 
         // Remove endpoint binding.
         match socket {
@@ -284,6 +301,55 @@ impl UdpPeer {
         }
     }
 
+    /// Sets a socket option on a UDP socket.
+    pub fn setsockopt(&mut self, qd: QDesc, option: SocketOption) -> Result<(), Fail> {
+        if !self.sockets.contains_key(&qd) {
+            return Err(Fail::new(EBADF, "invalid queue descriptor"));
+        }
+        let mut config: UdpConfig = self.get_config(qd);
+        config.set_socket_option(option)?;
+        self.configs.insert(qd, config);
+        Ok(())
+    }
+
+    /// Gets the value of a socket option on a UDP socket.
+    pub fn getsockopt(&self, qd: QDesc, name: SocketOptionName) -> Result<SocketOption, Fail> {
+        if !self.sockets.contains_key(&qd) {
+            return Err(Fail::new(EBADF, "invalid queue descriptor"));
+        }
+        self.get_config(qd).get_socket_option(name)
+    }
+
+    /// Gets the local address of a UDP socket. Unbound sockets report the unspecified address.
+    pub fn getsockname(&self, qd: QDesc) -> Result<SocketAddrV4, Fail> {
+        match self.sockets.get(&qd) {
+            Some(Some(local)) if self.bound.contains_key(local) => Ok(*local),
+            Some(_) => Ok(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 0)),
+            None => Err(Fail::new(EBADF, "invalid queue descriptor")),
+        }
+    }
+
+    /// Gets the remote address of a UDP socket. UDP sockets are never connected.
+    pub fn getpeername(&self, qd: QDesc) -> Result<SocketAddrV4, Fail> {
+        match self.sockets.get(&qd) {
+            Some(_) => Err(Fail::new(libc::ENOTCONN, "socket is not connected")),
+            None => Err(Fail::new(EBADF, "invalid queue descriptor")),
+        }
+    }
+
+    /// Checks if datagrams may be popped from or pushed to a UDP socket. Only bound sockets are ready for either.
+    pub fn readiness(&self, qd: QDesc) -> Result<u32, Fail> {
+        match self.sockets.get(&qd) {
+            Some(Some(local)) => match self.bound.get(local) {
+                Some((_, recv_queue)) if !recv_queue.is_empty() => Ok(DEMI_POLLIN | DEMI_POLLOUT),
+                Some(_) => Ok(DEMI_POLLOUT),
+                None => Ok(0),
+            },
+            Some(None) => Ok(0),
+            None => Err(Fail::new(EBADF, "invalid queue descriptor")),
+        }
+    }
+
     /// Pushes data to a remote UDP peer.
     pub fn do_pushto(&self, qd: QDesc, data: DemiBuffer, remote: SocketAddrV4) -> Result<(), Fail> {
         #[cfg(feature = "profiler")]
@@ -316,19 +382,19 @@ impl UdpPeer {
         Ok(())
     }
 
-    /// Pops data from a socket.
-    pub fn do_pop(&self, qd: QDesc) -> UdpPopFuture {
+    /// Pops data from a socket, at most `max_size` bytes of a datagram if set.
+    pub fn do_pop(&self, qd: QDesc, max_size: Option<usize>) -> UdpPopFuture {
         #[cfg(feature = "profiler")]
         timer!("udp::pop");
 
         // Lookup associated receiver-side shared queue.
         let recv_queue: SharedQueue<SharedQueueSlot<DemiBuffer>> = match self.sockets.get(&qd) {
-            Some(s) if s.is_some() => self.bound.get(&s.unwrap()).unwrap().clone(),
+            Some(s) if s.is_some() => self.bound.get(&s.unwrap()).unwrap().1.clone(),
             _ => panic!("invalid queue descriptor"),
         };
 
         // Issue pop operation.
-        UdpPopFuture::new(qd, recv_queue)
+        UdpPopFuture::new(qd, recv_queue, max_size)
     }
 
     /// Consumes the payload from a buffer.
@@ -344,12 +410,12 @@ impl UdpPeer {
         let remote: SocketAddrV4 = SocketAddrV4::new(ipv4_hdr.get_src_addr(), hdr.src_port());
 
         // Lookup associated receiver-side shared queue.
-        let recv_queue: &mut SharedQueue<SharedQueueSlot<DemiBuffer>> = match self.bound.get_mut(&local) {
+        let (qd, recv_queue): &(QDesc, SharedQueue<SharedQueueSlot<DemiBuffer>>) = match self.bound.get(&local) {
             Some(q) => q,
             None => {
                 // Handle wildcard address.
                 let local: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, hdr.dest_port());
-                match self.bound.get_mut(&local) {
+                match self.bound.get(&local) {
                     Some(q) => q,
                     // TODO: Send ICMPv4 error in this condition.
                     None => Err(Fail::new(libc::ENOTCONN, "port not bound"))?,
@@ -359,13 +425,35 @@ impl UdpPeer {
 
         // TODO: Drop this packet if local address/port pair is not bound.
 
+        // Drop the datagram if it does not fit in the receive buffer. A datagram is always accepted in an empty queue,
+        // so that datagrams larger than the receive buffer may still be received.
+        let size: usize = data.len();
+        let receive_buffer_size: usize = self.get_config(*qd).get_receive_buffer_size();
+        if !recv_queue.is_empty() && recv_queue.size() + size > receive_buffer_size {
+            debug!(
+                "receive buffer full, dropping datagram (local={:?}, remote={:?})",
+                local, remote
+            );
+            return Ok(());
+        }
+
         // Push data to the receiver-side shared queue. This will cause the
         // associated pool operation to be ready.
-        recv_queue.push(SharedQueueSlot { local, remote, data }).unwrap();
+        recv_queue
+            .push_sized(SharedQueueSlot { local, remote, data }, size)
+            .unwrap();
 
         Ok(())
     }
 
+    /// Gets the UDP configuration of a socket, including any socket option overrides.
+    fn get_config(&self, qd: QDesc) -> UdpConfig {
+        match self.configs.get(&qd) {
+            Some(config) => config.clone(),
+            None => self.udp_config.clone(),
+        }
+    }
+
     /// Sends a UDP datagram.
     fn do_send(
         rt: Rc<dyn NetworkRuntime>,
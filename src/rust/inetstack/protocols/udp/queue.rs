@@ -25,6 +25,9 @@ use ::std::{
 // Structures
 //======================================================================================================================
 
+/// Message of a [SharedQueue], along with its size.
+type SizedMessage<T> = (T, usize);
+
 /// Shared Queue Slot
 pub struct SharedQueueSlot<T> {
     /// Local endpoint.
@@ -40,11 +43,13 @@ pub struct SharedQueueSlot<T> {
 /// TODO: Reuse this structure in TCP stack, for send/receive queues.
 pub struct SharedQueue<T> {
     /// Send-side endpoint.
-    tx: Rc<RefCell<Sender<T>>>,
+    tx: Rc<RefCell<Sender<SizedMessage<T>>>>,
     /// Receive-side endpoint.
-    rx: Rc<RefCell<Receiver<T>>>,
+    rx: Rc<RefCell<Receiver<SizedMessage<T>>>>,
     /// Length of shared queue.
     length: Rc<RefCell<usize>>,
+    /// Total size of the messages in the shared queue.
+    size: Rc<RefCell<usize>>,
     /// Capacity of shahred queue.
     capacity: usize,
 }
@@ -57,27 +62,34 @@ pub struct SharedQueue<T> {
 impl<T> SharedQueue<T> {
     /// Instantiates a shared queue.
     pub fn new(size: usize) -> Self {
-        let (tx, rx): (Sender<T>, Receiver<T>) = mpsc::channel(size);
+        let (tx, rx): (Sender<SizedMessage<T>>, Receiver<SizedMessage<T>>) = mpsc::channel(size);
         Self {
             tx: Rc::new(RefCell::new(tx)),
             rx: Rc::new(RefCell::new(rx)),
             length: Rc::new(RefCell::new(0)),
+            size: Rc::new(RefCell::new(0)),
             capacity: size,
         }
     }
 
     /// Pushes a message to the target shared queue.
-    #[allow(unused_must_use)]
     pub fn push(&self, msg: T) -> Result<(), Fail> {
+        self.push_sized(msg, 0)
+    }
+
+    /// Pushes a message of `size` bytes to the target shared queue.
+    #[allow(unused_must_use)]
+    pub fn push_sized(&self, msg: T, size: usize) -> Result<(), Fail> {
         if *self.length.borrow() == self.capacity {
             while *self.length.borrow() >= self.capacity / 2 {
                 self.try_pop();
             }
         }
 
-        match self.tx.borrow_mut().try_send(msg) {
+        match self.tx.borrow_mut().try_send((msg, size)) {
             Ok(_) => {
                 *self.length.borrow_mut() += 1;
+                *self.size.borrow_mut() += size;
                 Ok(())
             },
             Err(_) => Err(Fail::new(EIO, "failed to push to shared queue")),
@@ -87,8 +99,9 @@ impl<T> SharedQueue<T> {
     /// Synchronously attempts to pop a message from the target shared queue.
     pub fn try_pop(&self) -> Result<Option<T>, Fail> {
         match self.rx.borrow_mut().try_next() {
-            Ok(Some(msg)) => {
+            Ok(Some((msg, size))) => {
                 *self.length.borrow_mut() -= 1;
+                *self.size.borrow_mut() -= size;
                 Ok(Some(msg))
             },
             Ok(None) => Err(Fail::new(EIO, "failed to pop from shared queue")),
@@ -96,11 +109,22 @@ impl<T> SharedQueue<T> {
         }
     }
 
+    /// Checks if the target shared queue holds no message.
+    pub fn is_empty(&self) -> bool {
+        *self.length.borrow() == 0
+    }
+
+    /// Gets the total size of the messages in the target shared queue.
+    pub fn size(&self) -> usize {
+        *self.size.borrow()
+    }
+
     /// Asynchronously pops a message from the target shared queue.
     pub async fn pop(&mut self) -> Result<T, Fail> {
         match self.rx.borrow_mut().next().await {
-            Some(msg) => {
+            Some((msg, size)) => {
                 *self.length.borrow_mut() -= 1;
+                *self.size.borrow_mut() -= size;
                 Ok(msg)
             },
             None => Err(Fail::new(EIO, "failed to pop from shared queue")),
@@ -120,6 +144,7 @@ impl<T> Clone for SharedQueue<T> {
             tx: self.tx.clone(),
             rx: self.rx.clone(),
             length: self.length.clone(),
+            size: self.size.clone(),
             capacity: self.capacity,
         }
     }
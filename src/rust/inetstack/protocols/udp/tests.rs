@@ -8,6 +8,7 @@ use crate::{
     },
     runtime::{
         memory::DemiBuffer,
+        network::types::SocketOption,
         QDesc,
     },
 };
@@ -110,6 +111,61 @@ fn udp_push_pop() {
     bob.udp_close(bob_fd).unwrap();
 }
 
+#[test]
+fn udp_push_pop_receive_buffer_full() {
+    let mut ctx: Context = Context::from_waker(noop_waker_ref());
+    let now: Instant = Instant::now();
+
+    // Setup Alice.
+    let mut alice: Engine = test_helpers::new_alice2(now);
+    let alice_port: u16 = 80;
+    let alice_addr: SocketAddrV4 = SocketAddrV4::new(test_helpers::ALICE_IPV4, alice_port);
+    let alice_fd: QDesc = alice.udp_socket().unwrap();
+    alice.udp_bind(alice_fd, alice_addr).unwrap();
+
+    // Setup Bob, with room for a single datagram in its receive buffer.
+    let mut bob: Engine = test_helpers::new_bob2(now);
+    let bob_port: u16 = 80;
+    let bob_addr: SocketAddrV4 = SocketAddrV4::new(test_helpers::BOB_IPV4, bob_port);
+    let bob_fd: QDesc = bob.udp_socket().unwrap();
+    bob.ipv4
+        .udp
+        .setsockopt(bob_fd, SocketOption::ReceiveBufferSize(48))
+        .unwrap();
+    bob.udp_bind(bob_fd, bob_addr).unwrap();
+
+    // Send two datagrams to Bob. The second one does not fit in the receive buffer.
+    let first: DemiBuffer = DemiBuffer::from_slice(&vec![0x5a; 32][..]).expect("slice should fit in DemiBuffer");
+    let second: DemiBuffer = DemiBuffer::from_slice(&vec![0xa5; 32][..]).expect("slice should fit in DemiBuffer");
+    alice.udp_pushto(alice_fd, first.clone(), bob_addr).unwrap();
+    alice.udp_pushto(alice_fd, second.clone(), bob_addr).unwrap();
+    alice.rt.poll_scheduler();
+    bob.receive(alice.rt.pop_frame()).unwrap();
+    bob.receive(alice.rt.pop_frame()).unwrap();
+
+    // Only the first datagram is received.
+    let mut pop_future = bob.udp_pop(bob_fd);
+    match Future::poll(Pin::new(&mut pop_future), &mut ctx) {
+        Poll::Ready(Ok((_, received_buf))) => assert_eq!(received_buf[..], first[..]),
+        _ => panic!("first datagram should be received"),
+    }
+    let mut pop_future = bob.udp_pop(bob_fd);
+    assert!(Future::poll(Pin::new(&mut pop_future), &mut ctx).is_pending());
+
+    // Once the receive buffer is drained, datagrams are received again.
+    alice.udp_pushto(alice_fd, second.clone(), bob_addr).unwrap();
+    alice.rt.poll_scheduler();
+    bob.receive(alice.rt.pop_frame()).unwrap();
+    match Future::poll(Pin::new(&mut pop_future), &mut ctx) {
+        Poll::Ready(Ok((_, received_buf))) => assert_eq!(received_buf[..], second[..]),
+        _ => panic!("second datagram should be received"),
+    }
+
+    // Close peers.
+    alice.udp_close(alice_fd).unwrap();
+    bob.udp_close(bob_fd).unwrap();
+}
+
 //==============================================================================
 // Push & Pop
 //==============================================================================
@@ -114,7 +114,7 @@ impl Engine {
     }
 
     pub fn udp_pop(&mut self, fd: QDesc) -> UdpPopFuture {
-        self.ipv4.udp.do_pop(fd)
+        self.ipv4.udp.do_pop(fd, None)
     }
 
     pub fn udp_socket(&mut self) -> Result<QDesc, Fail> {
@@ -155,13 +155,17 @@ impl Engine {
     }
 
     pub fn tcp_pop(&mut self, socket_fd: QDesc) -> PopFuture {
-        self.ipv4.tcp.pop(socket_fd)
+        self.ipv4.tcp.pop(socket_fd, 0, None)
     }
 
     pub fn tcp_close(&mut self, socket_fd: QDesc) -> Result<(), Fail> {
         self.ipv4.tcp.do_close(socket_fd)
     }
 
+    pub fn tcp_shutdown(&mut self, socket_fd: QDesc, how: libc::c_int) -> Result<(), Fail> {
+        self.ipv4.tcp.shutdown(socket_fd, how)
+    }
+
     pub fn tcp_listen(&mut self, socket_fd: QDesc, backlog: usize) -> Result<(), Fail> {
         self.ipv4.tcp.listen(socket_fd, backlog)
     }
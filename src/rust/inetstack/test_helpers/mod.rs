@@ -79,6 +79,10 @@ pub fn new_bob(now: Instant) -> Engine {
 }
 
 pub fn new_alice2(now: Instant) -> Engine {
+    new_alice2_with_config(now, TcpConfig::default())
+}
+
+pub fn new_alice2_with_config(now: Instant, tcp_config: TcpConfig) -> Engine {
     let mut arp: HashMap<Ipv4Addr, MacAddress> = HashMap::<Ipv4Addr, MacAddress>::new();
     arp.insert(ALICE_IPV4, ALICE_MAC);
     arp.insert(BOB_IPV4, BOB_MAC);
@@ -90,7 +94,6 @@ pub fn new_alice2(now: Instant) -> Engine {
         Some(false),
     );
     let udp_config = UdpConfig::default();
-    let tcp_config = TcpConfig::default();
     let rt = TestRuntime::new(now, arp_options, udp_config, tcp_config, ALICE_MAC, ALICE_IPV4);
     let scheduler: Scheduler = rt.scheduler.clone();
     let clock: TimerRc = rt.clock.clone();
@@ -58,13 +58,24 @@ mod catnapw;
 #[cfg(feature = "catmem-libos")]
 mod catmem;
 
-pub use crate::demikernel::libos::network::OperationResult;
+pub use crate::inetstack::operations::OperationResult;
 
 pub use self::demikernel::libos::{
+    asynchronous::{
+        Accepted,
+        AsyncLibOS,
+        Popped,
+    },
+    backend::{
+        register_backend,
+        BackendFactory,
+        LibOSBackend,
+    },
     name::LibOSName,
     LibOS,
 };
 pub use crate::runtime::{
+    memory::Sga,
     network::types::{
         MacAddress,
         Port16,
@@ -11,12 +11,51 @@ use windows::Win32::Networking::WinSock;
 #[cfg(target_os = "windows")]
 pub const AF_INET: i32 = WinSock::AF_INET.0 as i32;
 
+#[cfg(target_os = "windows")]
+pub const AF_INET6: i32 = WinSock::AF_INET6.0 as i32;
+
 #[cfg(target_os = "windows")]
 pub const SOCK_STREAM: i32 = WinSock::SOCK_STREAM as i32;
 
 #[cfg(target_os = "windows")]
 pub const SOCK_DGRAM: i32 = WinSock::SOCK_DGRAM as i32;
 
+#[cfg(target_os = "windows")]
+pub const SOL_SOCKET: i32 = WinSock::SOL_SOCKET as i32;
+
+#[cfg(target_os = "windows")]
+pub const IPPROTO_TCP: i32 = WinSock::IPPROTO_TCP.0 as i32;
+
+#[cfg(target_os = "windows")]
+pub const SO_REUSEADDR: i32 = WinSock::SO_REUSEADDR as i32;
+
+#[cfg(target_os = "windows")]
+pub const SO_RCVBUF: i32 = WinSock::SO_RCVBUF as i32;
+
+#[cfg(target_os = "windows")]
+pub const SO_SNDBUF: i32 = WinSock::SO_SNDBUF as i32;
+
+#[cfg(target_os = "windows")]
+pub const SO_LINGER: i32 = WinSock::SO_LINGER as i32;
+
+#[cfg(target_os = "windows")]
+pub const SO_KEEPALIVE: i32 = WinSock::SO_KEEPALIVE as i32;
+
+#[cfg(target_os = "windows")]
+pub const TCP_NODELAY: i32 = WinSock::TCP_NODELAY as i32;
+
+#[cfg(target_os = "windows")]
+pub const TCP_MAXSEG: i32 = WinSock::TCP_MAXSEG as i32;
+
+#[cfg(target_os = "windows")]
+pub const SHUT_RD: i32 = WinSock::SD_RECEIVE as i32;
+
+#[cfg(target_os = "windows")]
+pub const SHUT_WR: i32 = WinSock::SD_SEND as i32;
+
+#[cfg(target_os = "windows")]
+pub const SHUT_RDWR: i32 = WinSock::SD_BOTH as i32;
+
 //==============================================================================
 // Linux constants
 //==============================================================================
@@ -24,8 +63,47 @@ pub const SOCK_DGRAM: i32 = WinSock::SOCK_DGRAM as i32;
 #[cfg(target_os = "linux")]
 pub const AF_INET: i32 = libc::AF_INET;
 
+#[cfg(target_os = "linux")]
+pub const AF_INET6: i32 = libc::AF_INET6;
+
 #[cfg(target_os = "linux")]
 pub const SOCK_STREAM: i32 = libc::SOCK_STREAM;
 
 #[cfg(target_os = "linux")]
 pub const SOCK_DGRAM: i32 = libc::SOCK_DGRAM;
+
+#[cfg(target_os = "linux")]
+pub const SOL_SOCKET: i32 = libc::SOL_SOCKET;
+
+#[cfg(target_os = "linux")]
+pub const IPPROTO_TCP: i32 = libc::IPPROTO_TCP;
+
+#[cfg(target_os = "linux")]
+pub const SO_REUSEADDR: i32 = libc::SO_REUSEADDR;
+
+#[cfg(target_os = "linux")]
+pub const SO_RCVBUF: i32 = libc::SO_RCVBUF;
+
+#[cfg(target_os = "linux")]
+pub const SO_SNDBUF: i32 = libc::SO_SNDBUF;
+
+#[cfg(target_os = "linux")]
+pub const SO_LINGER: i32 = libc::SO_LINGER;
+
+#[cfg(target_os = "linux")]
+pub const SO_KEEPALIVE: i32 = libc::SO_KEEPALIVE;
+
+#[cfg(target_os = "linux")]
+pub const TCP_NODELAY: i32 = libc::TCP_NODELAY;
+
+#[cfg(target_os = "linux")]
+pub const TCP_MAXSEG: i32 = libc::TCP_MAXSEG;
+
+#[cfg(target_os = "linux")]
+pub const SHUT_RD: i32 = libc::SHUT_RD;
+
+#[cfg(target_os = "linux")]
+pub const SHUT_WR: i32 = libc::SHUT_WR;
+
+#[cfg(target_os = "linux")]
+pub const SHUT_RDWR: i32 = libc::SHUT_RDWR;
@@ -1,31 +1,63 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT license.
 
-use crate::pal::data_structures::SockAddrIn;
+use crate::pal::{
+    constants::{
+        AF_INET,
+        AF_INET6,
+    },
+    data_structures::{
+        SockAddrIn,
+        SockAddrIn6,
+        SockAddrStorage,
+        Socklen,
+    },
+};
+use ::std::{
+    mem,
+    net::{
+        Ipv4Addr,
+        Ipv6Addr,
+        SocketAddr,
+        SocketAddrV4,
+        SocketAddrV6,
+    },
+    ptr,
+};
 
-#[cfg(feature = "catnip-libos")]
 const NUM_OCTETS_IN_IPV4: usize = 4;
 
-#[cfg(feature = "catnip-libos")]
+const NUM_OCTETS_IN_IPV6: usize = 16;
+
 const NUM_SIN_ZERO_BYTES: usize = 8;
 
-#[cfg(all(feature = "catnip-libos", target_os = "windows"))]
+#[cfg(target_os = "windows")]
 use windows::Win32::Foundation::CHAR;
 
-#[cfg(all(feature = "catnip-libos", target_os = "windows"))]
+#[cfg(target_os = "windows")]
 use windows::Win32::Networking::WinSock::IN_ADDR;
 
-#[cfg(all(feature = "catnip-libos", target_os = "windows"))]
+#[cfg(target_os = "windows")]
 use windows::Win32::Networking::WinSock::IN_ADDR_0;
 
-#[cfg(all(feature = "catnip-libos", target_os = "linux"))]
+#[cfg(target_os = "windows")]
+use windows::Win32::Networking::WinSock::{
+    IN6_ADDR,
+    IN6_ADDR_0,
+    SOCKADDR_IN6_0,
+};
+
+#[cfg(target_os = "linux")]
 use libc::in_addr;
 
+#[cfg(target_os = "linux")]
+use libc::in6_addr;
+
 //======================================================================================================================
 // Windows functions
 //======================================================================================================================
 
-#[cfg(all(feature = "catnip-libos", target_os = "windows"))]
+#[cfg(target_os = "windows")]
 pub fn create_sin_addr(octets: &[u8; NUM_OCTETS_IN_IPV4]) -> IN_ADDR {
     IN_ADDR {
         S_un: (IN_ADDR_0 {
@@ -34,7 +66,7 @@ pub fn create_sin_addr(octets: &[u8; NUM_OCTETS_IN_IPV4]) -> IN_ADDR {
     }
 }
 
-#[cfg(all(feature = "catnip-libos", target_os = "windows"))]
+#[cfg(target_os = "windows")]
 pub fn create_sin_zero() -> [CHAR; NUM_SIN_ZERO_BYTES] {
     [CHAR(0); 8]
 }
@@ -44,18 +76,48 @@ pub fn get_addr_from_sock_addr_in(sock_addr_in: &SockAddrIn) -> u32 {
     unsafe { sock_addr_in.sin_addr.S_un.S_addr }
 }
 
+#[cfg(target_os = "windows")]
+pub fn create_sin6_addr(octets: &[u8; NUM_OCTETS_IN_IPV6]) -> IN6_ADDR {
+    IN6_ADDR {
+        u: IN6_ADDR_0 { Byte: *octets },
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub fn create_sock_addr_in6(endpoint: &SocketAddrV6) -> SockAddrIn6 {
+    SockAddrIn6 {
+        sin6_family: AF_INET6 as u16,
+        sin6_port: u16::to_be(endpoint.port()),
+        sin6_flowinfo: endpoint.flowinfo(),
+        sin6_addr: create_sin6_addr(&endpoint.ip().octets()),
+        Anonymous: SOCKADDR_IN6_0 {
+            sin6_scope_id: endpoint.scope_id(),
+        },
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub fn get_addr_from_sock_addr_in6(sock_addr_in6: &SockAddrIn6) -> [u8; NUM_OCTETS_IN_IPV6] {
+    unsafe { sock_addr_in6.sin6_addr.u.Byte }
+}
+
+#[cfg(target_os = "windows")]
+pub fn get_scope_id_from_sock_addr_in6(sock_addr_in6: &SockAddrIn6) -> u32 {
+    unsafe { sock_addr_in6.Anonymous.sin6_scope_id }
+}
+
 //======================================================================================================================
 // Linux functions
 //======================================================================================================================
 
-#[cfg(all(feature = "catnip-libos", target_os = "linux"))]
+#[cfg(target_os = "linux")]
 pub fn create_sin_addr(octets: &[u8; NUM_OCTETS_IN_IPV4]) -> in_addr {
     in_addr {
         s_addr: u32::from_le_bytes(*octets),
     }
 }
 
-#[cfg(all(feature = "catnip-libos", target_os = "linux"))]
+#[cfg(target_os = "linux")]
 pub fn create_sin_zero() -> [u8; NUM_SIN_ZERO_BYTES] {
     [0; 8]
 }
@@ -64,3 +126,83 @@ pub fn create_sin_zero() -> [u8; NUM_SIN_ZERO_BYTES] {
 pub fn get_addr_from_sock_addr_in(sock_addr_in: &SockAddrIn) -> u32 {
     sock_addr_in.sin_addr.s_addr
 }
+
+#[cfg(target_os = "linux")]
+pub fn create_sin6_addr(octets: &[u8; NUM_OCTETS_IN_IPV6]) -> in6_addr {
+    in6_addr { s6_addr: *octets }
+}
+
+#[cfg(target_os = "linux")]
+pub fn create_sock_addr_in6(endpoint: &SocketAddrV6) -> SockAddrIn6 {
+    SockAddrIn6 {
+        sin6_family: AF_INET6 as u16,
+        sin6_port: u16::to_be(endpoint.port()),
+        sin6_flowinfo: endpoint.flowinfo(),
+        sin6_addr: create_sin6_addr(&endpoint.ip().octets()),
+        sin6_scope_id: endpoint.scope_id(),
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn get_addr_from_sock_addr_in6(sock_addr_in6: &SockAddrIn6) -> [u8; NUM_OCTETS_IN_IPV6] {
+    sock_addr_in6.sin6_addr.s6_addr
+}
+
+#[cfg(target_os = "linux")]
+pub fn get_scope_id_from_sock_addr_in6(sock_addr_in6: &SockAddrIn6) -> u32 {
+    sock_addr_in6.sin6_scope_id
+}
+
+//======================================================================================================================
+// Portable functions
+//======================================================================================================================
+
+/// Converts a [SocketAddrV4] into a [SockAddrIn], with both address and port in network byte order.
+pub fn create_sock_addr_in(endpoint: &SocketAddrV4) -> SockAddrIn {
+    SockAddrIn {
+        sin_family: AF_INET as u16,
+        sin_port: u16::to_be(endpoint.port()),
+        sin_addr: create_sin_addr(&endpoint.ip().octets()),
+        sin_zero: create_sin_zero(),
+    }
+}
+
+/// Converts a [SocketAddr] into a [SockAddrStorage], along with the length of the socket address that it holds.
+pub fn create_sock_addr_storage(endpoint: &SocketAddr) -> (SockAddrStorage, Socklen) {
+    let mut storage: SockAddrStorage = unsafe { mem::zeroed() };
+    let len: usize = match endpoint {
+        SocketAddr::V4(endpoint) => {
+            let sin: SockAddrIn = create_sock_addr_in(endpoint);
+            unsafe { ptr::write(&mut storage as *mut SockAddrStorage as *mut SockAddrIn, sin) };
+            mem::size_of::<SockAddrIn>()
+        },
+        SocketAddr::V6(endpoint) => {
+            let sin6: SockAddrIn6 = create_sock_addr_in6(endpoint);
+            unsafe { ptr::write(&mut storage as *mut SockAddrStorage as *mut SockAddrIn6, sin6) };
+            mem::size_of::<SockAddrIn6>()
+        },
+    };
+    (storage, len as Socklen)
+}
+
+/// Converts a [SockAddrStorage] into a [SocketAddr]. This fails if it does not hold an IPv4 nor an IPv6 address.
+pub fn get_socketaddr_from_sock_addr_storage(storage: &SockAddrStorage) -> Option<SocketAddr> {
+    match storage.ss_family as i32 {
+        AF_INET => {
+            let sin: &SockAddrIn = unsafe { &*(storage as *const SockAddrStorage as *const SockAddrIn) };
+            let addr: Ipv4Addr = Ipv4Addr::from(u32::from_be(get_addr_from_sock_addr_in(sin)));
+            Some(SocketAddr::V4(SocketAddrV4::new(addr, u16::from_be(sin.sin_port))))
+        },
+        AF_INET6 => {
+            let sin6: &SockAddrIn6 = unsafe { &*(storage as *const SockAddrStorage as *const SockAddrIn6) };
+            let addr: Ipv6Addr = Ipv6Addr::from(get_addr_from_sock_addr_in6(sin6));
+            Some(SocketAddr::V6(SocketAddrV6::new(
+                addr,
+                u16::from_be(sin6.sin6_port),
+                sin6.sin6_flowinfo,
+                get_scope_id_from_sock_addr_in6(sin6),
+            )))
+        },
+        _ => None,
+    }
+}
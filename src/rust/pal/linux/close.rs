@@ -0,0 +1,140 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//======================================================================================================================
+// Imports
+//======================================================================================================================
+
+use crate::runtime::fail::Fail;
+use ::nix::{
+    errno::Errno,
+    sys::socket::{
+        self,
+        Shutdown,
+    },
+    unistd,
+};
+use ::std::{
+    os::unix::prelude::RawFd,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+//======================================================================================================================
+// Structures
+//======================================================================================================================
+
+/// Closing Socket
+///
+/// This is used by LibOSes that are built on top of kernel sockets to close them once data that was pushed to them is
+/// acknowledged by the remote peer. If it is dropped before the socket is closed, the connection is reset, so that the
+/// underlying file descriptor is never leaked.
+pub struct ClosingSocket {
+    /// Underlying file descriptor, until it is closed.
+    fd: Option<RawFd>,
+    /// Was the sending half of the socket shut down?
+    shut: bool,
+    /// Time by which the send queue should be flushed, as set with the `SO_LINGER` socket option.
+    deadline: Option<Instant>,
+}
+
+//======================================================================================================================
+// Associated Functions
+//======================================================================================================================
+
+/// Associated Functions for Closing Sockets
+impl ClosingSocket {
+    /// Takes ownership of a socket that is to be closed.
+    pub fn new(fd: RawFd) -> Self {
+        let deadline: Option<Instant> = match socket::getsockopt(fd, socket::sockopt::Linger) {
+            Ok(linger) if linger.l_onoff != 0 => Some(Instant::now() + Duration::from_secs(linger.l_linger as u64)),
+            _ => None,
+        };
+        Self {
+            fd: Some(fd),
+            shut: false,
+            deadline,
+        }
+    }
+
+    /// Flushes the send queue of the target socket. This returns whether some data is yet to be acknowledged by the
+    /// remote peer, in which case it should be called again later on, as the kernel does not notify when the send queue
+    /// empties.
+    pub fn flush(&mut self) -> Result<bool, Fail> {
+        let fd: RawFd = match self.fd {
+            Some(fd) => fd,
+            None => return Err(Fail::new(libc::EBADF, "socket already closed")),
+        };
+
+        // Send a FIN first. Sockets that are not connected have nothing to flush.
+        if !self.shut {
+            match socket::shutdown(fd, Shutdown::Write) {
+                Ok(()) | Err(Errno::ENOTCONN) => self.shut = true,
+                Err(e) => {
+                    warn!("failed to shut down socket ({:?})", e);
+                    return Err(Fail::new(e as i32, "operation failed"));
+                },
+            }
+        }
+
+        // Wait for data in the send queue to be acknowledged by the remote peer. Listening sockets have no send queue.
+        let mut outq: libc::c_int = 0;
+        if unsafe { libc::ioctl(fd, libc::TIOCOUTQ, &mut outq) } != 0 {
+            let errno: Errno = Errno::last();
+            if errno == Errno::EINVAL {
+                return Ok(false);
+            }
+            warn!("failed to query send queue ({:?})", errno);
+            return Err(Fail::new(errno as i32, "operation failed"));
+        }
+        if outq > 0 && self.deadline.map_or(false, |deadline| Instant::now() >= deadline) {
+            return Err(Fail::new(libc::ETIMEDOUT, "linger timeout expired"));
+        }
+        Ok(outq > 0)
+    }
+
+    /// Closes the target socket. If `abort` is set, the connection is reset rather than shut down gracefully.
+    pub fn close(&mut self, abort: bool) -> Result<(), Fail> {
+        let fd: RawFd = match self.fd.take() {
+            Some(fd) => fd,
+            None => return Err(Fail::new(libc::EBADF, "socket already closed")),
+        };
+
+        if abort {
+            let linger: libc::linger = libc::linger {
+                l_onoff: 1,
+                l_linger: 0,
+            };
+            if let Err(e) = socket::setsockopt(fd, socket::sockopt::Linger, &linger) {
+                warn!("failed to reset connection ({:?})", e);
+            }
+        }
+
+        // The send queue is also emptied when the connection is aborted, so check for pending errors.
+        let error: i32 = socket::getsockopt(fd, socket::sockopt::SocketError).unwrap_or(0);
+        if let Err(e) = unistd::close(fd) {
+            warn!("failed to close socket ({:?})", e);
+            return Err(Fail::new(e as i32, "operation failed"));
+        }
+        match error {
+            0 => Ok(()),
+            errno => Err(Fail::new(errno, "connection was not shut down gracefully")),
+        }
+    }
+}
+
+//======================================================================================================================
+// Trait Implementations
+//======================================================================================================================
+
+/// Drop Trait Implementation for Closing Sockets
+impl Drop for ClosingSocket {
+    /// Resets the connection of the target socket, unless it was already closed.
+    fn drop(&mut self) {
+        if self.fd.is_some() {
+            let _ = self.close(true);
+        }
+    }
+}
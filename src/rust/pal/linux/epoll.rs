@@ -0,0 +1,144 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//======================================================================================================================
+// Imports
+//======================================================================================================================
+
+use crate::runtime::fail::Fail;
+use ::nix::errno;
+use ::std::{
+    mem,
+    os::unix::prelude::RawFd,
+    time::Duration,
+};
+
+//======================================================================================================================
+// Constants
+//======================================================================================================================
+
+/// Maximum number of events that are taken out of an epoll instance at once.
+const EPOLL_MAX_EVENTS: usize = 64;
+
+//======================================================================================================================
+// Structures
+//======================================================================================================================
+
+/// Epoll Instance
+///
+/// This is used by LibOSes that are built on top of kernel file descriptors to block until some of them have changed
+/// state, instead of polling them over and over.
+pub struct Epoll {
+    /// Underlying file descriptor.
+    fd: RawFd,
+}
+
+//======================================================================================================================
+// Associated Functions
+//======================================================================================================================
+
+/// Associated Functions for Epoll Instances
+impl Epoll {
+    /// Creates an epoll instance.
+    pub fn new() -> Result<Self, Fail> {
+        match unsafe { libc::epoll_create1(libc::EPOLL_CLOEXEC) } {
+            -1 => Err(Fail::new(errno::errno(), "failed to create epoll instance")),
+            fd => Ok(Self { fd }),
+        }
+    }
+
+    /// Adds a file descriptor to the interest list of the target epoll instance. File descriptors are registered as
+    /// edge-triggered for input and output events, so that any change in their state is reported once. They are
+    /// removed from the interest list when they are closed.
+    pub fn add(&self, fd: RawFd) -> Result<(), Fail> {
+        let mut event: libc::epoll_event = libc::epoll_event {
+            events: (libc::EPOLLIN | libc::EPOLLOUT | libc::EPOLLRDHUP | libc::EPOLLET) as u32,
+            u64: fd as u64,
+        };
+        match unsafe { libc::epoll_ctl(self.fd, libc::EPOLL_CTL_ADD, fd, &mut event) } {
+            0 => Ok(()),
+            _ => Err(Fail::new(
+                errno::errno(),
+                "failed to add file descriptor to epoll instance",
+            )),
+        }
+    }
+
+    /// Blocks until some file descriptor in the interest list of the target epoll instance has changed state, or a
+    /// timeout expires. The timeout is rounded up to the next millisecond. On success, the file descriptors that have
+    /// changed state are returned.
+    pub fn wait(&self, timeout: Duration) -> Result<Vec<RawFd>, Fail> {
+        let mut events: [libc::epoll_event; EPOLL_MAX_EVENTS] = unsafe { mem::zeroed() };
+        let timeout_ms: libc::c_int = ((timeout.as_micros() + 999) / 1000).min(libc::c_int::MAX as u128) as libc::c_int;
+        match unsafe {
+            libc::epoll_wait(
+                self.fd,
+                events.as_mut_ptr(),
+                EPOLL_MAX_EVENTS as libc::c_int,
+                timeout_ms,
+            )
+        } {
+            -1 if errno::errno() == libc::EINTR => Ok(Vec::new()),
+            -1 => Err(Fail::new(errno::errno(), "failed to wait on epoll instance")),
+            nevents => Ok(events[..nevents as usize]
+                .iter()
+                .map(|event| event.u64 as RawFd)
+                .collect()),
+        }
+    }
+}
+
+//======================================================================================================================
+// Trait Implementations
+//======================================================================================================================
+
+/// Drop Trait Implementation for Epoll Instances
+impl Drop for Epoll {
+    fn drop(&mut self) {
+        if unsafe { libc::close(self.fd) } == -1 {
+            warn!("failed to close epoll instance");
+        }
+    }
+}
+
+//======================================================================================================================
+// Unit Tests
+//======================================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::Epoll;
+    use crate::runtime::fail::Fail;
+    use ::std::{
+        os::unix::prelude::RawFd,
+        time::Duration,
+    };
+
+    /// Tests if an epoll instance reports changes in the state of its file descriptors once.
+    #[test]
+    fn epoll_wait() -> Result<(), Fail> {
+        let epoll: Epoll = Epoll::new()?;
+        let mut fds: [RawFd; 2] = [-1; 2];
+        assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+        let [read_fd, write_fd]: [RawFd; 2] = fds;
+        epoll.add(read_fd)?;
+        assert!(epoll.add(read_fd).is_err());
+
+        // Nothing happened yet, so the timeout expires.
+        assert!(epoll.wait(Duration::from_millis(1))?.is_empty());
+
+        // The pipe became readable, and this is reported only once.
+        assert_eq!(
+            unsafe { libc::write(write_fd, b"x".as_ptr() as *const libc::c_void, 1) },
+            1
+        );
+        assert_eq!(epoll.wait(Duration::from_secs(1))?, [read_fd]);
+        assert!(epoll.wait(Duration::ZERO)?.is_empty());
+
+        unsafe {
+            libc::close(read_fd);
+            libc::close(write_fd);
+        }
+        Ok(())
+    }
+}
@@ -5,17 +5,90 @@
 // Exports
 //======================================================================================================================
 
+pub mod close;
+pub mod epoll;
 pub mod shm;
 
 //======================================================================================================================
 // Imports
 //======================================================================================================================
 
+use crate::runtime::{
+    fail::Fail,
+    network::types::UnixSocketAddr,
+};
+use ::nix::{
+    errno,
+    sys::socket::{
+        SockaddrLike,
+        SockaddrStorage,
+        UnixAddr,
+    },
+};
 use ::std::{
+    ffi::OsStr,
+    io::{
+        IoSlice,
+        IoSliceMut,
+    },
     mem,
-    os::unix::prelude::RawFd,
+    net::{
+        SocketAddr,
+        SocketAddrV4,
+        SocketAddrV6,
+    },
+    os::unix::prelude::{
+        OsStrExt,
+        RawFd,
+    },
+    path::PathBuf,
+    ptr,
+    slice,
 };
 
+//======================================================================================================================
+// Structures
+//======================================================================================================================
+
+/// Leading fields of the `tcp_info` structure of Linux, which have been stable since it was introduced. The kernel
+/// only fills as many bytes as requested, so later fields are left out.
+#[repr(C)]
+#[derive(Default)]
+pub struct TcpInfo {
+    pub tcpi_state: u8,
+    pub tcpi_ca_state: u8,
+    pub tcpi_retransmits: u8,
+    pub tcpi_probes: u8,
+    pub tcpi_backoff: u8,
+    pub tcpi_options: u8,
+    pub tcpi_snd_rcv_wscale: u8,
+    pub tcpi_delivery_rate_app_limited: u8,
+    pub tcpi_rto: u32,
+    pub tcpi_ato: u32,
+    pub tcpi_snd_mss: u32,
+    pub tcpi_rcv_mss: u32,
+    pub tcpi_unacked: u32,
+    pub tcpi_sacked: u32,
+    pub tcpi_lost: u32,
+    pub tcpi_retrans: u32,
+    pub tcpi_fackets: u32,
+    pub tcpi_last_data_sent: u32,
+    pub tcpi_last_ack_sent: u32,
+    pub tcpi_last_data_recv: u32,
+    pub tcpi_last_ack_recv: u32,
+    pub tcpi_pmtu: u32,
+    pub tcpi_rcv_ssthresh: u32,
+    pub tcpi_rtt: u32,
+    pub tcpi_rttvar: u32,
+    pub tcpi_snd_ssthresh: u32,
+    pub tcpi_snd_cwnd: u32,
+    pub tcpi_advmss: u32,
+    pub tcpi_reordering: u32,
+    pub tcpi_rcv_rtt: u32,
+    pub tcpi_rcv_space: u32,
+    pub tcpi_total_retrans: u32,
+}
+
 //======================================================================================================================
 // Standalone Functions
 //======================================================================================================================
@@ -61,3 +134,312 @@ pub unsafe fn set_nonblock(fd: RawFd) -> i32 {
     flags |= libc::O_NONBLOCK;
     libc::fcntl(fd, libc::F_SETFL, flags, 1)
 }
+
+/// Converts a [SockaddrStorage] into a [SocketAddr], if it belongs to the IPv4 or IPv6 communication domain.
+pub fn sockaddr_to_socketaddr(addr: &SockaddrStorage) -> Option<SocketAddr> {
+    if let Some(sin) = addr.as_sockaddr_in() {
+        return Some(SocketAddr::V4(SocketAddrV4::from(*sin)));
+    }
+    addr.as_sockaddr_in6()
+        .map(|sin6| SocketAddr::V6(SocketAddrV6::from(*sin6)))
+}
+
+/// Converts a [UnixSocketAddr] into a [UnixAddr]. This fails if the path or name that it holds is too long.
+pub fn unixsocketaddr_to_unixaddr(addr: &UnixSocketAddr) -> Result<UnixAddr, Fail> {
+    let unix_addr: ::nix::Result<UnixAddr> = match addr {
+        UnixSocketAddr::Unnamed => Ok(UnixAddr::new_unnamed()),
+        UnixSocketAddr::Pathname(path) => UnixAddr::new(path.as_path()),
+        UnixSocketAddr::Abstract(name) => UnixAddr::new_abstract(name),
+    };
+    unix_addr.map_err(|e| Fail::new(e as i32, "invalid unix socket address"))
+}
+
+/// Converts a `sockaddr_un` structure of a given length into a [UnixSocketAddr]. As in Linux, a path ends at its first
+/// null byte, while a name in the abstract namespace starts with a null byte and spans the whole length.
+pub unsafe fn sockaddr_to_unixsocketaddr(
+    saddr: *const libc::sockaddr,
+    size: libc::socklen_t,
+) -> Result<UnixSocketAddr, Fail> {
+    let len: usize = size as usize;
+    let offset: usize = mem::size_of::<libc::sa_family_t>();
+    if len < offset || len > mem::size_of::<libc::sockaddr_un>() {
+        return Err(Fail::new(libc::EINVAL, "invalid socket address length"));
+    }
+    if (*saddr).sa_family as i32 != libc::AF_UNIX {
+        return Err(Fail::new(libc::EAFNOSUPPORT, "communication domain not supported"));
+    }
+
+    let mut sun: libc::sockaddr_un = mem::zeroed();
+    ptr::copy_nonoverlapping(saddr as *const u8, &mut sun as *mut libc::sockaddr_un as *mut u8, len);
+    let path: &[u8] = slice::from_raw_parts(sun.sun_path.as_ptr() as *const u8, len - offset);
+    match path.first() {
+        None => Ok(UnixSocketAddr::Unnamed),
+        Some(0) => Ok(UnixSocketAddr::Abstract(path[1..].to_vec())),
+        Some(_) => {
+            let end: usize = path.iter().position(|&b| b == 0).unwrap_or(path.len());
+            Ok(UnixSocketAddr::Pathname(PathBuf::from(OsStr::from_bytes(&path[..end]))))
+        },
+    }
+}
+
+/// Converts a [UnixAddr] into a [UnixSocketAddr].
+pub fn unixaddr_to_unixsocketaddr(addr: &UnixAddr) -> UnixSocketAddr {
+    if let Some(path) = addr.path() {
+        UnixSocketAddr::Pathname(path.to_path_buf())
+    } else if let Some(name) = addr.as_abstract() {
+        UnixSocketAddr::Abstract(name.to_vec())
+    } else {
+        UnixSocketAddr::Unnamed
+    }
+}
+
+/// Writes a [UnixSocketAddr] into a `sockaddr_storage` structure. Addresses that do not fit in a `sockaddr_un`
+/// structure, which Linux never reports, are written as unnamed ones.
+pub fn unixsocketaddr_to_sockaddr_storage(addr: &UnixSocketAddr) -> libc::sockaddr_storage {
+    let unix_addr: UnixAddr = unixsocketaddr_to_unixaddr(addr).unwrap_or_else(|_| UnixAddr::new_unnamed());
+    unixaddr_to_sockaddr_storage(&unix_addr)
+}
+
+/// Writes a [UnixAddr] into a `sockaddr_storage` structure, which always has room for it.
+pub fn unixaddr_to_sockaddr_storage(addr: &UnixAddr) -> libc::sockaddr_storage {
+    let mut storage: libc::sockaddr_storage = unsafe { mem::zeroed() };
+    unsafe {
+        ptr::copy_nonoverlapping(
+            addr.as_ptr() as *const u8,
+            &mut storage as *mut libc::sockaddr_storage as *mut u8,
+            addr.len() as usize,
+        )
+    };
+    storage
+}
+
+/// Gets the TCP_INFO option of a socket. This fails if the socket is not a TCP one.
+pub fn get_tcp_info(fd: RawFd) -> Option<TcpInfo> {
+    let mut info: TcpInfo = TcpInfo::default();
+    let mut option_len: libc::socklen_t = mem::size_of::<TcpInfo>() as libc::socklen_t;
+    match unsafe {
+        libc::getsockopt(
+            fd,
+            libc::IPPROTO_TCP,
+            libc::TCP_INFO,
+            &mut info as *mut TcpInfo as *mut libc::c_void,
+            &mut option_len,
+        )
+    } {
+        0 => Some(info),
+        _ => None,
+    }
+}
+
+/// Polls a socket for readiness without blocking, and returns the events that are reported by the kernel (e.g.
+/// `POLLIN`, `POLLOUT` or `POLLHUP`).
+pub fn get_poll_events(fd: RawFd) -> libc::c_short {
+    let mut pollfd: libc::pollfd = libc::pollfd {
+        fd,
+        events: libc::POLLIN | libc::POLLOUT | libc::POLLRDHUP,
+        revents: 0,
+    };
+    match unsafe { libc::poll(&mut pollfd, 1, 0) } {
+        1 => pollfd.revents,
+        _ => 0,
+    }
+}
+
+/// Checks if a socket is a listening one, with the SO_ACCEPTCONN option.
+pub fn is_listening(fd: RawFd) -> bool {
+    let mut value: libc::c_int = 0;
+    let mut option_len: libc::socklen_t = mem::size_of::<libc::c_int>() as libc::socklen_t;
+    match unsafe {
+        libc::getsockopt(
+            fd,
+            libc::SOL_SOCKET,
+            libc::SO_ACCEPTCONN,
+            &mut value as *mut libc::c_int as *mut libc::c_void,
+            &mut option_len,
+        )
+    } {
+        0 => value != 0,
+        _ => false,
+    }
+}
+
+/// Sends a batch of datagrams on a socket with a single `sendmmsg()` system call. Each datagram is gathered from a list
+/// of segments, and it is sent to the given address, if any. On success, the number of datagrams that were sent is
+/// returned, which may be less than the size of the batch.
+pub fn sendmmsg(fd: RawFd, datagrams: &[(Option<&SockaddrStorage>, &[IoSlice])]) -> Result<usize, Fail> {
+    let mut headers: Vec<libc::mmsghdr> = datagrams
+        .iter()
+        .map(|(addr, segments)| {
+            let mut header: libc::mmsghdr = unsafe { mem::zeroed() };
+            if let Some(addr) = addr {
+                header.msg_hdr.msg_name = addr.as_ptr() as *mut libc::c_void;
+                header.msg_hdr.msg_namelen = addr.len();
+            }
+            // I/O slices are guaranteed to be ABI compatible with iovec structures.
+            header.msg_hdr.msg_iov = segments.as_ptr() as *mut libc::iovec;
+            header.msg_hdr.msg_iovlen = segments.len();
+            header
+        })
+        .collect();
+    match unsafe { libc::sendmmsg(fd, headers.as_mut_ptr(), headers.len() as libc::c_uint, 0) } {
+        -1 => Err(Fail::new(errno::errno(), "failed to send datagrams")),
+        count => Ok(count as usize),
+    }
+}
+
+/// Receives a batch of datagrams from a socket with a single `recvmmsg()` system call. Each datagram is received into
+/// one of the given buffers, and it is truncated if it does not fit. On success, the length and the source address of
+/// each datagram that was received are returned, along with whether it was truncated.
+pub fn recvmmsg(fd: RawFd, buffers: &mut [&mut [u8]]) -> Result<Vec<(usize, Option<SockaddrStorage>, bool)>, Fail> {
+    let mut addrs: Vec<libc::sockaddr_storage> = vec![unsafe { mem::zeroed() }; buffers.len()];
+    let mut segments: Vec<IoSliceMut> = buffers.iter_mut().map(|buf| IoSliceMut::new(buf)).collect();
+    let mut headers: Vec<libc::mmsghdr> = addrs
+        .iter_mut()
+        .zip(segments.iter_mut())
+        .map(|(addr, segment)| {
+            let mut header: libc::mmsghdr = unsafe { mem::zeroed() };
+            header.msg_hdr.msg_name = addr as *mut libc::sockaddr_storage as *mut libc::c_void;
+            header.msg_hdr.msg_namelen = mem::size_of::<libc::sockaddr_storage>() as libc::socklen_t;
+            header.msg_hdr.msg_iov = segment as *mut IoSliceMut as *mut libc::iovec;
+            header.msg_hdr.msg_iovlen = 1;
+            header
+        })
+        .collect();
+    match unsafe {
+        libc::recvmmsg(
+            fd,
+            headers.as_mut_ptr(),
+            headers.len() as libc::c_uint,
+            0,
+            ptr::null_mut(),
+        )
+    } {
+        -1 => Err(Fail::new(errno::errno(), "failed to receive datagrams")),
+        count => Ok(headers[..count as usize]
+            .iter()
+            .map(|header| {
+                let addr: Option<SockaddrStorage> = match header.msg_hdr.msg_namelen {
+                    0 => None,
+                    len => unsafe {
+                        SockaddrStorage::from_raw(header.msg_hdr.msg_name as *const libc::sockaddr, Some(len))
+                    },
+                };
+                let truncated: bool = header.msg_hdr.msg_flags & libc::MSG_TRUNC != 0;
+                (header.msg_len as usize, addr, truncated)
+            })
+            .collect()),
+    }
+}
+
+//======================================================================================================================
+// Unit Tests
+//======================================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        recvmmsg,
+        sendmmsg,
+        sockaddr_to_unixsocketaddr,
+        unixaddr_to_sockaddr_storage,
+        unixsocketaddr_to_unixaddr,
+    };
+    use crate::runtime::{
+        fail::Fail,
+        network::types::UnixSocketAddr,
+    };
+    use ::nix::sys::socket::{
+        SockaddrLike,
+        UnixAddr,
+    };
+    use ::std::{
+        io::IoSlice,
+        mem,
+        os::unix::prelude::RawFd,
+        path::PathBuf,
+    };
+
+    /// Tests if addresses of Unix domain sockets are converted back and forth.
+    #[test]
+    fn unix_socket_addr() -> Result<(), Fail> {
+        for addr in [
+            UnixSocketAddr::Unnamed,
+            UnixSocketAddr::Pathname(PathBuf::from("/tmp/demikernel.sock")),
+            UnixSocketAddr::Abstract(b"demikernel".to_vec()),
+        ] {
+            let unix_addr: UnixAddr = unixsocketaddr_to_unixaddr(&addr)?;
+            let storage: libc::sockaddr_storage = unixaddr_to_sockaddr_storage(&unix_addr);
+            let saddr: *const libc::sockaddr = &storage as *const libc::sockaddr_storage as *const libc::sockaddr;
+            assert_eq!(unsafe { sockaddr_to_unixsocketaddr(saddr, unix_addr.len()) }?, addr);
+        }
+
+        // Raw addresses may span the whole structure, in which case paths end at their first null byte.
+        let addr: UnixSocketAddr = UnixSocketAddr::Pathname(PathBuf::from("/tmp/demikernel.sock"));
+        let storage: libc::sockaddr_storage = unixaddr_to_sockaddr_storage(&unixsocketaddr_to_unixaddr(&addr)?);
+        let saddr: *const libc::sockaddr = &storage as *const libc::sockaddr_storage as *const libc::sockaddr;
+        let size: libc::socklen_t = mem::size_of::<libc::sockaddr_un>() as libc::socklen_t;
+        assert_eq!(unsafe { sockaddr_to_unixsocketaddr(saddr, size) }?, addr);
+        assert_eq!(
+            unsafe { sockaddr_to_unixsocketaddr(saddr, size + 1) }
+                .err()
+                .map(|e| e.errno),
+            Some(libc::EINVAL)
+        );
+
+        let too_long: UnixSocketAddr = UnixSocketAddr::Pathname(PathBuf::from("x".repeat(200)));
+        assert_eq!(
+            unixsocketaddr_to_unixaddr(&too_long).err().map(|e| e.errno),
+            Some(libc::ENAMETOOLONG)
+        );
+
+        Ok(())
+    }
+
+    /// Tests if batches of datagrams are sent and received with a single system call each.
+    #[test]
+    fn mmsg() -> Result<(), Fail> {
+        let mut fds: [RawFd; 2] = [-1; 2];
+        assert_eq!(
+            unsafe {
+                libc::socketpair(
+                    libc::AF_UNIX,
+                    libc::SOCK_DGRAM | libc::SOCK_NONBLOCK,
+                    0,
+                    fds.as_mut_ptr(),
+                )
+            },
+            0
+        );
+        let [fd1, fd2]: [RawFd; 2] = fds;
+
+        // Datagrams are gathered from their segments.
+        let first: [IoSlice; 2] = [IoSlice::new(b"hello"), IoSlice::new(b" world")];
+        let second: [IoSlice; 1] = [IoSlice::new(b"demikernel")];
+        assert_eq!(sendmmsg(fd1, &[(None, &first), (None, &second)])?, 2);
+
+        // Datagrams that do not fit in their buffer are truncated.
+        let mut buf1: [u8; 16] = [0; 16];
+        let mut buf2: [u8; 4] = [0; 4];
+        let mut buf3: [u8; 16] = [0; 16];
+        let mut buffers: [&mut [u8]; 3] = [&mut buf1, &mut buf2, &mut buf3];
+        let received: Vec<(usize, bool)> = recvmmsg(fd2, &mut buffers)?
+            .into_iter()
+            .map(|(len, _, truncated)| (len, truncated))
+            .collect();
+        assert_eq!(received, [(11, false), (4, true)]);
+        assert_eq!(&buf1[..11], b"hello world");
+        assert_eq!(&buf2, b"demi");
+
+        // Nothing else was sent.
+        assert_eq!(
+            recvmmsg(fd2, &mut [&mut buf3]).err().map(|e| e.errno),
+            Some(libc::EAGAIN)
+        );
+
+        unsafe {
+            libc::close(fd1);
+            libc::close(fd2);
+        }
+        Ok(())
+    }
+}
@@ -13,10 +13,10 @@
 // Note: if compiled without the "libdpdk" feature defined, the DPDK-specific functionality won't be present.
 
 // Note on buffer chain support:
-// DPDK has a concept of MBuf chaining where multiple MBufs may be linked together to form a "packet".  While the
-// DemiBuffer routines for heap-allocated buffers also now support this functionality, it isn't yet exposed via the
-// DemiBuffer interface.
-// ToDo: Expose buffer chain support once we have a solid use case.
+// DPDK has a concept of MBuf chaining where multiple MBufs may be linked together to form a "packet".  The DemiBuffer
+// routines for heap-allocated buffers also support this functionality, and it is exposed via the append(), segments(),
+// and into_segments() routines.  This is what backs scatter-gather arrays with multiple segments.  Note that len(),
+// Deref, and DerefMut only ever refer to the first segment in the chain, whereas total_len() covers all of them.
 
 // Note on intrusive queueing:
 // Since all DemiBuffer types keep the metadata for each "view" in a separate allocated region, they can be queued
@@ -37,6 +37,7 @@ use ::dpdk_rs::{
     rte_mbuf,
     rte_mempool,
     rte_pktmbuf_adj,
+    rte_pktmbuf_chain,
     rte_pktmbuf_clone,
     rte_pktmbuf_free,
     rte_pktmbuf_trim,
@@ -48,6 +49,7 @@ use ::std::{
         handle_alloc_error,
         Layout,
     },
+    iter,
     marker::PhantomData,
     mem::{
         self,
@@ -188,7 +190,7 @@ impl MetaData {
 // DemiBuffer type tags.
 // Since our MetaData structure is 64-byte aligned, the lower 6 bits of a pointer to it are guaranteed to be zero.
 // We currently only use the lower 2 of those bits to hold the type tag.
-#[derive(PartialEq)]
+#[derive(Clone, Copy, PartialEq)]
 enum Tag {
     Heap = 1,
     #[cfg(feature = "libdpdk")]
@@ -474,6 +476,119 @@ impl DemiBuffer {
         Ok(back_half)
     }
 
+    /// Returns the number of segments in the `DemiBuffer` chain.
+    pub fn num_segments(&self) -> usize {
+        // Note: Since our MetaData and DPDK's rte_mbuf have equivalent layouts, this works for both types of buffers.
+        self.as_metadata().nb_segs as usize
+    }
+
+    /// Returns the length of the data stored across all segments of the `DemiBuffer` chain.
+    pub fn total_len(&self) -> usize {
+        self.segments().map(|segment| segment.len()).sum()
+    }
+
+    /// Appends the `tail` buffer chain to the end of this `DemiBuffer` chain.
+    // Note: Both `DemiBuffer` chains must be of the same type (i.e. heap-allocated or DPDK-allocated).  The data is not
+    // copied, rather this `DemiBuffer` takes over the references that `tail` held on its segments.
+    pub fn append(&mut self, tail: DemiBuffer) -> Result<(), Fail> {
+        if self.get_tag() != tail.get_tag() {
+            return Err(Fail::new(
+                libc::EINVAL,
+                "attempted to chain DemiBuffers of different types",
+            ));
+        }
+
+        match self.get_tag() {
+            Tag::Heap => {
+                let md_first: &mut MetaData = self.as_metadata();
+                let md_tail: &mut MetaData = tail.as_metadata();
+                let nb_segs: usize = md_first.nb_segs as usize + md_tail.nb_segs as usize;
+                if nb_segs > u16::MAX as usize {
+                    return Err(Fail::new(libc::EINVAL, "too many segments in DemiBuffer chain"));
+                }
+
+                // Update the first segment, as it is the only one holding valid chain-wide information.
+                md_first.nb_segs = nb_segs as u16;
+                md_first.pkt_len += md_tail.pkt_len;
+
+                // Link the tail chain after our last segment.
+                md_first.get_last_segment().next = Some(tail.get_ptr::<MetaData>());
+            },
+            #[cfg(feature = "libdpdk")]
+            Tag::Dpdk => {
+                // Safety: rte_pktmbuf_chain is a FFI that is safe to call as both of its args are valid MBuf pointers.
+                if unsafe { rte_pktmbuf_chain(self.as_mbuf(), tail.as_mbuf()) } != 0 {
+                    return Err(Fail::new(libc::EINVAL, "too many segments in DemiBuffer chain"));
+                }
+            },
+        }
+
+        // The references held by `tail` now belong to our chain, so don't run its destructor.
+        mem::forget(tail);
+
+        Ok(())
+    }
+
+    /// Returns an iterator over the data of each segment in the `DemiBuffer` chain.
+    pub fn segments(&self) -> impl Iterator<Item = &[u8]> {
+        // Note: Since our MetaData and DPDK's rte_mbuf have equivalent layouts for the buf_addr, data_off, data_len,
+        // and next fields, chains of both types of buffers are walked the same way.
+        iter::successors(Some(self.get_ptr::<MetaData>()), |entry| {
+            // Safety: This is safe, as `entry` is aligned, dereferenceable, and the MetaData struct it points to is
+            // initialized.
+            unsafe { entry.as_ref() }.next
+        })
+        .map(|entry| {
+            // Safety: This is safe, as `entry` is aligned, dereferenceable, and the MetaData struct it points to is
+            // initialized.
+            let metadata: &MetaData = unsafe { entry.as_ref() };
+            if metadata.data_len == 0 {
+                return &[][..];
+            }
+            // Safety: the call to from_raw_parts is safe, as its arguments refer to a valid readable memory region
+            // of the size specified and is contained within a single allocated object.  Also, since the data type is
+            // u8, proper alignment is not an issue.
+            unsafe {
+                slice::from_raw_parts(
+                    metadata.buf_addr.offset(metadata.data_off as isize),
+                    metadata.data_len as usize,
+                )
+            }
+        })
+    }
+
+    /// Consumes the `DemiBuffer` chain, returning each of its segments as a `DemiBuffer` of its own.
+    pub fn into_segments(self) -> Vec<DemiBuffer> {
+        let tag: Tag = self.get_tag();
+        let mut segments: Vec<DemiBuffer> = Vec::with_capacity(self.num_segments());
+
+        // Note: Since our MetaData and DPDK's rte_mbuf have equivalent layouts for the nb_segs, pkt_len, data_len, and
+        // next fields, chains of both types of buffers are unlinked the same way.
+        let mut next_entry: Option<NonNull<MetaData>> = Some(self.get_ptr::<MetaData>());
+        while let Some(mut entry) = next_entry {
+            // Safety: This is safe, as `entry` is aligned, dereferenceable, and the MetaData struct it points to is
+            // initialized.
+            let metadata: &mut MetaData = unsafe { entry.as_mut() };
+
+            // Unlink this segment from the chain, making it the first (and only) one of a chain of its own.
+            next_entry = metadata.next;
+            metadata.next = None;
+            metadata.nb_segs = 1;
+            metadata.pkt_len = metadata.data_len as u32;
+
+            // The new DemiBuffer inherits the reference that the chain held on this segment.
+            segments.push(DemiBuffer {
+                tagged_ptr: entry.with_addr(entry.addr() | tag),
+                _phantom: PhantomData,
+            });
+        }
+
+        // The references held by the chain now belong to the segments, so don't run the destructor.
+        mem::forget(self);
+
+        segments
+    }
+
     /// Provides a raw pointer to the buffer data.
     ///
     /// The reference count is not affected in any way and the DemiBuffer is not consumed.  The pointer is valid for as
@@ -1036,4 +1151,41 @@ mod tests {
         assert_eq!(&*split_buf, split_str.as_bytes());
         assert_eq!(&*another_buf, another_str.as_bytes());
     }
+
+    // Test chaining, segment iteration, and unchaining.
+    #[test]
+    fn chain() {
+        let header: DemiBuffer = DemiBuffer::from_slice(b"header").expect("from_slice() should succeed");
+        let body: DemiBuffer = DemiBuffer::from_slice(b"body").expect("from_slice() should succeed");
+        let trailer: DemiBuffer = DemiBuffer::from_slice(b"trailer").expect("from_slice() should succeed");
+
+        // Chain all buffers.  The length of a chain is the length of its first segment, and its total length covers all
+        // of its segments.
+        let mut buf: DemiBuffer = header;
+        assert!(buf.append(body).is_ok());
+        assert!(buf.append(trailer).is_ok());
+        assert_eq!(buf.num_segments(), 3);
+        assert_eq!(buf.len(), 6);
+        assert_eq!(buf.total_len(), 17);
+        let segments: Vec<&[u8]> = buf.segments().collect();
+        assert_eq!(segments, [&b"header"[..], &b"body"[..], &b"trailer"[..]]);
+
+        // Clone the chain and trim its last segment.  The original chain should be unaffected.
+        let mut clone: DemiBuffer = buf.clone();
+        assert_eq!(clone.num_segments(), 3);
+        assert!(clone.trim(3).is_ok());
+        assert_eq!(clone.total_len(), 14);
+        let segments: Vec<&[u8]> = clone.segments().collect();
+        assert_eq!(segments, [&b"header"[..], &b"body"[..], &b"trai"[..]]);
+        let segments: Vec<&[u8]> = buf.segments().collect();
+        assert_eq!(segments, [&b"header"[..], &b"body"[..], &b"trailer"[..]]);
+
+        // Break the original chain apart.
+        let segments: Vec<DemiBuffer> = buf.into_segments();
+        assert_eq!(segments.len(), 3);
+        for (segment, expected) in segments.iter().zip([&b"header"[..], &b"body"[..], &b"trailer"[..]]) {
+            assert_eq!(segment.num_segments(), 1);
+            assert_eq!(&segment[..], expected);
+        }
+    }
 }
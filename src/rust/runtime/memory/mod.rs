@@ -2,6 +2,7 @@
 // Licensed under the MIT license.
 
 mod buffer;
+mod sga;
 
 //==============================================================================
 // Imports
@@ -9,30 +10,195 @@ mod buffer;
 
 use crate::runtime::{
     fail::Fail,
-    types::demi_sgarray_t,
+    types::{
+        demi_sgarray_t,
+        demi_sgaseg_t,
+        DEMI_SGARRAY_MAXLEN,
+    },
+};
+use ::libc::c_void;
+use ::std::{
+    mem,
+    ptr::{
+        self,
+        NonNull,
+    },
 };
 
 //==============================================================================
 // Exports
 //==============================================================================
 
-pub use self::buffer::*;
+pub use self::{
+    buffer::*,
+    sga::Sga,
+};
 
 //==============================================================================
 // Traits
 //==============================================================================
 
 /// Memory Runtime
+///
+/// Every scatter-gather array handed out to the user is backed by a [DemiBuffer] chain, with one buffer segment for
+/// each scatter-gather segment. The token of that chain is kept in the `sga_buf` field.
 pub trait MemoryRuntime {
-    /// Creates a [demi_sgarray_t] from a [DemiBuffer].
-    fn into_sgarray(&self, buf: DemiBuffer) -> Result<demi_sgarray_t, Fail>;
-
     /// Allocates a [demi_sgarray_t].
     fn alloc_sgarray(&self, size: usize) -> Result<demi_sgarray_t, Fail>;
 
+    /// Creates a [demi_sgarray_t] from a [DemiBuffer].
+    fn into_sgarray(&self, buf: DemiBuffer) -> Result<demi_sgarray_t, Fail> {
+        // Check if the DemiBuffer chain fits in a single scatter-gather array.
+        let numsegs: usize = buf.num_segments();
+        if numsegs > DEMI_SGARRAY_MAXLEN {
+            return Err(Fail::new(libc::EINVAL, "too many segments for a single demi_sgarray_t"));
+        }
+
+        // Create a scatter-gather segment to expose each segment of the DemiBuffer chain to the user.
+        let mut sga_segs: [demi_sgaseg_t; DEMI_SGARRAY_MAXLEN] = [demi_sgaseg_t {
+            sgaseg_buf: ptr::null_mut(),
+            sgaseg_len: 0,
+        }; DEMI_SGARRAY_MAXLEN];
+        for (sga_seg, data) in sga_segs.iter_mut().zip(buf.segments()) {
+            sga_seg.sgaseg_buf = data.as_ptr() as *mut c_void;
+            sga_seg.sgaseg_len = data.len() as u32;
+        }
+
+        // Create and return a new scatter-gather array (which inherits the DemiBuffer's reference).
+        Ok(demi_sgarray_t {
+            sga_buf: buf.into_raw().as_ptr() as *mut c_void,
+            sga_numsegs: numsegs as u32,
+            sga_segs,
+            sga_addr: unsafe { mem::zeroed() },
+        })
+    }
+
     /// Releases a [demi_sgarray_t].
-    fn free_sgarray(&self, sga: demi_sgarray_t) -> Result<(), Fail>;
+    fn free_sgarray(&self, sga: demi_sgarray_t) -> Result<(), Fail> {
+        // Check arguments.
+        if sga.sga_numsegs == 0 || sga.sga_numsegs as usize > DEMI_SGARRAY_MAXLEN {
+            return Err(Fail::new(libc::EINVAL, "demi_sgarray_t has invalid segment count"));
+        }
+
+        if sga.sga_buf.is_null() {
+            return Err(Fail::new(libc::EINVAL, "demi_sgarray_t has invalid DemiBuffer token"));
+        }
+
+        // Convert back to a DemiBuffer chain and drop it.
+        // Safety: The `NonNull::new_unchecked()` call is safe, as we verified `sga.sga_buf` is not null above.
+        let token: NonNull<u8> = unsafe { NonNull::new_unchecked(sga.sga_buf as *mut u8) };
+        // Safety: The `DemiBuffer::from_raw()` call *should* be safe, as the `sga_buf` field in the `demi_sgarray_t`
+        // contained a valid `DemiBuffer` token when we provided it to the user (and the user shouldn't change it).
+        let buf: DemiBuffer = unsafe { DemiBuffer::from_raw(token) };
+        drop(buf);
+
+        Ok(())
+    }
 
     /// Clones a [demi_sgarray_t] into a [DemiBuffer].
-    fn clone_sgarray(&self, sga: &demi_sgarray_t) -> Result<DemiBuffer, Fail>;
+    fn clone_sgarray(&self, sga: &demi_sgarray_t) -> Result<DemiBuffer, Fail> {
+        // Check arguments.
+        let numsegs: usize = sga.sga_numsegs as usize;
+        if numsegs == 0 || numsegs > DEMI_SGARRAY_MAXLEN {
+            return Err(Fail::new(libc::EINVAL, "demi_sgarray_t has invalid segment count"));
+        }
+
+        if sga.sga_buf.is_null() {
+            return Err(Fail::new(libc::EINVAL, "demi_sgarray_t has invalid DemiBuffer token"));
+        }
+
+        // Convert back to a DemiBuffer chain.
+        // Safety: The `NonNull::new_unchecked()` call is safe, as we verified `sga.sga_buf` is not null above.
+        let token: NonNull<u8> = unsafe { NonNull::new_unchecked(sga.sga_buf as *mut u8) };
+        // Safety: The `DemiBuffer::from_raw()` call *should* be safe, as the `sga_buf` field in the `demi_sgarray_t`
+        // contained a valid `DemiBuffer` token when we provided it to the user (and the user shouldn't change it).
+        let buf: DemiBuffer = unsafe { DemiBuffer::from_raw(token) };
+        let clone: DemiBuffer = buf.clone();
+
+        // Don't drop buf, as it holds the same reference to the data as the sgarray (which should keep it).
+        mem::forget(buf);
+
+        // The DemiBuffer chain should have one segment for each scatter-gather segment.
+        let segments: Vec<DemiBuffer> = clone.into_segments();
+        if segments.len() != numsegs {
+            return Err(Fail::new(libc::EINVAL, "demi_sgarray_t has invalid segment count"));
+        }
+
+        // Adjust each segment to match what the user sees, and chain them back together.
+        let mut chain: Option<DemiBuffer> = None;
+        for (mut segment, sga_seg) in segments.into_iter().zip(&sga.sga_segs[..numsegs]) {
+            adjust_segment(&mut segment, sga_seg)?;
+            match chain {
+                Some(ref mut chain) => chain.append(segment)?,
+                None => chain = Some(segment),
+            }
+        }
+
+        // Return the clone.
+        // This won't panic, as we checked above that there is at least one segment.
+        Ok(chain.expect("demi_sgarray_t should have at least one segment"))
+    }
+
+    /// Appends the segments of the `tail` [demi_sgarray_t] to `sga`, and releases `tail`. No data is copied.
+    fn append_sgarray(&self, sga: &mut demi_sgarray_t, tail: demi_sgarray_t) -> Result<(), Fail> {
+        // Check if the resulting scatter-gather array fits.
+        if sga.sga_numsegs as usize + tail.sga_numsegs as usize > DEMI_SGARRAY_MAXLEN {
+            return Err(Fail::new(libc::EINVAL, "too many segments for a single demi_sgarray_t"));
+        }
+
+        // Chain views of both scatter-gather arrays and expose them as a new one.
+        let mut buf: DemiBuffer = self.clone_sgarray(sga)?;
+        buf.append(self.clone_sgarray(&tail)?)?;
+        let mut new_sga: demi_sgarray_t = self.into_sgarray(buf)?;
+        new_sga.sga_addr = sga.sga_addr;
+
+        // Release the original scatter-gather arrays, as the new one holds its own references to the same data.
+        self.free_sgarray(*sga)?;
+        self.free_sgarray(tail)?;
+        *sga = new_sga;
+
+        Ok(())
+    }
+}
+
+//==============================================================================
+// Standalone Functions
+//==============================================================================
+
+/// Adjusts a single-segment [DemiBuffer] to match the data described by a scatter-gather segment.
+fn adjust_segment(buf: &mut DemiBuffer, sga_seg: &demi_sgaseg_t) -> Result<(), Fail> {
+    // Check to see if the user has reduced the size of the buffer described by the sgarray segment since we
+    // provided it to them.  They could have increased the starting address of the buffer (`sgaseg_buf`),
+    // decreased the ending address of the buffer (`sgaseg_buf + sgaseg_len`), or both.
+    let sga_data: *const u8 = sga_seg.sgaseg_buf as *const u8;
+    let sga_len: usize = sga_seg.sgaseg_len as usize;
+    let buf_data: *const u8 = buf.as_ptr();
+    let mut buf_len: usize = buf.len();
+    // Note that empty segments are left as is, as there is no data for their addresses to refer to.
+    if (sga_data != buf_data || sga_len != buf_len) && (sga_len != 0 || buf_len != 0) {
+        // We need to adjust the DemiBuffer to match the user's changes.
+
+        // First check that the user didn't do something non-sensical, like change the buffer description to
+        // reference address space outside of the allocated memory area.
+        if sga_data < buf_data || sga_data.addr() + sga_len > buf_data.addr() + buf_len {
+            return Err(Fail::new(
+                libc::EINVAL,
+                "demi_sgarray_t describes data outside backing buffer's allocated region",
+            ));
+        }
+
+        // Calculate the amount the new starting address is ahead of the old.  And then adjust `buf` to match.
+        let adjustment_amount: usize = sga_data.addr() - buf_data.addr();
+        buf.adjust(adjustment_amount)?;
+
+        // An adjustment above would have reduced buf.len() by the adjustment amount.
+        buf_len -= adjustment_amount;
+        debug_assert_eq!(buf_len, buf.len());
+
+        // Trim the buffer down to size.
+        let trim_amount: usize = buf_len - sga_len;
+        buf.trim(trim_amount)?;
+    }
+
+    Ok(())
 }
@@ -0,0 +1,177 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//==============================================================================
+// Imports
+//==============================================================================
+
+use crate::runtime::{
+    memory::MemoryRuntime,
+    types::demi_sgarray_t,
+};
+use ::std::{
+    fmt,
+    ptr,
+    rc::Rc,
+    slice,
+};
+
+//==============================================================================
+// Structures
+//==============================================================================
+
+/// Owned Scatter-Gather Array
+///
+/// This takes ownership of a [demi_sgarray_t] handed out by a LibOS and releases it when dropped, so that it does not
+/// have to be released by hand with `sgafree()`. The scatter-gather array is released through the memory runtime of
+/// the LibOS that handed it out, which is kept alive until then.
+pub struct Sga {
+    /// Underlying scatter-gather array.
+    sga: demi_sgarray_t,
+    /// Memory runtime that releases the underlying scatter-gather array.
+    memory: Rc<dyn MemoryRuntime>,
+}
+
+//==============================================================================
+// Associate Functions
+//==============================================================================
+
+/// Associate Functions for Owned Scatter-Gather Arrays
+impl Sga {
+    /// Takes ownership of a scatter-gather array, which is released through `memory` when dropped.
+    ///
+    /// # Safety
+    ///
+    /// `sga` must have been handed out by the LibOS whose memory runtime is `memory`, and it must not be released nor
+    /// used elsewhere afterwards.
+    pub unsafe fn from_raw(sga: demi_sgarray_t, memory: Rc<dyn MemoryRuntime>) -> Self {
+        Self { sga, memory }
+    }
+
+    /// Returns an iterator over the data of each segment.
+    pub fn segments(&self) -> impl Iterator<Item = &[u8]> {
+        let numsegs: usize = self.sga.sga_numsegs as usize;
+        self.sga.sga_segs.iter().take(numsegs).map(|sga_seg| {
+            let ptr: *const u8 = sga_seg.sgaseg_buf as *const u8;
+            let len: usize = sga_seg.sgaseg_len as usize;
+            unsafe { slice::from_raw_parts(ptr, len) }
+        })
+    }
+
+    /// Returns an iterator over the data of each segment, which may be modified.
+    pub fn segments_mut(&mut self) -> impl Iterator<Item = &mut [u8]> {
+        let numsegs: usize = self.sga.sga_numsegs as usize;
+        self.sga.sga_segs.iter_mut().take(numsegs).map(|sga_seg| {
+            let ptr: *mut u8 = sga_seg.sgaseg_buf as *mut u8;
+            let len: usize = sga_seg.sgaseg_len as usize;
+            unsafe { slice::from_raw_parts_mut(ptr, len) }
+        })
+    }
+
+    /// Returns the total length of the data in all segments.
+    pub fn len(&self) -> usize {
+        self.segments().map(|data| data.len()).sum()
+    }
+
+    /// Checks if there is no data in any segment.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Gives up ownership of the underlying scatter-gather array. It is up to the caller to release it afterwards.
+    pub fn into_raw(mut self) -> demi_sgarray_t {
+        let sga: demi_sgarray_t = self.sga;
+        self.sga.sga_buf = ptr::null_mut();
+        sga
+    }
+}
+
+//==============================================================================
+// Trait Implementations
+//==============================================================================
+
+/// As Reference Trait Implementation for Owned Scatter-Gather Arrays
+impl AsRef<demi_sgarray_t> for Sga {
+    /// Borrows the underlying scatter-gather array, so that it may be pushed.
+    fn as_ref(&self) -> &demi_sgarray_t {
+        &self.sga
+    }
+}
+
+/// Debug Trait Implementation for Owned Scatter-Gather Arrays
+impl fmt::Debug for Sga {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Sga({:?} bytes)", self.len())
+    }
+}
+
+/// Drop Trait Implementation for Owned Scatter-Gather Arrays
+impl Drop for Sga {
+    /// Releases the underlying scatter-gather array, unless it was given up.
+    fn drop(&mut self) {
+        if !self.sga.sga_buf.is_null() {
+            if let Err(e) = self.memory.free_sgarray(self.sga) {
+                warn!("failed to release scatter-gather array: {:?}", e);
+            }
+        }
+    }
+}
+
+//==============================================================================
+// Unit Tests
+//==============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::Sga;
+    use crate::runtime::{
+        fail::Fail,
+        memory::{
+            DemiBuffer,
+            MemoryRuntime,
+        },
+        types::demi_sgarray_t,
+    };
+    use ::std::{
+        cell::Cell,
+        ptr::NonNull,
+        rc::Rc,
+    };
+
+    /// Memory runtime that counts the scatter-gather arrays that it releases.
+    #[derive(Default)]
+    struct CountingRuntime {
+        released: Cell<usize>,
+    }
+
+    impl MemoryRuntime for CountingRuntime {
+        fn alloc_sgarray(&self, size: usize) -> Result<demi_sgarray_t, Fail> {
+            self.into_sgarray(DemiBuffer::new(size as u16))
+        }
+
+        fn free_sgarray(&self, sga: demi_sgarray_t) -> Result<(), Fail> {
+            self.released.set(self.released.get() + 1);
+            let token: NonNull<u8> = NonNull::new(sga.sga_buf as *mut u8).expect("sga_buf should not be null");
+            drop(unsafe { DemiBuffer::from_raw(token) });
+            Ok(())
+        }
+    }
+
+    /// Tests if owned scatter-gather arrays are released through the memory runtime that handed them out, unless they
+    /// are given up.
+    #[test]
+    fn sga_release() -> Result<(), Fail> {
+        let runtime: Rc<CountingRuntime> = Rc::new(CountingRuntime::default());
+
+        let sga: Sga = unsafe { Sga::from_raw(runtime.alloc_sgarray(8)?, runtime.clone()) };
+        drop(sga);
+        assert_eq!(runtime.released.get(), 1);
+
+        let sga: Sga = unsafe { Sga::from_raw(runtime.alloc_sgarray(8)?, runtime.clone()) };
+        let sga: demi_sgarray_t = sga.into_raw();
+        assert_eq!(runtime.released.get(), 1);
+        runtime.free_sgarray(sga)?;
+        assert_eq!(runtime.released.get(), 2);
+        Ok(())
+    }
+}
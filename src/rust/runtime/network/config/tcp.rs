@@ -5,11 +5,22 @@
 // Imports
 //==============================================================================
 
-use crate::runtime::network::consts::{
-    DEFAULT_MSS,
-    MAX_MSS,
-    MIN_MSS,
+use crate::runtime::{
+    fail::Fail,
+    network::{
+        consts::{
+            DEFAULT_MSS,
+            MAX_MSS,
+            MAX_SOCKET_BUFFER_SIZE,
+            MIN_MSS,
+        },
+        types::{
+            SocketOption,
+            SocketOptionName,
+        },
+    },
 };
+use ::libc::EINVAL;
 use ::std::time::Duration;
 
 //==============================================================================
@@ -35,6 +46,16 @@ pub struct TcpConfig {
     rx_checksum_offload: bool,
     /// Offload Checksum to Hardware When Sending?
     tx_checksum_offload: bool,
+    /// Allow Reuse of Local Addresses?
+    reuse_address: bool,
+    /// Linger Timeout on Close
+    linger: Option<Duration>,
+    /// Maximum Amount of Unacknowledged Data in Flight
+    send_buffer_size: usize,
+    /// Send Keep-Alive Probes on Idle Connections?
+    keepalive: bool,
+    /// Disable Nagle's Algorithm?
+    nodelay: bool,
 }
 
 //==============================================================================
@@ -124,6 +145,66 @@ impl TcpConfig {
         self.rx_checksum_offload
     }
 
+    /// Gets the reuse address option in the target [TcpConfig].
+    pub fn get_reuse_address(&self) -> bool {
+        self.reuse_address
+    }
+
+    /// Gets the linger timeout in the target [TcpConfig].
+    pub fn get_linger(&self) -> Option<Duration> {
+        self.linger
+    }
+
+    /// Gets the send buffer size in the target [TcpConfig].
+    pub fn get_send_buffer_size(&self) -> usize {
+        self.send_buffer_size
+    }
+
+    /// Gets the keep-alive option in the target [TcpConfig].
+    pub fn get_keepalive(&self) -> bool {
+        self.keepalive
+    }
+
+    /// Gets the no-delay option in the target [TcpConfig].
+    pub fn get_nodelay(&self) -> bool {
+        self.nodelay
+    }
+
+    /// Gets the value of a socket option from the target [TcpConfig].
+    pub fn get_socket_option(&self, name: SocketOptionName) -> Result<SocketOption, Fail> {
+        match name {
+            SocketOptionName::ReuseAddress => Ok(SocketOption::ReuseAddress(self.reuse_address)),
+            SocketOptionName::ReceiveBufferSize => {
+                Ok(SocketOption::ReceiveBufferSize(self.receive_window_size as usize))
+            },
+            SocketOptionName::Linger => Ok(SocketOption::Linger(self.linger)),
+            SocketOptionName::SendBufferSize => Ok(SocketOption::SendBufferSize(self.send_buffer_size)),
+            SocketOptionName::KeepAlive => Ok(SocketOption::KeepAlive(self.keepalive)),
+            SocketOptionName::NoDelay => Ok(SocketOption::NoDelay(self.nodelay)),
+            SocketOptionName::MaxSegmentSize => Ok(SocketOption::MaxSegmentSize(self.advertised_mss)),
+        }
+    }
+
+    /// Overrides a socket option in the target [TcpConfig]. The receive buffer size is clamped to the largest
+    /// window that can be advertised, and the send buffer size to the largest window that may be advertised with
+    /// window scaling.
+    pub fn set_socket_option(&mut self, option: SocketOption) -> Result<(), Fail> {
+        match option {
+            SocketOption::ReuseAddress(value) => self.reuse_address = value,
+            SocketOption::ReceiveBufferSize(0) | SocketOption::SendBufferSize(0) => {
+                return Err(Fail::new(EINVAL, "buffer size may not be zero"))
+            },
+            SocketOption::ReceiveBufferSize(value) => self.receive_window_size = value.min(u16::MAX as usize) as u16,
+            SocketOption::SendBufferSize(value) => self.send_buffer_size = value.min(MAX_SOCKET_BUFFER_SIZE),
+            SocketOption::Linger(value) => self.linger = value,
+            SocketOption::KeepAlive(value) => self.keepalive = value,
+            SocketOption::NoDelay(value) => self.nodelay = value,
+            SocketOption::MaxSegmentSize(value) if (MIN_MSS..=MAX_MSS).contains(&value) => self.advertised_mss = value,
+            SocketOption::MaxSegmentSize(_) => return Err(Fail::new(EINVAL, "invalid maximum segment size")),
+        }
+        Ok(())
+    }
+
     /// Sets the advertised maximum segment size in the target [TcpConfig].
     fn set_advertised_mss(mut self, value: usize) -> Self {
         assert!(value >= MIN_MSS);
@@ -184,6 +265,11 @@ impl Default for TcpConfig {
             window_scale: 0,
             rx_checksum_offload: false,
             tx_checksum_offload: false,
+            reuse_address: false,
+            linger: None,
+            send_buffer_size: MAX_SOCKET_BUFFER_SIZE,
+            keepalive: false,
+            nodelay: true,
         }
     }
 }
@@ -194,9 +280,19 @@ impl Default for TcpConfig {
 
 #[cfg(test)]
 mod tests {
-    use crate::runtime::network::{
-        config::TcpConfig,
-        consts::DEFAULT_MSS,
+    use crate::runtime::{
+        fail::Fail,
+        network::{
+            config::TcpConfig,
+            consts::{
+                DEFAULT_MSS,
+                MAX_SOCKET_BUFFER_SIZE,
+            },
+            types::{
+                SocketOption,
+                SocketOptionName,
+            },
+        },
     };
     use ::std::time::Duration;
 
@@ -211,5 +307,42 @@ mod tests {
         assert_eq!(config.get_window_scale(), 0);
         assert_eq!(config.get_rx_checksum_offload(), false);
         assert_eq!(config.get_tx_checksum_offload(), false);
+        assert_eq!(config.get_send_buffer_size(), MAX_SOCKET_BUFFER_SIZE);
+        assert_eq!(config.get_keepalive(), false);
+        assert_eq!(config.get_nodelay(), true);
+    }
+
+    /// Tests socket option overrides for [TcpConfig].
+    #[test]
+    fn test_tcp_config_socket_option() -> Result<(), Fail> {
+        let mut config: TcpConfig = TcpConfig::default();
+        config.set_socket_option(SocketOption::NoDelay(true))?;
+        config.set_socket_option(SocketOption::MaxSegmentSize(1000))?;
+        config.set_socket_option(SocketOption::ReceiveBufferSize(1 << 20))?;
+        assert_eq!(config.get_advertised_mss(), 1000);
+        assert_eq!(
+            config.get_socket_option(SocketOptionName::ReceiveBufferSize)?,
+            SocketOption::ReceiveBufferSize(0xffff)
+        );
+        assert_eq!(
+            config.get_socket_option(SocketOptionName::NoDelay)?,
+            SocketOption::NoDelay(true)
+        );
+        assert!(config.set_socket_option(SocketOption::MaxSegmentSize(1)).is_err());
+
+        config.set_socket_option(SocketOption::NoDelay(false))?;
+        config.set_socket_option(SocketOption::KeepAlive(true))?;
+        config.set_socket_option(SocketOption::SendBufferSize(1 << 20))?;
+        assert_eq!(config.get_nodelay(), false);
+        assert_eq!(config.get_keepalive(), true);
+        assert_eq!(
+            config.get_socket_option(SocketOptionName::SendBufferSize)?,
+            SocketOption::SendBufferSize(1 << 20)
+        );
+        config.set_socket_option(SocketOption::SendBufferSize(usize::MAX))?;
+        assert_eq!(config.get_send_buffer_size(), MAX_SOCKET_BUFFER_SIZE);
+        assert!(config.set_socket_option(SocketOption::SendBufferSize(0)).is_err());
+
+        Ok(())
     }
 }
@@ -1,6 +1,25 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT license.
 
+//==============================================================================
+// Imports
+//==============================================================================
+
+use crate::runtime::{
+    fail::Fail,
+    network::{
+        consts::MAX_SOCKET_BUFFER_SIZE,
+        types::{
+            SocketOption,
+            SocketOptionName,
+        },
+    },
+};
+use ::libc::{
+    EINVAL,
+    ENOPROTOOPT,
+};
+
 //==============================================================================
 // Constants & Structures
 //==============================================================================
@@ -12,6 +31,8 @@ pub struct UdpConfig {
     rx_checksum: bool,
     /// Offload Checksum to Hardware When Sending?
     tx_checksum: bool,
+    /// Maximum Number of Bytes Queued for Receiving
+    receive_buffer_size: usize,
 }
 
 //==============================================================================
@@ -42,6 +63,34 @@ impl UdpConfig {
         self.tx_checksum
     }
 
+    /// Gets the receive buffer size in the target [UdpConfig].
+    pub fn get_receive_buffer_size(&self) -> usize {
+        self.receive_buffer_size
+    }
+
+    /// Gets the value of a socket option from the target [UdpConfig]. Only the receive buffer size is supported:
+    /// datagrams are sent right away so there is no send buffer, ports are released as soon as sockets are closed
+    /// so there is no address to reuse, and the other options only apply to TCP connections.
+    pub fn get_socket_option(&self, name: SocketOptionName) -> Result<SocketOption, Fail> {
+        match name {
+            SocketOptionName::ReceiveBufferSize => Ok(SocketOption::ReceiveBufferSize(self.receive_buffer_size)),
+            _ => Err(Fail::new(ENOPROTOOPT, "socket option not supported on UDP sockets")),
+        }
+    }
+
+    /// Overrides a socket option in the target [UdpConfig]. The receive buffer size is clamped to the largest socket
+    /// buffer size. See [UdpConfig::get_socket_option] for the supported options.
+    pub fn set_socket_option(&mut self, option: SocketOption) -> Result<(), Fail> {
+        match option {
+            SocketOption::ReceiveBufferSize(0) => Err(Fail::new(EINVAL, "buffer size may not be zero")),
+            SocketOption::ReceiveBufferSize(value) => {
+                self.receive_buffer_size = value.min(MAX_SOCKET_BUFFER_SIZE);
+                Ok(())
+            },
+            _ => Err(Fail::new(ENOPROTOOPT, "socket option not supported on UDP sockets")),
+        }
+    }
+
     /// Sets the RX hardware checksum offload option in the target [UdpConfig].
     fn set_rx_checksum_offload(&mut self, rx_checksum: bool) {
         self.rx_checksum = rx_checksum;
@@ -64,6 +113,7 @@ impl Default for UdpConfig {
         UdpConfig {
             rx_checksum: false,
             tx_checksum: false,
+            receive_buffer_size: MAX_SOCKET_BUFFER_SIZE,
         }
     }
 }
@@ -75,6 +125,16 @@ impl Default for UdpConfig {
 #[cfg(test)]
 mod tests {
     use super::UdpConfig;
+    use crate::runtime::{
+        fail::Fail,
+        network::{
+            consts::MAX_SOCKET_BUFFER_SIZE,
+            types::{
+                SocketOption,
+                SocketOptionName,
+            },
+        },
+    };
 
     /// Tests default instantiation for [UdpConfig].
     #[test]
@@ -82,6 +142,34 @@ mod tests {
         let config: UdpConfig = UdpConfig::default();
         assert!(!config.get_rx_checksum_offload());
         assert!(!config.get_tx_checksum_offload());
+        assert_eq!(config.get_receive_buffer_size(), MAX_SOCKET_BUFFER_SIZE);
+    }
+
+    /// Tests socket option overrides for [UdpConfig].
+    #[test]
+    fn test_udp_config_socket_option() -> Result<(), Fail> {
+        let mut config: UdpConfig = UdpConfig::default();
+        config.set_socket_option(SocketOption::ReceiveBufferSize(4096))?;
+        assert_eq!(
+            config.get_socket_option(SocketOptionName::ReceiveBufferSize)?,
+            SocketOption::ReceiveBufferSize(4096)
+        );
+        assert!(config.set_socket_option(SocketOption::ReceiveBufferSize(0)).is_err());
+
+        // Options that have no meaning for datagrams are rejected.
+        for option in [
+            SocketOption::ReuseAddress(true),
+            SocketOption::SendBufferSize(4096),
+            SocketOption::KeepAlive(true),
+            SocketOption::NoDelay(true),
+        ] {
+            assert_eq!(
+                config.set_socket_option(option).err().map(|e| e.errno),
+                Some(libc::ENOPROTOOPT)
+            );
+        }
+
+        Ok(())
     }
 
     /// Tests custom instantiation for [UdpConfig].
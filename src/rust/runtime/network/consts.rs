@@ -1,6 +1,12 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT license.
 
+//==============================================================================
+// Imports
+//==============================================================================
+
+use ::std::time::Duration;
+
 //==============================================================================
 // Constants
 //==============================================================================
@@ -19,6 +25,20 @@ pub const MAX_MSS: usize = u16::max_value() as usize;
 /// TODO: Auto-Discovery MTU Size
 pub const DEFAULT_MSS: usize = 1450;
 
+/// Maximum Socket Buffer Size
+///
+/// This is also the largest TCP window that may be advertised with window scaling.
+pub const MAX_SOCKET_BUFFER_SIZE: usize = 1 << 30;
+
+/// Idle Time Before the First TCP Keep-Alive Probe
+pub const KEEPALIVE_IDLE: Duration = Duration::from_secs(7200);
+
+/// Interval Between TCP Keep-Alive Probes
+pub const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(75);
+
+/// Number of Unanswered TCP Keep-Alive Probes Before a Connection is Dropped
+pub const KEEPALIVE_PROBES: u32 = 9;
+
 /// Length of a [crate::memory::DemiBuffer] batch.
 ///
 /// TODO: This Should be Generic
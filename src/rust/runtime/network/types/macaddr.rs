@@ -8,7 +8,10 @@
 use crate::runtime::fail::Fail;
 use ::eui48;
 use ::libc::EINVAL;
-use ::std::fmt;
+use ::std::{
+    fmt,
+    str::FromStr,
+};
 
 //==============================================================================
 // Structures
@@ -104,6 +107,15 @@ impl fmt::Display for MacAddress {
     }
 }
 
+/// From String Trait Implementation for MAC Addresses
+impl FromStr for MacAddress {
+    type Err = Fail;
+
+    fn from_str(s: &str) -> Result<Self, Fail> {
+        Self::parse_str(s)
+    }
+}
+
 /// Debug Trait Implementation for MAC Addresses
 impl fmt::Debug for MacAddress {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
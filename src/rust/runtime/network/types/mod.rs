@@ -3,6 +3,8 @@
 
 mod macaddr;
 mod portnum;
+mod sockopt;
+mod unixaddr;
 
 //==============================================================================
 // Exports
@@ -11,4 +13,9 @@ mod portnum;
 pub use self::{
     macaddr::MacAddress,
     portnum::Port16,
+    sockopt::{
+        SocketOption,
+        SocketOptionName,
+    },
+    unixaddr::UnixSocketAddr,
 };
@@ -0,0 +1,240 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//==============================================================================
+// Imports
+//==============================================================================
+
+use crate::{
+    pal::{
+        constants::{
+            IPPROTO_TCP,
+            SOL_SOCKET,
+            SO_KEEPALIVE,
+            SO_LINGER,
+            SO_RCVBUF,
+            SO_REUSEADDR,
+            SO_SNDBUF,
+            TCP_MAXSEG,
+            TCP_NODELAY,
+        },
+        data_structures::Linger,
+    },
+    runtime::fail::Fail,
+};
+use ::libc::{
+    c_int,
+    EINVAL,
+    ENOPROTOOPT,
+};
+use ::std::{
+    convert::TryFrom,
+    mem,
+    ptr,
+    time::Duration,
+};
+
+//==============================================================================
+// Enumerations
+//==============================================================================
+
+/// Socket Option Name
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SocketOptionName {
+    /// SO_REUSEADDR
+    ReuseAddress,
+    /// SO_RCVBUF
+    ReceiveBufferSize,
+    /// SO_SNDBUF
+    SendBufferSize,
+    /// SO_LINGER
+    Linger,
+    /// SO_KEEPALIVE
+    KeepAlive,
+    /// TCP_NODELAY
+    NoDelay,
+    /// TCP_MAXSEG
+    MaxSegmentSize,
+}
+
+/// Socket Option
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SocketOption {
+    /// Allow reuse of local addresses?
+    ReuseAddress(bool),
+    /// Size of the receive buffer (in bytes).
+    ReceiveBufferSize(usize),
+    /// Size of the send buffer (in bytes).
+    SendBufferSize(usize),
+    /// Linger timeout on close, if lingering is enabled.
+    Linger(Option<Duration>),
+    /// Send keep-alive probes?
+    KeepAlive(bool),
+    /// Disable Nagle's algorithm?
+    NoDelay(bool),
+    /// Maximum segment size (in bytes).
+    MaxSegmentSize(usize),
+}
+
+//==============================================================================
+// Associate Functions
+//==============================================================================
+
+/// Associate Functions for Socket Options
+impl SocketOption {
+    /// Decodes a socket option from the raw value `optval`, as it is passed to `setsockopt()`.
+    pub fn decode(name: SocketOptionName, optval: &[u8]) -> Result<Self, Fail> {
+        match name {
+            SocketOptionName::ReuseAddress => Ok(SocketOption::ReuseAddress(read_int(optval)? != 0)),
+            SocketOptionName::ReceiveBufferSize => Ok(SocketOption::ReceiveBufferSize(read_size(optval)?)),
+            SocketOptionName::SendBufferSize => Ok(SocketOption::SendBufferSize(read_size(optval)?)),
+            SocketOptionName::Linger => {
+                if optval.len() < mem::size_of::<Linger>() {
+                    return Err(Fail::new(EINVAL, "invalid socket option length"));
+                }
+                let linger: Linger = unsafe { ptr::read_unaligned(optval.as_ptr() as *const Linger) };
+                match linger.l_onoff {
+                    0 => Ok(SocketOption::Linger(None)),
+                    _ => Ok(SocketOption::Linger(Some(Duration::from_secs(linger.l_linger as u64)))),
+                }
+            },
+            SocketOptionName::KeepAlive => Ok(SocketOption::KeepAlive(read_int(optval)? != 0)),
+            SocketOptionName::NoDelay => Ok(SocketOption::NoDelay(read_int(optval)? != 0)),
+            SocketOptionName::MaxSegmentSize => Ok(SocketOption::MaxSegmentSize(read_size(optval)?)),
+        }
+    }
+
+    /// Encodes the target socket option into `optval`, as it is expected from `getsockopt()`. On success, the number
+    /// of bytes written is returned.
+    pub fn encode(&self, optval: &mut [u8]) -> Result<usize, Fail> {
+        match *self {
+            SocketOption::ReuseAddress(value) | SocketOption::KeepAlive(value) | SocketOption::NoDelay(value) => {
+                write_int(optval, value as c_int)
+            },
+            SocketOption::ReceiveBufferSize(value)
+            | SocketOption::SendBufferSize(value)
+            | SocketOption::MaxSegmentSize(value) => write_int(optval, c_int::try_from(value).unwrap_or(c_int::MAX)),
+            SocketOption::Linger(value) => {
+                let len: usize = mem::size_of::<Linger>();
+                if optval.len() < len {
+                    return Err(Fail::new(EINVAL, "invalid socket option length"));
+                }
+                let mut linger: Linger = unsafe { mem::zeroed() };
+                if let Some(timeout) = value {
+                    linger.l_onoff = 1;
+                    linger.l_linger = timeout.as_secs() as _;
+                }
+                unsafe { ptr::write_unaligned(optval.as_mut_ptr() as *mut Linger, linger) };
+                Ok(len)
+            },
+        }
+    }
+}
+
+//==============================================================================
+// Trait Implementations
+//==============================================================================
+
+/// Try From Trait Implementation for Socket Option Names
+impl TryFrom<(c_int, c_int)> for SocketOptionName {
+    type Error = Fail;
+
+    /// Tries to convert a `(level, optname)` pair into a [SocketOptionName].
+    fn try_from((level, optname): (c_int, c_int)) -> Result<Self, Fail> {
+        match (level, optname) {
+            (SOL_SOCKET, SO_REUSEADDR) => Ok(SocketOptionName::ReuseAddress),
+            (SOL_SOCKET, SO_RCVBUF) => Ok(SocketOptionName::ReceiveBufferSize),
+            (SOL_SOCKET, SO_SNDBUF) => Ok(SocketOptionName::SendBufferSize),
+            (SOL_SOCKET, SO_LINGER) => Ok(SocketOptionName::Linger),
+            (SOL_SOCKET, SO_KEEPALIVE) => Ok(SocketOptionName::KeepAlive),
+            (IPPROTO_TCP, TCP_NODELAY) => Ok(SocketOptionName::NoDelay),
+            (IPPROTO_TCP, TCP_MAXSEG) => Ok(SocketOptionName::MaxSegmentSize),
+            _ => Err(Fail::new(ENOPROTOOPT, "socket option not supported")),
+        }
+    }
+}
+
+//==============================================================================
+// Standalone Functions
+//==============================================================================
+
+/// Reads an integer socket option value.
+fn read_int(optval: &[u8]) -> Result<c_int, Fail> {
+    if optval.len() < mem::size_of::<c_int>() {
+        return Err(Fail::new(EINVAL, "invalid socket option length"));
+    }
+    Ok(unsafe { ptr::read_unaligned(optval.as_ptr() as *const c_int) })
+}
+
+/// Reads a non-negative integer socket option value.
+fn read_size(optval: &[u8]) -> Result<usize, Fail> {
+    match usize::try_from(read_int(optval)?) {
+        Ok(value) => Ok(value),
+        Err(_) => Err(Fail::new(EINVAL, "invalid socket option value")),
+    }
+}
+
+/// Writes an integer socket option value.
+fn write_int(optval: &mut [u8], value: c_int) -> Result<usize, Fail> {
+    let len: usize = mem::size_of::<c_int>();
+    if optval.len() < len {
+        return Err(Fail::new(EINVAL, "invalid socket option length"));
+    }
+    unsafe { ptr::write_unaligned(optval.as_mut_ptr() as *mut c_int, value) };
+    Ok(len)
+}
+
+//==============================================================================
+// Unit Tests
+//==============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        SocketOption,
+        SocketOptionName,
+    };
+    use crate::pal::constants::{
+        IPPROTO_TCP,
+        SOL_SOCKET,
+        SO_LINGER,
+        TCP_NODELAY,
+    };
+    use ::std::{
+        convert::TryFrom,
+        time::Duration,
+    };
+
+    /// Tests if an integer socket option survives an encode/decode round trip.
+    #[test]
+    fn test_sockopt_int_round_trip() {
+        let name: SocketOptionName = SocketOptionName::try_from((IPPROTO_TCP, TCP_NODELAY)).unwrap();
+        assert_eq!(name, SocketOptionName::NoDelay);
+
+        let mut optval: [u8; 4] = [0; 4];
+        assert_eq!(SocketOption::NoDelay(true).encode(&mut optval).unwrap(), 4);
+        assert_eq!(
+            SocketOption::decode(name, &optval).unwrap(),
+            SocketOption::NoDelay(true)
+        );
+    }
+
+    /// Tests if a linger socket option survives an encode/decode round trip.
+    #[test]
+    fn test_sockopt_linger_round_trip() {
+        let name: SocketOptionName = SocketOptionName::try_from((SOL_SOCKET, SO_LINGER)).unwrap();
+        let option: SocketOption = SocketOption::Linger(Some(Duration::from_secs(5)));
+
+        let mut optval: [u8; 16] = [0; 16];
+        let len: usize = option.encode(&mut optval).unwrap();
+        assert_eq!(SocketOption::decode(name, &optval[..len]).unwrap(), option);
+    }
+
+    /// Tests if short buffers and unknown options are rejected.
+    #[test]
+    fn test_sockopt_invalid() {
+        assert!(SocketOptionName::try_from((SOL_SOCKET, -1)).is_err());
+        assert!(SocketOption::decode(SocketOptionName::KeepAlive, &[1]).is_err());
+        assert!(SocketOption::MaxSegmentSize(1460).encode(&mut [0; 2]).is_err());
+    }
+}
@@ -0,0 +1,24 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//==============================================================================
+// Imports
+//==============================================================================
+
+use ::std::path::PathBuf;
+
+//==============================================================================
+// Enumerations
+//==============================================================================
+
+/// Address of a Unix Domain Socket
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum UnixSocketAddr {
+    /// Socket that is not bound to any address (e.g. the remote peer of a connection that did not bind its socket).
+    Unnamed,
+    /// Socket that is bound to a path in the filesystem.
+    Pathname(PathBuf),
+    /// Socket that is bound to a name in the abstract namespace of Linux. The name does not include the leading null
+    /// byte of its raw form.
+    Abstract(Vec<u8>),
+}
@@ -115,6 +115,12 @@ impl<P: TimerPtr> Timer<P> {
         self.inner.borrow().now
     }
 
+    /// Returns the expiration time of the earliest timer that is waited on, if any.
+    pub fn next_expiry(&self) -> Option<Instant> {
+        let inner = self.inner.borrow();
+        inner.heap.peek_min().map(|first| unsafe { first.as_ref().expiry })
+    }
+
     pub fn wait(&self, ptr: P, timeout: Duration) -> WaitFuture<P> {
         self.wait_until(ptr, self.now() + timeout)
     }
@@ -297,4 +303,28 @@ mod tests {
 
         assert!(Future::poll(Pin::new(&mut wait_future1), &mut ctx).is_ready());
     }
+
+    #[test]
+    fn test_next_expiry() {
+        let mut ctx = Context::from_waker(noop_waker_ref());
+        let now = Instant::now();
+
+        let timer = TimerRc(Rc::new(Timer::new(now)));
+        assert_eq!(timer.next_expiry(), None);
+
+        // Timers are only accounted for once they are waited on.
+        let wait_future1 = timer.wait(timer.clone(), Duration::from_secs(2));
+        futures::pin_mut!(wait_future1);
+        let wait_future2 = timer.wait(timer.clone(), Duration::from_secs(1));
+        futures::pin_mut!(wait_future2);
+        assert_eq!(timer.next_expiry(), None);
+
+        assert!(Future::poll(Pin::new(&mut wait_future1), &mut ctx).is_pending());
+        assert_eq!(timer.next_expiry(), Some(now + Duration::from_secs(2)));
+        assert!(Future::poll(Pin::new(&mut wait_future2), &mut ctx).is_pending());
+        assert_eq!(timer.next_expiry(), Some(now + Duration::from_secs(1)));
+
+        timer.advance_clock(now + Duration::from_secs(1));
+        assert_eq!(timer.next_expiry(), Some(now + Duration::from_secs(2)));
+    }
 }
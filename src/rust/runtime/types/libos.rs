@@ -0,0 +1,16 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+#![allow(non_camel_case_types)]
+
+//==============================================================================
+// Structures
+//==============================================================================
+
+/// Demikernel Instance
+///
+/// This is an opaque type, which is only handled through pointers.
+#[repr(C)]
+pub struct demi_libos_t {
+    _private: [u8; 0],
+}
@@ -9,14 +9,14 @@
 
 use ::libc::c_void;
 
-use crate::pal::data_structures::SockAddr;
+use crate::pal::data_structures::SockAddrStorage;
 
 //==============================================================================
 // Constants
 //==============================================================================
 
 /// Maximum Length for Scatter-Gather Arrays
-pub const DEMI_SGARRAY_MAXLEN: usize = 1;
+pub const DEMI_SGARRAY_MAXLEN: usize = 16;
 
 //==============================================================================
 // Structures
@@ -44,5 +44,16 @@ pub struct demi_sgarray_t {
     /// Scatter-gather array segments.
     pub sga_segs: [demi_sgaseg_t; DEMI_SGARRAY_MAXLEN],
     /// Source address of the data contained in this scatter-gather array (if present).
-    pub sga_addr: SockAddr,
+    pub sga_addr: SockAddrStorage,
+}
+
+//==============================================================================
+// Trait Implementations
+//==============================================================================
+
+/// As Reference Trait Implementation for Scatter-Gather Arrays
+impl AsRef<demi_sgarray_t> for demi_sgarray_t {
+    fn as_ref(&self) -> &demi_sgarray_t {
+        self
+    }
 }
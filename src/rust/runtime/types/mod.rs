@@ -1,15 +1,19 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT license.
 
+mod libos;
 mod memory;
 mod ops;
+mod poll;
 mod queue;
+mod stats;
 
 //==============================================================================
 // Exports
 //==============================================================================
 
 pub use self::{
+    libos::demi_libos_t,
     memory::{
         demi_sgarray_t,
         demi_sgaseg_t,
@@ -21,5 +25,16 @@ pub use self::{
         demi_qr_value_t,
         demi_qresult_t,
     },
+    poll::{
+        DEMI_POLLACCEPT,
+        DEMI_POLLHUP,
+        DEMI_POLLIN,
+        DEMI_POLLOUT,
+    },
     queue::demi_qtoken_t,
+    stats::{
+        demi_qstats_t,
+        DEMI_QSTATS_TCP,
+        DEMI_QSTATS_VERSION,
+    },
 };
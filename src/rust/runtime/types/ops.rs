@@ -7,14 +7,14 @@
 // Imports
 //==============================================================================
 
-use crate::runtime::types::{
-    memory::demi_sgarray_t,
-    queue::demi_qtoken_t,
-};
-use ::libc::{
-    c_int,
-    sockaddr,
+use crate::{
+    pal::data_structures::SockAddrStorage,
+    runtime::types::{
+        memory::demi_sgarray_t,
+        queue::demi_qtoken_t,
+    },
 };
+use ::libc::c_int;
 
 //==============================================================================
 // Structures
@@ -30,6 +30,7 @@ pub enum demi_opcode_t {
     DEMI_OPC_ACCEPT,
     DEMI_OPC_CONNECT,
     DEMI_OPC_FAILED,
+    DEMI_OPC_CLOSE,
 }
 
 /// Result for `accept()`
@@ -37,7 +38,7 @@ pub enum demi_opcode_t {
 #[derive(Copy, Clone)]
 pub struct demi_accept_result_t {
     pub qd: c_int,
-    pub addr: sockaddr,
+    pub addr: SockAddrStorage,
 }
 
 #[repr(C)]
@@ -52,5 +53,7 @@ pub struct demi_qresult_t {
     pub qr_opcode: demi_opcode_t,
     pub qr_qd: c_int,
     pub qr_qt: demi_qtoken_t,
+    /// Error code of the operation, zero on success.
+    pub qr_ret: c_int,
     pub qr_value: demi_qr_value_t,
 }
@@ -0,0 +1,18 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//==============================================================================
+// Constants
+//==============================================================================
+
+/// Readiness event of an I/O queue from which data may be popped, or whose end of stream was reached.
+pub const DEMI_POLLIN: u32 = 1 << 0;
+
+/// Readiness event of an I/O queue to which data may be pushed without being held back.
+pub const DEMI_POLLOUT: u32 = 1 << 1;
+
+/// Readiness event of a passive socket on which an incoming connection may be accepted.
+pub const DEMI_POLLACCEPT: u32 = 1 << 2;
+
+/// Readiness event of a connected socket whose remote peer has hung up. This is reported even if not requested.
+pub const DEMI_POLLHUP: u32 = 1 << 3;
@@ -0,0 +1,48 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+#![allow(non_camel_case_types)]
+
+//==============================================================================
+// Constants
+//==============================================================================
+
+/// Version of the [demi_qstats_t] structure.
+pub const DEMI_QSTATS_VERSION: u32 = 1;
+
+/// Flag that is set in [demi_qstats_t] when its TCP fields are valid.
+pub const DEMI_QSTATS_TCP: u32 = 1 << 0;
+
+//==============================================================================
+// Structures
+//==============================================================================
+
+/// Statistics of an I/O Queue
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct demi_qstats_t {
+    /// Version of this structure.
+    pub qs_version: u32,
+    /// Flags stating which optional fields are valid.
+    pub qs_flags: u32,
+    /// Number of bytes pushed by completed operations.
+    pub qs_bytes_pushed: u64,
+    /// Number of bytes popped by completed operations.
+    pub qs_bytes_popped: u64,
+    /// Number of completed push operations.
+    pub qs_pushes: u64,
+    /// Number of completed pop operations.
+    pub qs_pops: u64,
+    /// Number of pending operations.
+    pub qs_pending: u64,
+    /// Congestion window of a TCP connection, in bytes.
+    pub qs_tcp_cwnd: u64,
+    /// Retransmission timeout of a TCP connection, in microseconds.
+    pub qs_tcp_rto_us: u64,
+    /// Number of segments retransmitted on a TCP connection.
+    pub qs_tcp_retransmissions: u64,
+    /// Receive window of a TCP connection, in bytes.
+    pub qs_tcp_recv_window: u64,
+    /// Send window of a TCP connection (i.e. as advertised by the remote peer), in bytes, or zero if unknown.
+    pub qs_tcp_send_window: u64,
+}
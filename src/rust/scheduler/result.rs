@@ -5,6 +5,7 @@
 // Imports
 //==============================================================================
 
+use crate::runtime::fail::Fail;
 use ::std::{
     future::Future,
     pin::Pin,
@@ -38,6 +39,16 @@ impl<F: Future> FutureResult<F> {
     }
 }
 
+/// Associate Functions for Future Results of Fallible Futures
+impl<F: Future<Output = Result<T, Fail>>, T> FutureResult<F> {
+    /// Completes the target [FutureResult] with `ECANCELED`, unless it has already completed.
+    pub fn cancel(&mut self) {
+        if self.done.is_none() {
+            self.done = Some(Err(Fail::new(libc::ECANCELED, "operation cancelled")));
+        }
+    }
+}
+
 //==============================================================================
 // Trait Implementations
 //==============================================================================
@@ -31,6 +31,7 @@ use ::std::{
         RefCell,
         RefMut,
     },
+    collections::HashSet,
     future::Future,
     pin::Pin,
     ptr::NonNull,
@@ -52,6 +53,8 @@ struct Inner<F: Future<Output = ()> + Unpin> {
     slab: PinSlab<F>,
     /// Holds the status tasks.
     pages: Vec<WakerPageRef>,
+    /// Keys of tasks that have completed, but were not taken out yet.
+    completed: HashSet<u64>,
 }
 
 /// Future Scheduler
@@ -96,6 +99,7 @@ impl Scheduler {
         let (page, subpage_ix): (&WakerPageRef, usize) = inner.get_page(key);
         assert!(!page.was_dropped(subpage_ix));
         page.clear(subpage_ix);
+        inner.completed.remove(&key);
         inner.slab.remove_unpin(key as usize).unwrap()
     }
 
@@ -108,6 +112,13 @@ impl Scheduler {
         Some(handle)
     }
 
+    /// Returns the raw keys of all tasks that have completed, but were not taken out yet. The cost of this function is
+    /// proportional to the number of such tasks, not to the number of tasks held by the scheduler.
+    pub fn completed(&self) -> Vec<u64> {
+        let inner: Ref<Inner<Box<dyn SchedulerFuture>>> = self.inner.borrow();
+        inner.completed.iter().copied().collect()
+    }
+
     /// Insert a new task into our scheduler returning a handle corresponding to it.
     pub fn insert<F: SchedulerFuture>(&self, future: F) -> Option<SchedulerHandle> {
         let mut inner: RefMut<Inner<Box<dyn SchedulerFuture>>> = self.inner.borrow_mut();
@@ -150,7 +161,10 @@ impl Scheduler {
                     inner = self.inner.borrow_mut();
 
                     match poll_result {
-                        Poll::Ready(()) => inner.pages[page_ix].mark_completed(subpage_ix),
+                        Poll::Ready(()) => {
+                            inner.pages[page_ix].mark_completed(subpage_ix);
+                            inner.completed.insert(ix as u64);
+                        },
                         Poll::Pending => (),
                     }
                 }
@@ -163,6 +177,7 @@ impl Scheduler {
                         let ix: usize = (page_ix << WAKER_BIT_LENGTH_SHIFT) + subpage_ix;
                         inner.slab.remove(ix);
                         inner.pages[page_ix].clear(subpage_ix);
+                        inner.completed.remove(&(ix as u64));
                     }
                 }
             }
@@ -181,6 +196,7 @@ impl Default for Scheduler {
         let inner: Inner<Box<dyn SchedulerFuture>> = Inner {
             slab: PinSlab::new(),
             pages: vec![],
+            completed: HashSet::new(),
         };
         Self {
             inner: Rc::new(RefCell::new(inner)),
@@ -306,6 +322,31 @@ mod tests {
         assert_eq!(handle.has_completed(), true);
     }
 
+    #[test]
+    fn scheduler_completed() {
+        let scheduler: Scheduler = Scheduler::default();
+
+        // Insert two futures in the scheduler. Only the first one shall complete with a single poll operation.
+        let handle_once: SchedulerHandle = scheduler.insert(DummyFuture::new(0)).expect("insert() failed");
+        let handle_twice: SchedulerHandle = scheduler.insert(DummyFuture::new(1)).expect("insert() failed");
+        let key_once: u64 = handle_once.into_raw();
+        let key_twice: u64 = handle_twice.into_raw();
+        assert!(scheduler.completed().is_empty());
+
+        scheduler.poll();
+        assert_eq!(scheduler.completed(), vec![key_once]);
+
+        scheduler.poll();
+        let mut completed: Vec<u64> = scheduler.completed();
+        completed.sort();
+        assert_eq!(completed, vec![key_once, key_twice]);
+
+        // Taking out a future shall remove it from the completed ones.
+        let handle_once: SchedulerHandle = scheduler.from_raw_handle(key_once).expect("invalid key");
+        scheduler.take(handle_once);
+        assert_eq!(scheduler.completed(), vec![key_twice]);
+    }
+
     #[bench]
     fn bench_scheduler_poll(b: &mut Bencher) {
         let scheduler: Scheduler = Scheduler::default();
@@ -16,6 +16,28 @@
  * System Calls in demi/libos.h                                                                                      *
  *===================================================================================================================*/
 
+/**
+ * @brief Issues an invalid call to demi_init_instance().
+ */
+static bool inval_init_instance(void)
+{
+    demi_libos_t **libos = NULL;
+    int argc = 0;
+    char *const *argv = NULL;
+
+    return (demi_init_instance(libos, argc, argv) != 0);
+}
+
+/**
+ * @brief Issues an invalid call to demi_destroy_instance().
+ */
+static bool inval_destroy_instance(void)
+{
+    demi_libos_t *libos = NULL;
+
+    return (demi_destroy_instance(libos) != 0);
+}
+
 /**
  * @brief Issues an invalid call to demi_socket().
  */
@@ -76,6 +98,58 @@ static bool inval_connect(void)
     return (demi_connect(qt, qd, saddr, size) != 0);
 }
 
+/**
+ * @brief Issues an invalid call to demi_setsockopt().
+ */
+static bool inval_setsockopt(void)
+{
+    int qd = -1;
+    int level = -1;
+    int optname = -1;
+    void *optval = NULL;
+    socklen_t optlen = 0;
+
+    return (demi_setsockopt(qd, level, optname, optval, optlen) != 0);
+}
+
+/**
+ * @brief Issues an invalid call to demi_getsockopt().
+ */
+static bool inval_getsockopt(void)
+{
+    int qd = -1;
+    int level = -1;
+    int optname = -1;
+    void *optval = NULL;
+    socklen_t *optlen = NULL;
+
+    return (demi_getsockopt(qd, level, optname, optval, optlen) != 0);
+}
+
+/**
+ * @brief Issues an invalid call to demi_getsockname().
+ */
+static bool inval_getsockname(void)
+{
+    int qd = -1;
+    struct sockaddr *addr = NULL;
+    socklen_t *size = NULL;
+
+    return (demi_getsockname(qd, addr, size) != 0);
+}
+
+/**
+ * @brief Issues an invalid call to demi_getpeername().
+ */
+static bool inval_getpeername(void)
+{
+    int qd = -1;
+    struct sockaddr *addr = NULL;
+    socklen_t *size = NULL;
+
+    return (demi_getpeername(qd, addr, size) != 0);
+}
+
 /**
  * @brief Issues an invalid call to demi_close().
  */
@@ -148,6 +222,17 @@ static bool inval_sgafree(void)
     return (demi_sgafree(sga) != 0);
 }
 
+/**
+ * @brief Issues an invalid call to demi_sgaappend().
+ */
+static bool inval_sgaappend(void)
+{
+    demi_sgarray_t *sga = NULL;
+    demi_sgarray_t *tail = NULL;
+
+    return (demi_sgaappend(sga, tail) != 0);
+}
+
 /*===================================================================================================================*
  * System Calls in demi/wait.h                                                                                       *
  *===================================================================================================================*/
@@ -190,6 +275,32 @@ static bool inval_wait_any(void)
     return (demi_wait_any(qr, ready_offset, qts, num_qts, timeout) != 0);
 }
 
+/**
+ * @brief Issues an invalid system call to demi_wait_many().
+ */
+static bool inval_wait_many(void)
+{
+    demi_qresult_t *qrs = NULL;
+    int max_qrs = -1;
+    int *nqrs = NULL;
+    demi_qtoken_t *qts = NULL;
+    int num_qts = -1;
+    struct timespec *timeout = NULL;
+
+    return (demi_wait_many(qrs, max_qrs, nqrs, qts, num_qts, timeout) != 0);
+}
+
+/**
+ * @brief Issues an invalid system call to demi_cancel().
+ */
+static bool inval_cancel(void)
+{
+    demi_qresult_t *qr = NULL;
+    demi_qtoken_t qt = -1;
+
+    return (demi_cancel(qr, qt) != 0);
+}
+
 /*===================================================================================================================*
  * main()                                                                                                            *
  *===================================================================================================================*/
@@ -206,24 +317,37 @@ struct test
 /**
  * @brief Tests for system calls in demi/libos.h
  */
-static struct test tests_libos[] = {{inval_socket, "invalid demi_socket()"},   {inval_accept, "invalid demi_accept()"},
-                                    {inval_bind, "invalid demi_bind()"},       {inval_close, "invalid_demi_close()"},
-                                    {inval_connect, "invalid demi_connect()"}, {inval_listen, "invalid demi_listen()"},
-                                    {inval_pop, "invalid demi_pop()"},         {inval_push, "invalid demi_push()"},
-                                    {inval_pushto, "invalid demi_pushto()"}};
+static struct test tests_libos[] = {{inval_init_instance, "invalid demi_init_instance()"},
+                                    {inval_destroy_instance, "invalid demi_destroy_instance()"},
+                                    {inval_socket, "invalid demi_socket()"},
+                                    {inval_accept, "invalid demi_accept()"},
+                                    {inval_bind, "invalid demi_bind()"},
+                                    {inval_close, "invalid_demi_close()"},
+                                    {inval_connect, "invalid demi_connect()"},
+                                    {inval_listen, "invalid demi_listen()"},
+                                    {inval_pop, "invalid demi_pop()"},
+                                    {inval_push, "invalid demi_push()"},
+                                    {inval_pushto, "invalid demi_pushto()"},
+                                    {inval_setsockopt, "invalid demi_setsockopt()"},
+                                    {inval_getsockopt, "invalid demi_getsockopt()"},
+                                    {inval_getsockname, "invalid demi_getsockname()"},
+                                    {inval_getpeername, "invalid demi_getpeername()"}};
 
 /**
  * @brief Tests for system calls in demi/sga.h
  */
 static struct test tests_sga[] = {{inval_sgaalloc, "invalid demi_sgaalloc()"},
-                                  {inval_sgafree, "invalid demi_sgafree()"}};
+                                  {inval_sgafree, "invalid demi_sgafree()"},
+                                  {inval_sgaappend, "invalid demi_sgaappend()"}};
 
 /**
  * @brief Tests for system calls in demi/wait.h
  */
 static struct test tests_wait[] = {{inval_timedwait, "invalid demi_timedwait()"},
                                    {inval_wait, "invalid demi_wait()"},
-                                   {inval_wait_any, "invalid demi_wait_any()"}};
+                                   {inval_wait_any, "invalid demi_wait_any()"},
+                                   {inval_wait_many, "invalid demi_wait_many()"},
+                                   {inval_cancel, "invalid demi_cancel()"}};
 
 /**
  * @brief Drives the application.
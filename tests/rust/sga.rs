@@ -9,6 +9,7 @@ use ::demikernel::{
     runtime::types::demi_sgarray_t,
     LibOS,
     LibOSName,
+    Sga,
 };
 
 //==============================================================================
@@ -147,3 +148,52 @@ fn test_unit_sga_alloc_free_loop_decoupled_small() {
 fn test_unit_sga_alloc_free_loop_decoupled_big() {
     do_test_unit_sga_alloc_free_loop_decoupled(SGA_SIZE_BIG)
 }
+
+//==============================================================================
+// test_unit_sga_owned()
+//==============================================================================
+
+/// Tests if an owned scatter-gather array may be filled in and released when dropped.
+fn do_test_unit_sga_owned(size: usize) {
+    let libos_name: LibOSName = match LibOSName::from_env() {
+        Ok(libos_name) => libos_name.into(),
+        Err(e) => panic!("{:?}", e),
+    };
+    let libos: LibOS = match LibOS::new(libos_name) {
+        Ok(libos) => libos,
+        Err(e) => panic!("failed to initialize libos: {:?}", e.cause),
+    };
+
+    // Allocate, fill in and drop several times.
+    for i in 0..1_000 {
+        let mut sga: Sga = match libos.alloc_sga(size) {
+            Ok(sga) => sga,
+            Err(e) => panic!("failed to allocate sga: {:?}", e.cause),
+        };
+        assert_eq!(sga.len(), size);
+        sga.segments_mut().for_each(|data| data.fill((i % 256) as u8));
+        assert!(sga.segments().flatten().all(|&b| b == (i % 256) as u8));
+    }
+
+    // Give up ownership and release by hand.
+    let sga: Sga = match libos.alloc_sga(size) {
+        Ok(sga) => sga,
+        Err(e) => panic!("failed to allocate sga: {:?}", e.cause),
+    };
+    match libos.sgafree(sga.into_raw()) {
+        Ok(()) => (),
+        Err(e) => panic!("failed to release sga: {:?}", e.cause),
+    };
+}
+
+/// Tests an owned small scatter-gather array.
+#[test]
+fn test_unit_sga_owned_small() {
+    do_test_unit_sga_owned(SGA_SIZE_SMALL)
+}
+
+/// Tests an owned big scatter-gather array.
+#[test]
+fn test_unit_sga_owned_big() {
+    do_test_unit_sga_owned(SGA_SIZE_BIG)
+}
@@ -16,6 +16,14 @@ use ::demikernel::{
     },
     runtime::{
         memory::DemiBuffer,
+        types::{
+            demi_qstats_t,
+            DEMI_POLLACCEPT,
+            DEMI_POLLHUP,
+            DEMI_POLLIN,
+            DEMI_POLLOUT,
+            DEMI_QSTATS_TCP,
+        },
         QDesc,
         QToken,
     },
@@ -126,7 +134,7 @@ fn tcp_establish_connection_unbound() {
         let (_, qr): (QDesc, OperationResult) = safe_wait2(&mut libos, qt);
 
         let qd: QDesc = match qr {
-            OperationResult::Accept(qd) => qd,
+            OperationResult::Accept((qd, addr)) if addr.ip() == BOB_IPV4 => qd,
             _ => panic!("accept() has failed"),
         };
 
@@ -149,6 +157,12 @@ fn tcp_establish_connection_unbound() {
             _ => panic!("connect() has failed"),
         }
 
+        // Check endpoints of the connection.
+        let local: SocketAddrV4 = safe_getsockname(&mut libos, sockqd);
+        assert_eq!(local.ip(), &BOB_IPV4);
+        assert_ne!(local.port(), 0);
+        assert_eq!(safe_getpeername(&mut libos, sockqd), remote);
+
         // Close connection.
         safe_close_active(&mut libos, sockqd);
     });
@@ -176,7 +190,7 @@ fn tcp_establish_connection_bound() {
         let (_, qr): (QDesc, OperationResult) = safe_wait2(&mut libos, qt);
 
         let qd: QDesc = match qr {
-            OperationResult::Accept(qd) => qd,
+            OperationResult::Accept((qd, _)) => qd,
             _ => panic!("accept() has failed"),
         };
 
@@ -232,7 +246,7 @@ fn tcp_push_remote() {
         let qt: QToken = safe_accept(&mut libos, sockqd);
         let (_, qr): (QDesc, OperationResult) = safe_wait2(&mut libos, qt);
         let qd: QDesc = match qr {
-            OperationResult::Accept(qd) => qd,
+            OperationResult::Accept((qd, _)) => qd,
             _ => panic!("accept() has failed"),
         };
 
@@ -283,6 +297,523 @@ fn tcp_push_remote() {
     bob.join().unwrap();
 }
 
+//======================================================================================================================
+// Shutdown
+//======================================================================================================================
+
+/// Tests if data can still be popped from a connection after shutting down its sending half.
+#[test]
+fn tcp_shutdown_half_close() {
+    let (alice_tx, alice_rx): (Sender<DemiBuffer>, Receiver<DemiBuffer>) = crossbeam_channel::unbounded();
+    let (bob_tx, bob_rx): (Sender<DemiBuffer>, Receiver<DemiBuffer>) = crossbeam_channel::unbounded();
+
+    let alice: JoinHandle<()> = thread::spawn(move || {
+        let mut libos: InetStack = DummyLibOS::new(ALICE_MAC, ALICE_IPV4, alice_tx, bob_rx, arp());
+
+        let port: u16 = PORT_BASE;
+        let local: SocketAddrV4 = SocketAddrV4::new(ALICE_IPV4, port);
+
+        // Open connection.
+        let sockqd: QDesc = safe_socket(&mut libos);
+        safe_bind(&mut libos, sockqd, local);
+        safe_listen(&mut libos, sockqd);
+        let qt: QToken = safe_accept(&mut libos, sockqd);
+        let (_, qr): (QDesc, OperationResult) = safe_wait2(&mut libos, qt);
+        let qd: QDesc = match qr {
+            OperationResult::Accept((qd, _)) => qd,
+            _ => panic!("accept() has failed"),
+        };
+
+        // Pop the request.
+        let qt: QToken = safe_pop(&mut libos, qd);
+        let (_, qr): (QDesc, OperationResult) = safe_wait2(&mut libos, qt);
+        match qr {
+            OperationResult::Pop(_, buf) if buf.len() == 32 => (),
+            _ => panic!("pop() has failed {:?}", qr),
+        }
+
+        // Pop the end of the request stream.
+        let qt: QToken = safe_pop(&mut libos, qd);
+        let (_, qr): (QDesc, OperationResult) = safe_wait2(&mut libos, qt);
+        match qr {
+            OperationResult::Pop(_, buf) if buf.is_empty() => (),
+            _ => panic!("pop() should have reached the end of the stream {:?}", qr),
+        }
+
+        // Push the response.
+        let bytes: DemiBuffer = DummyLibOS::cook_data(16);
+        let qt: QToken = safe_push2(&mut libos, qd, &bytes);
+        let (_, qr): (QDesc, OperationResult) = safe_wait2(&mut libos, qt);
+        match qr {
+            OperationResult::Push => (),
+            _ => panic!("push() has failed"),
+        }
+
+        // Close connection.
+        safe_close_active(&mut libos, qd);
+        safe_close_passive(&mut libos, sockqd);
+    });
+
+    let bob: JoinHandle<()> = thread::spawn(move || {
+        let mut libos: InetStack = DummyLibOS::new(BOB_MAC, BOB_IPV4, bob_tx, alice_rx, arp());
+
+        let port: u16 = PORT_BASE;
+        let remote: SocketAddrV4 = SocketAddrV4::new(ALICE_IPV4, port);
+
+        // Open connection.
+        let sockqd: QDesc = safe_socket(&mut libos);
+        let qt: QToken = safe_connect(&mut libos, sockqd, remote);
+        let (_, qr): (QDesc, OperationResult) = safe_wait2(&mut libos, qt);
+        match qr {
+            OperationResult::Connect => (),
+            _ => panic!("connect() has failed"),
+        }
+
+        // Push the request.
+        let bytes: DemiBuffer = DummyLibOS::cook_data(32);
+        let qt: QToken = safe_push2(&mut libos, sockqd, &bytes);
+        let (_, qr): (QDesc, OperationResult) = safe_wait2(&mut libos, qt);
+        match qr {
+            OperationResult::Push => (),
+            _ => panic!("push() has failed"),
+        }
+
+        // Shut down the sending half of the connection, so that no more data may be pushed.
+        if let Err(e) = libos.shutdown(sockqd, libc::SHUT_WR) {
+            panic!("shutdown() failed: {:?}", e);
+        }
+        let qt: QToken = safe_push2(&mut libos, sockqd, &bytes);
+        let (_, qr): (QDesc, OperationResult) = safe_wait2(&mut libos, qt);
+        match qr {
+            OperationResult::Failed(e) if e.errno == libc::EPIPE => (),
+            _ => panic!("push() should have failed {:?}", qr),
+        }
+
+        // Pop the response.
+        let qt: QToken = safe_pop(&mut libos, sockqd);
+        let (_, qr): (QDesc, OperationResult) = safe_wait2(&mut libos, qt);
+        match qr {
+            OperationResult::Pop(_, buf) if buf.len() == 16 => (),
+            _ => panic!("pop() has failed {:?}", qr),
+        }
+
+        // Close connection.
+        safe_close_active(&mut libos, sockqd);
+    });
+
+    alice.join().unwrap();
+    bob.join().unwrap();
+}
+
+//======================================================================================================================
+// Async Close
+//======================================================================================================================
+
+/// Tests if an asynchronous close completes once pushed data and the end of the stream are acknowledged.
+#[test]
+fn tcp_async_close() {
+    let (alice_tx, alice_rx): (Sender<DemiBuffer>, Receiver<DemiBuffer>) = crossbeam_channel::unbounded();
+    let (bob_tx, bob_rx): (Sender<DemiBuffer>, Receiver<DemiBuffer>) = crossbeam_channel::unbounded();
+
+    let alice: JoinHandle<()> = thread::spawn(move || {
+        let mut libos: InetStack = DummyLibOS::new(ALICE_MAC, ALICE_IPV4, alice_tx, bob_rx, arp());
+
+        let port: u16 = PORT_BASE;
+        let local: SocketAddrV4 = SocketAddrV4::new(ALICE_IPV4, port);
+
+        // Open connection.
+        let sockqd: QDesc = safe_socket(&mut libos);
+        safe_bind(&mut libos, sockqd, local);
+        safe_listen(&mut libos, sockqd);
+        let qt: QToken = safe_accept(&mut libos, sockqd);
+        let (_, qr): (QDesc, OperationResult) = safe_wait2(&mut libos, qt);
+        let qd: QDesc = match qr {
+            OperationResult::Accept((qd, _)) => qd,
+            _ => panic!("accept() has failed"),
+        };
+
+        // Pop the data.
+        let qt: QToken = safe_pop(&mut libos, qd);
+        let (_, qr): (QDesc, OperationResult) = safe_wait2(&mut libos, qt);
+        match qr {
+            OperationResult::Pop(_, buf) if buf.len() == 32 => (),
+            _ => panic!("pop() has failed {:?}", qr),
+        }
+
+        // Pop the end of the stream.
+        let qt: QToken = safe_pop(&mut libos, qd);
+        let (_, qr): (QDesc, OperationResult) = safe_wait2(&mut libos, qt);
+        match qr {
+            OperationResult::Pop(_, buf) if buf.is_empty() => (),
+            _ => panic!("pop() should have reached the end of the stream {:?}", qr),
+        }
+
+        // Close connection.
+        safe_close_active(&mut libos, qd);
+
+        // Closing a listening socket completes right away.
+        let qt: QToken = match libos.async_close(sockqd) {
+            Ok(qt) => qt,
+            Err(e) => panic!("async_close() failed: {:?}", e),
+        };
+        let (qd, qr): (QDesc, OperationResult) = safe_wait2(&mut libos, qt);
+        match qr {
+            OperationResult::Close if qd == sockqd => (),
+            _ => panic!("async_close() has failed {:?}", qr),
+        }
+    });
+
+    let bob: JoinHandle<()> = thread::spawn(move || {
+        let mut libos: InetStack = DummyLibOS::new(BOB_MAC, BOB_IPV4, bob_tx, alice_rx, arp());
+
+        let port: u16 = PORT_BASE;
+        let remote: SocketAddrV4 = SocketAddrV4::new(ALICE_IPV4, port);
+
+        // Closing a socket that was never connected completes right away.
+        let sockqd: QDesc = safe_socket(&mut libos);
+        let qt: QToken = match libos.async_close(sockqd) {
+            Ok(qt) => qt,
+            Err(e) => panic!("async_close() failed: {:?}", e),
+        };
+        let (qd, qr): (QDesc, OperationResult) = safe_wait2(&mut libos, qt);
+        match qr {
+            OperationResult::Close if qd == sockqd => (),
+            _ => panic!("async_close() has failed {:?}", qr),
+        }
+
+        // Open connection.
+        let sockqd: QDesc = safe_socket(&mut libos);
+        let qt: QToken = safe_connect(&mut libos, sockqd, remote);
+        let (_, qr): (QDesc, OperationResult) = safe_wait2(&mut libos, qt);
+        match qr {
+            OperationResult::Connect => (),
+            _ => panic!("connect() has failed"),
+        }
+
+        // Push data.
+        let bytes: DemiBuffer = DummyLibOS::cook_data(32);
+        let qt: QToken = safe_push2(&mut libos, sockqd, &bytes);
+        let (_, qr): (QDesc, OperationResult) = safe_wait2(&mut libos, qt);
+        match qr {
+            OperationResult::Push => (),
+            _ => panic!("push() has failed"),
+        }
+
+        // Close connection, and wait for it to be shut down.
+        let qt: QToken = match libos.async_close(sockqd) {
+            Ok(qt) => qt,
+            Err(e) => panic!("async_close() failed: {:?}", e),
+        };
+        let (qd, qr): (QDesc, OperationResult) = safe_wait2(&mut libos, qt);
+        match qr {
+            OperationResult::Close if qd == sockqd => (),
+            _ => panic!("async_close() has failed {:?}", qr),
+        }
+
+        // The queue descriptor is released once the close operation completes.
+        match libos.async_close(sockqd) {
+            Err(e) if e.errno == libc::EBADF => (),
+            _ => panic!("async_close() on a closed socket should have failed"),
+        }
+    });
+
+    alice.join().unwrap();
+    bob.join().unwrap();
+}
+
+//======================================================================================================================
+// Statistics
+//======================================================================================================================
+
+/// Tests if the statistics of an established TCP connection expose its control block.
+#[test]
+fn tcp_get_stats() {
+    let (alice_tx, alice_rx): (Sender<DemiBuffer>, Receiver<DemiBuffer>) = crossbeam_channel::unbounded();
+    let (bob_tx, bob_rx): (Sender<DemiBuffer>, Receiver<DemiBuffer>) = crossbeam_channel::unbounded();
+
+    let alice: JoinHandle<()> = thread::spawn(move || {
+        let mut libos: InetStack = DummyLibOS::new(ALICE_MAC, ALICE_IPV4, alice_tx, bob_rx, arp());
+
+        let port: u16 = PORT_BASE;
+        let local: SocketAddrV4 = SocketAddrV4::new(ALICE_IPV4, port);
+
+        // Open connection.
+        let sockqd: QDesc = safe_socket(&mut libos);
+        safe_bind(&mut libos, sockqd, local);
+        safe_listen(&mut libos, sockqd);
+        let qt: QToken = safe_accept(&mut libos, sockqd);
+        let (_, qr): (QDesc, OperationResult) = safe_wait2(&mut libos, qt);
+        let qd: QDesc = match qr {
+            OperationResult::Accept((qd, _)) => qd,
+            _ => panic!("accept() has failed"),
+        };
+
+        // Listening sockets have no TCP statistics.
+        match libos.get_stats(sockqd) {
+            Ok(stats) => assert_eq!(stats.qs_flags & DEMI_QSTATS_TCP, 0),
+            Err(e) => panic!("get_stats() failed: {:?}", e),
+        }
+
+        // Pop the data.
+        let qt: QToken = safe_pop(&mut libos, qd);
+        let (_, qr): (QDesc, OperationResult) = safe_wait2(&mut libos, qt);
+        match qr {
+            OperationResult::Pop(_, buf) if buf.len() == 32 => (),
+            _ => panic!("pop() has failed {:?}", qr),
+        }
+
+        // Close connection.
+        safe_close_active(&mut libos, qd);
+        safe_close_passive(&mut libos, sockqd);
+    });
+
+    let bob: JoinHandle<()> = thread::spawn(move || {
+        let mut libos: InetStack = DummyLibOS::new(BOB_MAC, BOB_IPV4, bob_tx, alice_rx, arp());
+
+        let port: u16 = PORT_BASE;
+        let remote: SocketAddrV4 = SocketAddrV4::new(ALICE_IPV4, port);
+
+        // Open connection.
+        let sockqd: QDesc = safe_socket(&mut libos);
+        let qt: QToken = safe_connect(&mut libos, sockqd, remote);
+        let (_, qr): (QDesc, OperationResult) = safe_wait2(&mut libos, qt);
+        match qr {
+            OperationResult::Connect => (),
+            _ => panic!("connect() has failed"),
+        }
+
+        // Push data.
+        let bytes: DemiBuffer = DummyLibOS::cook_data(32);
+        let qt: QToken = safe_push2(&mut libos, sockqd, &bytes);
+        let (_, qr): (QDesc, OperationResult) = safe_wait2(&mut libos, qt);
+        match qr {
+            OperationResult::Push => (),
+            _ => panic!("push() has failed"),
+        }
+
+        // Check statistics.
+        let stats: demi_qstats_t = match libos.get_stats(sockqd) {
+            Ok(stats) => stats,
+            Err(e) => panic!("get_stats() failed: {:?}", e),
+        };
+        assert_ne!(stats.qs_flags & DEMI_QSTATS_TCP, 0);
+        assert!(stats.qs_tcp_cwnd > 0);
+        assert!(stats.qs_tcp_rto_us > 0);
+        assert_eq!(stats.qs_tcp_retransmissions, 0);
+        assert!(stats.qs_tcp_recv_window > 0);
+        assert!(stats.qs_tcp_send_window > 0);
+
+        // Close connection.
+        safe_close_active(&mut libos, sockqd);
+    });
+
+    alice.join().unwrap();
+    bob.join().unwrap();
+}
+
+//======================================================================================================================
+// Readiness
+//======================================================================================================================
+
+/// Tests if passive and established sockets are reported as ready from the state of the connection.
+#[test]
+fn tcp_readiness() {
+    let (alice_tx, alice_rx): (Sender<DemiBuffer>, Receiver<DemiBuffer>) = crossbeam_channel::unbounded();
+    let (bob_tx, bob_rx): (Sender<DemiBuffer>, Receiver<DemiBuffer>) = crossbeam_channel::unbounded();
+    // Data that arrives before the connection is accepted is dropped, so Bob waits for Alice to accept it.
+    let (accepted_tx, accepted_rx): (Sender<()>, Receiver<()>) = crossbeam_channel::unbounded();
+
+    let alice: JoinHandle<()> = thread::spawn(move || {
+        let mut libos: InetStack = DummyLibOS::new(ALICE_MAC, ALICE_IPV4, alice_tx, bob_rx, arp());
+
+        let port: u16 = PORT_BASE;
+        let local: SocketAddrV4 = SocketAddrV4::new(ALICE_IPV4, port);
+
+        // Open connection, once it is ready to be accepted.
+        let sockqd: QDesc = safe_socket(&mut libos);
+        safe_bind(&mut libos, sockqd, local);
+        safe_listen(&mut libos, sockqd);
+        assert_eq!(safe_readiness(&mut libos, sockqd), 0);
+        poll_readiness(&mut libos, sockqd, DEMI_POLLACCEPT);
+        let qt: QToken = safe_accept(&mut libos, sockqd);
+        let (_, qr): (QDesc, OperationResult) = safe_wait2(&mut libos, qt);
+        let qd: QDesc = match qr {
+            OperationResult::Accept((qd, _)) => qd,
+            _ => panic!("accept() has failed"),
+        };
+        assert_eq!(safe_readiness(&mut libos, sockqd), 0);
+        accepted_tx.send(()).unwrap();
+
+        // Pop the data, once it is ready to be popped.
+        poll_readiness(&mut libos, qd, DEMI_POLLIN);
+        let qt: QToken = safe_pop(&mut libos, qd);
+        let (_, qr): (QDesc, OperationResult) = safe_wait2(&mut libos, qt);
+        match qr {
+            OperationResult::Pop(_, buf) if buf.len() == 32 => (),
+            _ => panic!("pop() has failed {:?}", qr),
+        }
+
+        // The remote peer hangs up, so the end of the stream may be popped.
+        let events: u32 = poll_readiness(&mut libos, qd, DEMI_POLLHUP);
+        assert_ne!(events & DEMI_POLLIN, 0);
+
+        // Close connection.
+        safe_close_active(&mut libos, qd);
+        safe_close_passive(&mut libos, sockqd);
+    });
+
+    let bob: JoinHandle<()> = thread::spawn(move || {
+        let mut libos: InetStack = DummyLibOS::new(BOB_MAC, BOB_IPV4, bob_tx, alice_rx, arp());
+
+        let port: u16 = PORT_BASE;
+        let remote: SocketAddrV4 = SocketAddrV4::new(ALICE_IPV4, port);
+
+        // Open connection. Sockets that are not connected yet are not ready.
+        let sockqd: QDesc = safe_socket(&mut libos);
+        let qt: QToken = safe_connect(&mut libos, sockqd, remote);
+        assert_eq!(safe_readiness(&mut libos, sockqd), 0);
+        let (_, qr): (QDesc, OperationResult) = safe_wait2(&mut libos, qt);
+        match qr {
+            OperationResult::Connect => (),
+            _ => panic!("connect() has failed"),
+        }
+
+        // Push data, as the connection is writable but has nothing to read.
+        assert_eq!(safe_readiness(&mut libos, sockqd), DEMI_POLLOUT);
+        accepted_rx.recv().unwrap();
+        let bytes: DemiBuffer = DummyLibOS::cook_data(32);
+        let qt: QToken = safe_push2(&mut libos, sockqd, &bytes);
+        let (_, qr): (QDesc, OperationResult) = safe_wait2(&mut libos, qt);
+        match qr {
+            OperationResult::Push => (),
+            _ => panic!("push() has failed"),
+        }
+
+        // Close connection.
+        safe_close_active(&mut libos, sockqd);
+    });
+
+    alice.join().unwrap();
+    bob.join().unwrap();
+}
+
+//======================================================================================================================
+// Cancel
+//======================================================================================================================
+
+/// Tests if pending accept and connect operations may be cancelled.
+#[test]
+fn tcp_cancel() {
+    let (tx, rx): (Sender<DemiBuffer>, Receiver<DemiBuffer>) = crossbeam_channel::unbounded();
+    let mut libos: InetStack = DummyLibOS::new(ALICE_MAC, ALICE_IPV4, tx, rx, arp());
+
+    let port: u16 = PORT_BASE;
+    let local: SocketAddrV4 = SocketAddrV4::new(ALICE_IPV4, port);
+    let remote: SocketAddrV4 = SocketAddrV4::new(BOB_IPV4, port);
+
+    // Cancel a pending accept.
+    let sockqd: QDesc = safe_socket(&mut libos);
+    safe_bind(&mut libos, sockqd, local);
+    safe_listen(&mut libos, sockqd);
+    let qt: QToken = safe_accept(&mut libos, sockqd);
+    let (qd, qr): (QDesc, OperationResult) = safe_cancel2(&mut libos, qt);
+    assert_eq!(qd, sockqd);
+    match qr {
+        OperationResult::Failed(e) if e.errno == libc::ECANCELED => (),
+        _ => panic!("accept() should have been cancelled"),
+    }
+    safe_close_passive(&mut libos, sockqd);
+
+    // Cancel a pending connect, then connect again.
+    let sockqd: QDesc = safe_socket(&mut libos);
+    let qt: QToken = safe_connect(&mut libos, sockqd, remote);
+    let (_, qr): (QDesc, OperationResult) = safe_cancel2(&mut libos, qt);
+    match qr {
+        OperationResult::Failed(e) if e.errno == libc::ECANCELED => (),
+        _ => panic!("connect() should have been cancelled"),
+    }
+    let qt: QToken = safe_connect(&mut libos, sockqd, remote);
+    let (_, qr): (QDesc, OperationResult) = safe_cancel2(&mut libos, qt);
+    match qr {
+        OperationResult::Failed(e) if e.errno == libc::ECANCELED => (),
+        _ => panic!("connect() should have been cancelled"),
+    }
+    assert_ne!(safe_getsockname(&mut libos, sockqd).port(), 0);
+
+    // Cancel an invalid queue token.
+    assert!(libos.cancel2(QToken::from(u64::MAX)).is_err());
+}
+
+//======================================================================================================================
+// Socket Addresses
+//======================================================================================================================
+
+/// Tests if the ephemeral port allocated by a bind to port zero may be retrieved.
+#[test]
+fn tcp_bind_ephemeral_port() {
+    let (tx, rx): (Sender<DemiBuffer>, Receiver<DemiBuffer>) = crossbeam_channel::unbounded();
+    let mut libos: InetStack = DummyLibOS::new(ALICE_MAC, ALICE_IPV4, tx, rx, arp());
+    let sockqd: QDesc = safe_socket(&mut libos);
+
+    // Unbound sockets report the unspecified address.
+    assert_eq!(
+        safe_getsockname(&mut libos, sockqd),
+        SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 0)
+    );
+
+    // Bind to port zero and retrieve the port that was allocated.
+    safe_bind(&mut libos, sockqd, SocketAddrV4::new(ALICE_IPV4, 0));
+    let local: SocketAddrV4 = safe_getsockname(&mut libos, sockqd);
+    assert_eq!(local.ip(), &ALICE_IPV4);
+    assert_ne!(local.port(), 0);
+
+    // Unconnected sockets have no peer.
+    match libos.getpeername(sockqd) {
+        Err(e) if e.errno == libc::ENOTCONN => (),
+        _ => panic!("getpeername() should fail on an unconnected socket"),
+    }
+}
+
+//======================================================================================================================
+// Socket Options
+//======================================================================================================================
+
+/// Tests if socket options may be set and retrieved on a TCP socket.
+#[test]
+#[cfg(target_os = "linux")]
+fn tcp_socket_options() {
+    let (tx, rx): (Sender<DemiBuffer>, Receiver<DemiBuffer>) = crossbeam_channel::unbounded();
+    let mut libos: InetStack = DummyLibOS::new(ALICE_MAC, ALICE_IPV4, tx, rx, arp());
+    let sockqd: QDesc = safe_socket(&mut libos);
+
+    // Set and retrieve TCP_NODELAY.
+    let optval: libc::c_int = 1;
+    match libos.setsockopt(sockqd, libc::IPPROTO_TCP, libc::TCP_NODELAY, &optval.to_ne_bytes()) {
+        Ok(()) => (),
+        Err(e) => panic!("setsockopt() failed: {:?}", e),
+    }
+    let mut optval: [u8; 4] = [0; 4];
+    match libos.getsockopt(sockqd, libc::IPPROTO_TCP, libc::TCP_NODELAY, &mut optval) {
+        Ok(4) => assert_eq!(libc::c_int::from_ne_bytes(optval), 1),
+        Ok(len) => panic!("getsockopt() returned an unexpected length: {:?}", len),
+        Err(e) => panic!("getsockopt() failed: {:?}", e),
+    }
+
+    // Set an invalid maximum segment size.
+    let optval: libc::c_int = 1;
+    match libos.setsockopt(sockqd, libc::IPPROTO_TCP, libc::TCP_MAXSEG, &optval.to_ne_bytes()) {
+        Err(e) if e.errno == libc::EINVAL => (),
+        _ => panic!("setsockopt() should fail with an invalid maximum segment size"),
+    }
+
+    // Set an unsupported option.
+    let optval: libc::c_int = 1;
+    match libos.setsockopt(sockqd, libc::SOL_SOCKET, libc::SO_BROADCAST, &optval.to_ne_bytes()) {
+        Err(e) if e.errno == libc::ENOPROTOOPT => (),
+        _ => panic!("setsockopt() should fail with an unsupported option"),
+    }
+}
+
 //======================================================================================================================
 // Bad Socket
 //======================================================================================================================
@@ -522,7 +1053,7 @@ fn tcp_bad_connect() {
         let qt: QToken = safe_accept(&mut libos, sockqd);
         let (_, qr): (QDesc, OperationResult) = safe_wait2(&mut libos, qt);
         let qd: QDesc = match qr {
-            OperationResult::Accept(qd) => qd,
+            OperationResult::Accept((qd, _)) => qd,
             _ => panic!("accept() has failed"),
         };
 
@@ -594,7 +1125,7 @@ fn tcp_bad_close() {
         let qt: QToken = safe_accept(&mut libos, sockqd);
         let (_, qr): (QDesc, OperationResult) = safe_wait2(&mut libos, qt);
         let qd: QDesc = match qr {
-            OperationResult::Accept(qd) => qd,
+            OperationResult::Accept((qd, _)) => qd,
             _ => panic!("accept() has failed"),
         };
 
@@ -673,7 +1204,7 @@ fn tcp_bad_push() {
         let qt: QToken = safe_accept(&mut libos, sockqd);
         let (_, qr): (QDesc, OperationResult) = safe_wait2(&mut libos, qt);
         let qd: QDesc = match qr {
-            OperationResult::Accept(qd) => qd,
+            OperationResult::Accept((qd, _)) => qd,
             _ => panic!("accept() has failed"),
         };
 
@@ -763,12 +1294,12 @@ fn tcp_bad_pop() {
         let qt: QToken = safe_accept(&mut libos, sockqd);
         let (_, qr): (QDesc, OperationResult) = safe_wait2(&mut libos, qt);
         let qd: QDesc = match qr {
-            OperationResult::Accept(qd) => qd,
+            OperationResult::Accept((qd, _)) => qd,
             _ => panic!("accept() has failed"),
         };
 
         // Pop from bad socket.
-        match libos.pop(QDesc::from(2)) {
+        match libos.pop(QDesc::from(2), 0, None) {
             Ok(_) => panic!("pop() form bad socket should fail."),
             Err(_) => (),
         };
@@ -848,6 +1379,22 @@ fn safe_bind(libos: &mut InetStack, sockqd: QDesc, local: SocketAddrV4) {
     };
 }
 
+/// Safe call to `getsockname()`.
+fn safe_getsockname(libos: &mut InetStack, sockqd: QDesc) -> SocketAddrV4 {
+    match libos.getsockname(sockqd) {
+        Ok(local) => local,
+        Err(e) => panic!("getsockname() failed: {:?}", e),
+    }
+}
+
+/// Safe call to `getpeername()`.
+fn safe_getpeername(libos: &mut InetStack, sockqd: QDesc) -> SocketAddrV4 {
+    match libos.getpeername(sockqd) {
+        Ok(remote) => remote,
+        Err(e) => panic!("getpeername() failed: {:?}", e),
+    }
+}
+
 /// Safe call to `listen()`.
 fn safe_listen(libos: &mut InetStack, sockqd: QDesc) {
     match libos.listen(sockqd, 8) {
@@ -866,7 +1413,7 @@ fn safe_accept(libos: &mut InetStack, sockqd: QDesc) -> QToken {
 
 /// Safe call to `pop()`.
 fn safe_pop(libos: &mut InetStack, qd: QDesc) -> QToken {
-    match libos.pop(qd) {
+    match libos.pop(qd, 0, None) {
         Ok(qt) => qt,
         Err(e) => panic!("pop() failed: {:?}", e),
     }
@@ -888,6 +1435,33 @@ fn safe_wait2(libos: &mut InetStack, qt: QToken) -> (QDesc, OperationResult) {
     }
 }
 
+/// Safe call to `cancel2()`.
+fn safe_cancel2(libos: &mut InetStack, qt: QToken) -> (QDesc, OperationResult) {
+    match libos.cancel2(qt) {
+        Ok((qd, qr)) => (qd, qr),
+        Err(e) => panic!("cancel failed: {:?}", e.cause),
+    }
+}
+
+/// Safe call to `readiness()`.
+fn safe_readiness(libos: &mut InetStack, qd: QDesc) -> u32 {
+    match libos.readiness(qd) {
+        Ok(events) => events,
+        Err(e) => panic!("readiness() failed: {:?}", e),
+    }
+}
+
+/// Polls a socket until it is ready for some events, and returns all of its readiness events.
+fn poll_readiness(libos: &mut InetStack, qd: QDesc, events: u32) -> u32 {
+    loop {
+        libos.poll_bg_work();
+        let ready: u32 = safe_readiness(libos, qd);
+        if ready & events != 0 {
+            return ready;
+        }
+    }
+}
+
 /// Safe call to `close()` on passive socket.
 fn safe_close_passive(libos: &mut InetStack, sockqd: QDesc) {
     match libos.close(sockqd) {
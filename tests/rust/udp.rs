@@ -147,7 +147,7 @@ fn udp_push_remote() {
         }
 
         // Pop data.
-        let qt: QToken = libos.pop(sockfd).unwrap();
+        let qt: QToken = libos.pop(sockfd, 0, None).unwrap();
         let (_, qr): (QDesc, OperationResult) = match libos.wait2(qt) {
             Ok((qd, qr)) => (qd, qr),
             Err(e) => panic!("operation failed: {:?}", e.cause),
@@ -169,7 +169,7 @@ fn udp_push_remote() {
         libos.bind(sockfd, bob_addr).unwrap();
 
         // Pop data.
-        let qt: QToken = libos.pop(sockfd).unwrap();
+        let qt: QToken = libos.pop(sockfd, 0, None).unwrap();
         let (_, qr): (QDesc, OperationResult) = match libos.wait2(qt) {
             Ok((qd, qr)) => (qd, qr),
             Err(e) => panic!("operation failed: {:?}", e.cause),
@@ -231,7 +231,7 @@ fn udp_loopback() {
         }
 
         // Pop data.
-        let qt: QToken = libos.pop(sockfd).unwrap();
+        let qt: QToken = libos.pop(sockfd, 0, None).unwrap();
         let (_, qr): (QDesc, OperationResult) = match libos.wait2(qt) {
             Ok((qd, qr)) => (qd, qr),
             Err(e) => panic!("operation failed: {:?}", e.cause),
@@ -253,7 +253,7 @@ fn udp_loopback() {
         libos.bind(sockfd, bob_addr).unwrap();
 
         // Pop data.
-        let qt: QToken = libos.pop(sockfd).unwrap();
+        let qt: QToken = libos.pop(sockfd, 0, None).unwrap();
         let (_, qr): (QDesc, OperationResult) = match libos.wait2(qt) {
             Ok((qd, qr)) => (qd, qr),
             Err(e) => panic!("operation failed: {:?}", e.cause),